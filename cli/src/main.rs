@@ -1,8 +1,8 @@
-use clap::{Parser, Subcommand};
-use nwl_compiler::build_project;
+use clap::{Parser, Subcommand, ValueEnum};
+use nwl_compiler::format::format_source;
+use nwl_compiler::{build_project, BuildOptions, FileKind};
 use std::fs;
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const GITHUB_REPO_OWNER: &str = "anomaly";
 const GITHUB_REPO_NAME: &str = "nwl";
@@ -26,6 +26,9 @@ enum Commands {
         input: PathBuf,
         #[arg(short, long)]
         watch: bool,
+        /// Parse the generated files and report any that do not parse
+        #[arg(long)]
+        verify: bool,
     },
     #[command(name = "compile")]
     Compile {
@@ -54,17 +57,78 @@ enum Commands {
         #[arg(short, long)]
         watch: bool,
     },
+    /// Print the JSON Schema of a kind of project file, for editor support
+    #[command(name = "schema")]
+    Schema {
+        #[arg(value_enum, required_unless_present = "out")]
+        kind: Option<SchemaKind>,
+        /// Write the schemas to this directory instead, as `<kind>.schema.json`
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+    },
+    /// Run the language server over stdio, for editors
+    #[command(name = "lsp")]
+    Lsp,
+    /// Rewrite `nwl.yaml`, pages, actions and components in the canonical layout
+    #[command(name = "fmt")]
+    Fmt {
+        /// A project directory, or a single file
+        #[arg(default_value = ".")]
+        input: PathBuf,
+        /// List the files that are not formatted, and fail if there are any,
+        /// instead of rewriting them
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum SchemaKind {
+    /// A `pages/*.yaml` file
+    Page,
+    /// The project's `nwl.yaml`
+    Project,
+    /// An `actions/*.yaml` file
+    Action,
+    /// A `components/*.yaml` file
+    Component,
+}
+
+impl SchemaKind {
+    fn name(self) -> &'static str {
+        match self {
+            SchemaKind::Page => "page",
+            SchemaKind::Project => "project",
+            SchemaKind::Action => "action",
+            SchemaKind::Component => "component",
+        }
+    }
+
+    fn schema(self) -> String {
+        let schema = match self {
+            SchemaKind::Page => nwl_shared::page_schema(),
+            SchemaKind::Project => nwl_shared::project_schema(),
+            SchemaKind::Action => nwl_shared::action_schema(),
+            SchemaKind::Component => nwl_shared::component_schema(),
+        };
+        serde_json::to_string_pretty(&schema).expect("schemas are JSON")
+    }
 }
 
 fn main() {
     let args = Args::parse();
 
     match args.command {
-        Commands::Build { input, watch } => {
+        Commands::Build {
+            input,
+            watch,
+            verify,
+        } => {
+            let options = BuildOptions { verify };
             if watch {
-                run_watch_build(input);
+                run_watch_build(input, &options);
             } else {
-                run_build(input);
+                run_build(input, &options);
             }
         }
         Commands::Compile { output, file } => {
@@ -86,27 +150,40 @@ fn main() {
         } => {
             run_dev_server(input, port, host, no_open, watch);
         }
+        Commands::Schema { kind, out } => {
+            run_schema(kind, out);
+        }
+        Commands::Lsp => {
+            if let Err(e) = nwl_lsp::run_stdio() {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Fmt { input, check } => {
+            run_fmt(input, check);
+        }
     }
 }
 
-fn run_build(input: PathBuf) {
+fn run_build(input: PathBuf, options: &BuildOptions) {
     println!("Building NWL project in {}", input.display());
     if !input.exists() {
         eprintln!("Error: Directory not found: {}", input.display());
         std::process::exit(1);
     }
-    match build_project(input) {
+    match build_project(input, options) {
         Ok(()) => {
             println!("Build successful! Routes generated automatically.");
         }
         Err(e) => {
-            eprintln!("Build error: {}", e);
+            eprintln!("{}", e);
+            eprintln!("Build failed.");
             std::process::exit(1);
         }
     }
 }
 
-fn run_watch_build(input: PathBuf) {
+fn run_watch_build(input: PathBuf, options: &BuildOptions) {
     println!("Watching for changes in {}...", input.display());
     println!("Press Ctrl+C to stop.");
 
@@ -121,7 +198,7 @@ fn run_watch_build(input: PathBuf) {
     println!("Watching {} file(s)", paths.len());
 
     // Initial build
-    run_build(input.clone());
+    run_build(input.clone(), options);
 
     // Simple polling watcher
     let mut last_modified: Vec<(PathBuf, std::time::SystemTime)> = paths
@@ -142,7 +219,7 @@ fn run_watch_build(input: PathBuf) {
                     if let Some((_, last_mod)) = last_modified.iter().find(|(p, _)| p == path) {
                         if modified > *last_mod {
                             println!("\nDetected change in: {}", path.display());
-                            run_build(input.clone());
+                            run_build(input.clone(), options);
 
                             if let Ok(m) = path.metadata() {
                                 if let Ok(mtime) = m.modified() {
@@ -176,14 +253,84 @@ fn collect_yaml_files(dir: &PathBuf, paths: &mut Vec<PathBuf>) {
     }
 }
 
+fn run_fmt(input: PathBuf, check: bool) {
+    let files = if input.is_dir() {
+        let mut files = Vec::new();
+        let config = input.join("nwl.yaml");
+        if config.is_file() {
+            files.push(config);
+        }
+        for dir in ["pages", "actions", "components"] {
+            let mut found = Vec::new();
+            collect_yaml_files(&input.join(dir), &mut found);
+            found.sort();
+            files.extend(found);
+        }
+        files
+    } else if input.is_file() {
+        vec![input.clone()]
+    } else {
+        eprintln!("Error: File not found: {}", input.display());
+        std::process::exit(1);
+    };
+    if files.is_empty() {
+        eprintln!("No NWL files found in {}", input.display());
+        std::process::exit(1);
+    }
+
+    let mut changed = 0;
+    let mut failed = false;
+    for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error: {}: {}", file.display(), e);
+                failed = true;
+                continue;
+            }
+        };
+        let formatted = match format_source(&source, FileKind::of(Some(file)), Some(file)) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}", e);
+                failed = true;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        changed += 1;
+        if check {
+            println!("{}", file.display());
+        } else if let Err(e) = fs::write(file, formatted) {
+            eprintln!("Error: {}: {}", file.display(), e);
+            failed = true;
+        }
+    }
+
+    if check {
+        if changed > 0 {
+            eprintln!(
+                "{} file(s) not formatted. Run `nwl fmt` to format them.",
+                changed
+            );
+        }
+    } else {
+        println!("Formatted {} of {} file(s).", changed, files.len());
+    }
+    if failed || check && changed > 0 {
+        std::process::exit(1);
+    }
+}
+
 fn run_compile(output: Option<PathBuf>, file: PathBuf) {
     if !file.exists() {
         eprintln!("Error: File not found: {}", file.display());
         std::process::exit(1);
     }
 
-    let content = fs::read_to_string(&file).expect("Failed to read file");
-    match nwl_compiler::compile(&content) {
+    match nwl_compiler::compile_file(file) {
         Ok(output_code) => {
             if let Some(output_path) = output {
                 fs::write(&output_path, &output_code).expect("Failed to write output");
@@ -193,12 +340,30 @@ fn run_compile(output: Option<PathBuf>, file: PathBuf) {
             }
         }
         Err(e) => {
-            eprintln!("Compilation error: {}", e);
+            eprintln!("{}", e);
+            eprintln!("Compilation failed.");
             std::process::exit(1);
         }
     }
 }
 
+fn run_schema(kind: Option<SchemaKind>, out: Option<PathBuf>) {
+    let Some(out) = out else {
+        let kind = kind.expect("clap requires a kind without --out");
+        println!("{}", kind.schema());
+        return;
+    };
+    let kinds = match kind {
+        Some(kind) => vec![kind],
+        None => SchemaKind::value_variants().to_vec(),
+    };
+    for kind in kinds {
+        let path = out.join(format!("{}.schema.json", kind.name()));
+        write_file(&path, &format!("{}\n", kind.schema()));
+        println!("Wrote {}", path.display());
+    }
+}
+
 fn run_new_project(name: String, location: Option<PathBuf>, template: String) {
     // Determine project path
     let project_path = match location {
@@ -253,8 +418,8 @@ fn run_new_project(name: String, location: Option<PathBuf>, template: String) {
 }
 
 fn fetch_and_extract_template(
-    url: &str,
-    project_path: &PathBuf,
+    _url: &str,
+    project_path: &Path,
     template: &str,
     project_name: &str,
 ) -> Result<(), String> {
@@ -296,8 +461,8 @@ fn fetch_and_extract_template(
 }
 
 fn copy_template(
-    template_path: &PathBuf,
-    project_path: &PathBuf,
+    template_path: &Path,
+    project_path: &Path,
     project_name: &str,
 ) -> Result<(), String> {
     println!("Using local template: {}", template_path.display());
@@ -332,11 +497,7 @@ fn copy_template(
     Ok(())
 }
 
-fn copy_directory_recursive(
-    from: &PathBuf,
-    to: &PathBuf,
-    project_name: &str,
-) -> Result<(), String> {
+fn copy_directory_recursive(from: &Path, to: &Path, project_name: &str) -> Result<(), String> {
     if let Ok(entries) = fs::read_dir(from) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -362,7 +523,7 @@ fn copy_directory_recursive(
     Ok(())
 }
 
-fn print_next_steps(project_path: &PathBuf) {
+fn print_next_steps(project_path: &Path) {
     println!("\nNext steps:");
     println!("  cd {}", project_path.display());
     println!("  npm install");
@@ -392,7 +553,7 @@ fn run_dev_server(input: PathBuf, port: String, host: String, _no_open: bool, wa
 
     // Build first
     println!("\nBuilding project...");
-    run_build(input.clone());
+    run_build(input.clone(), &BuildOptions::default());
 
     if watch {
         println!("\nStarting Vite dev server with watch mode...");
@@ -403,12 +564,12 @@ fn run_dev_server(input: PathBuf, port: String, host: String, _no_open: bool, wa
         // Start watch build in background thread
         let watch_input = input.clone();
         std::thread::spawn(move || {
-            run_watch_build(watch_input);
+            run_watch_build(watch_input, &BuildOptions::default());
         });
 
         // Start Vite dev server
         let status = std::process::Command::new("npx")
-            .args(&["vite", "--port", &port, "--host", &host])
+            .args(["vite", "--port", &port, "--host", &host])
             .stdout(std::process::Stdio::inherit())
             .stderr(std::process::Stdio::inherit())
             .status();
@@ -432,7 +593,7 @@ fn run_dev_server(input: PathBuf, port: String, host: String, _no_open: bool, wa
         std::env::set_current_dir(&input).ok();
 
         let status = std::process::Command::new("npx")
-            .args(&["vite", "--port", &port, "--host", &host])
+            .args(["vite", "--port", &port, "--host", &host])
             .stdout(std::process::Stdio::inherit())
             .stderr(std::process::Stdio::inherit())
             .status();
@@ -0,0 +1,194 @@
+//! Actions declared in a project's `actions/*.yaml` files. They compile to
+//! functions exported from `src/actions.ts`, which pages call by name from
+//! their event handlers.
+
+use crate::expression::calls;
+use crate::types::{self, StateType, TypeSyntaxError};
+use nwl_shared::{ActionDefinition, Element, Handler, Span, Step};
+
+/// A declared parameter of an action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionInput {
+    pub name: String,
+    pub ty: StateType,
+}
+
+impl ActionInput {
+    /// Parses an `inputs:` entry, `name` or `name: type`. Error offsets are
+    /// relative to the whole entry.
+    pub fn parse(source: &str) -> Result<ActionInput, TypeSyntaxError> {
+        let (name, ty) = match source.split_once(':') {
+            Some((name, ty)) => (name, Some(ty)),
+            None => (source, None),
+        };
+        let trimmed = name.trim();
+        if !types::is_identifier(trimmed) {
+            let leading = name.chars().count() - name.trim_start().chars().count();
+            return Err(TypeSyntaxError {
+                message: "expected an input name".to_string(),
+                offset: leading,
+                length: trimmed.chars().count().max(1),
+            });
+        }
+
+        let ty = match ty {
+            Some(ty) => StateType::parse(ty).map_err(|error| TypeSyntaxError {
+                offset: error.offset + name.chars().count() + 1,
+                ..error
+            })?,
+            None => StateType::Unknown,
+        };
+        Ok(ActionInput {
+            name: trimmed.to_string(),
+            ty,
+        })
+    }
+}
+
+/// A validated action, ready to be called from pages and generated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    pub name: String,
+    pub inputs: Vec<ActionInput>,
+    pub handler: String,
+    /// Where the action's name is declared, for notes pointing back at it.
+    pub span: Option<Span>,
+}
+
+impl Action {
+    pub fn new(definition: &ActionDefinition, inputs: Vec<ActionInput>) -> Self {
+        Action {
+            name: definition.name.clone(),
+            inputs,
+            handler: definition.handler.clone(),
+            span: None,
+        }
+    }
+
+    /// The action as it is called, e.g. `save(draft, count)`.
+    pub fn signature(&self) -> String {
+        let inputs: Vec<&str> = self.inputs.iter().map(|i| i.name.as_str()).collect();
+        format!("{}({})", self.name, inputs.join(", "))
+    }
+}
+
+/// Every action of a project, in declaration order.
+#[derive(Debug, Clone, Default)]
+pub struct Actions {
+    actions: Vec<Action>,
+}
+
+impl Actions {
+    pub fn add(&mut self, action: Action) {
+        self.actions.push(action);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Action> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Action> {
+        self.actions.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Names of the actions `elements` call from their event handlers, in
+    /// declaration order.
+    pub fn used_by(&self, elements: &[Element]) -> Vec<&str> {
+        let mut called = Vec::new();
+        collect_calls(elements, &mut called);
+        self.actions
+            .iter()
+            .map(|action| action.name.as_str())
+            .filter(|name| called.contains(name))
+            .collect()
+    }
+}
+
+fn collect_calls<'a>(elements: &'a [Element], called: &mut Vec<&'a str>) {
+    for element in elements {
+        for (_, handler) in element.handlers() {
+            match handler {
+                Handler::Script(script) => {
+                    called.extend(calls(script).into_iter().map(|call| call.name))
+                }
+                Handler::Steps(steps) => {
+                    called.extend(steps.iter().filter_map(|step| match step {
+                        Step::Call { call, .. } => Some(call.as_str()),
+                        _ => None,
+                    }))
+                }
+            }
+        }
+        match element {
+            Element::Conditional(conditional) => {
+                collect_calls(std::slice::from_ref(&conditional.element), called);
+                collect_calls(&conditional.otherwise, called);
+            }
+            Element::List(list) => {
+                collect_calls(&list.children, called);
+                collect_calls(&list.empty, called);
+            }
+            _ => collect_calls(element.children(), called),
+        }
+        for (_, children) in element.slots() {
+            collect_calls(children, called);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::Page;
+
+    #[test]
+    fn test_parse_inputs() {
+        let input = ActionInput::parse("count: number").unwrap();
+        assert_eq!(input.name, "count");
+        assert_eq!(input.ty, StateType::Number);
+        assert_eq!(
+            ActionInput::parse(" draft ").unwrap().ty,
+            StateType::Unknown
+        );
+
+        let error = ActionInput::parse("tags: strin[]").unwrap_err();
+        assert_eq!((error.offset, error.length), (6, 5));
+        let error = ActionInput::parse("2fast: number").unwrap_err();
+        assert_eq!(error.message, "expected an input name");
+    }
+
+    #[test]
+    fn test_used_by() {
+        let mut actions = Actions::default();
+        for name in ["save", "load", "reset"] {
+            actions.add(Action::new(
+                &ActionDefinition {
+                    name: name.to_string(),
+                    inputs: vec![],
+                    handler: String::new(),
+                },
+                vec![],
+            ));
+        }
+        let page: Page = serde_yaml::from_str(
+            r#"
+page:
+  name: Editor
+  children:
+    - form:
+        onSubmit: "reset(); save()"
+        children:
+          - button: {content: Undo, onClick: "reset()", if: dirty}
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            actions.used_by(&page.page_data.children),
+            vec!["save", "reset"]
+        );
+    }
+}
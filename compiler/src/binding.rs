@@ -0,0 +1,356 @@
+use crate::codegen::jsx::{Expr, Statement};
+use crate::escape::js_string;
+use crate::types::StateType;
+use nwl_shared::Element;
+
+/// The kind of value a bindable element reads from and writes back to its
+/// state. Validation uses it to reject incompatible state types, codegen to
+/// convert DOM values into the state's type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Control {
+    /// `input`: text, or a number when bound to a numeric state.
+    Input,
+    /// Text-only fields such as `textarea`, `time-input` or `color-picker`.
+    Text,
+    /// `date-input`, which holds a `YYYY-MM-DD` string.
+    Date,
+    /// `slider`, `counter`, `pagination` and `progress`.
+    Number,
+    /// `checkbox`, `toggle` and `modal`.
+    Boolean,
+    /// `select`, `radio-group` and `tabs`, with the values of their options.
+    Choice(Vec<String>),
+    /// `file-upload`, holding every selected file when `multiple` is set.
+    Files { multiple: bool },
+    /// `chip-input`, which keeps a list of strings.
+    List,
+}
+
+/// How values move between a bound element and a state of a given type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conversion {
+    Text,
+    /// Text stored as `null` while the field is empty.
+    NullableText,
+    Number,
+    /// A number stored as `null` while the field is empty.
+    NullableNumber,
+    Boolean,
+    Files,
+    File,
+    /// A list of strings, written from comma-separated text.
+    List,
+}
+
+/// Why a state can't be bound to an element.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindError {
+    /// The state's type can't hold the element's value at all.
+    Incompatible,
+    /// An option value is not a member of the state's union type.
+    UnknownOption(String),
+    /// A numeric state is bound to a choice whose option isn't a number.
+    NonNumericOption(String),
+}
+
+impl Control {
+    pub fn of(element: &Element) -> Option<Control> {
+        let control = match element {
+            Element::Input(_) => Control::Input,
+            Element::Textarea(_)
+            | Element::TimeInput(_)
+            | Element::DateTimeInput(_)
+            | Element::ColorPicker(_)
+            | Element::SearchInput(_)
+            | Element::Url(_)
+            | Element::Email(_) => Control::Text,
+            Element::DateInput(_) => Control::Date,
+            Element::Slider(_)
+            | Element::Counter(_)
+            | Element::Pagination(_)
+            | Element::Progress(_) => Control::Number,
+            Element::Checkbox(_) | Element::Toggle(_) | Element::Modal(_) => Control::Boolean,
+            Element::Select(select) => {
+                Control::Choice(select.options.iter().map(|o| o.value.clone()).collect())
+            }
+            Element::RadioGroup(radio) => {
+                Control::Choice(radio.options.iter().map(|o| o.value.clone()).collect())
+            }
+            Element::Tabs(tabs) => {
+                Control::Choice(tabs.options.iter().map(|t| t.value.clone()).collect())
+            }
+            Element::FileUpload(file) => Control::Files {
+                multiple: file.multiple == Some(true),
+            },
+            Element::ChipInput(_) => Control::List,
+            _ => return None,
+        };
+        Some(control)
+    }
+
+    /// Describes the state types this control accepts.
+    pub fn expected(&self) -> &'static str {
+        match self {
+            Control::Input => "a `string` or `number` state",
+            Control::Text => "a `string` state",
+            Control::Date => "a `string` or `string | null` state",
+            Control::Number => "a `number` state",
+            Control::Boolean => "a `boolean` state",
+            Control::Choice(_) => "a `string`, `number` or string union state",
+            Control::Files { multiple: true } => "a `File[]` state",
+            Control::Files { multiple: false } => "a `File | null` state",
+            Control::List => "a `string[]` state",
+        }
+    }
+
+    /// Resolves how a state of type `ty` is bound to this control. States
+    /// without a known type fall back to the control's natural value.
+    pub fn conversion(&self, ty: &StateType) -> Result<Conversion, BindError> {
+        let (inner, nullable) = match ty {
+            StateType::Nullable(inner) => (inner.as_ref(), true),
+            _ => (ty, false),
+        };
+        if *inner == StateType::Unknown {
+            return Ok(self.natural());
+        }
+
+        let text = if nullable {
+            Conversion::NullableText
+        } else {
+            Conversion::Text
+        };
+        let number = if nullable {
+            Conversion::NullableNumber
+        } else {
+            Conversion::Number
+        };
+
+        match (self, inner) {
+            (Control::Input | Control::Text | Control::Date, StateType::String) => Ok(text),
+            (Control::Input, StateType::Number) => Ok(number),
+            (Control::Number, StateType::Number) if !nullable => Ok(Conversion::Number),
+            (Control::Boolean, StateType::Boolean) if !nullable => Ok(Conversion::Boolean),
+            (Control::Choice(_), StateType::String) => Ok(text),
+            (Control::Choice(values), StateType::Union(members)) => {
+                match values.iter().find(|value| !members.contains(value)) {
+                    Some(value) => Err(BindError::UnknownOption(value.clone())),
+                    None => Ok(text),
+                }
+            }
+            (Control::Choice(values), StateType::Number) => {
+                match values.iter().find(|value| value.parse::<f64>().is_err()) {
+                    Some(value) => Err(BindError::NonNumericOption(value.clone())),
+                    None => Ok(number),
+                }
+            }
+            (Control::Files { multiple: true }, StateType::Array(item))
+                if !nullable && matches!(**item, StateType::File | StateType::Unknown) =>
+            {
+                Ok(Conversion::Files)
+            }
+            (Control::Files { multiple: false }, StateType::File) if nullable => {
+                Ok(Conversion::File)
+            }
+            (Control::List, StateType::Array(item))
+                if !nullable && matches!(**item, StateType::String | StateType::Unknown) =>
+            {
+                Ok(Conversion::List)
+            }
+            _ => Err(BindError::Incompatible),
+        }
+    }
+
+    /// The conversion used for states without a known type.
+    pub fn natural(&self) -> Conversion {
+        match self {
+            Control::Input | Control::Text | Control::Date | Control::Choice(_) => Conversion::Text,
+            Control::Number => Conversion::Number,
+            Control::Boolean => Conversion::Boolean,
+            Control::Files { multiple: true } => Conversion::Files,
+            Control::Files { multiple: false } => Conversion::File,
+            Control::List => Conversion::List,
+        }
+    }
+}
+
+/// A resolved binding between an element and a state variable, rendered as
+/// the expressions that read and write it.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub state: String,
+    pub setter: String,
+    pub conversion: Conversion,
+    /// The state's type, when the text written to it must be cast to it:
+    /// a union of strings, which `e.target.value` isn't.
+    pub cast: Option<String>,
+}
+
+impl Binding {
+    /// The expression an input's `value` is read from. Nullable states read
+    /// as an empty field while they are `null`.
+    pub fn read(&self) -> String {
+        match self.conversion {
+            Conversion::NullableText | Conversion::NullableNumber => {
+                format!("{} ?? \"\"", self.state)
+            }
+            _ => self.state.clone(),
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self.conversion,
+            Conversion::Number | Conversion::NullableNumber
+        )
+    }
+
+    /// The `onChange` handler storing the element's new value, followed by
+    /// the statements of the element's own `onChange` handler.
+    pub fn on_change(&self, handler: Vec<Statement>) -> Expr {
+        let value = match self.conversion {
+            Conversion::Text => match &self.cast {
+                Some(ty) => format!("e.target.value as {}", ty),
+                None => "e.target.value".to_string(),
+            },
+            Conversion::NullableText => match &self.cast {
+                Some(ty) => format!("(e.target.value || null) as {}", ty),
+                None => "e.target.value || null".to_string(),
+            },
+            Conversion::Number => "Number(e.target.value)".to_string(),
+            Conversion::NullableNumber => {
+                "e.target.value === \"\" ? null : Number(e.target.value)".to_string()
+            }
+            Conversion::Boolean => "e.target.checked".to_string(),
+            Conversion::Files => "Array.from(e.target.files ?? [])".to_string(),
+            Conversion::File => "e.target.files?.[0] ?? null".to_string(),
+            Conversion::List => {
+                "e.target.value.split(\",\").map((s) => s.trim()).filter(Boolean)".to_string()
+            }
+        };
+        Self::handler(format!("{}({})", self.setter, value), handler)
+    }
+
+    /// Whether the state currently holds `option`.
+    pub fn matches(&self, option: &str) -> String {
+        format!("{} === {}", self.state, self.literal(option))
+    }
+
+    /// The `onChange` handler selecting `option` of a radio-style choice.
+    pub fn on_select(&self, option: &str, handler: Vec<Statement>) -> Expr {
+        Self::handler(
+            format!("{}({})", self.setter, self.literal(option)),
+            handler,
+        )
+    }
+
+    /// An option value as a literal of the state's type.
+    fn literal(&self, option: &str) -> String {
+        match option.parse::<f64>() {
+            Ok(number) if self.is_numeric() && number.is_finite() => option.trim().to_string(),
+            _ => js_string(option),
+        }
+    }
+
+    fn handler(update: String, handler: Vec<Statement>) -> Expr {
+        if !handler.is_empty() {
+            let mut statements = vec![Statement::from(Expr::Raw(update))];
+            statements.extend(handler);
+            Expr::block(&["e"], statements)
+        } else if update.contains("e.target") {
+            Expr::arrow(&["e"], update)
+        } else {
+            Expr::arrow(&[], update)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ty(source: &str) -> StateType {
+        StateType::parse(source).unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            Control::Input.conversion(&ty("number")),
+            Ok(Conversion::Number)
+        );
+        assert_eq!(
+            Control::Date.conversion(&ty("string | null")),
+            Ok(Conversion::NullableText)
+        );
+        assert_eq!(
+            Control::Number.conversion(&ty("string")),
+            Err(BindError::Incompatible)
+        );
+        assert_eq!(
+            Control::Files { multiple: true }.conversion(&ty("File[]")),
+            Ok(Conversion::Files)
+        );
+        assert_eq!(
+            Control::Files { multiple: false }.conversion(&ty("File[]")),
+            Err(BindError::Incompatible)
+        );
+        assert_eq!(
+            Control::List.conversion(&StateType::Unknown),
+            Ok(Conversion::List)
+        );
+
+        let sizes = Control::Choice(vec!["sm".to_string(), "xl".to_string()]);
+        assert_eq!(
+            sizes.conversion(&ty("'sm' | 'lg'")),
+            Err(BindError::UnknownOption("xl".to_string()))
+        );
+        assert_eq!(
+            sizes.conversion(&ty("number")),
+            Err(BindError::NonNumericOption("sm".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_binding_props() {
+        let binding = Binding {
+            state: "rating".to_string(),
+            setter: "setRating".to_string(),
+            conversion: Conversion::NullableNumber,
+            cast: None,
+        };
+        assert_eq!(binding.read(), "rating ?? \"\"");
+        assert_eq!(binding.matches("3"), "rating === 3");
+        assert_eq!(
+            binding.on_change(Vec::new()),
+            Expr::arrow(
+                &["e"],
+                "setRating(e.target.value === \"\" ? null : Number(e.target.value))"
+            )
+        );
+        assert_eq!(
+            binding.on_select("3", vec![Statement::from(Expr::raw("track()"))]),
+            Expr::block(
+                &["e"],
+                vec![
+                    Statement::from(Expr::raw("setRating(3)")),
+                    Statement::from(Expr::raw("track()")),
+                ]
+            )
+        );
+
+        // `e.target.value` is any string, so it is cast to a union state.
+        let binding = Binding {
+            state: "size".to_string(),
+            setter: "setSize".to_string(),
+            conversion: Conversion::NullableText,
+            cast: Some(ty("'sm' | 'lg' | null").to_ts()),
+        };
+        assert_eq!(
+            binding.on_change(Vec::new()),
+            Expr::arrow(
+                &["e"],
+                "setSize((e.target.value || null) as \"sm\" | \"lg\" | null)"
+            )
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::actions::Actions;
+
+/// Generates `src/actions.ts`, exporting one function per action.
+pub fn generate_actions(actions: &Actions) -> String {
+    let mut output = String::from("// Generated from actions/*.yaml. Do not edit.\n");
+
+    for action in actions.iter() {
+        let parameters: Vec<String> = action
+            .inputs
+            .iter()
+            .map(|input| format!("{}: {}", input.name, input.ty.to_ts()))
+            .collect();
+        let keyword = if action.handler.contains("await") {
+            "async function"
+        } else {
+            "function"
+        };
+
+        output.push_str(&format!(
+            "\nexport {} {}({}) {{\n",
+            keyword,
+            action.name,
+            parameters.join(", ")
+        ));
+        for line in action.handler.trim().lines() {
+            if line.trim().is_empty() {
+                output.push('\n');
+            } else {
+                output.push_str(&format!("  {}\n", line.trim_end()));
+            }
+        }
+        output.push_str("}\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{Action, ActionInput};
+    use nwl_shared::ActionDefinition;
+
+    #[test]
+    fn test_generate_actions() {
+        let mut actions = Actions::default();
+        let definition = ActionDefinition {
+            name: "saveDraft".to_string(),
+            inputs: vec!["title: string".to_string(), "tags: string[]".to_string()],
+            handler: "const body = JSON.stringify({ title, tags });\nawait fetch(\"/api/drafts\", { method: \"POST\", body });\n".to_string(),
+        };
+        let inputs = definition
+            .inputs
+            .iter()
+            .map(|input| ActionInput::parse(input).unwrap())
+            .collect();
+        actions.add(Action::new(&definition, inputs));
+        actions.add(Action::new(
+            &ActionDefinition {
+                name: "log".to_string(),
+                inputs: vec!["message".to_string()],
+                handler: "console.log(message)".to_string(),
+            },
+            vec![ActionInput::parse("message").unwrap()],
+        ));
+
+        assert_eq!(
+            generate_actions(&actions),
+            r#"// Generated from actions/*.yaml. Do not edit.
+
+export async function saveDraft(title: string, tags: string[]) {
+  const body = JSON.stringify({ title, tags });
+  await fetch("/api/drafts", { method: "POST", body });
+}
+
+export function log(message: any) {
+  console.log(message)
+}
+"#
+        );
+    }
+}
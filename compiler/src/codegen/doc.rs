@@ -0,0 +1,578 @@
+//! A document layout engine in the style of Wadler's "prettier printer".
+//! The printer describes code as text, line breaks and groups, and the
+//! layout below decides which groups fit on a line. It follows the
+//! algorithm Prettier uses, so generated code comes out the way Prettier
+//! would format it.
+
+/// Where a group's line breaks go when it doesn't fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    /// A space when flat.
+    Normal,
+    /// Nothing when flat.
+    Soft,
+    /// Always a line break, which also breaks every enclosing group.
+    Hard,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Doc {
+    Text(String),
+    Concat(Vec<Doc>),
+    Indent(Box<Doc>),
+    /// Printed flat when it fits on the rest of the line and broken
+    /// otherwise. With `alternatives`, each is tried in turn before the
+    /// last one is printed broken.
+    Group {
+        contents: Box<Doc>,
+        broken: bool,
+        alternatives: Vec<Doc>,
+    },
+    /// Alternating contents and separators, where each separator breaks
+    /// only when the contents after it don't fit, like words in a
+    /// paragraph.
+    Fill(Vec<Doc>),
+    /// Chooses between two documents by whether the enclosing group broke.
+    IfBreak {
+        broken: Box<Doc>,
+        flat: Box<Doc>,
+    },
+    Line(Line),
+    /// Contents generated from `origin`, recorded as a [`Mark`] wherever
+    /// its text starts a line. Takes no space of its own.
+    Source {
+        origin: String,
+        contents: Box<Doc>,
+    },
+}
+
+/// A position in the printed text, as a 0-based line and UTF-16 column,
+/// and the origin of the [`Doc::Source`] printed from there on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mark {
+    pub line: usize,
+    pub column: usize,
+    pub origin: String,
+}
+
+pub fn text(text: impl Into<String>) -> Doc {
+    Doc::Text(text.into())
+}
+
+pub fn concat(parts: Vec<Doc>) -> Doc {
+    Doc::Concat(parts)
+}
+
+pub fn indent(doc: Doc) -> Doc {
+    Doc::Indent(Box::new(doc))
+}
+
+pub fn group(doc: Doc) -> Doc {
+    Doc::Group {
+        contents: Box::new(doc),
+        broken: false,
+        alternatives: Vec::new(),
+    }
+}
+
+/// A group that is always printed broken.
+pub fn broken_group(doc: Doc) -> Doc {
+    Doc::Group {
+        contents: Box::new(doc),
+        broken: true,
+        alternatives: Vec::new(),
+    }
+}
+
+/// The first of `states` that fits, or the last one broken.
+pub fn conditional_group(mut states: Vec<Doc>) -> Doc {
+    let first = states.remove(0);
+    Doc::Group {
+        contents: Box::new(first),
+        broken: false,
+        alternatives: states,
+    }
+}
+
+pub fn fill(parts: Vec<Doc>) -> Doc {
+    Doc::Fill(parts)
+}
+
+pub fn if_break(broken: Doc, flat: Doc) -> Doc {
+    Doc::IfBreak {
+        broken: Box::new(broken),
+        flat: Box::new(flat),
+    }
+}
+
+pub fn line() -> Doc {
+    Doc::Line(Line::Normal)
+}
+
+pub fn softline() -> Doc {
+    Doc::Line(Line::Soft)
+}
+
+pub fn hardline() -> Doc {
+    Doc::Line(Line::Hard)
+}
+
+pub fn source(origin: impl Into<String>, doc: Doc) -> Doc {
+    Doc::Source {
+        origin: origin.into(),
+        contents: Box::new(doc),
+    }
+}
+
+pub fn nil() -> Doc {
+    Doc::Concat(Vec::new())
+}
+
+/// `docs` with `separator` between each.
+pub fn join(separator: Doc, docs: Vec<Doc>) -> Doc {
+    let mut parts = Vec::with_capacity(docs.len() * 2);
+    for (i, doc) in docs.into_iter().enumerate() {
+        if i > 0 {
+            parts.push(separator.clone());
+        }
+        parts.push(doc);
+    }
+    Doc::Concat(parts)
+}
+
+/// Whether `doc` is certain to print a line break.
+pub fn will_break(doc: &Doc) -> bool {
+    match doc {
+        Doc::Text(_) => false,
+        Doc::Concat(parts) | Doc::Fill(parts) => parts.iter().any(will_break),
+        Doc::Indent(contents) => will_break(contents),
+        Doc::Group {
+            contents, broken, ..
+        } => *broken || will_break(contents),
+        Doc::IfBreak { broken, flat } => will_break(broken) || will_break(flat),
+        Doc::Line(line) => *line == Line::Hard,
+        Doc::Source { contents, .. } => will_break(contents),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// A document to print, the rest of a fill once its first parts are
+/// printed, or the end of a [`Doc::Source`].
+#[derive(Debug, Clone, Copy)]
+enum Item<'d> {
+    Doc(&'d Doc),
+    Fill(&'d [Doc]),
+    EndSource,
+}
+
+type Command<'d> = (usize, Mode, Item<'d>);
+
+/// Lays `doc` out in lines of at most `width` columns where it can,
+/// indenting by two spaces. Also returns a [`Mark`] where each
+/// [`Doc::Source`] starts and at the start of every line inside one.
+pub fn print_marked(mut doc: Doc, width: usize) -> (String, Vec<Mark>) {
+    propagate_breaks(&mut doc);
+
+    let width = width as isize;
+    let mut out = String::new();
+    let mut position: isize = 0;
+    let mut should_remeasure = false;
+    let mut commands: Vec<Command> = vec![(0, Mode::Break, Item::Doc(&doc))];
+
+    let mut marks = Vec::new();
+    let mut row = 0;
+    let mut column = 0;
+    let mut origins: Vec<&str> = Vec::new();
+    // The origin to mark at the next text, set when a source starts and
+    // after every line break inside one.
+    let mut pending: Option<&str> = None;
+
+    while let Some((level, mode, item)) = commands.pop() {
+        let doc = match item {
+            Item::Doc(doc) => doc,
+            Item::Fill(parts) => {
+                let remaining = width - position;
+                print_fill(parts, level, mode, remaining, &mut commands);
+                continue;
+            }
+            Item::EndSource => {
+                origins.pop();
+                if pending.is_some() {
+                    pending = origins.last().copied();
+                }
+                continue;
+            }
+        };
+        match doc {
+            Doc::Text(text) => {
+                if let Some(origin) = pending.filter(|_| !text.is_empty()) {
+                    marks.push(Mark {
+                        line: row,
+                        column,
+                        origin: origin.to_string(),
+                    });
+                    pending = None;
+                }
+                out.push_str(text);
+                position += text_width(text);
+                column += text.encode_utf16().count();
+            }
+            Doc::Concat(parts) => {
+                commands.extend(
+                    parts
+                        .iter()
+                        .rev()
+                        .map(|part| (level, mode, Item::Doc(part))),
+                );
+            }
+            Doc::Fill(parts) => commands.push((level, mode, Item::Fill(parts))),
+            Doc::Indent(contents) => commands.push((level + 1, mode, Item::Doc(contents))),
+            Doc::Group {
+                contents,
+                broken,
+                alternatives,
+            } => {
+                if mode == Mode::Flat && !should_remeasure {
+                    let mode = if *broken { Mode::Break } else { Mode::Flat };
+                    commands.push((level, mode, Item::Doc(contents)));
+                    continue;
+                }
+                should_remeasure = false;
+                let remaining = width - position;
+                let flat = (level, Mode::Flat, Item::Doc(contents));
+                if !broken && fits(flat, &commands, remaining, false) {
+                    commands.push(flat);
+                } else if let Some(most_expanded) = alternatives.last() {
+                    let fitting = alternatives
+                        .iter()
+                        .map(|state| (level, Mode::Flat, Item::Doc(state)))
+                        .find(|&state| !broken && fits(state, &commands, remaining, false));
+                    commands.push(fitting.unwrap_or((
+                        level,
+                        Mode::Break,
+                        Item::Doc(most_expanded),
+                    )));
+                } else {
+                    commands.push((level, Mode::Break, Item::Doc(contents)));
+                }
+            }
+            Doc::IfBreak { broken, flat } => {
+                let chosen = if mode == Mode::Break { broken } else { flat };
+                commands.push((level, mode, Item::Doc(chosen)));
+            }
+            Doc::Line(line) => {
+                if mode == Mode::Flat {
+                    match line {
+                        Line::Normal => {
+                            out.push(' ');
+                            position += 1;
+                            continue;
+                        }
+                        Line::Soft => continue,
+                        Line::Hard => should_remeasure = true,
+                    }
+                }
+                while out.ends_with([' ', '\t']) {
+                    out.pop();
+                }
+                out.push('\n');
+                out.push_str(&"  ".repeat(level));
+                position = 2 * level as isize;
+                row += 1;
+                column = 2 * level;
+                pending = origins.last().copied();
+            }
+            Doc::Source { origin, contents } => {
+                origins.push(origin);
+                pending = Some(origin);
+                commands.push((level, mode, Item::EndSource));
+                commands.push((level, mode, Item::Doc(contents)));
+            }
+        }
+    }
+
+    (out, marks)
+}
+
+/// Queues the first contents and separator of a fill, breaking the
+/// separator only when the contents after it don't fit, followed by the
+/// rest of the fill.
+fn print_fill<'d>(
+    parts: &'d [Doc],
+    level: usize,
+    mode: Mode,
+    remaining: isize,
+    commands: &mut Vec<Command<'d>>,
+) {
+    let Some(content) = parts.first() else {
+        return;
+    };
+    let content_flat = (level, Mode::Flat, Item::Doc(content));
+    let content_break = (level, Mode::Break, Item::Doc(content));
+    let content_fits = fits(content_flat, &[], remaining, true);
+    let Some(separator) = parts.get(1) else {
+        commands.push(if content_fits {
+            content_flat
+        } else {
+            content_break
+        });
+        return;
+    };
+    let separator_flat = (level, Mode::Flat, Item::Doc(separator));
+    let separator_break = (level, Mode::Break, Item::Doc(separator));
+    if parts.len() == 2 {
+        if content_fits {
+            commands.extend([separator_flat, content_flat]);
+        } else {
+            commands.extend([separator_break, content_break]);
+        }
+        return;
+    }
+
+    commands.push((level, mode, Item::Fill(&parts[2..])));
+    let pair = (level, Mode::Flat, Item::Fill(&parts[..3]));
+    if fits(pair, &[], remaining, true) {
+        commands.extend([separator_flat, content_flat]);
+    } else if content_fits {
+        commands.extend([separator_break, content_flat]);
+    } else {
+        commands.extend([separator_break, content_break]);
+    }
+}
+
+/// Whether `next` fits in `width` columns, followed by the commands still
+/// to print up to their first line break. With `must_be_flat`, groups that
+/// are already broken don't fit.
+fn fits(next: Command, rest: &[Command], mut width: isize, must_be_flat: bool) -> bool {
+    let mut rest = rest.iter().rev();
+    let mut stack: Vec<(Mode, Item)> = vec![(next.1, next.2)];
+    while width >= 0 {
+        let (mode, item) = match stack.pop() {
+            Some(command) => command,
+            None => match rest.next() {
+                Some(&(_, mode, item)) => (mode, item),
+                None => return true,
+            },
+        };
+        let doc = match item {
+            Item::Doc(doc) => doc,
+            Item::Fill(parts) => {
+                stack.extend(parts.iter().rev().map(|part| (mode, Item::Doc(part))));
+                continue;
+            }
+            Item::EndSource => continue,
+        };
+        match doc {
+            Doc::Text(text) => width -= text_width(text),
+            Doc::Concat(parts) | Doc::Fill(parts) => {
+                stack.extend(parts.iter().rev().map(|part| (mode, Item::Doc(part))));
+            }
+            Doc::Indent(contents) => stack.push((mode, Item::Doc(contents))),
+            Doc::Group {
+                contents,
+                broken,
+                alternatives,
+            } => {
+                if must_be_flat && *broken {
+                    return false;
+                }
+                let mode = if *broken { Mode::Break } else { mode };
+                let contents = match alternatives.last() {
+                    Some(most_expanded) if mode == Mode::Break => most_expanded,
+                    _ => contents,
+                };
+                stack.push((mode, Item::Doc(contents)));
+            }
+            Doc::IfBreak { broken, flat } => {
+                let chosen = if mode == Mode::Break { broken } else { flat };
+                stack.push((mode, Item::Doc(chosen)));
+            }
+            Doc::Source { contents, .. } => stack.push((mode, Item::Doc(contents))),
+            Doc::Line(line) => {
+                if mode == Mode::Break || *line == Line::Hard {
+                    return true;
+                }
+                if *line == Line::Normal {
+                    width -= 1;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Marks every group containing a hard line as broken. Groups with
+/// alternatives choose between them instead, so they stop the
+/// propagation. Returns whether `doc` breaks its enclosing group.
+fn propagate_breaks(doc: &mut Doc) -> bool {
+    match doc {
+        Doc::Text(_) => false,
+        Doc::Concat(parts) | Doc::Fill(parts) => {
+            let mut breaks = false;
+            for part in parts.iter_mut() {
+                breaks = propagate_breaks(part) || breaks;
+            }
+            breaks
+        }
+        Doc::Indent(contents) => propagate_breaks(contents),
+        Doc::IfBreak { broken, flat } => {
+            let broken = propagate_breaks(broken);
+            propagate_breaks(flat) || broken
+        }
+        Doc::Line(line) => *line == Line::Hard,
+        Doc::Source { contents, .. } => propagate_breaks(contents),
+        Doc::Group {
+            contents,
+            broken,
+            alternatives,
+        } => {
+            let mut breaks = propagate_breaks(contents);
+            for alternative in alternatives.iter_mut() {
+                breaks = propagate_breaks(alternative) || breaks;
+            }
+            if breaks && alternatives.is_empty() {
+                *broken = true;
+            }
+            *broken
+        }
+    }
+}
+
+/// The columns `text` takes up: two for wide East Asian characters and
+/// emoji, none for combining marks.
+fn text_width(text: &str) -> isize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+            0x1100..=0x115F
+            | 0x2E80..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(doc: Doc, width: usize) -> String {
+        print_marked(doc, width).0
+    }
+
+    fn call(arguments: &[&str]) -> Doc {
+        group(concat(vec![
+            text("call("),
+            indent(concat(vec![
+                softline(),
+                join(
+                    concat(vec![text(","), line()]),
+                    arguments.iter().map(|a| text(*a)).collect(),
+                ),
+            ])),
+            if_break(text(","), nil()),
+            softline(),
+            text(")"),
+        ]))
+    }
+
+    #[test]
+    fn test_groups_break_when_too_long() {
+        assert_eq!(print(call(&["a", "b"]), 80), "call(a, b)");
+        assert_eq!(
+            print(call(&["first", "second"]), 10),
+            "call(\n  first,\n  second,\n)"
+        );
+    }
+
+    #[test]
+    fn test_hard_lines_break_enclosing_groups() {
+        let doc = group(concat(vec![
+            text("{"),
+            indent(concat(vec![line(), text("a"), hardline(), text("b")])),
+            line(),
+            text("}"),
+        ]));
+        assert_eq!(print(doc, 80), "{\n  a\n  b\n}");
+    }
+
+    #[test]
+    fn test_fill_wraps_like_words() {
+        let words = ["one", "two", "three", "four"];
+        let mut parts = Vec::new();
+        for (i, word) in words.iter().enumerate() {
+            if i > 0 {
+                parts.push(line());
+            }
+            parts.push(text(*word));
+        }
+        assert_eq!(print(fill(parts.clone()), 80), "one two three four");
+        assert_eq!(print(fill(parts), 9), "one two\nthree\nfour");
+    }
+
+    #[test]
+    fn test_conditional_group_picks_the_first_fit() {
+        let doc = conditional_group(vec![
+            text("a very long alternative"),
+            concat(vec![text("short"), hardline(), text("tail")]),
+        ]);
+        assert_eq!(print(doc.clone(), 80), "a very long alternative");
+        assert_eq!(print(doc, 10), "short\ntail");
+    }
+
+    #[test]
+    fn test_sources_are_marked_on_every_line() {
+        let doc = concat(vec![
+            text("x = "),
+            source(
+                "outer",
+                concat(vec![
+                    text("<a>"),
+                    indent(concat(vec![
+                        hardline(),
+                        source("inner", text("<b />")),
+                        hardline(),
+                        text("é"),
+                    ])),
+                    hardline(),
+                    text("</a>"),
+                ]),
+            ),
+            text(";"),
+        ]);
+        let (out, marks) = print_marked(doc, 80);
+        assert_eq!(out, "x = <a>\n  <b />\n  é\n</a>;");
+        let marks: Vec<_> = marks
+            .iter()
+            .map(|mark| (mark.line, mark.column, mark.origin.as_str()))
+            .collect();
+        assert_eq!(
+            marks,
+            [
+                (0, 4, "outer"),
+                (1, 2, "inner"),
+                (2, 2, "outer"),
+                (3, 0, "outer")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_width() {
+        assert_eq!(text_width("abc"), 3);
+        assert_eq!(text_width("🔍"), 2);
+        assert_eq!(text_width("e\u{301}"), 1);
+    }
+}
@@ -0,0 +1,375 @@
+//! The syntax tree generated components are built as: a TypeScript module
+//! of imports and statements, with JSX for the markup. Values copied from
+//! pages are held unescaped; the printer escapes them for where they land.
+
+/// A generated `.tsx` file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Module {
+    pub imports: Vec<Import>,
+    pub statements: Vec<Statement>,
+}
+
+/// `import Default, { names } from "source";`, or a bare
+/// `import "source";` when it imports nothing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub default: Option<String>,
+    pub names: Vec<String>,
+    pub source: String,
+}
+
+impl Import {
+    pub fn new(source: &str) -> Self {
+        Import {
+            default: None,
+            names: Vec::new(),
+            source: source.to_string(),
+        }
+    }
+
+    pub fn default(mut self, name: &str) -> Self {
+        self.default = Some(name.to_string());
+        self
+    }
+
+    pub fn names<S: ToString>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        self.names
+            .extend(names.into_iter().map(|name| name.to_string()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Expression(Expr),
+    /// `const pattern = init;`, or `let` when `mutable`.
+    Declare {
+        mutable: bool,
+        pattern: String,
+        init: Expr,
+    },
+    Return(Option<Expr>),
+    If {
+        test: Expr,
+        then: Vec<Statement>,
+    },
+    /// `export default function name() { … }`. With `parameters`, the
+    /// props are destructured as `({ a, b = 1 }: Props)`, typed by `props`.
+    Component {
+        name: String,
+        parameters: Vec<(String, Option<Expr>)>,
+        props: Option<String>,
+        body: Vec<Statement>,
+    },
+    /// `export interface name { field: type; optional?: type; }`
+    Interface {
+        name: String,
+        fields: Vec<Field>,
+    },
+}
+
+/// A field of an interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub optional: bool,
+    pub ty: String,
+}
+
+impl Statement {
+    pub fn constant(pattern: impl Into<String>, init: impl Into<Expr>) -> Self {
+        Statement::Declare {
+            mutable: false,
+            pattern: pattern.into(),
+            init: init.into(),
+        }
+    }
+
+    pub fn variable(pattern: impl Into<String>, init: impl Into<Expr>) -> Self {
+        Statement::Declare {
+            mutable: true,
+            pattern: pattern.into(),
+            init: init.into(),
+        }
+    }
+}
+
+impl From<Expr> for Statement {
+    fn from(expr: Expr) -> Self {
+        Statement::Expression(expr)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// JavaScript printed as written, such as an identifier or an emitted
+    /// NWL expression. It is never broken across lines.
+    Raw(String),
+    Jsx(Box<Node>),
+    Call {
+        callee: Box<Expr>,
+        arguments: Vec<Expr>,
+    },
+    Arrow {
+        parameters: Vec<String>,
+        body: Box<Body>,
+    },
+    Conditional {
+        test: Box<Expr>,
+        consequent: Box<Expr>,
+        alternate: Box<Expr>,
+    },
+    /// `left && right`
+    And(Box<Expr>, Box<Expr>),
+    Array(Vec<Expr>),
+    /// An object literal of keys, already quoted where needed, and values.
+    Object(Vec<(String, Expr)>),
+}
+
+impl Expr {
+    pub fn raw(code: impl Into<String>) -> Self {
+        Expr::Raw(code.into())
+    }
+
+    pub fn null() -> Self {
+        Expr::raw("null")
+    }
+
+    /// See [`Node::set_origin`].
+    fn set_origin(&mut self, origin: &str) {
+        match self {
+            Expr::Jsx(node) => node.set_origin(origin),
+            Expr::Call { arguments, .. } => {
+                for argument in arguments {
+                    argument.set_origin(origin);
+                }
+            }
+            Expr::Arrow { body, .. } => {
+                if let Body::Expression(body) = body.as_mut() {
+                    body.set_origin(origin);
+                }
+            }
+            Expr::Conditional {
+                consequent,
+                alternate,
+                ..
+            } => {
+                consequent.set_origin(origin);
+                alternate.set_origin(origin);
+            }
+            Expr::And(_, right) => right.set_origin(origin),
+            Expr::Raw(_) | Expr::Array(_) | Expr::Object(_) => {}
+        }
+    }
+
+    pub fn call(callee: impl Into<Expr>, arguments: Vec<Expr>) -> Self {
+        Expr::Call {
+            callee: Box::new(callee.into()),
+            arguments,
+        }
+    }
+
+    /// An arrow function returning `body`.
+    pub fn arrow(parameters: &[&str], body: impl Into<Expr>) -> Self {
+        Expr::Arrow {
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+            body: Box::new(Body::Expression(body.into())),
+        }
+    }
+
+    /// An arrow function running `statements`.
+    pub fn block(parameters: &[&str], statements: Vec<Statement>) -> Self {
+        Expr::Arrow {
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+            body: Box::new(Body::Block(statements)),
+        }
+    }
+
+    pub fn conditional(
+        test: impl Into<Expr>,
+        consequent: impl Into<Expr>,
+        alternate: impl Into<Expr>,
+    ) -> Self {
+        Expr::Conditional {
+            test: Box::new(test.into()),
+            consequent: Box::new(consequent.into()),
+            alternate: Box::new(alternate.into()),
+        }
+    }
+
+    pub fn and(left: impl Into<Expr>, right: impl Into<Expr>) -> Self {
+        Expr::And(Box::new(left.into()), Box::new(right.into()))
+    }
+
+    pub fn is_jsx(&self) -> bool {
+        matches!(self, Expr::Jsx(_))
+    }
+}
+
+impl From<&str> for Expr {
+    fn from(code: &str) -> Self {
+        Expr::raw(code)
+    }
+}
+
+impl From<String> for Expr {
+    fn from(code: String) -> Self {
+        Expr::Raw(code)
+    }
+}
+
+impl From<Node> for Expr {
+    fn from(node: Node) -> Self {
+        match node {
+            Node::Expression(expr) => expr,
+            node => Expr::Jsx(Box::new(node)),
+        }
+    }
+}
+
+impl From<Element> for Expr {
+    fn from(element: Element) -> Self {
+        Expr::from(Node::Element(element))
+    }
+}
+
+/// The body of an arrow function.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Body {
+    Expression(Expr),
+    Block(Vec<Statement>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Fragment(Vec<Node>),
+    /// Literal text. Whitespace is significant only as a separator, as in
+    /// HTML.
+    Text(String),
+    /// `{expression}`
+    Expression(Expr),
+}
+
+impl Node {
+    pub fn text(text: impl Into<String>) -> Self {
+        Node::Text(text.into())
+    }
+
+    pub fn expression(expr: impl Into<Expr>) -> Self {
+        Node::Expression(expr.into())
+    }
+
+    /// Records `origin` on the outermost elements of this node that don't
+    /// have one yet, looking through fragments and expressions.
+    pub fn set_origin(&mut self, origin: &str) {
+        match self {
+            Node::Element(element) => {
+                if element.origin.is_none() {
+                    element.origin = Some(origin.to_string());
+                }
+            }
+            Node::Fragment(children) => {
+                for child in children {
+                    child.set_origin(origin);
+                }
+            }
+            Node::Text(_) => {}
+            Node::Expression(expr) => expr.set_origin(origin),
+        }
+    }
+}
+
+impl From<Element> for Node {
+    fn from(element: Element) -> Self {
+        Node::Element(element)
+    }
+}
+
+/// A JSX element. Elements without children are self-closing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+    /// Path of the page element this was generated from, such as
+    /// `page.children[0]`, for source maps.
+    pub origin: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// A boolean attribute written without a value, such as `disabled`.
+    True,
+    String(String),
+    Expression(Expr),
+}
+
+impl Element {
+    pub fn new(name: &str) -> Self {
+        Element {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            origin: None,
+        }
+    }
+
+    /// Sets `className`, unless `classes` is empty. Classes that
+    /// interpolate `{expression}` make it a template literal.
+    pub fn class(self, classes: &str) -> Self {
+        let classes = classes.split_whitespace().collect::<Vec<_>>().join(" ");
+        if classes.is_empty() {
+            self
+        } else if classes.contains('{') {
+            let value = super::ReactGenerator::string(&classes);
+            self.prop("className", Expr::Raw(value))
+        } else {
+            self.attr("className", classes)
+        }
+    }
+
+    pub fn attr(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.attributes.push(Attribute {
+            name: name.to_string(),
+            value: Value::String(value.into()),
+        });
+        self
+    }
+
+    pub fn prop(mut self, name: &str, value: impl Into<Expr>) -> Self {
+        self.attributes.push(Attribute {
+            name: name.to_string(),
+            value: Value::Expression(value.into()),
+        });
+        self
+    }
+
+    pub fn flag(mut self, name: &str) -> Self {
+        self.attributes.push(Attribute {
+            name: name.to_string(),
+            value: Value::True,
+        });
+        self
+    }
+
+    pub fn child(mut self, child: impl Into<Node>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = Node>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.child(Node::Text(text.into()))
+    }
+}
@@ -1,769 +1,1147 @@
+mod actions;
+mod doc;
+pub(crate) mod jsx;
+mod print;
+
+pub use actions::generate_actions;
+pub use doc::Mark;
+
+use crate::actions::Actions;
+use crate::binding::{Binding, Control, Conversion};
+use crate::components::{self, Component, Components};
+use crate::computed;
+use crate::escape;
+use crate::expression::{self, Segment};
+use crate::modules;
+use crate::routes;
+use crate::types::{self, StateType};
+use crate::verify::{self, InvalidOutput};
+use jsx::{Element, Expr, Field, Import, Module, Node, Statement};
 use nwl_shared::{
-    ButtonElement, CaptchaConfig, CardElement, ContainerElement, Document, FormElement,
-    HeadingElement, ImageElement, InputElement, LayoutElement, LayoutType, ListElement, PageData,
-    SpacerElement, TextElement,
+    ButtonElement, CaptchaConfig, CardElement, ComputedDefinition, ContainerElement, Document,
+    FormElement, Handler, HeadingElement, ImageElement, InputElement, LayoutElement, LayoutType,
+    ListElement, PageData, ParamDefinition, ReactElement, SlotElement, SpacerElement,
+    StateDefinition, Step, TextElement, UseElement,
 };
+use std::collections::HashMap;
 
 #[derive(Debug, thiserror::Error)]
 pub enum CodegenError {
     #[error("Unsupported element type")]
     UnsupportedElement,
+    #[error("Invalid type for state `{0}`: {1}")]
+    InvalidStateType(String, String),
+    #[error("Invalid type for param `{0}`: {1}")]
+    InvalidParamType(String, String),
+    #[error("{0}")]
+    InvalidOutput(#[from] InvalidOutput),
 }
 
-pub struct ReactGenerator;
+/// Generates a React component per page, and one per project component. An
+/// instance carries the types of the state it generates elements for, so
+/// bound elements can convert values to match them.
+///
+/// Components are built as a syntax tree and printed as Prettier would
+/// format them.
+pub struct ReactGenerator {
+    state: HashMap<String, StateType>,
+}
 
 impl ReactGenerator {
-    pub fn generate(document: &Document) -> Result<String, CodegenError> {
-        let mut output = String::new();
+    pub fn generate(
+        document: &Document,
+        actions: &Actions,
+        components: &Components,
+    ) -> Result<Output, CodegenError> {
+        let mut module = Module::default();
+
+        let mut hooks = Vec::new();
+        if document
+            .pages
+            .iter()
+            .any(|p| !p.page_data.state.is_empty() || Self::needs_menu_state(&p.page_data.children))
+        {
+            hooks.push("useState");
+        }
+        if document
+            .pages
+            .iter()
+            .any(|p| !p.page_data.computed.is_empty())
+        {
+            hooks.push("useMemo");
+        }
+        module
+            .imports
+            .push(Import::new("react").default("React").names(hooks));
+        let params = document
+            .pages
+            .iter()
+            .any(|p| !p.page_data.params.is_empty());
+        let children: Vec<&[nwl_shared::Element]> = document
+            .pages
+            .iter()
+            .map(|page| page.page_data.children.as_slice())
+            .collect();
+        let router = Self::router_imports(params, &children);
+        if !router.is_empty() {
+            module
+                .imports
+                .push(Import::new("react-router-dom").names(router));
+        }
+
+        let mut used = Vec::new();
+        for page in &document.pages {
+            for name in components.used_by(&page.page_data.children) {
+                if !used.contains(&name) {
+                    used.push(name);
+                }
+            }
+        }
+        for name in used {
+            let name = Self::to_pascal_case(name);
+            module
+                .imports
+                .push(Import::new(&format!("./components/{}", name)).default(&name));
+        }
+        let children: Vec<&[nwl_shared::Element]> = document
+            .pages
+            .iter()
+            .map(|page| page.page_data.children.as_slice())
+            .collect();
+        module.imports.extend(Self::module_imports(&children, 0));
+
+        let mut used = Vec::new();
+        for page in &document.pages {
+            for name in actions.used_by(&page.page_data.children) {
+                if !used.contains(&name) {
+                    used.push(name);
+                }
+            }
+        }
+        if !used.is_empty() {
+            module.imports.push(Import::new("./actions").names(used));
+        }
 
-        let has_state = document.pages.iter().any(|p| !p.page_data.state.is_empty());
+        for (i, page) in document.pages.iter().enumerate() {
+            module.statements.push(Self::generate_page(
+                &page.page_data,
+                &page_path(document, i),
+            )?);
+        }
 
-        if has_state {
-            output.push_str("import React, { useState } from 'react';\n\n");
-        } else {
-            output.push_str("import React from 'react';\n\n");
+        let (code, marks) = print::print_module_marked(&module);
+        if cfg!(test) {
+            verify::verify_document(document, &code)?;
         }
+        Ok(Output { code, marks })
+    }
 
-        for page in &document.pages {
-            output.push_str(&Self::generate_page(&page.page_data)?);
-            output.push('\n');
+    /// Generates the module of `component`, a React component whose props
+    /// are typed by an interface exported next to it. The module lives in
+    /// `src/components/`, beside the modules of the components it uses.
+    pub fn generate_component(
+        component: &Component,
+        actions: &Actions,
+        components: &Components,
+    ) -> Result<Output, CodegenError> {
+        let definition = &component.definition;
+        let mut module = Module::default();
+
+        let mut hooks = Vec::new();
+        if !definition.state.is_empty() || Self::needs_menu_state(&definition.children) {
+            hooks.push("useState");
+        }
+        if !definition.computed.is_empty() {
+            hooks.push("useMemo");
+        }
+        module
+            .imports
+            .push(Import::new("react").default("React").names(hooks));
+        let router = Self::router_imports(false, &[&definition.children]);
+        if !router.is_empty() {
+            module
+                .imports
+                .push(Import::new("react-router-dom").names(router));
+        }
+        for name in components.used_by(&definition.children) {
+            let name = Self::to_pascal_case(name);
+            module
+                .imports
+                .push(Import::new(&format!("./{}", name)).default(&name));
+        }
+        module
+            .imports
+            .extend(Self::module_imports(&[&definition.children], 1));
+        let used = actions.used_by(&definition.children);
+        if !used.is_empty() {
+            module.imports.push(Import::new("../actions").names(used));
+        }
+
+        let name = component.module();
+        let mut fields = Vec::new();
+        let mut parameters = Vec::new();
+        for prop in &component.props {
+            let prop_name = Self::to_camel_case(&prop.name);
+            fields.push(Field {
+                name: prop_name.clone(),
+                optional: prop.default.is_some(),
+                ty: prop.ty.to_ts(),
+            });
+            parameters.push((prop_name, prop.default.as_ref().map(Self::literal)));
+        }
+        let mut slots: Vec<String> = component
+            .slots()
+            .iter()
+            .map(|slot| Self::to_camel_case(slot))
+            .collect();
+        if component.has_children() {
+            slots.push("children".to_string());
+        }
+        for slot in slots {
+            fields.push(Field {
+                name: slot.clone(),
+                optional: true,
+                ty: "React.ReactNode".to_string(),
+            });
+            parameters.push((slot, None));
         }
+        let props = if fields.is_empty() {
+            None
+        } else {
+            let props = format!("{}Props", name);
+            module.statements.push(Statement::Interface {
+                name: props.clone(),
+                fields,
+            });
+            Some(props)
+        };
+
+        let generator = Self::for_state(&definition.state)?;
+        let mut body = generator.declarations(
+            &[],
+            &definition.state,
+            &definition.computed,
+            &definition.children,
+        )?;
+        let children = generator.generate_children(&definition.children, "component.children")?;
+        body.push(Statement::Return(Some(Node::Fragment(children).into())));
+        module.statements.push(Statement::Component {
+            name,
+            parameters,
+            props,
+            body,
+        });
 
-        Ok(output)
+        let (code, marks) = print::print_module_marked(&module);
+        if cfg!(test) {
+            verify::verify_component(component, &code)?;
+        }
+        Ok(Output { code, marks })
     }
 
-    fn generate_page(page: &PageData) -> Result<String, CodegenError> {
-        let mut output = String::new();
+    /// Imports of the hand-written components rendered by `react` elements
+    /// among `children`, for a module `depth` directories below `src/`.
+    /// Each module is imported once, in the order of first use.
+    fn module_imports(children: &[&[nwl_shared::Element]], depth: usize) -> Vec<Import> {
+        let mut imports: Vec<Import> = Vec::new();
+        for (react, _) in children.iter().flat_map(|c| modules::imports(c, "")) {
+            let source = modules::relative_to(&react.import, depth);
+            let name = modules::local_name(react);
+            let (default, named) = match &react.export {
+                None => (Some(name), None),
+                Some(export) if *export == name => (None, Some(name)),
+                Some(export) => (None, Some(format!("{} as {}", export, name))),
+            };
+            // A module can only be imported with one default name per
+            // statement.
+            let existing = imports.iter_mut().find(|import| {
+                import.source == source
+                    && (default.is_none() || import.default.is_none() || import.default == default)
+            });
+            let import = match existing {
+                Some(import) => import,
+                None => {
+                    imports.push(Import::new(&source));
+                    imports.last_mut().expect("just pushed")
+                }
+            };
+            if default.is_some() {
+                import.default = default;
+            }
+            if let Some(named) = named.filter(|named| !import.names.contains(named)) {
+                import.names.push(named);
+            }
+        }
+        imports
+    }
 
-        let component_name = Self::to_pascal_case(&page.name);
+    /// The names a module rendering `children` imports from React Router:
+    /// `useParams` when it reads route `params`, `useNavigate` when its
+    /// handlers navigate within the app, the link components its links
+    /// to the app's paths need, and `Outlet`.
+    fn router_imports(params: bool, children: &[&[nwl_shared::Element]]) -> Vec<&'static str> {
+        let (mut navigate, mut link, mut nav_link, mut outlet) = (false, false, false, false);
+        for children in children {
+            navigate |= Self::navigates(children);
+            components::walk(children, "", &mut |element, _| match element {
+                nwl_shared::Element::Nav(nav) => {
+                    link |= nav.logo.is_some();
+                    for item in &nav.links {
+                        let internal = item.href.as_deref().is_some_and(routes::is_internal);
+                        link |= internal && item.active.is_some();
+                        nav_link |= internal && item.active.is_none();
+                    }
+                }
+                nwl_shared::Element::Menu(menu) => {
+                    link |= menu
+                        .items
+                        .iter()
+                        .any(|item| item.href.as_deref().is_some_and(routes::is_internal));
+                }
+                nwl_shared::Element::Breadcrumb(breadcrumb) => {
+                    link |= breadcrumb
+                        .items
+                        .iter()
+                        .any(|item| item.href.as_deref().is_some_and(routes::is_internal));
+                }
+                nwl_shared::Element::Url(url) => {
+                    link |= !url.is_input() && url.href.as_deref().is_some_and(routes::is_internal);
+                }
+                nwl_shared::Element::Outlet(_) => outlet = true,
+                _ => {}
+            });
+        }
+        [
+            ("useParams", params),
+            ("useNavigate", navigate),
+            ("Link", link),
+            ("NavLink", nav_link),
+            ("Outlet", outlet),
+        ]
+        .into_iter()
+        .filter(|(_, used)| *used)
+        .map(|(name, _)| name)
+        .collect()
+    }
 
-        output.push_str(&format!(
-            "export default function {}() {{\n",
-            component_name
-        ));
+    /// Whether handlers among `children` navigate to paths of the app,
+    /// which they do with React Router's `navigate`.
+    fn navigates(children: &[nwl_shared::Element]) -> bool {
+        let mut found = false;
+        components::walk(children, "", &mut |element, _| {
+            for (_, handler) in element.handlers() {
+                found |= match handler {
+                    Handler::Script(path) => {
+                        matches!(element, nwl_shared::Element::Button(_))
+                            && routes::is_internal(path)
+                    }
+                    Handler::Steps(steps) => steps.iter().any(|step| {
+                        matches!(step, Step::Navigate { navigate } if routes::is_internal(navigate))
+                    }),
+                };
+            }
+        });
+        found
+    }
 
-        // Check if any menu has hamburger enabled
-        let needs_menu_state = page.children.iter().any(|child| {
+    /// Whether a hamburger menu among `children` keeps its open/closed flag
+    /// in internal state.
+    fn needs_menu_state(children: &[nwl_shared::Element]) -> bool {
+        children.iter().any(|child| {
+            let child = match child {
+                nwl_shared::Element::Conditional(conditional) => conditional.element.as_ref(),
+                _ => child,
+            };
             if let nwl_shared::Element::Menu(menu) = child {
                 menu.hamburger == Some(true)
             } else {
                 false
             }
-        });
+        })
+    }
 
-        let total_states = if needs_menu_state {
-            page.state.len() + 1
+    /// Generates the component for `page`, whose `page:` mapping lives at
+    /// `path` in the source.
+    fn generate_page(page: &PageData, path: &str) -> Result<Statement, CodegenError> {
+        let generator = Self::for_state(&page.state)?;
+        let mut body =
+            generator.declarations(&page.params, &page.state, &page.computed, &page.children)?;
+
+        let children =
+            generator.generate_children(&page.children, &format!("{}.children", path))?;
+        let children = if page.layout.is_some() {
+            vec![Element::new("div")
+                .class(&Self::format_style(&page.style))
+                .children(children)
+                .into()]
         } else {
-            page.state.len()
+            children
         };
+        body.push(Statement::Return(Some(Node::Fragment(children).into())));
+
+        Ok(Statement::Component {
+            name: Self::to_pascal_case(&page.name),
+            parameters: Vec::new(),
+            props: None,
+            body,
+        })
+    }
 
-        if !page.state.is_empty() || needs_menu_state {
-            output.push_str("  const ");
-            let mut first = true;
-            let mut state_index = 0;
-
-            // First, add menuOpen state if needed
-            if needs_menu_state {
-                output.push_str("[menuOpen, setMenuOpen] = useState(false)");
-                first = false;
-            }
+    /// The hooks a page or component starts with: the hamburger menu flag
+    /// `children` need, route `params`, `state` and then `computed` values,
+    /// ordered so that each is declared after the values it reads.
+    fn declarations(
+        &self,
+        params: &[ParamDefinition],
+        state: &[StateDefinition],
+        computed: &[ComputedDefinition],
+        children: &[nwl_shared::Element],
+    ) -> Result<Vec<Statement>, CodegenError> {
+        let mut body = Vec::new();
+
+        if Self::navigates(children) {
+            body.push(Statement::constant(
+                "navigate",
+                Expr::call("useNavigate", Vec::new()),
+            ));
+        }
+        if Self::needs_menu_state(children) {
+            body.push(Statement::constant(
+                "[menuOpen, setMenuOpen]",
+                Expr::call("useState<boolean>", vec![Expr::raw("false")]),
+            ));
+        }
 
-            // Then add the rest of the states
-            for state in &page.state {
-                if !first {
-                    output.push_str(", ");
-                }
-                let camel_name = Self::to_camel_case(&state.name);
-                let setter_name = format!("set{}", Self::to_pascal_case(&state.name));
-                let initial = match &state.initial {
-                    Some(serde_yaml::Value::Number(n)) => n.to_string(),
-                    Some(serde_yaml::Value::String(s)) => format!("\"{}\"", s),
-                    Some(serde_yaml::Value::Bool(b)) => b.to_string(),
-                    Some(serde_yaml::Value::Null) => "null".to_string(),
-                    Some(serde_yaml::Value::Sequence(arr)) => {
-                        let items: Vec<String> = arr
-                            .iter()
-                            .map(|v| match v {
-                                serde_yaml::Value::String(s) => format!("\"{}\"", s),
-                                serde_yaml::Value::Number(n) => n.to_string(),
-                                serde_yaml::Value::Bool(b) => b.to_string(),
-                                _ => "null".to_string(),
-                            })
-                            .collect();
-                        format!("[{}]", items.join(", "))
-                    }
-                    _ => "null".to_string(),
-                };
-                output.push_str(&format!(
-                    "[{}, {}] = useState({})",
-                    camel_name, setter_name, initial
-                ));
-                first = false;
-                state_index += 1;
-            }
-            output.push_str(";\n");
+        if !params.is_empty() {
+            body.push(Statement::constant(
+                "routeParams",
+                Expr::call("useParams", Vec::new()),
+            ));
+        }
+        for param in params {
+            body.push(Statement::constant(
+                Self::to_camel_case(&param.name),
+                Self::param_value(param)?,
+            ));
         }
 
-        output.push_str("  return (\n");
-        output.push_str("    <>\n");
+        for state in state {
+            let ty = &self.state[&state.name];
+            let initial = types::initial_value(state, ty);
+            body.push(Statement::constant(
+                format!(
+                    "[{}, set{}]",
+                    Self::to_camel_case(&state.name),
+                    Self::to_pascal_case(&state.name)
+                ),
+                Expr::call(
+                    format!("useState<{}>", ty.to_ts()),
+                    vec![Self::literal(&initial)],
+                ),
+            ));
+        }
 
-        if let Some(layout) = &page.layout {
-            let page_style = Self::format_style(&page.style);
-            let page_style_attr = if !page_style.is_empty() {
-                format!(" className=\"{}\"", page_style)
+        let (order, _) = computed::order(computed);
+        for value in order.into_iter().map(|i| &computed[i]) {
+            let type_param = match &value.value_type {
+                Some(declared) => {
+                    let ty = StateType::parse(declared).map_err(|e| {
+                        CodegenError::InvalidStateType(value.name.clone(), e.message)
+                    })?;
+                    format!("<{}>", ty.to_ts())
+                }
+                None => String::new(),
+            };
+            let deps = computed::dependencies(value)
+                .into_iter()
+                .map(|dep| Expr::Raw(Self::to_camel_case(dep)))
+                .collect();
+            // An arrow body starting with `{` would parse as a block.
+            let body_expr = Self::expression(&value.value);
+            let body_expr = if body_expr.starts_with('{') {
+                format!("({})", body_expr)
             } else {
-                String::new()
+                body_expr
             };
-            let layout_classes = Self::format_layout_props(layout);
-            output.push_str(&format!("      <div{}>\n", page_style_attr));
-            for child in &page.children {
-                output.push_str(&format!("        {}\n", Self::generate_element(child, 2)?));
-            }
-            output.push_str("      </div>\n");
+            body.push(Statement::constant(
+                Self::to_camel_case(&value.name),
+                Expr::call(
+                    format!("useMemo{}", type_param),
+                    vec![Expr::arrow(&[], body_expr), Expr::Array(deps)],
+                ),
+            ));
+        }
+        Ok(body)
+    }
+
+    /// The value of route parameter `param`, read from the URL text in
+    /// `routeParams` and converted to its type. The text is empty when a
+    /// param without a default is missing.
+    fn param_value(param: &ParamDefinition) -> Result<Expr, CodegenError> {
+        let ty = types::param_type(param)
+            .map_err(|e| CodegenError::InvalidParamType(param.name.clone(), e.message))?;
+        let text = if types::is_identifier(&param.name) {
+            format!("routeParams.{}", param.name)
         } else {
-            for child in &page.children {
-                output.push_str(&format!("      {}\n", Self::generate_element(child, 1)?));
-            }
+            format!("routeParams[{}]", escape::js_string(&param.name))
+        };
+        let default = match &param.default {
+            Some(serde_yaml::Value::String(default)) => default.clone(),
+            Some(serde_yaml::Value::Bool(default)) => default.to_string(),
+            Some(serde_yaml::Value::Number(default)) => default.to_string(),
+            _ => String::new(),
+        };
+        let text = format!("{} ?? {}", text, escape::js_string(&default));
+        let value = match ty {
+            StateType::Number => format!("Number({})", text),
+            StateType::Boolean => format!("({}) === \"true\"", text),
+            StateType::Union(_) => format!("({}) as {}", text, ty.to_ts()),
+            _ => text,
+        };
+        Ok(Expr::Raw(value))
+    }
+
+    /// A generator for elements that can bind to `state`, knowing its types.
+    fn for_state(definitions: &[StateDefinition]) -> Result<Self, CodegenError> {
+        let mut state = HashMap::new();
+        for definition in definitions {
+            let ty = types::state_type(definition)
+                .map_err(|e| CodegenError::InvalidStateType(definition.name.clone(), e.message))?;
+            state.insert(definition.name.clone(), ty);
         }
+        Ok(Self { state })
+    }
 
-        output.push_str("    </>\n");
-        output.push_str("  );\n");
-        output.push_str("}\n");
+    /// Finds the innermost element of `children`, found at `path` and
+    /// bound to `state`, whose generated code does not parse on its own, as
+    /// a path like `page.children[2].children[0]`. Returns `None` when every
+    /// element parses, so the problem lies in the component around them.
+    pub(crate) fn broken_element(
+        state: &[StateDefinition],
+        children: &[nwl_shared::Element],
+        path: &str,
+    ) -> Option<String> {
+        let generator = Self::for_state(state).ok()?;
+        generator.broken_child(children, path)
+    }
 
-        Ok(output)
+    fn broken_child(&self, children: &[nwl_shared::Element], path: &str) -> Option<String> {
+        children
+            .iter()
+            .enumerate()
+            .find_map(|(i, child)| self.broken_in(child, &format!("{}[{}]", path, i)))
     }
 
+    fn broken_in(&self, element: &nwl_shared::Element, path: &str) -> Option<String> {
+        let node = self.generate_element(element, path).ok()?;
+        let code = print::print_module(&Module {
+            imports: Vec::new(),
+            statements: vec![Statement::from(Expr::from(Node::Fragment(vec![node])))],
+        });
+        if verify::parse_tsx(&code).is_ok() {
+            return None;
+        }
+        let nested = match element {
+            nwl_shared::Element::Conditional(conditional) => self
+                .broken_in(&conditional.element, path)
+                .filter(|inner| inner != path)
+                .or_else(|| self.broken_child(&conditional.otherwise, &format!("{}.else", path))),
+            _ => self
+                .broken_child(element.children(), &format!("{}.children", path))
+                .or_else(|| {
+                    element.slots().into_iter().find_map(|(key, children)| {
+                        self.broken_child(children, &format!("{}.{}", path, key))
+                    })
+                }),
+        };
+        Some(nested.unwrap_or_else(|| path.to_string()))
+    }
+
+    /// Generates `element`, found at `path` in the page source. The
+    /// resulting JSX remembers the path, so source maps can point back at it.
     fn generate_element(
+        &self,
         element: &nwl_shared::Element,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        match element {
-            nwl_shared::Element::Heading(heading) => Self::generate_heading(heading, indent),
-            nwl_shared::Element::Text(text) => Self::generate_text(text, indent),
-            nwl_shared::Element::Button(button) => Self::generate_button(button, indent),
-            nwl_shared::Element::Card(card) => Self::generate_card(card, indent),
-            nwl_shared::Element::List(list) => Self::generate_list(list, indent),
-            nwl_shared::Element::Layout(layout) => Self::generate_layout(layout, indent),
-            nwl_shared::Element::Input(input) => Self::generate_input(input, indent),
-            nwl_shared::Element::Image(image) => Self::generate_image(image, indent),
-            nwl_shared::Element::Spacer(spacer) => Self::generate_spacer(spacer, indent),
-            nwl_shared::Element::Container(container) => {
-                Self::generate_container(container, indent)
-            }
-            nwl_shared::Element::Checkbox(checkbox) => Self::generate_checkbox(checkbox, indent),
-            nwl_shared::Element::Slider(slider) => Self::generate_slider(slider, indent),
-            nwl_shared::Element::Select(select) => Self::generate_select(select, indent),
-            nwl_shared::Element::RadioGroup(radio) => Self::generate_radio_group(radio, indent),
-            nwl_shared::Element::Textarea(textarea) => Self::generate_textarea(textarea, indent),
-            nwl_shared::Element::Form(form) => Self::generate_form(form, indent),
-            nwl_shared::Element::DateInput(date) => Self::generate_date_input(date, indent),
-            nwl_shared::Element::TimeInput(time) => Self::generate_time_input(time, indent),
-            nwl_shared::Element::DateTimeInput(datetime) => {
-                Self::generate_datetime_input(datetime, indent)
-            }
-            nwl_shared::Element::ColorPicker(color) => Self::generate_color_picker(color, indent),
-            nwl_shared::Element::FileUpload(file) => Self::generate_file_upload(file, indent),
-            nwl_shared::Element::Progress(progress) => Self::generate_progress(progress, indent),
-            nwl_shared::Element::Toggle(toggle) => Self::generate_toggle(toggle, indent),
-            nwl_shared::Element::Tabs(tabs) => Self::generate_tabs(tabs, indent),
-            nwl_shared::Element::Accordion(accordion) => {
-                Self::generate_accordion(accordion, indent)
-            }
-            nwl_shared::Element::Modal(modal) => Self::generate_modal(modal, indent),
-            nwl_shared::Element::Badge(badge) => Self::generate_badge(badge, indent),
-            nwl_shared::Element::Tag(tag) => Self::generate_tag(tag, indent),
-            nwl_shared::Element::Alert(alert) => Self::generate_alert(alert, indent),
-            nwl_shared::Element::Spinner(spinner) => Self::generate_spinner(spinner, indent),
-            nwl_shared::Element::Counter(counter) => Self::generate_counter(counter, indent),
-            nwl_shared::Element::SearchInput(search) => Self::generate_search_input(search, indent),
-            nwl_shared::Element::CopyButton(copy) => Self::generate_copy_button(copy, indent),
-            nwl_shared::Element::Pagination(pagination) => {
-                Self::generate_pagination(pagination, indent)
+        path: &str,
+    ) -> Result<Node, CodegenError> {
+        let mut node = match element {
+            nwl_shared::Element::Conditional(conditional) => {
+                self.generate_conditional(conditional, path)
             }
-            nwl_shared::Element::Breadcrumb(breadcrumb) => {
-                Self::generate_breadcrumb(breadcrumb, indent)
-            }
-            nwl_shared::Element::Avatar(avatar) => Self::generate_avatar(avatar, indent),
-            nwl_shared::Element::ChipInput(chip) => Self::generate_chip_input(chip, indent),
-            nwl_shared::Element::Nav(nav) => Self::generate_nav(nav, indent),
-            nwl_shared::Element::Menu(menu) => Self::generate_menu(menu, indent),
-            nwl_shared::Element::Url(url) => Self::generate_url(url, indent),
-            nwl_shared::Element::Email(email) => Self::generate_email(email, indent),
-        }
+            nwl_shared::Element::Heading(heading) => self.generate_heading(heading),
+            nwl_shared::Element::Text(text) => self.generate_text(text),
+            nwl_shared::Element::Button(button) => self.generate_button(button),
+            nwl_shared::Element::Card(card) => self.generate_card(card, path),
+            nwl_shared::Element::List(list) => self.generate_list(list, path),
+            nwl_shared::Element::Layout(layout) => self.generate_layout(layout, path),
+            nwl_shared::Element::Input(input) => self.generate_input(input),
+            nwl_shared::Element::Image(image) => self.generate_image(image),
+            nwl_shared::Element::Spacer(spacer) => self.generate_spacer(spacer),
+            nwl_shared::Element::Container(container) => self.generate_container(container, path),
+            nwl_shared::Element::Checkbox(checkbox) => self.generate_checkbox(checkbox),
+            nwl_shared::Element::Slider(slider) => self.generate_slider(slider),
+            nwl_shared::Element::Select(select) => self.generate_select(select),
+            nwl_shared::Element::RadioGroup(radio) => self.generate_radio_group(radio),
+            nwl_shared::Element::Textarea(textarea) => self.generate_textarea(textarea),
+            nwl_shared::Element::Form(form) => self.generate_form(form, path),
+            nwl_shared::Element::DateInput(date) => self.generate_date_input(date),
+            nwl_shared::Element::TimeInput(time) => self.generate_time_input(time),
+            nwl_shared::Element::DateTimeInput(datetime) => self.generate_datetime_input(datetime),
+            nwl_shared::Element::ColorPicker(color) => self.generate_color_picker(color),
+            nwl_shared::Element::FileUpload(file) => self.generate_file_upload(file),
+            nwl_shared::Element::Progress(progress) => self.generate_progress(progress),
+            nwl_shared::Element::Toggle(toggle) => self.generate_toggle(toggle),
+            nwl_shared::Element::Tabs(tabs) => self.generate_tabs(tabs),
+            nwl_shared::Element::Accordion(accordion) => self.generate_accordion(accordion),
+            nwl_shared::Element::Modal(modal) => self.generate_modal(modal, path),
+            nwl_shared::Element::Badge(badge) => self.generate_badge(badge),
+            nwl_shared::Element::Tag(tag) => self.generate_tag(tag),
+            nwl_shared::Element::Alert(alert) => self.generate_alert(alert),
+            nwl_shared::Element::Spinner(spinner) => self.generate_spinner(spinner),
+            nwl_shared::Element::Counter(counter) => self.generate_counter(counter),
+            nwl_shared::Element::SearchInput(search) => self.generate_search_input(search),
+            nwl_shared::Element::CopyButton(copy) => self.generate_copy_button(copy),
+            nwl_shared::Element::Pagination(pagination) => self.generate_pagination(pagination),
+            nwl_shared::Element::Breadcrumb(breadcrumb) => self.generate_breadcrumb(breadcrumb),
+            nwl_shared::Element::Avatar(avatar) => self.generate_avatar(avatar),
+            nwl_shared::Element::ChipInput(chip) => self.generate_chip_input(chip),
+            nwl_shared::Element::Nav(nav) => self.generate_nav(nav),
+            nwl_shared::Element::Menu(menu) => self.generate_menu(menu),
+            nwl_shared::Element::Url(url) => self.generate_url(url),
+            nwl_shared::Element::Email(email) => self.generate_email(email),
+            nwl_shared::Element::Use(instance) => self.generate_use(instance, path),
+            nwl_shared::Element::Slot(slot) => self.generate_slot(slot),
+            nwl_shared::Element::React(react) => self.generate_react_element(react, path),
+            nwl_shared::Element::Outlet(_) => Ok(Element::new("Outlet").into()),
+        }?;
+        node.set_origin(path);
+        Ok(node)
     }
 
-    fn generate_heading(heading: &HeadingElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&heading.style);
-
-        if class_name.is_empty() {
-            Ok(format!("{}<h1>{}</h1>", indent_str, heading.content))
-        } else {
-            Ok(format!(
-                "{}<h1 className=\"{}\">{}</h1>",
-                indent_str, class_name, heading.content
-            ))
-        }
+    /// Generates the elements of the sequence at `path`, such as
+    /// `page.children` or a list's `empty`.
+    fn generate_children(
+        &self,
+        children: &[nwl_shared::Element],
+        path: &str,
+    ) -> Result<Vec<Node>, CodegenError> {
+        children
+            .iter()
+            .enumerate()
+            .map(|(i, child)| self.generate_element(child, &format!("{}[{}]", path, i)))
+            .collect()
     }
 
-    fn generate_text(text: &TextElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&text.style);
-
-        if class_name.is_empty() {
-            Ok(format!("{}<p>{}</p>", indent_str, text.content))
+    /// Wraps an element written with `if:` in a JSX conditional. The
+    /// ternary avoids rendering `0` when the condition is a number.
+    fn generate_conditional(
+        &self,
+        conditional: &nwl_shared::ConditionalElement,
+        path: &str,
+    ) -> Result<Node, CodegenError> {
+        let element = self.generate_element(&conditional.element, path)?;
+        let otherwise = if conditional.otherwise.is_empty() {
+            Expr::null()
         } else {
-            Ok(format!(
-                "{}<p className=\"{}\">{}</p>",
-                indent_str, class_name, text.content
-            ))
-        }
+            Node::Fragment(
+                self.generate_children(&conditional.otherwise, &format!("{}.else", path))?,
+            )
+            .into()
+        };
+        Ok(Node::expression(Expr::conditional(
+            Self::expression(&conditional.condition),
+            element,
+            otherwise,
+        )))
     }
 
-    fn generate_button(button: &ButtonElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&button.style);
-
-        let mut props = Vec::new();
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
+    /// Places a project component. A prop written as a single
+    /// `{expression}` passes the expression's value; slots are passed as
+    /// props holding the elements given to them.
+    fn generate_use(&self, instance: &UseElement, path: &str) -> Result<Node, CodegenError> {
+        let mut props: Vec<(&String, &serde_yaml::Value)> = instance.props.iter().collect();
+        props.sort_by_key(|(name, _)| *name);
+        let mut element = Self::pass_props(
+            Element::new(&Self::to_pascal_case(&instance.component)),
+            props
+                .into_iter()
+                .map(|(name, value)| (Self::to_camel_case(name), value)),
+        );
+
+        let mut slots: Vec<(&String, &Vec<nwl_shared::Element>)> = instance.slots.iter().collect();
+        slots.sort_by_key(|(name, _)| *name);
+        for (name, children) in slots {
+            let mut nodes =
+                self.generate_children(children, &format!("{}.slots.{}", path, name))?;
+            let node = if nodes.len() == 1 {
+                nodes.remove(0)
+            } else {
+                Node::Fragment(nodes)
+            };
+            element = element.prop(&Self::to_camel_case(name), Expr::from(node));
         }
 
-        if let Some(onClick) = &button.onClick {
-            if onClick.starts_with('/') {
-                props.push(format!(
-                    "onClick={{() => window.location.href = \"{}\"}}",
-                    onClick
-                ));
+        let children = self.generate_children(&instance.children, &format!("{}.children", path))?;
+        Ok(element.children(children).into())
+    }
+
+    /// Renders a hand-written component, imported by the module. Event
+    /// handlers take the value the component calls them with when they
+    /// read it.
+    fn generate_react_element(
+        &self,
+        react: &ReactElement,
+        path: &str,
+    ) -> Result<Node, CodegenError> {
+        let mut props: Vec<(&String, &serde_yaml::Value)> = react.props.iter().collect();
+        props.sort_by_key(|(name, _)| *name);
+        let mut element = Self::pass_props(
+            Element::new(&modules::local_name(react)),
+            props.into_iter().map(|(name, value)| (name.clone(), value)),
+        );
+
+        let mut events: Vec<(&String, &Handler)> = react.events.iter().collect();
+        events.sort_by_key(|(name, _)| *name);
+        for (name, handler) in events {
+            let parameters: &[&str] = if Self::reads_value(handler) {
+                &["value"]
             } else {
-                props.push(format!("onClick={{() => {}}}", onClick));
-            }
+                &[]
+            };
+            element = element.prop(name, self.handler(parameters, handler));
         }
 
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!(
-            "{}<button{}>{}</button>",
-            indent_str, props_str, button.content
-        ))
+        let children = self.generate_children(&react.children, &format!("{}.children", path))?;
+        Ok(element.children(children).into())
     }
 
-    fn generate_card(card: &CardElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&card.style);
+    /// Sets `props` on a component: a string that is a single
+    /// `{expression}` passes the expression's value, other strings are
+    /// text, and other values literals.
+    fn pass_props<'v>(
+        mut element: Element,
+        props: impl IntoIterator<Item = (String, &'v serde_yaml::Value)>,
+    ) -> Element {
+        for (name, value) in props {
+            element = match value {
+                serde_yaml::Value::String(text) => match expression::parse_text(text).as_deref() {
+                    Ok([Segment::Expression(expr)]) => element.prop(
+                        &name,
+                        Expr::Raw(expression::emit(expr, &Self::to_camel_case)),
+                    ),
+                    Ok(segments) if segments.iter().all(|s| matches!(s, Segment::Text(_))) => {
+                        element.attr(&name, text.as_str())
+                    }
+                    _ => element.prop(&name, Expr::Raw(Self::string(text))),
+                },
+                value => element.prop(&name, Self::literal(value)),
+            };
+        }
+        element
+    }
 
-        let mut output = String::new();
-        let props = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
+    /// Whether `handler` reads `value`, the first argument a hand-written
+    /// component calls it with.
+    fn reads_value(handler: &Handler) -> bool {
+        let reads = |source: &str| {
+            expression::reads(source)
+                .iter()
+                .any(|(_, name)| *name == "value")
         };
-
-        output.push_str(&format!("{}<div{}>\n", indent_str, props));
-
-        for child in &card.children {
-            output.push_str(&format!(
-                "{}  {}\n",
-                indent_str,
-                Self::generate_element(child, indent + 1)?
-            ));
+        match handler {
+            Handler::Script(script) => expression::references(script)
+                .iter()
+                .any(|(_, name)| *name == "value"),
+            Handler::Steps(steps) => steps.iter().any(|step| match step {
+                Step::Set { to, .. } => reads(to),
+                Step::Append { value, .. } => reads(value),
+                Step::Call { with, .. } => with.iter().any(|argument| reads(argument)),
+                Step::Navigate { .. } | Step::Toggle { .. } => false,
+            }),
         }
+    }
 
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
+    /// Renders what `use:` gave the slot: a named slot's prop, or
+    /// `children`.
+    fn generate_slot(&self, slot: &SlotElement) -> Result<Node, CodegenError> {
+        let name = match &slot.name {
+            Some(name) => Self::to_camel_case(name),
+            None => "children".to_string(),
+        };
+        Ok(Node::expression(name))
     }
 
-    fn generate_list(list: &ListElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_heading(&self, heading: &HeadingElement) -> Result<Node, CodegenError> {
+        Ok(Element::new("h1")
+            .class(&Self::format_style(&heading.style))
+            .children(Self::text(&heading.content))
+            .into())
+    }
 
-        let mut output = String::new();
+    fn generate_text(&self, text: &TextElement) -> Result<Node, CodegenError> {
+        Ok(Element::new("p")
+            .class(&Self::format_style(&text.style))
+            .children(Self::text(&text.content))
+            .into())
+    }
 
-        output.push_str(&format!("{}<div className=\"border rounded", indent_str));
+    fn generate_button(&self, button: &ButtonElement) -> Result<Node, CodegenError> {
+        let mut element = Element::new("button").class(&Self::format_style(&button.style));
 
-        if !list.style.is_empty() {
-            let style_str = list.style.join(" ");
-            output.push_str(&format!(" {}\"", style_str));
-        } else {
-            output.push_str("\"");
+        match &button.onClick {
+            Some(Handler::Script(path)) if path.starts_with('/') => {
+                element = element.prop("onClick", Expr::arrow(&[], Self::navigate(path, true)));
+            }
+            Some(on_click) => {
+                element = element.prop("onClick", self.handler(&[], on_click));
+            }
+            None => {}
         }
 
-        output.push_str(">\n");
+        Ok(element.children(Self::text(&button.content)).into())
+    }
 
-        for (index, item) in list.items.iter().enumerate() {
-            let key = format!("list-item-{}", index);
-            let click_handler = if let Some(onClick) = &item.onClick {
-                format!(" onClick={{() => {}}}", onClick)
+    fn generate_card(&self, card: &CardElement, path: &str) -> Result<Node, CodegenError> {
+        Ok(Element::new("div")
+            .class(&Self::format_style(&card.style))
+            .children(self.generate_children(&card.children, &format!("{}.children", path))?)
+            .into())
+    }
+
+    fn generate_list(&self, list: &ListElement, path: &str) -> Result<Node, CodegenError> {
+        let mut element =
+            Element::new("div").class(&format!("border rounded {}", list.style.join(" ")));
+
+        if let Some(data) = &list.data {
+            let data = if data.contains('.') {
+                Self::expression(data)
             } else {
-                String::new()
+                Self::to_camel_case(data)
             };
+            let name = Self::to_camel_case(&list.item_name());
+            if !list.empty.is_empty() {
+                element = element.child(Node::expression(Expr::and(
+                    format!("{}.length === 0", data),
+                    Node::Fragment(
+                        self.generate_children(&list.empty, &format!("{}.empty", path))?,
+                    ),
+                )));
+            }
 
-            output.push_str(&format!(
-                "{}  <div key=\"{}\" className=\"p-2 border-b last:border-b-0 cursor-pointer hover:bg-gray-50\"{}>\n",
-                indent_str, key, click_handler
-            ));
-            output.push_str(&format!("{}    {}\n", indent_str, item.content));
-            output.push_str(&format!("{}  </div>\n", indent_str));
+            let (params, key) = match &list.key {
+                Some(key) => (vec![name.as_str()], format!("{}.{}", name, key)),
+                None => (vec![name.as_str(), "index"], "index".to_string()),
+            };
+            let item = Element::new("div")
+                .prop("key", key)
+                .class("p-2 border-b last:border-b-0")
+                .children(self.generate_children(&list.children, &format!("{}.children", path))?);
+            element = element.child(Node::expression(Expr::call(
+                format!("{}.map", data),
+                vec![Expr::arrow(&params, item)],
+            )));
         }
 
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
-    }
-
-    fn generate_layout(layout: &LayoutElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let mut output = String::new();
-
-        output.push_str(&format!(
-            "{}<div{}>\n",
-            indent_str,
-            Self::format_layout_props(&layout.layout)
-        ));
-
-        for child in &layout.children {
-            output.push_str(&format!(
-                "{}  {}\n",
-                indent_str,
-                Self::generate_element(child, indent + 1)?
-            ));
+        for (index, item) in list.items.iter().enumerate() {
+            let mut row = Element::new("div")
+                .attr("key", format!("list-item-{}", index))
+                .class("p-2 border-b last:border-b-0 cursor-pointer hover:bg-gray-50");
+            if let Some(on_click) = &item.onClick {
+                row = row.prop("onClick", self.handler(&[], on_click));
+            }
+            element = element.child(row.children(Self::text(&item.content)));
         }
 
-        output.push_str(&format!("{}</div>\n", indent_str));
+        Ok(element.into())
+    }
 
-        Ok(output)
+    fn generate_layout(&self, layout: &LayoutElement, path: &str) -> Result<Node, CodegenError> {
+        Ok(Element::new("div")
+            .class(&Self::layout_class(&layout.layout))
+            .children(self.generate_children(&layout.children, &format!("{}.children", path))?)
+            .into())
     }
 
-    fn generate_input(input: &InputElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&input.style);
+    fn generate_input(&self, input: &InputElement) -> Result<Node, CodegenError> {
+        let binding = input
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Input));
 
-        let mut props = Vec::new();
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
+        let mut element = Element::new("input");
+        if binding.as_ref().is_some_and(Binding::is_numeric) {
+            element = element.attr("type", "number");
         }
+        element = element.class(&Self::format_style(&input.style));
         if let Some(placeholder) = &input.placeholder {
-            props.push(format!("placeholder=\"{}\"", placeholder));
-        }
-
-        if let Some(bind) = &input.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
-        } else if let Some(value) = &input.value {
-            props.push(format!("value=\"{}\"", value));
+            element = element.attr("placeholder", placeholder);
         }
 
-        if let Some(onChange) = &input.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
+        if let Some(binding) = binding {
+            element = element.prop("value", binding.read()).prop(
+                "onChange",
+                binding.on_change(self.statements(input.onChange.as_ref())),
+            );
         } else {
-            format!(" {}", props.join(" "))
-        };
+            if let Some(value) = &input.value {
+                element = element.attr("value", value);
+            }
+            if let Some(on_change) = &input.onChange {
+                element = element.prop("onChange", self.handler(&["e"], on_change));
+            }
+        }
 
-        Ok(format!("{}<input{} />", indent_str, props_str))
+        Ok(element.into())
     }
 
-    fn generate_image(image: &ImageElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&image.style);
-
-        let mut props = Vec::new();
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    fn generate_image(&self, image: &ImageElement) -> Result<Node, CodegenError> {
+        let mut element = Element::new("img").class(&Self::format_style(&image.style));
         if let Some(src) = &image.src {
-            props.push(format!("src=\"{}\"", src));
+            element = element.attr("src", src);
         }
         if let Some(alt) = &image.alt {
-            props.push(format!("alt=\"{}\"", alt));
+            element = element.attr("alt", alt);
         }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<img{} />", indent_str, props_str))
+        Ok(element.into())
     }
 
-    fn generate_spacer(_spacer: &SpacerElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        Ok(format!("{}<div className=\"h-6\" />", indent_str))
+    fn generate_spacer(&self, _spacer: &SpacerElement) -> Result<Node, CodegenError> {
+        Ok(Element::new("div").class("h-6").into())
     }
 
     fn generate_container(
+        &self,
         container: &ContainerElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&container.style);
-
-        let mut output = String::new();
-        let props = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        output.push_str(&format!("{}<div{}>\n", indent_str, props));
+        path: &str,
+    ) -> Result<Node, CodegenError> {
+        Ok(Element::new("div")
+            .class(&Self::format_style(&container.style))
+            .children(self.generate_children(&container.children, &format!("{}.children", path))?)
+            .into())
+    }
 
-        for child in &container.children {
-            output.push_str(&format!(
-                "{}  {}\n",
-                indent_str,
-                Self::generate_element(child, indent + 1)?
-            ));
+    /// Sets `onChange` on a field bound with `bind`, or to the element's own
+    /// handler when it is unbound.
+    fn bound_change(
+        &self,
+        element: Element,
+        binding: Option<Binding>,
+        checked: bool,
+        on_change: Option<&Handler>,
+    ) -> Element {
+        match (binding, on_change) {
+            (Some(binding), _) => element
+                .prop(if checked { "checked" } else { "value" }, binding.read())
+                .prop("onChange", binding.on_change(self.statements(on_change))),
+            (None, Some(on_change)) => element.prop("onChange", self.handler(&["e"], on_change)),
+            (None, None) => element,
         }
-
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
     }
 
     fn generate_checkbox(
+        &self,
         checkbox: &nwl_shared::CheckboxElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&checkbox.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"checkbox\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
-
-        if let Some(bind) = &checkbox.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("checked={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{() => {}(!{})}}",
-                setter_name, camel_name
-            ));
-        }
-
-        if let Some(onChange) = &checkbox.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
+    ) -> Result<Node, CodegenError> {
+        let element = Element::new("input")
+            .attr("type", "checkbox")
+            .class(&Self::format_style(&checkbox.style));
+        let binding = checkbox
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Boolean));
+        let input = self.bound_change(element, binding, true, checkbox.onChange.as_ref());
 
-        if let Some(label) = &checkbox.label {
-            Ok(format!(
-                "{}<label className=\"flex items-center gap-2\"><input{} />{}</label>",
-                indent_str, props_str, label
-            ))
-        } else {
-            Ok(format!("{}<input{} />", indent_str, props_str))
+        match &checkbox.label {
+            Some(label) => Ok(Element::new("label")
+                .class("flex items-center gap-2")
+                .child(input)
+                .children(Self::text(label))
+                .into()),
+            None => Ok(input.into()),
         }
     }
 
-    fn generate_slider(
-        slider: &nwl_shared::SliderElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&slider.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"range\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    fn generate_slider(&self, slider: &nwl_shared::SliderElement) -> Result<Node, CodegenError> {
+        let mut element = Element::new("input")
+            .attr("type", "range")
+            .class(&Self::format_style(&slider.style));
         if let Some(min) = slider.min {
-            props.push(format!("min=\"{}\"", min));
+            element = element.attr("min", min.to_string());
         }
         if let Some(max) = slider.max {
-            props.push(format!("max=\"{}\"", max));
+            element = element.attr("max", max.to_string());
         }
         if let Some(step) = slider.step {
-            props.push(format!("step=\"{}\"", step));
-        }
-
-        if let Some(bind) = &slider.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(Number(e.target.value))}}",
-                setter_name
-            ));
-        }
-
-        if let Some(onChange) = &slider.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
+            element = element.attr("step", step.to_string());
         }
+        let binding = slider
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Number));
+        let input = self.bound_change(element, binding, false, slider.onChange.as_ref());
 
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        if let Some(label) = &slider.label {
-            Ok(format!(
-                "{}<label className=\"flex flex-col gap-1\"><span>{}</span><input{} /></label>",
-                indent_str, label, props_str
-            ))
-        } else {
-            Ok(format!("{}<input{} />", indent_str, props_str))
+        match &slider.label {
+            Some(label) => Ok(Element::new("label")
+                .class("flex flex-col gap-1")
+                .child(Element::new("span").children(Self::text(label)))
+                .child(input)
+                .into()),
+            None => Ok(input.into()),
         }
     }
 
-    fn generate_select(
-        select: &nwl_shared::SelectElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&select.style);
-
-        let mut props = Vec::new();
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
-
-        if let Some(bind) = &select.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
-        }
-
-        if let Some(onChange) = &select.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
+    fn generate_select(&self, select: &nwl_shared::SelectElement) -> Result<Node, CodegenError> {
+        let element = Element::new("select").class(&Self::format_style(&select.style));
+        let binding = select.bind.as_ref().map(|bind| {
+            let options = select.options.iter().map(|o| o.value.clone()).collect();
+            self.binding(bind, Control::Choice(options))
+        });
+        let mut element = self.bound_change(element, binding, false, select.onChange.as_ref());
 
-        let mut options = String::new();
         if let Some(placeholder) = &select.placeholder {
-            options.push_str(&format!(
-                "{}<option value=\"\" disabled>{}</option>\n",
-                indent_str, placeholder
-            ));
+            element = element.child(
+                Element::new("option")
+                    .attr("value", "")
+                    .flag("disabled")
+                    .text(placeholder),
+            );
         }
         for opt in &select.options {
             let label = opt.label.as_ref().unwrap_or(&opt.value);
-            options.push_str(&format!(
-                "{}<option value=\"{}\">{}</option>\n",
-                indent_str, opt.value, label
-            ));
+            element = element.child(
+                Element::new("option")
+                    .attr("value", &opt.value)
+                    .children(Self::text(label)),
+            );
         }
 
-        Ok(format!(
-            "{}<select{}>\n{}{}</select>",
-            indent_str, props_str, options, indent_str
-        ))
+        Ok(element.into())
     }
 
     fn generate_radio_group(
+        &self,
         radio: &nwl_shared::RadioGroupElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&radio.style);
-
-        let mut output = String::new();
-        let wrapper_class = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        output.push_str(&format!("{}<div{}>\n", indent_str, wrapper_class));
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("div").class(&Self::format_style(&radio.style));
 
         if let Some(label) = &radio.label {
-            output.push_str(&format!(
-                "{}  <p className=\"font-semibold mb-2\">{}</p>\n",
-                indent_str, label
-            ));
+            element = element.child(
+                Element::new("p")
+                    .class("font-semibold mb-2")
+                    .children(Self::text(label)),
+            );
         }
 
+        let binding = radio.bind.as_ref().map(|bind| {
+            let options = radio.options.iter().map(|o| o.value.clone()).collect();
+            self.binding(bind, Control::Choice(options))
+        });
+
         for opt in &radio.options {
             let label = opt.label.as_ref().unwrap_or(&opt.value);
-            let option_class = format!("flex items-center gap-2");
-
-            let mut input_props = Vec::new();
-            input_props.push("type=\"radio\"".to_string());
-            input_props.push(format!("value=\"{}\"", opt.value));
-
-            if let Some(bind) = &radio.bind {
-                let camel_name = Self::to_camel_case(bind);
-                input_props.push(format!("checked={{{} === \"{}\"}}", camel_name, opt.value));
-                let setter_name = format!("set{}", Self::to_pascal_case(bind));
-                input_props.push(format!(
-                    "onChange={{() => {}(\"{}\")}}",
-                    setter_name, opt.value
-                ));
-            }
 
-            if let Some(onChange) = &radio.onChange {
-                input_props.push(format!("onChange={{(e) => {}}}", onChange));
+            let mut input = Element::new("input")
+                .attr("type", "radio")
+                .attr("value", &opt.value);
+            if let Some(binding) = &binding {
+                input = input.prop("checked", binding.matches(&opt.value)).prop(
+                    "onChange",
+                    binding.on_select(&opt.value, self.statements(radio.onChange.as_ref())),
+                );
+            } else if let Some(on_change) = &radio.onChange {
+                input = input.prop("onChange", self.handler(&["e"], on_change));
             }
 
-            let input_str = format!(
-                "<label className=\"{}\"><input {} />{}</label>",
-                option_class,
-                input_props.join(" "),
-                label
+            element = element.child(
+                Element::new("label")
+                    .class("flex items-center gap-2")
+                    .child(input)
+                    .children(Self::text(label)),
             );
-            output.push_str(&format!("{}  {}\n", indent_str, input_str));
         }
 
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
+        Ok(element.into())
     }
 
     fn generate_textarea(
+        &self,
         textarea: &nwl_shared::TextareaElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&textarea.style);
-
-        let mut props = Vec::new();
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("textarea").class(&Self::format_style(&textarea.style));
         if let Some(placeholder) = &textarea.placeholder {
-            props.push(format!("placeholder=\"{}\"", placeholder));
+            element = element.attr("placeholder", placeholder);
         }
         if let Some(rows) = textarea.rows {
-            props.push(format!("rows={{{}}}", rows));
+            element = element.prop("rows", rows.to_string());
         }
-
-        if let Some(bind) = &textarea.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
-        }
-
-        if let Some(onChange) = &textarea.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<textarea{} />", indent_str, props_str))
+        let binding = textarea
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Text));
+        Ok(self
+            .bound_change(element, binding, false, textarea.onChange.as_ref())
+            .into())
     }
 
-    fn generate_form(form: &FormElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&form.style);
-
-        let mut output = String::new();
-        let props = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        let on_submit = Self::generate_form_submit_handler(form);
-
-        output.push_str(&format!("{}<form{} {}>\n", indent_str, props, on_submit));
-
-        for child in &form.children {
-            output.push_str(&format!(
-                "{}  {}\n",
-                indent_str,
-                Self::generate_element(child, indent + 1)?
-            ));
-        }
+    fn generate_form(&self, form: &FormElement, path: &str) -> Result<Node, CodegenError> {
+        let mut element = Element::new("form")
+            .class(&Self::format_style(&form.style))
+            .prop("onSubmit", self.generate_form_submit_handler(form))
+            .children(self.generate_children(&form.children, &format!("{}.children", path))?);
 
         if let Some(captcha) = &form.captcha {
-            output.push_str(&format!(
-                "{}  {}\n",
-                indent_str,
-                Self::generate_captcha(captcha, indent + 1)
-            ));
+            element = element.child(Self::generate_captcha(captcha));
         }
 
-        output.push_str(&format!("{}</form>\n", indent_str));
-
-        Ok(output)
+        Ok(element.into())
     }
 
-    fn generate_captcha(captcha: &CaptchaConfig, indent: usize) -> String {
-        let indent_str = "  ".repeat(indent);
+    fn generate_captcha(captcha: &CaptchaConfig) -> Element {
+        let site_key = captcha.siteKey.as_deref().unwrap_or("");
         match captcha.provider {
-            nwl_shared::CaptchaProvider::Cloudflare => {
-                let site_key = captcha.siteKey.as_deref().unwrap_or("");
-                let theme = captcha.theme.as_deref().unwrap_or("auto");
-                format!(
-                    "{}<div className=\"cf-turnstile\" data-sitekey=\"{}\" data-theme=\"{}\"></div>",
-                    indent_str,
-                    site_key,
-                    theme
-                )
-            }
+            nwl_shared::CaptchaProvider::Cloudflare => Element::new("div")
+                .class("cf-turnstile")
+                .attr("data-sitekey", site_key)
+                .attr("data-theme", captcha.theme.as_deref().unwrap_or("auto")),
             nwl_shared::CaptchaProvider::Recaptcha => {
-                let site_key = captcha.siteKey.as_deref().unwrap_or("");
                 let version = captcha.version.as_deref().unwrap_or("v2");
                 if version == "v3" {
-                    let action = captcha.action.as_deref().unwrap_or("submit");
-                    format!(
-                        "{}<div id=\"recaptcha-container\" data-sitekey=\"{}\" data-action=\"{}\"></div>",
-                        indent_str,
-                        site_key,
-                        action
-                    )
+                    Element::new("div")
+                        .attr("id", "recaptcha-container")
+                        .attr("data-sitekey", site_key)
+                        .attr("data-action", captcha.action.as_deref().unwrap_or("submit"))
                 } else {
-                    format!(
-                        "{}<div className=\"g-recaptcha\" data-sitekey=\"{}\"></div>",
-                        indent_str, site_key
-                    )
+                    Element::new("div")
+                        .class("g-recaptcha")
+                        .attr("data-sitekey", site_key)
                 }
             }
-            nwl_shared::CaptchaProvider::HCaptcha => {
-                let site_key = captcha.siteKey.as_deref().unwrap_or("");
-                let theme = captcha.theme.as_deref().unwrap_or("light");
-                format!(
-                    "{}<div className=\"h-captcha\" data-sitekey=\"{}\" data-theme=\"{}\"></div>",
-                    indent_str, site_key, theme
-                )
-            }
+            nwl_shared::CaptchaProvider::HCaptcha => Element::new("div")
+                .class("h-captcha")
+                .attr("data-sitekey", site_key)
+                .attr("data-theme", captcha.theme.as_deref().unwrap_or("light")),
         }
     }
 
-    fn generate_form_submit_handler(form: &FormElement) -> String {
-        let mut validation_checks = Vec::new();
+    fn generate_form_submit_handler(&self, form: &FormElement) -> Expr {
+        let mut checks = Vec::new();
+        let mut check = |test: String, message: String| {
+            checks.push(Statement::If {
+                test: Expr::Raw(test),
+                then: vec![
+                    Statement::from(Expr::Raw(format!(
+                        "console.error({})",
+                        escape::js_string(&message)
+                    ))),
+                    Statement::from(Expr::raw("_hasError = true")),
+                ],
+            });
+        };
 
         if let Some(validation) = &form.validation {
-            for (field, rules) in validation {
+            let mut fields: Vec<_> = validation.iter().collect();
+            fields.sort_by_key(|(field, _)| *field);
+            for (field, rules) in fields {
                 let camel_field = Self::to_camel_case(field);
                 for rule in rules {
                     if let Some(true) = rule.required {
@@ -771,10 +1149,7 @@ impl ReactGenerator {
                             .message
                             .clone()
                             .unwrap_or_else(|| format!("{} is required", field));
-                        validation_checks.push(format!(
-                            "if (!{}.trim()) {{ console.error('{}'); _hasError = true; }}",
-                            camel_field, msg
-                        ));
+                        check(format!("!{}.trim()", camel_field), msg);
                     }
 
                     if let Some(pattern) = &rule.pattern {
@@ -782,388 +1157,262 @@ impl ReactGenerator {
                             .message
                             .clone()
                             .unwrap_or_else(|| format!("Invalid format for {}", field));
-                        validation_checks.push(format!(
-                            "if (!new RegExp('{}').test({})) {{ console.error('{}'); _hasError = true; }}",
-                            pattern.replace("\\", "\\\\"),
-                            camel_field,
-                            msg
-                        ));
+                        check(
+                            format!(
+                                "!new RegExp({}).test({})",
+                                escape::js_string(pattern),
+                                camel_field
+                            ),
+                            msg,
+                        );
                     }
 
                     if let Some(min_len) = rule.minLength {
                         let msg = rule.message.clone().unwrap_or_else(|| {
                             format!("{} must be at least {} characters", field, min_len)
                         });
-                        validation_checks.push(format!(
-                            "if ({}.length < {}) {{ console.error('{}'); _hasError = true; }}",
-                            camel_field, min_len, msg
-                        ));
+                        check(format!("{}.length < {}", camel_field, min_len), msg);
                     }
 
                     if let Some(max_len) = rule.maxLength {
                         let msg = rule.message.clone().unwrap_or_else(|| {
                             format!("{} must be no more than {} characters", field, max_len)
                         });
-                        validation_checks.push(format!(
-                            "if ({}.length > {}) {{ console.error('{}'); _hasError = true; }}",
-                            camel_field, max_len, msg
-                        ));
+                        check(format!("{}.length > {}", camel_field, max_len), msg);
                     }
                 }
             }
         }
 
-        let mut all_checks = validation_checks;
-
         if form.captcha.is_some() {
-            all_checks.push(
-                "if (!window.captchaToken) { console.error('Please complete the captcha'); _hasError = true; }"
-                    .to_string(),
+            check(
+                "!window.captchaToken".to_string(),
+                "Please complete the captcha".to_string(),
             );
         }
 
-        let validation_code = if all_checks.is_empty() {
-            String::new()
-        } else {
-            let error_handling = form
-                .onValidationError
-                .as_ref()
-                .map(|h| format!("{};", h))
-                .unwrap_or_default();
-            format!(
-                "let _hasError = false; {} if (_hasError) {{ console.error('Validation failed'); {} return; }}",
-                all_checks.join(" "),
-                error_handling
-            )
-        };
-
-        if let Some(handler) = &form.onSubmit {
-            format!(
-                "onSubmit={{(e) => {{ e.preventDefault(); {} {} }}}}",
-                validation_code, handler
-            )
-        } else {
-            format!(
-                "onSubmit={{(e) => {{ e.preventDefault(); {} }}}}",
-                validation_code
-            )
+        let mut body = vec![Statement::from(Expr::raw("e.preventDefault()"))];
+        if !checks.is_empty() {
+            body.push(Statement::variable("_hasError", "false"));
+            body.extend(checks);
+            let mut failed = vec![Statement::from(Expr::raw(
+                "console.error(\"Validation failed\")",
+            ))];
+            failed.extend(self.statements(form.onValidationError.as_ref()));
+            failed.push(Statement::Return(None));
+            body.push(Statement::If {
+                test: Expr::raw("_hasError"),
+                then: failed,
+            });
         }
+        body.extend(self.statements(form.onSubmit.as_ref()));
+
+        Expr::block(&["e"], body)
     }
 
     fn generate_date_input(
+        &self,
         date: &nwl_shared::DateInputElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&date.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"date\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("input")
+            .attr("type", "date")
+            .class(&Self::format_style(&date.style));
         if let Some(min) = &date.min {
-            props.push(format!("min=\"{}\"", min));
+            element = element.attr("min", min);
         }
         if let Some(max) = &date.max {
-            props.push(format!("max=\"{}\"", max));
+            element = element.attr("max", max);
         }
         if let Some(placeholder) = &date.placeholder {
-            props.push(format!("placeholder=\"{}\"", placeholder));
-        }
-
-        if let Some(bind) = &date.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
+            element = element.attr("placeholder", placeholder);
         }
-
-        if let Some(onChange) = &date.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<input{} />", indent_str, props_str))
+        let binding = date
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Date));
+        Ok(self
+            .bound_change(element, binding, false, date.onChange.as_ref())
+            .into())
     }
 
     fn generate_time_input(
+        &self,
         time: &nwl_shared::TimeInputElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&time.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"time\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("input")
+            .attr("type", "time")
+            .class(&Self::format_style(&time.style));
         if let Some(min) = &time.min {
-            props.push(format!("min=\"{}\"", min));
+            element = element.attr("min", min);
         }
         if let Some(max) = &time.max {
-            props.push(format!("max=\"{}\"", max));
+            element = element.attr("max", max);
         }
         if let Some(step) = time.step {
-            props.push(format!("step=\"{}\"", step));
-        }
-
-        if let Some(bind) = &time.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
+            element = element.attr("step", step.to_string());
         }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<input{} />", indent_str, props_str))
+        let binding = time
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Text));
+        Ok(self.bound_change(element, binding, false, None).into())
     }
 
     fn generate_datetime_input(
+        &self,
         datetime: &nwl_shared::DateTimeInputElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&datetime.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"datetime-local\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("input")
+            .attr("type", "datetime-local")
+            .class(&Self::format_style(&datetime.style));
         if let Some(min) = &datetime.min {
-            props.push(format!("min=\"{}\"", min));
+            element = element.attr("min", min);
         }
         if let Some(max) = &datetime.max {
-            props.push(format!("max=\"{}\"", max));
-        }
-
-        if let Some(bind) = &datetime.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
+            element = element.attr("max", max);
         }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<input{} />", indent_str, props_str))
+        let binding = datetime
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Text));
+        Ok(self.bound_change(element, binding, false, None).into())
     }
 
     fn generate_color_picker(
+        &self,
         color: &nwl_shared::ColorPickerElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&color.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"color\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
-
-        if let Some(bind) = &color.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
-        }
-
-        if let Some(onChange) = &color.onChange {
-            props.push(format!("onChange={{(e) => {}}}", onChange));
-        }
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        Ok(format!("{}<input{} />", indent_str, props_str))
+    ) -> Result<Node, CodegenError> {
+        let element = Element::new("input")
+            .attr("type", "color")
+            .class(&Self::format_style(&color.style));
+        let binding = color
+            .bind
+            .as_ref()
+            .map(|bind| self.binding(bind, Control::Text));
+        Ok(self
+            .bound_change(element, binding, false, color.onChange.as_ref())
+            .into())
     }
 
     fn generate_file_upload(
+        &self,
         file: &nwl_shared::FileUploadElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&file.style);
-
-        let mut props = Vec::new();
-        props.push("type=\"file\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("input")
+            .attr("type", "file")
+            .class(&Self::format_style(&file.style));
         if let Some(accept) = &file.accept {
-            props.push(format!("accept=\"{}\"", accept));
+            element = element.attr("accept", accept);
         }
-        if let Some(maxSize) = &file.maxSize {
-            props.push(format!("data-max-size=\"{}\"", maxSize));
+        if let Some(max_size) = &file.maxSize {
+            element = element.attr("data-max-size", max_size);
         }
-        if file.multiple == Some(true) {
-            props.push("multiple".to_string());
+        let multiple = file.multiple == Some(true);
+        if multiple {
+            element = element.flag("multiple");
         }
-
-        let mut output = String::new();
-        output.push_str(&format!("{}<input", indent_str));
-        for prop in &props {
-            output.push_str(&format!(" {}", prop));
+        // File inputs can't be given a value, so only `onChange` is bound.
+        if let Some(bind) = &file.bind {
+            let binding = self.binding(bind, Control::Files { multiple });
+            element = element.prop(
+                "onChange",
+                binding.on_change(self.statements(file.onChange.as_ref())),
+            );
+        } else if let Some(on_change) = &file.onChange {
+            element = element.prop("onChange", self.handler(&["e"], on_change));
         }
-        output.push_str(" />");
-        Ok(output)
+        Ok(element.into())
     }
 
     fn generate_progress(
+        &self,
         progress: &nwl_shared::ProgressElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&progress.style);
-
-        let mut props = Vec::new();
-        props.push("role=\"progressbar\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
-        if let Some(max) = progress.max {
-            props.push(format!("aria-valuemax=\"{}\"", max));
-        }
-
-        let (value_expr, display_value) = if let Some(bind) = &progress.bind {
-            let camel_name = Self::to_camel_case(bind);
-            (camel_name.clone(), camel_name)
+    ) -> Result<Node, CodegenError> {
+        let value = if let Some(bind) = &progress.bind {
+            Self::to_camel_case(bind)
         } else if let Some(value) = &progress.value {
-            (value.clone(), value.clone())
+            Self::expression(value)
         } else {
-            ("0".to_string(), "0".to_string())
+            "0".to_string()
         };
 
-        props.push(format!("aria-valuenow={{{}}}", value_expr));
-        props.push(format!("style={{{{width: `${{{}}}%`}}}}", display_value));
-
-        let props_str = if props.is_empty() {
-            String::new()
-        } else {
-            format!(" {}", props.join(" "))
-        };
-
-        let label = if progress.showLabel == Some(true) {
-            format!("<span className=\"text-sm ml-2\">{display_value}%</span>")
+        let mut bar = Element::new("div")
+            .attr("role", "progressbar")
+            .class(&Self::format_style(&progress.style));
+        if let Some(max) = progress.max {
+            bar = bar.attr("aria-valuemax", max.to_string());
+        }
+        bar = bar.prop("aria-valuenow", value.as_str()).prop(
+            "style",
+            Expr::Object(vec![(
+                "width".to_string(),
+                Expr::Raw(format!("`${{{}}}%`", value)),
+            )]),
+        );
+
+        if progress.showLabel == Some(true) {
+            let label = Element::new("span")
+                .class("text-sm ml-2")
+                .child(Node::expression(value))
+                .text("%");
+            Ok(Node::Fragment(vec![bar.into(), label.into()]))
         } else {
-            String::new()
-        };
-
-        Ok(format!(
-            "{}{}{}",
-            indent_str,
-            format!("<div{} />", props_str),
-            label
-        ))
+            Ok(bar.into())
+        }
     }
 
-    fn generate_toggle(
-        toggle: &nwl_shared::ToggleElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&toggle.style);
-
-        let on_color_default = "green".to_string();
-        let on_color = toggle.onColor.as_ref().unwrap_or(&on_color_default);
-        let off_color_default = "gray".to_string();
-        let off_color = toggle.offColor.as_ref().unwrap_or(&off_color_default);
-
-        let mut props = Vec::new();
-        props.push("type=\"checkbox\"".to_string());
-        props.push(format!("className=\"sr-only peer\""));
+    fn generate_toggle(&self, toggle: &nwl_shared::ToggleElement) -> Result<Node, CodegenError> {
+        let on_color = toggle.onColor.as_deref().unwrap_or("green");
+        let off_color = toggle.offColor.as_deref().unwrap_or("gray");
 
+        let mut input = Element::new("input")
+            .attr("type", "checkbox")
+            .class("sr-only peer");
         if let Some(bind) = &toggle.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("checked={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{() => {}(!{})}}",
-                setter_name, camel_name
-            ));
+            let binding = self.binding(bind, Control::Boolean);
+            input = input.prop("checked", binding.read()).prop(
+                "onChange",
+                binding.on_change(self.statements(toggle.onChange.as_ref())),
+            );
         }
 
-        let props_str = props.join(" ");
-
-        let label_html = if let Some(label) = &toggle.label {
-            format!(
-                "<span className=\"mr-3 text-sm font-medium text-gray-900\">{}</span>",
-                label
-            )
-        } else {
-            String::new()
-        };
-
-        Ok(format!(
-            "{}<label className=\"inline-flex relative items-center cursor-pointer\">{}<input {} /><div className=\"relative w-11 h-6 bg-{}-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-{}-500\"></div></label>",
-            indent_str, label_html, props_str, off_color, on_color
-        ))
+        let mut label = Element::new("label").class(&format!(
+            "inline-flex relative items-center cursor-pointer {}",
+            Self::format_style(&toggle.style)
+        ));
+        if let Some(text) = &toggle.label {
+            label = label.child(
+                Element::new("span")
+                    .class("mr-3 text-sm font-medium text-gray-900")
+                    .children(Self::text(text)),
+            );
+        }
+        Ok(label
+            .child(input)
+            .child(Element::new("div").class(&format!(
+                "relative w-11 h-6 bg-{}-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 rounded-full peer peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-{}-500",
+                off_color, on_color
+            )))
+            .into())
     }
 
-    fn generate_tabs(
-        tabs: &nwl_shared::TabsElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&tabs.style);
-
-        let mut output = String::new();
-        let wrapper_class = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        output.push_str(&format!("{}<div{}>\n", indent_str, wrapper_class));
+    fn generate_tabs(&self, tabs: &nwl_shared::TabsElement) -> Result<Node, CodegenError> {
+        let mut element = Element::new("div").class(&Self::format_style(&tabs.style));
 
         if let Some(label) = &tabs.label {
-            output.push_str(&format!(
-                "{}  <p className=\"font-semibold mb-2\">{}</p>\n",
-                indent_str, label
-            ));
+            element = element.child(
+                Element::new("p")
+                    .class("font-semibold mb-2")
+                    .children(Self::text(label)),
+            );
         }
 
-        output.push_str(&format!(
-            "{}  <div className=\"border-b border-gray-200\">\n",
-            indent_str
-        ));
-        output.push_str(&format!(
-            "{}    <nav className=\"-mb-px flex space-x-8\">\n",
-            indent_str
-        ));
+        let binding = tabs.bind.as_ref().map(|bind| {
+            let options = tabs.options.iter().map(|t| t.value.clone()).collect();
+            self.binding(bind, Control::Choice(options))
+        });
 
+        let mut nav = Element::new("nav").class("-mb-px flex space-x-8");
         for (index, tab) in tabs.options.iter().enumerate() {
             let tab_label = tab.label.as_ref().unwrap_or(&tab.value);
             let active_class = if index == 0 {
@@ -1172,143 +1421,116 @@ impl ReactGenerator {
                 "border-transparent text-gray-500 hover:text-gray-700 hover:border-gray-300"
             };
 
-            let mut input_props = Vec::new();
-            input_props.push("type=\"radio\"".to_string());
-            input_props.push("name=\"tabs\"".to_string());
-            input_props.push(format!("value=\"{}\"", tab.value));
-            input_props.push(format!("className=\"sr-only peer\""));
-
-            if let Some(bind) = &tabs.bind {
-                let camel_name = Self::to_camel_case(bind);
-                input_props.push(format!("checked={{{} === \"{}\"}}", camel_name, tab.value));
-                let setter_name = format!("set{}", Self::to_pascal_case(bind));
-                input_props.push(format!(
-                    "onChange={{() => {}(\"{}\")}}",
-                    setter_name, tab.value
-                ));
+            let mut input = Element::new("input")
+                .attr("type", "radio")
+                .attr("name", "tabs")
+                .attr("value", &tab.value)
+                .class("sr-only peer");
+            if let Some(binding) = &binding {
+                input = input
+                    .prop("checked", binding.matches(&tab.value))
+                    .prop("onChange", binding.on_select(&tab.value, Vec::new()));
             }
 
-            output.push_str(&format!(
-                "{}      <label className=\"{} cursor-pointer whitespace-nowrap py-4 px-1 border-b-2 font-medium text-sm\">{}<input {} /></label>\n",
-                indent_str,
-                active_class,
-                tab_label,
-                input_props.join(" ")
-            ));
+            nav = nav.child(
+                Element::new("label")
+                    .class(&format!(
+                        "{} cursor-pointer whitespace-nowrap py-4 px-1 border-b-2 font-medium text-sm",
+                        active_class
+                    ))
+                    .children(Self::text(tab_label))
+                    .child(input),
+            );
         }
 
-        output.push_str(&format!("{}    </nav>\n", indent_str));
-        output.push_str(&format!("{}  </div>\n", indent_str));
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
+        Ok(element
+            .child(
+                Element::new("div")
+                    .class("border-b border-gray-200")
+                    .child(nav),
+            )
+            .into())
     }
 
     fn generate_accordion(
+        &self,
         accordion: &nwl_shared::AccordionElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&accordion.style);
-
-        let mut output = String::new();
-        let wrapper_class = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        output.push_str(&format!("{}<div{}>\n", indent_str, wrapper_class));
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("div").class(&Self::format_style(&accordion.style));
 
         for item in &accordion.items {
-            let item_title = &item.title;
-            let item_content = &item.content;
-            output.push_str(&format!(
-                "{}  <details className=\"mb-2 border rounded-lg\">\n{}    <summary className=\"flex items-center justify-between p-4 font-medium cursor-pointer list-none\">{}      <svg className=\"w-5 h-5 ml-2 transition-transform\" fill=\"none\" stroke=\"currentColor\" viewBox=\"0 0 24 24\"><path stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-width=\"2\" d=\"M19 9l-7 7-7-7\"></path></svg>\n    </summary>{}    <div className=\"p-4 text-gray-600\">{}</div>\n  </details>\n",
-                indent_str, indent_str, item_title, indent_str, item_content
-            ));
+            let summary = Element::new("summary")
+                .class("flex items-center justify-between p-4 font-medium cursor-pointer list-none")
+                .children(Self::text(&item.title))
+                .child(Self::icon(
+                    "w-5 h-5 ml-2 transition-transform",
+                    "M19 9l-7 7-7-7",
+                ));
+            element = element.child(
+                Element::new("details")
+                    .class("mb-2 border rounded-lg")
+                    .child(summary)
+                    .child(
+                        Element::new("div")
+                            .class("p-4 text-gray-600")
+                            .children(Self::text(&item.content)),
+                    ),
+            );
         }
 
-        output.push_str(&format!("{}</div>\n", indent_str));
-
-        Ok(output)
+        Ok(element.into())
     }
 
     fn generate_modal(
+        &self,
         modal: &nwl_shared::ModalElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-
-        let close_handler = if let Some(onClose) = &modal.onClose {
-            format!("onClick={{(e) => {{ e.stopPropagation(); {} }}}}", onClose)
-        } else {
-            String::new()
-        };
-
-        let backdrop_handler = if let Some(onClose) = &modal.onClose {
-            format!("onClick={{() => {}}}", onClose)
-        } else {
-            String::new()
-        };
-
-        let mut output = String::new();
-
-        if let Some(bind) = &modal.bind {
-            let var = Self::to_camel_case(bind);
-            output.push_str(&format!("{{{} && (\n", var));
+        path: &str,
+    ) -> Result<Node, CodegenError> {
+        let mut dialog =
+            Element::new("div").class("bg-white rounded-lg p-6 max-w-md w-full shadow-xl");
+        let mut backdrop = Element::new("div")
+            .class("fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50");
+        if let Some(on_close) = &modal.onClose {
+            backdrop = backdrop.prop("onClick", self.handler(&[], on_close));
+            let mut close = vec![Statement::from(Expr::raw("e.stopPropagation()"))];
+            close.extend(self.statements(Some(on_close)));
+            dialog = dialog.prop("onClick", Expr::block(&["e"], close));
         }
 
-        output.push_str(&format!("{}  <div className=\"fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50\" {}>\n", indent_str, backdrop_handler));
-        output.push_str(&format!(
-            "{}    <div className=\"bg-white rounded-lg p-6 max-w-md w-full shadow-xl\" {}>\n",
-            indent_str, close_handler
-        ));
-
         // Header with title and close button
         if modal.title.is_some() || modal.onClose.is_some() {
-            output.push_str(&format!(
-                "{}      <div className=\"flex items-center justify-between mb-4\">\n",
-                indent_str
-            ));
+            let mut header = Element::new("div").class("flex items-center justify-between mb-4");
             if let Some(title) = &modal.title {
-                output.push_str(&format!(
-                    "{}        <h2 className=\"text-xl font-bold\">{}</h2>\n",
-                    indent_str, title
-                ));
+                header = header.child(
+                    Element::new("h2")
+                        .class("text-xl font-bold")
+                        .children(Self::text(title)),
+                );
             }
-            if let Some(onClose) = &modal.onClose {
-                output.push_str(&format!(
-                    "{}        <button className=\"text-gray-500 hover:text-gray-700\" onClick={{() => {}}}>\n{}          <svg className=\"w-5 h-5\" fill=\"none\" stroke=\"currentColor\" viewBox=\"0 0 24 24\"><path stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-width=\"2\" d=\"M6 18L18 6M6 6l12 12\"></path></svg>\n{}        </button>\n",
-                    indent_str, onClose, indent_str, indent_str
-                ));
+            if let Some(on_close) = &modal.onClose {
+                header = header.child(
+                    Element::new("button")
+                        .class("text-gray-500 hover:text-gray-700")
+                        .prop("onClick", self.handler(&[], on_close))
+                        .child(Self::icon("w-5 h-5", "M6 18L18 6M6 6l12 12")),
+                );
             }
-            output.push_str(&format!("{}      </div>\n", indent_str));
-        }
-
-        for child in &modal.children {
-            output.push_str(&format!(
-                "{}        {}\n",
-                indent_str,
-                Self::generate_element(child, indent + 4)?
-            ));
+            dialog = dialog.child(header);
         }
+        dialog = dialog
+            .children(self.generate_children(&modal.children, &format!("{}.children", path))?);
 
-        output.push_str(&format!("{}    </div>\n", indent_str));
-        output.push_str(&format!("{}  </div>\n", indent_str));
-
-        if modal.bind.is_some() {
-            output.push_str(")}");
+        let backdrop = backdrop.child(dialog);
+        match &modal.bind {
+            Some(bind) => Ok(Node::expression(Expr::and(
+                Self::to_camel_case(bind),
+                backdrop,
+            ))),
+            None => Ok(backdrop.into()),
         }
-
-        Ok(output)
     }
 
-    fn generate_badge(
-        badge: &nwl_shared::BadgeElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_badge(&self, badge: &nwl_shared::BadgeElement) -> Result<Node, CodegenError> {
         let variant = badge
             .variant
             .as_ref()
@@ -1321,41 +1543,39 @@ impl ReactGenerator {
             })
             .unwrap_or("bg-gray-100 text-gray-800");
 
-        Ok(format!(
-            "{}<span className=\"{} px-2 py-1 rounded-full text-xs font-semibold\">{}</span>",
-            indent_str, variant, badge.content
-        ))
-    }
-
-    fn generate_tag(tag: &nwl_shared::TagElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&tag.style);
-
-        let close_btn = if tag.removable == Some(true) {
-            let handler = tag
-                .onRemove
-                .as_ref()
-                .map(|h| format!("onClick={{() => {}}}", h))
-                .unwrap_or_default();
-            format!(
-                "<button {} className=\"ml-1 text-gray-400 hover:text-gray-600\">×</button>",
-                handler
-            )
-        } else {
-            String::new()
-        };
+        Ok(Element::new("span")
+            .class(&format!(
+                "{} px-2 py-1 rounded-full text-xs font-semibold",
+                variant
+            ))
+            .children(Self::text(&badge.content))
+            .into())
+    }
+
+    fn generate_tag(&self, tag: &nwl_shared::TagElement) -> Result<Node, CodegenError> {
+        let mut element = Element::new("span")
+            .class(&format!(
+                "inline-flex items-center px-3 py-1 rounded-full text-sm font-medium bg-blue-100 text-blue-800 {}",
+                Self::format_style(&tag.style)
+            ))
+            .children(Self::text(&tag.content));
+
+        if tag.removable == Some(true) {
+            let mut close = Element::new("button");
+            if let Some(on_remove) = &tag.onRemove {
+                close = close.prop("onClick", self.handler(&[], on_remove));
+            }
+            element = element.child(
+                close
+                    .class("ml-1 text-gray-400 hover:text-gray-600")
+                    .text("×"),
+            );
+        }
 
-        Ok(format!(
-            "{}<span className=\"inline-flex items-center px-3 py-1 rounded-full text-sm font-medium bg-blue-100 text-blue-800 {}\">{}{}</span>",
-            indent_str, class_name, tag.content, close_btn
-        ))
+        Ok(element.into())
     }
 
-    fn generate_alert(
-        alert: &nwl_shared::AlertElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_alert(&self, alert: &nwl_shared::AlertElement) -> Result<Node, CodegenError> {
         let alert_type = alert
             .alertType
             .as_ref()
@@ -1366,37 +1586,31 @@ impl ReactGenerator {
                 _ => "bg-blue-50 text-blue-800 border-blue-200",
             })
             .unwrap_or("bg-blue-50 text-blue-800 border-blue-200");
+        let dismissible = alert.dismissible == Some(true);
 
-        let dismiss_btn = if alert.dismissible == Some(true) {
-            let handler = alert
-                .onDismiss
-                .as_ref()
-                .map(|h| format!("onClick={{() => {}}}", h))
-                .unwrap_or_default();
-            format!("<button {} className=\"ml-auto -mx-1.5 -my-1.5 bg-transparent text-current rounded-lg focus:ring-2 inline-flex items-center justify-center h-8 w-8\">×</button>", handler)
-        } else {
-            String::new()
-        };
-
-        Ok(format!(
-            "{}<div className=\"my-2 {} border p-4 rounded-lg flex items-start {}\">{}<p>{}</p></div>",
-            indent_str,
+        let mut element = Element::new("div").class(&format!(
+            "my-2 {} border p-4 rounded-lg flex items-start {}",
             alert_type,
-            if alert.dismissible == Some(true) {
-                "relative"
-            } else {
-                ""
-            },
-            dismiss_btn,
-            alert.content
-        ))
+            if dismissible { "relative" } else { "" }
+        ));
+        if dismissible {
+            let mut dismiss = Element::new("button");
+            if let Some(on_dismiss) = &alert.onDismiss {
+                dismiss = dismiss.prop("onClick", self.handler(&[], on_dismiss));
+            }
+            element = element.child(
+                dismiss
+                    .class("ml-auto -mx-1.5 -my-1.5 bg-transparent text-current rounded-lg focus:ring-2 inline-flex items-center justify-center h-8 w-8")
+                    .text("×"),
+            );
+        }
+
+        Ok(element
+            .child(Element::new("p").children(Self::text(&alert.content)))
+            .into())
     }
 
-    fn generate_spinner(
-        spinner: &nwl_shared::SpinnerElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_spinner(&self, spinner: &nwl_shared::SpinnerElement) -> Result<Node, CodegenError> {
         let size = spinner
             .size
             .as_ref()
@@ -1407,260 +1621,229 @@ impl ReactGenerator {
             })
             .unwrap_or("w-6 h-6");
 
-        let label = spinner
-            .label
-            .as_ref()
-            .map(|l| {
-                format!(
-                    "<span className=\"ml-2 text-sm text-gray-600\">{}</span>",
-                    l
+        let mut element = Element::new("div").class("flex items-center justify-center");
+        if let Some(label) = &spinner.label {
+            element = element.child(
+                Element::new("span")
+                    .class("ml-2 text-sm text-gray-600")
+                    .children(Self::text(label)),
+            );
+        }
+        element = element.child(Element::new("div").class(&format!(
+            "{} animate-spin rounded-full border-2 border-gray-300 border-t-blue-600",
+            size
+        )));
+        if spinner.label.is_none() {
+            element = element.child(Element::new("div").class("sr-only").text("Loading..."));
+        }
+
+        Ok(element.into())
+    }
+
+    fn generate_counter(&self, counter: &nwl_shared::CounterElement) -> Result<Node, CodegenError> {
+        let (decrement, increment, value) = match &counter.bind {
+            Some(bind) => {
+                let camel_name = Self::to_camel_case(bind);
+                let setter_name = format!("set{}", Self::to_pascal_case(bind));
+                let min = counter.min.unwrap_or(0);
+                let max = counter.max.unwrap_or(999);
+                (
+                    Expr::arrow(
+                        &[],
+                        format!("{0} > {1} && {2}({0} - 1)", camel_name, min, setter_name),
+                    ),
+                    Expr::arrow(
+                        &[],
+                        format!("{0} < {1} && {2}({0} + 1)", camel_name, max, setter_name),
+                    ),
+                    Node::expression(camel_name),
                 )
-            })
-            .unwrap_or_default();
-
-        Ok(format!(
-            "{}<div className=\"flex items-center justify-center\">{}<div className=\"{} animate-spin rounded-full border-2 border-gray-300 border-t-blue-600\"></div>{}</div>",
-            indent_str,
-            label,
-            size,
-            if label.is_empty() { "<div className=\"sr-only\">Loading...</div>".to_string() } else { String::new() }
-        ))
-    }
-
-    fn generate_counter(
-        counter: &nwl_shared::CounterElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let _class_name = Self::format_style(&counter.style);
-
-        let decrement = if let Some(bind) = &counter.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            let min = counter.min.unwrap_or(0);
-            format!(
-                "onClick={{() => {} > {} && {}({} - 1)}}",
-                camel_name, min, setter_name, camel_name
-            )
-        } else {
-            "onClick={() => {}}".to_string()
+            }
+            None => (
+                Expr::block(&[], Vec::new()),
+                Expr::block(&[], Vec::new()),
+                Node::text("0"),
+            ),
         };
 
-        let increment = if let Some(bind) = &counter.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            let max = counter.max.unwrap_or(999);
-            format!(
-                "onClick={{() => {} < {} && {}({} + 1)}}",
-                camel_name, max, setter_name, camel_name
+        Ok(Element::new("div")
+            .class(&format!(
+                "flex items-center justify-center border rounded-lg {}",
+                if counter.step.is_some() { "w-32" } else { "" }
+            ))
+            .child(
+                Element::new("button")
+                    .prop("onClick", decrement)
+                    .class("w-10 px-3 py-1 border-r hover:bg-gray-100 flex items-center justify-center")
+                    .text("-"),
             )
-        } else {
-            "onClick={() => {}}".to_string()
-        };
-
-        let value = if let Some(bind) = &counter.bind {
-            format!(
-                "<span className=\"text-lg font-semibold mx-4\">{{{}}}</span>",
-                Self::to_camel_case(bind)
+            .child(
+                Element::new("span")
+                    .class("text-lg font-semibold mx-4")
+                    .child(value),
             )
-        } else {
-            "<span className=\"text-lg font-semibold mx-4\">0</span>".to_string()
-        };
-
-        Ok(format!(
-            "{}<div className=\"flex items-center justify-center border rounded-lg {}\">{}<button {} className=\"w-10 px-3 py-1 border-r hover:bg-gray-100 flex items-center justify-center\">-</button>{}<button {} className=\"w-10 px-3 py-1 border-l hover:bg-gray-100 flex items-center justify-center\">+</button></div>",
-            indent_str,
-            if counter.step.is_some() { "w-32" } else { "" },
-            indent_str,
-            decrement,
-            value,
-            increment
-        ))
+            .child(
+                Element::new("button")
+                    .prop("onClick", increment)
+                    .class("w-10 px-3 py-1 border-l hover:bg-gray-100 flex items-center justify-center")
+                    .text("+"),
+            )
+            .into())
     }
 
     fn generate_search_input(
+        &self,
         search: &nwl_shared::SearchInputElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    ) -> Result<Node, CodegenError> {
         let class_name = Self::format_style(&search.style);
 
-        let mut props = Vec::new();
-        props.push("type=\"search\"".to_string());
-        if !class_name.is_empty() {
-            props.push(format!("className=\"{}\"", class_name));
-        }
+        let mut input = Element::new("input")
+            .attr("type", "search")
+            .class(&class_name);
         if let Some(placeholder) = &search.placeholder {
-            props.push(format!("placeholder=\"{}\"", placeholder));
+            input = input.attr("placeholder", placeholder);
         }
-
         if let Some(bind) = &search.bind {
-            let camel_name = Self::to_camel_case(bind);
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            props.push(format!("value={{{}}}", camel_name));
-            props.push(format!(
-                "onChange={{(e) => {}(e.target.value)}}",
-                setter_name
-            ));
+            let binding = self.binding(bind, Control::Text);
+            input = input
+                .prop("value", binding.read())
+                .prop("onChange", binding.on_change(Vec::new()));
         }
-
         if search.clearable == Some(true) {
-            props.push("onClear".to_string());
+            input = input.flag("onClear");
         }
-
-        if let Some(onSearch) = &search.onSearch {
-            props.push(format!("onSearch={{() => {}}}", onSearch));
+        if let Some(on_search) = &search.onSearch {
+            input = input.prop("onSearch", self.handler(&[], on_search));
         }
 
-        let props_str = props.join(" ");
-
-        Ok(format!(
-            "{}<div className=\"relative\">{}<input {} /><button className=\"absolute right-3 top-1/2 -translate-y-1/2 text-gray-400\">🔍</button></div>",
-            indent_str,
-            if !class_name.is_empty() { format!("<span className=\"{}\"></span>", class_name) } else { String::new() },
-            props_str
-        ))
+        let mut element = Element::new("div").class("relative");
+        if !class_name.is_empty() {
+            element = element.child(Element::new("span").class(&class_name));
+        }
+        Ok(element
+            .child(input)
+            .child(
+                Element::new("button")
+                    .class("absolute right-3 top-1/2 -translate-y-1/2 text-gray-400")
+                    .text("🔍"),
+            )
+            .into())
     }
 
     fn generate_copy_button(
+        &self,
         copy: &nwl_shared::CopyButtonElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&copy.style);
-
+    ) -> Result<Node, CodegenError> {
         let content = copy
             .content
             .as_ref()
             .or(copy.text.as_ref())
-            .map(|s| format!("\"{}\"", s))
-            .unwrap_or("''".to_string());
-        let handler = copy
-            .onCopy
-            .as_ref()
-            .map(|h| {
-                format!(
-                    "onClick={{() => navigator.clipboard.writeText({}) && {}}}",
-                    content, h
-                )
-            })
-            .unwrap_or_else(|| {
-                format!(
-                    "onClick={{() => navigator.clipboard.writeText({})}}",
-                    content
-                )
-            });
+            .map(|s| Self::string(s))
+            .unwrap_or("\"\"".to_string());
+        let write = Expr::Raw(format!("navigator.clipboard.writeText({})", content));
+        let handler = match &copy.onCopy {
+            Some(on_copy) => {
+                let mut statements = vec![Statement::from(write)];
+                statements.extend(self.statements(Some(on_copy)));
+                Expr::block(&[], statements)
+            }
+            None => Expr::arrow(&[], write),
+        };
 
-        let button_text = copy
-            .text
-            .as_ref()
-            .map(|s| s.clone())
-            .unwrap_or_else(|| "Copy".to_string());
+        let button_text = copy.text.clone().unwrap_or_else(|| "Copy".to_string());
 
-        Ok(format!(
-            "{}<button {} className=\"inline-flex items-center px-3 py-2 border border-gray-300 rounded-md shadow-sm text-sm font-medium text-gray-700 bg-white hover:bg-gray-50 {}\">{}</button>",
-            indent_str,
-            handler,
-            class_name,
-            button_text
-        ))
+        Ok(Element::new("button")
+            .prop("onClick", handler)
+            .class(&format!(
+                "inline-flex items-center px-3 py-2 border border-gray-300 rounded-md shadow-sm text-sm font-medium text-gray-700 bg-white hover:bg-gray-50 {}",
+                Self::format_style(&copy.style)
+            ))
+            .children(Self::text(&button_text))
+            .into())
     }
 
     fn generate_pagination(
+        &self,
         pagination: &nwl_shared::PaginationElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&pagination.style);
-
+    ) -> Result<Node, CodegenError> {
         let total = pagination.total.unwrap_or(100);
         let per_page = pagination.perPage.unwrap_or(10);
         let total_pages = (total as f64 / per_page as f64).ceil() as u32;
 
-        let prev_handler = if let Some(bind) = &pagination.bind {
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            format!(
-                "onClick={{() => {} > 1 && {}({} - 1)}}",
-                Self::to_camel_case(bind),
-                setter_name,
-                Self::to_camel_case(bind)
-            )
-        } else {
-            "onClick={{() => {}}}".to_string()
-        };
-
-        let next_handler = if let Some(bind) = &pagination.bind {
-            let setter_name = format!("set{}", Self::to_pascal_case(bind));
-            format!(
-                "onClick={{() => {} < {} && {}({} + 1)}}",
-                Self::to_camel_case(bind),
-                total_pages,
-                setter_name,
-                Self::to_camel_case(bind)
-            )
-        } else {
-            "onClick={{() => {}}}".to_string()
+        let (previous, next, current) = match &pagination.bind {
+            Some(bind) => {
+                let camel_name = Self::to_camel_case(bind);
+                let setter_name = format!("set{}", Self::to_pascal_case(bind));
+                (
+                    Expr::arrow(
+                        &[],
+                        format!("{0} > 1 && {1}({0} - 1)", camel_name, setter_name),
+                    ),
+                    Expr::arrow(
+                        &[],
+                        format!(
+                            "{0} < {1} && {2}({0} + 1)",
+                            camel_name, total_pages, setter_name
+                        ),
+                    ),
+                    Node::expression(camel_name),
+                )
+            }
+            None => (
+                Expr::block(&[], Vec::new()),
+                Expr::block(&[], Vec::new()),
+                Node::text("1"),
+            ),
         };
-
-        let current = if let Some(bind) = &pagination.bind {
-            format!("<span className=\"px-4 py-2 border bg-blue-50 text-blue-600 font-medium\">Page {{ {bind} }} of {total_pages}</span>", bind = Self::to_camel_case(bind))
-        } else {
-            format!("<span className=\"px-4 py-2 border bg-blue-50 text-blue-600 font-medium\">Page 1 of {total_pages}</span>")
+        let button = |handler: Expr, label: &str| {
+            Element::new("button")
+                .prop("onClick", handler)
+                .class("px-3 py-1 border rounded hover:bg-gray-100")
+                .text(label)
         };
 
-        Ok(format!(
-            "{}<div className=\"flex items-center justify-center space-x-2 {}\">{}<button {} className=\"px-3 py-1 border rounded hover:bg-gray-100\">Previous</button>{}<button {} className=\"px-3 py-1 border rounded hover:bg-gray-100\">Next</button></div>",
-            indent_str,
-            class_name,
-            indent_str,
-            prev_handler,
-            current,
-            next_handler
-        ))
+        Ok(Element::new("div")
+            .class(&format!(
+                "flex items-center justify-center space-x-2 {}",
+                Self::format_style(&pagination.style)
+            ))
+            .child(button(previous, "Previous"))
+            .child(
+                Element::new("span")
+                    .class("px-4 py-2 border bg-blue-50 text-blue-600 font-medium")
+                    .text("Page ")
+                    .child(current)
+                    .text(format!(" of {}", total_pages)),
+            )
+            .child(button(next, "Next"))
+            .into())
     }
 
     fn generate_breadcrumb(
+        &self,
         breadcrumb: &nwl_shared::BreadcrumbElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&breadcrumb.style);
-
-        let mut items = String::new();
+    ) -> Result<Node, CodegenError> {
+        let mut list = Element::new("ol").class("flex items-center space-x-2");
         for (index, item) in breadcrumb.items.iter().enumerate() {
-            let separator = if index < breadcrumb.items.len() - 1 {
-                "<span className=\"mx-2 text-gray-400\">/</span>"
-            } else {
-                ""
+            let content = match &item.href {
+                Some(href) => Self::link(Some(href)).class("text-blue-600 hover:underline"),
+                None => Element::new("span").class("text-gray-600"),
             };
-            let content = if let Some(href) = &item.href {
-                format!(
-                    "<a href=\"{}\" className=\"text-blue-600 hover:underline\">{}</a>{}",
-                    href, item.label, separator
-                )
-            } else {
-                format!(
-                    "<span className=\"text-gray-600\">{}</span>{}",
-                    item.label, separator
-                )
-            };
-            items.push_str(&format!("{}<span>{}</span>\n", indent_str, content));
+            let mut entry = Element::new("span").child(content.children(Self::text(&item.label)));
+            if index < breadcrumb.items.len() - 1 {
+                entry = entry.child(Element::new("span").class("mx-2 text-gray-400").text("/"));
+            }
+            list = list.child(entry);
         }
 
-        let wrapper_class = if class_name.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", class_name)
-        };
-
-        Ok(format!("{}<nav{} aria-label=\"Breadcrumb\"><ol className=\"flex items-center space-x-2\">{}</ol></nav>", indent_str, wrapper_class, items))
+        Ok(Element::new("nav")
+            .class(&Self::format_style(&breadcrumb.style))
+            .attr("aria-label", "Breadcrumb")
+            .child(list)
+            .into())
     }
 
-    fn generate_avatar(
-        avatar: &nwl_shared::AvatarElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_avatar(&self, avatar: &nwl_shared::AvatarElement) -> Result<Node, CodegenError> {
         let size = avatar
             .size
             .as_ref()
@@ -1671,318 +1854,338 @@ impl ReactGenerator {
             })
             .unwrap_or("w-10 h-10 text-sm");
 
-        let image = if let Some(src) = &avatar.src {
-            format!(
-                "<img src=\"{}\" className=\"w-full h-full object-cover rounded-full\" />",
-                src
-            )
-        } else {
-            let fallback = avatar
-                .fallback
-                .as_ref()
-                .map(|f| format!("{}", f.chars().take(2).collect::<String>()))
-                .unwrap_or_else(|| "?".to_string());
-            format!("<span className=\"flex items-center justify-center w-full h-full rounded-full bg-gray-200 text-gray-600 font-medium\">{}</span>", fallback)
+        let image = match &avatar.src {
+            Some(src) => Element::new("img")
+                .attr("src", src)
+                .class("w-full h-full object-cover rounded-full"),
+            None => {
+                let fallback = avatar
+                    .fallback
+                    .as_ref()
+                    .map(|f| f.chars().take(2).collect::<String>())
+                    .unwrap_or_else(|| "?".to_string());
+                Element::new("span")
+                    .class("flex items-center justify-center w-full h-full rounded-full bg-gray-200 text-gray-600 font-medium")
+                    .text(fallback)
+            }
         };
 
-        let name = if let Some(name) = &avatar.name {
-            format!(
-                "<span className=\"ml-2 font-medium text-gray-700\">{}</span>",
-                name
-            )
-        } else {
-            String::new()
-        };
+        let mut element = Element::new("div")
+            .class(&format!(
+                "flex items-center {}",
+                Self::format_style(&avatar.style)
+            ))
+            .child(
+                Element::new("div")
+                    .class(&format!(
+                        "{} rounded-full overflow-hidden bg-gray-100",
+                        size
+                    ))
+                    .child(image),
+            );
+        if let Some(name) = &avatar.name {
+            element = element.child(
+                Element::new("span")
+                    .class("ml-2 font-medium text-gray-700")
+                    .text(name),
+            );
+        }
 
-        Ok(format!(
-            "{}<div className=\"flex items-center {}\">{}<div className=\"{} rounded-full overflow-hidden bg-gray-100\">{}</div>{}</div>",
-            indent_str,
-            Self::format_style(&avatar.style),
-            indent_str,
-            size,
-            image,
-            name
-        ))
+        Ok(element.into())
     }
 
     fn generate_chip_input(
+        &self,
         chip: &nwl_shared::ChipInputElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&chip.style);
-
-        let (bind_name, setter_name) = if let Some(bind) = &chip.bind {
-            let camel = Self::to_camel_case(bind);
-            let setter = format!("set{}", Self::to_pascal_case(bind));
-            (camel, setter)
-        } else {
-            (String::new(), String::new())
-        };
-
-        let suggestions_with_handlers: Vec<String> = chip.suggestions.iter().map(|suggestion| {
-            format!(
-                "<span key=\"{0}\" className=\"inline-block px-2 py-1 bg-gray-100 text-gray-700 text-xs rounded-full mr-2 mb-1 cursor-pointer hover:bg-gray-200\" style={{{1}.includes(\"{0}\") ? {{ opacity: 0.5, pointerEvents: 'none' }} : {{}}}} onClick={{() => {{ if (!{1}.includes(\"{0}\")) {{ {2}([...{1}, \"{0}\"]); }} }}}}>{0}</span>",
-                suggestion,
-                bind_name,
-                setter_name
-            )
-        }).collect();
-
-        let input_handler = if setter_name.is_empty() {
-            String::new()
-        } else {
-            format!(
-                "onKeyDown={{(e) => {{ if (e.key === 'Enter') {{ const newValue = e.target.value.trim(); if (newValue && !{0}.includes(newValue)) {{ {1}([...{0}, newValue]); }} e.target.value = ''; }} }}}}",
-                bind_name, setter_name
-            )
-        };
-
-        let chips_display = if bind_name.is_empty() {
-            String::new()
-        } else {
-            format!(
-                "<div className=\"flex flex-wrap gap-2 mb-2\">{{{0}.map((chip, i) => <span key={{i}} className=\"px-2 py-1 bg-blue-100 text-blue-800 rounded-full text-sm flex items-center\">{{chip}}<button className=\"ml-1 text-blue-600 hover:text-blue-800\" onClick={{() => {1}({0}.filter((_, j) => j !== i))}}>×</button></span>)}}</div>",
-                bind_name, setter_name
+    ) -> Result<Node, CodegenError> {
+        let mut element = Element::new("div").class(&Self::format_style(&chip.style));
+        let bound = chip.bind.as_ref().map(|bind| {
+            (
+                Self::to_camel_case(bind),
+                format!("set{}", Self::to_pascal_case(bind)),
             )
-        };
+        });
 
-        let suggestions_html = if !chip.suggestions.is_empty() {
-            format!(
-                "<div className=\"mb-2 flex flex-wrap\">{}</div>",
-                suggestions_with_handlers.join("")
-            )
-        } else {
-            String::new()
-        };
+        if let Some((chips, setter)) = &bound {
+            let remove = Expr::arrow(
+                &[],
+                format!("{0}({1}.filter((_, j) => j !== i))", setter, chips),
+            );
+            let chip = Element::new("span")
+                .prop("key", "i")
+                .class("px-2 py-1 bg-blue-100 text-blue-800 rounded-full text-sm flex items-center")
+                .child(Node::expression("chip"))
+                .child(
+                    Element::new("button")
+                        .class("ml-1 text-blue-600 hover:text-blue-800")
+                        .prop("onClick", remove)
+                        .text("×"),
+                );
+            element = element.child(
+                Element::new("div")
+                    .class("flex flex-wrap gap-2 mb-2")
+                    .child(Node::expression(Expr::call(
+                        format!("{}.map", chips),
+                        vec![Expr::arrow(&["chip", "i"], chip)],
+                    ))),
+            );
+        }
 
-        let placeholder = chip
-            .placeholder
-            .as_ref()
-            .map(|s| s.clone())
-            .unwrap_or_else(|| "Add tags...".to_string());
+        if !chip.suggestions.is_empty() {
+            let mut suggestions = Element::new("div").class("mb-2 flex flex-wrap");
+            for suggestion in &chip.suggestions {
+                let mut entry = Element::new("span")
+                    .attr("key", suggestion)
+                    .class("inline-block px-2 py-1 bg-gray-100 text-gray-700 text-xs rounded-full mr-2 mb-1 cursor-pointer hover:bg-gray-200");
+                if let Some((chips, setter)) = &bound {
+                    let value = escape::js_string(suggestion);
+                    let included = format!("{}.includes({})", chips, value);
+                    entry = entry
+                        .prop(
+                            "style",
+                            Expr::conditional(
+                                included.as_str(),
+                                Expr::Object(vec![
+                                    ("opacity".to_string(), Expr::raw("0.5")),
+                                    ("pointerEvents".to_string(), Expr::raw("\"none\"")),
+                                ]),
+                                Expr::Object(Vec::new()),
+                            ),
+                        )
+                        .prop(
+                            "onClick",
+                            Expr::block(
+                                &[],
+                                vec![Statement::If {
+                                    test: Expr::Raw(format!("!{}", included)),
+                                    then: vec![Statement::from(Expr::Raw(format!(
+                                        "{}([...{}, {}])",
+                                        setter, chips, value
+                                    )))],
+                                }],
+                            ),
+                        );
+                }
+                suggestions = suggestions.child(entry.text(suggestion));
+            }
+            element = element.child(suggestions);
+        }
+
+        let placeholder = chip.placeholder.as_deref().unwrap_or("Add tags...");
+        let mut input = Element::new("input")
+            .attr("type", "text")
+            .attr("placeholder", placeholder);
+        if let Some((chips, setter)) = &bound {
+            let add = vec![
+                Statement::constant("newValue", "e.currentTarget.value.trim()"),
+                Statement::If {
+                    test: Expr::Raw(format!("newValue && !{}.includes(newValue)", chips)),
+                    then: vec![Statement::from(Expr::Raw(format!(
+                        "{}([...{}, newValue])",
+                        setter, chips
+                    )))],
+                },
+                Statement::from(Expr::raw("e.currentTarget.value = \"\"")),
+            ];
+            input = input.prop(
+                "onKeyDown",
+                Expr::block(
+                    &["e"],
+                    vec![Statement::If {
+                        test: Expr::raw("e.key === \"Enter\""),
+                        then: add,
+                    }],
+                ),
+            );
+        }
+        element = element.child(input.class(
+            "w-full px-3 py-2 border rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500",
+        ));
 
-        Ok(format!(
-            "{}<div className=\"{}\">{}{}<input type=\"text\" placeholder=\"{}\" {} className=\"w-full px-3 py-2 border rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500\" /></div>",
-            indent_str,
-            class_name,
-            chips_display,
-            suggestions_html,
-            placeholder,
-            input_handler
-        ))
+        Ok(element.into())
     }
 
-    fn generate_nav(nav: &nwl_shared::NavElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&nav.style);
-
+    fn generate_nav(&self, nav: &nwl_shared::NavElement) -> Result<Node, CodegenError> {
         let sticky_suffix = if nav.sticky == Some(true) {
             " sticky top-0 z-50"
         } else {
             ""
         };
-
         let bg_class = if nav.transparent == Some(true) {
             "bg-transparent"
         } else {
             "bg-black"
         };
 
-        let logo_html = if let Some(logo) = &nav.logo {
-            format!(
-                "<a href=\"/\" className=\"text-xl font-bold text-white\">{}</a>",
-                logo
-            )
-        } else {
-            String::new()
-        };
+        let mut element = Element::new("nav").class(&format!(
+            "{} flex items-center justify-between px-6 py-4{} {}",
+            Self::format_style(&nav.style),
+            sticky_suffix,
+            bg_class
+        ));
+        if let Some(logo) = &nav.logo {
+            element = element.child(
+                Self::link(Some("/"))
+                    .class("text-xl font-bold text-white")
+                    .text(logo),
+            );
+        }
 
-        let mut links_html = String::new();
+        let mut links = Element::new("div").class("flex items-center gap-6");
+        let active_class = "text-sm font-medium transition-colors text-blue-400";
+        let inactive_class = "text-sm font-medium transition-colors text-white hover:text-blue-400";
         for link in &nav.links {
-            let active_class = if link.active == Some(true) {
-                " text-blue-400"
-            } else {
-                " text-white hover:text-blue-400"
+            let element = match (link.href.as_deref(), link.active) {
+                // React Router knows whether the page at `to` is showing.
+                (Some(href), None) if routes::is_internal(href) => {
+                    let mut element = Element::new("NavLink").attr("to", href);
+                    if href == "/" {
+                        element = element.flag("end");
+                    }
+                    element.prop(
+                        "className",
+                        Expr::arrow(
+                            &["{ isActive }"],
+                            Expr::conditional(
+                                "isActive",
+                                Expr::Raw(escape::js_string(active_class)),
+                                Expr::Raw(escape::js_string(inactive_class)),
+                            ),
+                        ),
+                    )
+                }
+                (href, active) => Self::link(href).class(if active == Some(true) {
+                    active_class
+                } else {
+                    inactive_class
+                }),
             };
-            let href = link
-                .href
-                .as_ref()
-                .map(|h| format!("href=\"{}\"", h))
-                .unwrap_or_default();
-            links_html.push_str(&format!(
-                "{}<a {} className=\"{} transition-colors{}\">{}</a>\n",
-                indent_str, href, "text-sm font-medium", active_class, link.label
-            ));
+            links = links.child(element.children(Self::text(&link.label)));
         }
 
-        Ok(format!(
-            "{}<nav className=\"{} flex items-center justify-between px-6 py-4{} {}\">{}<div className=\"flex items-center gap-6\">{}</div></nav>",
-            indent_str,
-            class_name,
-            sticky_suffix,
-            bg_class,
-            logo_html,
-            links_html
-        ))
-    }
-
-    fn generate_menu(
-        menu: &nwl_shared::MenuElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
-        let class_name = Self::format_style(&menu.style);
-
-        let hamburger = if menu.hamburger == Some(true) {
-            format!(
-                "{0}{1}<button className=\"md:hidden text-white p-2\" onClick={{() => setMenuOpen(!menuOpen)}}>\n{1}  <svg className=\"w-6 h-6\" fill=\"none\" stroke=\"currentColor\" viewBox=\"0 0 24 24\">\n{1}    <path stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-width=\"2\" d=\"M4 6h16M4 12h16M4 18h16\"></path>\n{1}  </svg>\n{1}</button>\n",
-                indent_str, indent_str
-            )
-        } else {
-            String::new()
-        };
+        Ok(element.child(links).into())
+    }
 
-        let mut desktop_links = String::new();
-        let mut mobile_links = String::new();
+    fn generate_menu(&self, menu: &nwl_shared::MenuElement) -> Result<Node, CodegenError> {
+        let hamburger = menu.hamburger == Some(true);
+
+        let mut bar =
+            Element::new("div").class("flex items-center justify-between px-6 py-4 bg-black");
+        if hamburger {
+            bar = bar.child(
+                Element::new("button")
+                    .class("md:hidden text-white p-2")
+                    .prop("onClick", Expr::arrow(&[], "setMenuOpen(!menuOpen)"))
+                    .child(Self::icon("w-6 h-6", "M4 6h16M4 12h16M4 18h16")),
+            );
+        }
+        let mut desktop_links = Element::new("div").class("hidden md:flex items-center gap-6");
         for link in &menu.items {
-            let href = link
-                .href
-                .as_ref()
-                .map(|h| format!("href=\"{}\"", h))
-                .unwrap_or_default();
-            desktop_links.push_str(&format!(
-                "{}<a {} className=\"text-white hover:text-blue-400 transition-colors text-sm font-medium\">{}</a>\n",
-                indent_str, href, link.label
-            ));
-            mobile_links.push_str(&format!(
-                "{}<a {} className=\"block py-3 text-white hover:text-blue-400 border-b border-gray-700\">{}</a>\n",
-                indent_str, href, link.label
-            ));
+            desktop_links = desktop_links.child(
+                Self::link(link.href.as_deref())
+                    .class("text-white hover:text-blue-400 transition-colors text-sm font-medium")
+                    .children(Self::text(&link.label)),
+            );
         }
-
-        let transform_class = if menu.hamburger == Some(true) {
-            "{{menuOpen ? 'translate-x-0' : 'translate-x-full'}}"
-        } else {
-            ""
-        };
-
-        let display_style = if menu.hamburger == Some(true) {
-            "{{display: menuOpen ? 'block' : 'none'}}"
-        } else {
-            ""
-        };
-
-        Ok(format!(
-            "{0}<div className=\"relative\">\n{0}  <div className=\"flex items-center justify-between px-6 py-4 bg-black\">\n{0}{1}{2}    <div className=\"hidden md:flex items-center gap-6\">\n{0}    </div>\n{0}  </div>\n{0}  <div className=\"fixed inset-0 bg-gray-900 z-50 transform {3} transition-transform duration-300 md:hidden\"\n{0}       style={4}>\n{0}    <div className=\"p-6\">\n{0}      <button className=\"absolute top-4 right-4 text-white\" onClick={{() => setMenuOpen(false)}}>\n{0}        <svg className=\"w-6 h-6\" fill=\"none\" stroke=\"currentColor\" viewBox=\"0 0 24 24\">\n{0}          <path stroke-linecap=\"round\" stroke-linejoin=\"round\" stroke-width=\"2\" d=\"M6 18L18 6M6 6l12 12\"></path>\n{0}        </svg>\n{0}      </button>\n{0}      <div className=\"mt-8\">\n{0}{5}      </div>\n{0}    </div>\n{0}  </div>\n{0}</div>\n",
-            indent_str,
-            hamburger,
-            desktop_links,
-            transform_class,
-            display_style,
-            mobile_links
-        ))
+        let mut element = Element::new("div")
+            .class(&format!("relative {}", Self::format_style(&menu.style)))
+            .child(bar.child(desktop_links));
+
+        // Without the hamburger button nothing opens the mobile menu.
+        if hamburger {
+            let mut mobile_links = Element::new("div").class("mt-8");
+            for link in &menu.items {
+                let mut element = Self::link(link.href.as_deref())
+                    .class("block py-3 text-white hover:text-blue-400 border-b border-gray-700");
+                // Following a link within the app doesn't reload the page,
+                // so the menu closes itself.
+                if link.href.as_deref().is_some_and(routes::is_internal) {
+                    element = element.prop("onClick", Expr::arrow(&[], "setMenuOpen(false)"));
+                }
+                mobile_links = mobile_links.child(element.children(Self::text(&link.label)));
+            }
+            let panel = Element::new("div")
+                .prop(
+                    "className",
+                    "`fixed inset-0 bg-gray-900 z-50 transform ${menuOpen ? \"translate-x-0\" : \"translate-x-full\"} transition-transform duration-300 md:hidden`",
+                )
+                .prop(
+                    "style",
+                    Expr::Object(vec![(
+                        "display".to_string(),
+                        Expr::conditional("menuOpen", "\"block\"", "\"none\""),
+                    )]),
+                )
+                .child(
+                    Element::new("div")
+                        .class("p-6")
+                        .child(
+                            Element::new("button")
+                                .class("absolute top-4 right-4 text-white")
+                                .prop("onClick", Expr::arrow(&[], "setMenuOpen(false)"))
+                                .child(Self::icon("w-6 h-6", "M6 18L18 6M6 6l12 12")),
+                        )
+                        .child(mobile_links),
+                );
+            element = element.child(panel);
+        }
+
+        Ok(element.into())
     }
 
-    fn generate_url(url: &nwl_shared::UrlElement, indent: usize) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_url(&self, url: &nwl_shared::UrlElement) -> Result<Node, CodegenError> {
         let class_name = Self::format_style(&url.style);
 
         // If it's used as an input (has placeholder or bind), generate input field
-        if url.placeholder.is_some() || url.bind.is_some() {
-            let placeholder = url
-                .placeholder
-                .as_ref()
-                .map(|s| format!(" placeholder=\"{}\"", s))
-                .unwrap_or_default();
-            let bind = url
-                .bind
-                .as_ref()
-                .map(|s| format!(" value={{{}}}", Self::to_camel_case(s)))
-                .unwrap_or_default();
-            let class_prop = if class_name.is_empty() {
-                String::new()
-            } else {
-                format!(" className=\"{}\"", class_name)
-            };
-
-            return Ok(format!(
-                "{}<input type=\"url\"{}{}{}/>",
-                indent_str, placeholder, bind, class_prop
-            ));
+        if url.is_input() {
+            return Ok(self
+                .text_field(
+                    "url",
+                    url.placeholder.as_deref(),
+                    url.bind.as_deref(),
+                    &class_name,
+                )
+                .into());
         }
 
-        // Otherwise, generate anchor link
-        let target = url
-            .target
-            .as_ref()
-            .map(|t| format!("target=\"{}\"", t))
-            .unwrap_or_default();
-
-        let rel = if url.target.as_ref().map(|t| t == "_blank").unwrap_or(false) {
-            "rel=\"noopener noreferrer\""
-        } else {
-            ""
-        };
+        // Otherwise, generate a link
+        let mut element = Self::link(Some(url.href.as_deref().unwrap_or(""))).class(&class_name);
+        if let Some(target) = &url.target {
+            element = element.attr("target", target);
+            if target == "_blank" {
+                element = element.attr("rel", "noopener noreferrer");
+            }
+        }
 
-        let href = url.href.as_deref().unwrap_or("");
         let content = url
             .content
             .as_ref()
             .or(url.href.as_ref())
             .map(|s| s.as_str())
             .unwrap_or("");
-
-        let props = if class_name.is_empty() {
-            format!("href=\"{}\"", href)
-        } else {
-            format!("href=\"{}\" className=\"{}\"", href, class_name)
-        };
-
-        Ok(format!(
-            "{}<a {} {} {}>{}</a>",
-            indent_str, props, target, rel, content
-        ))
+        Ok(element.children(Self::text(content)).into())
     }
 
-    fn generate_email(
-        email: &nwl_shared::EmailElement,
-        indent: usize,
-    ) -> Result<String, CodegenError> {
-        let indent_str = "  ".repeat(indent);
+    fn generate_email(&self, email: &nwl_shared::EmailElement) -> Result<Node, CodegenError> {
+        let class_name = Self::format_style(&email.style);
 
         // If it's used as an input (has placeholder or bind), generate input field
         if email.placeholder.is_some() || email.bind.is_some() {
-            let placeholder = email
-                .placeholder
-                .as_ref()
-                .map(|s| format!(" placeholder=\"{}\"", s))
-                .unwrap_or_default();
-            let bind = email
-                .bind
-                .as_ref()
-                .map(|s| format!(" value={{{}}}", Self::to_camel_case(s)))
-                .unwrap_or_default();
-            let class_name = Self::format_style(&email.style);
-            let class_prop = if class_name.is_empty() {
-                String::new()
-            } else {
-                format!(" className=\"{}\"", class_name)
-            };
-
-            return Ok(format!(
-                "{}<input type=\"email\"{}{}{}/>",
-                indent_str, placeholder, bind, class_prop
-            ));
+            return Ok(self
+                .text_field(
+                    "email",
+                    email.placeholder.as_deref(),
+                    email.bind.as_deref(),
+                    &class_name,
+                )
+                .into());
         }
 
         // Otherwise, generate mailto link
-        let class_name = Self::format_style(&email.style);
-
         let address = email.address.as_deref().unwrap_or("");
         let mut mailto = format!("mailto:{}", address);
         if let Some(subject) = &email.subject {
-            mailto.push_str(&format!("?subject={}", subject));
+            mailto.push_str(&format!("?subject={}", escape::url_component(subject)));
         }
 
         let content = email
@@ -1991,17 +2194,224 @@ impl ReactGenerator {
             .or(email.address.as_ref())
             .map(|s| s.as_str())
             .unwrap_or("");
+        Ok(Element::new("a")
+            .attr("href", mailto)
+            .class(&class_name)
+            .children(Self::text(content))
+            .into())
+    }
+
+    /// A text input of `type`, bound to a state when `bind` is set.
+    fn text_field(
+        &self,
+        ty: &str,
+        placeholder: Option<&str>,
+        bind: Option<&str>,
+        class_name: &str,
+    ) -> Element {
+        let mut element = Element::new("input").attr("type", ty);
+        if let Some(placeholder) = placeholder {
+            element = element.attr("placeholder", placeholder);
+        }
+        if let Some(bind) = bind {
+            let binding = self.binding(bind, Control::Text);
+            element = element
+                .prop("value", binding.read())
+                .prop("onChange", binding.on_change(Vec::new()));
+        }
+        element.class(class_name)
+    }
+
+    /// A React Router `Link` to a path of the app, an anchor to another
+    /// `href`, or one without a destination.
+    fn link(href: Option<&str>) -> Element {
+        match href {
+            Some(href) if routes::is_internal(href) => Element::new("Link").attr("to", href),
+            Some(href) => Element::new("a").attr("href", href),
+            None => Element::new("a"),
+        }
+    }
 
-        let props = if class_name.is_empty() {
-            format!("href=\"{}\"", mailto)
+    /// An outlined 24×24 icon drawing the SVG `path`.
+    fn icon(class_name: &str, path: &str) -> Element {
+        Element::new("svg")
+            .class(class_name)
+            .attr("fill", "none")
+            .attr("stroke", "currentColor")
+            .attr("viewBox", "0 0 24 24")
+            .child(
+                Element::new("path")
+                    .attr("stroke-linecap", "round")
+                    .attr("stroke-linejoin", "round")
+                    .attr("stroke-width", "2")
+                    .attr("d", path),
+            )
+    }
+
+    /// Emits an NWL expression as JavaScript. Expressions that don't parse
+    /// were reported during validation and are used as written.
+    fn expression(source: &str) -> String {
+        match expression::parse(source) {
+            Ok(expr) => expression::emit(&expr, &Self::to_camel_case),
+            Err(_) => source.trim().to_string(),
+        }
+    }
+
+    /// Text with `{expression}` interpolations as JSX children.
+    fn text(text: &str) -> Vec<Node> {
+        match expression::parse_text(text) {
+            Ok(segments) => segments
+                .iter()
+                .map(|segment| match segment {
+                    Segment::Text(text) => Node::text(*text),
+                    Segment::Expression(expr) => {
+                        Node::expression(expression::emit(expr, &Self::to_camel_case))
+                    }
+                })
+                .collect(),
+            Err(_) => vec![Node::text(text)],
+        }
+    }
+
+    /// Emits text with `{expression}` interpolations as a JavaScript string.
+    fn string(text: &str) -> String {
+        match expression::parse_text(text) {
+            Ok(segments) => expression::emit_string(&segments, &Self::to_camel_case),
+            Err(_) => escape::js_string(text),
+        }
+    }
+
+    /// A YAML value as a JavaScript literal, with arrays and objects as
+    /// syntax so long ones break across lines.
+    fn literal(value: &serde_yaml::Value) -> Expr {
+        match value {
+            serde_yaml::Value::Sequence(items) => {
+                Expr::Array(items.iter().map(Self::literal).collect())
+            }
+            serde_yaml::Value::Mapping(mapping) => Expr::Object(
+                mapping
+                    .iter()
+                    .map(|(key, value)| {
+                        (
+                            types::js_key(&types::key_to_string(key)),
+                            Self::literal(value),
+                        )
+                    })
+                    .collect(),
+            ),
+            serde_yaml::Value::Tagged(tagged) => Self::literal(&tagged.value),
+            value => Expr::Raw(types::js_literal(value)),
+        }
+    }
+
+    /// `navigate(path)` for a path of the app, or `window.location.href =
+    /// path` for another site, parenthesized when it is the body of an
+    /// arrow function.
+    fn navigate(path: &str, parenthesized: bool) -> String {
+        if routes::is_internal(path) {
+            return format!("navigate({})", escape::js_string(path));
+        }
+        let assignment = format!("window.location.href = {}", escape::js_string(path));
+        if parenthesized {
+            format!("({})", assignment)
         } else {
-            format!("href=\"{}\" className=\"{}\"", mailto, class_name)
+            assignment
+        }
+    }
+
+    /// The statements a handler runs. Script handlers are used verbatim.
+    fn statements(&self, handler: Option<&Handler>) -> Vec<Statement> {
+        let steps = match handler {
+            None => return Vec::new(),
+            Some(Handler::Script(script)) => {
+                return expression::statements(script)
+                    .into_iter()
+                    .map(|statement| Statement::from(Expr::raw(statement)))
+                    .collect();
+            }
+            Some(Handler::Steps(steps)) => steps,
         };
+        steps
+            .iter()
+            .map(|step| {
+                let statement = match step {
+                    Step::Navigate { navigate } => Self::navigate(navigate, false),
+                    // An empty `to:` clears a text state.
+                    Step::Set { set, to } if to.trim().is_empty() => {
+                        format!("set{}(\"\")", Self::to_pascal_case(set))
+                    }
+                    Step::Set { set, to } => {
+                        format!("set{}({})", Self::to_pascal_case(set), Self::expression(to))
+                    }
+                    Step::Toggle { toggle } => format!(
+                        "set{}(!{})",
+                        Self::to_pascal_case(toggle),
+                        Self::to_camel_case(toggle)
+                    ),
+                    Step::Append { append, value } => format!(
+                        "set{}([...{}, {}])",
+                        Self::to_pascal_case(append),
+                        Self::to_camel_case(append),
+                        Self::expression(value)
+                    ),
+                    Step::Call { call, with } => {
+                        let arguments: Vec<String> =
+                            with.iter().map(|a| Self::expression(a)).collect();
+                        format!("{}({})", call, arguments.join(", "))
+                    }
+                };
+                Statement::from(Expr::Raw(statement))
+            })
+            .collect()
+    }
+
+    /// A handler as an arrow function: returning a single expression, or
+    /// running a block when it has several steps.
+    fn handler(&self, parameters: &[&str], handler: &Handler) -> Expr {
+        if let Handler::Steps(steps) = handler {
+            if let [Step::Navigate { navigate }] = steps.as_slice() {
+                return Expr::arrow(parameters, Self::navigate(navigate, true));
+            }
+        }
+        let mut statements = self.statements(Some(handler));
+        match statements.pop() {
+            Some(Statement::Expression(expr)) if statements.is_empty() => {
+                Expr::arrow(parameters, expr)
+            }
+            last => {
+                statements.extend(last);
+                Expr::block(parameters, statements)
+            }
+        }
+    }
 
-        Ok(format!("{}<a {}>{}</a>", indent_str, props, content))
+    /// Resolves how `bind` is read and written by a `control` element.
+    /// Incompatible state types are rejected during validation, so here
+    /// they fall back to the control's untyped binding.
+    fn binding(&self, bind: &str, control: Control) -> Binding {
+        let ty = self.state.get(bind);
+        let conversion = ty
+            .and_then(|ty| control.conversion(ty).ok())
+            .unwrap_or_else(|| control.natural());
+        let cast = ty
+            .filter(|ty| {
+                let inner = match ty {
+                    StateType::Nullable(inner) => inner.as_ref(),
+                    ty => ty,
+                };
+                matches!(inner, StateType::Union(_))
+                    && matches!(conversion, Conversion::Text | Conversion::NullableText)
+            })
+            .map(StateType::to_ts);
+        Binding {
+            state: Self::to_camel_case(bind),
+            setter: format!("set{}", Self::to_pascal_case(bind)),
+            conversion,
+            cast,
+        }
     }
 
-    fn format_layout_props(layout: &nwl_shared::Layout) -> String {
+    fn layout_class(layout: &nwl_shared::Layout) -> String {
         let mut classes: Vec<String> = Vec::new();
 
         match layout.layout_type {
@@ -2020,18 +2430,14 @@ impl ReactGenerator {
             classes.push(prop.clone());
         }
 
-        if classes.is_empty() {
-            String::new()
-        } else {
-            format!(" className=\"{}\"", classes.join(" "))
-        }
+        classes.join(" ")
     }
 
     fn format_style(styles: &[String]) -> String {
         styles.join(" ")
     }
 
-    fn to_pascal_case(s: &str) -> String {
+    pub(crate) fn to_pascal_case(s: &str) -> String {
         let mut result = String::new();
         let mut capitalize = true;
 
@@ -2049,7 +2455,7 @@ impl ReactGenerator {
         result
     }
 
-    fn to_camel_case(s: &str) -> String {
+    pub(crate) fn to_camel_case(s: &str) -> String {
         let mut result = String::new();
         let mut capitalize = false;
 
@@ -2068,31 +2474,261 @@ impl ReactGenerator {
     }
 }
 
+/// Generated code, with a [`Mark`] where the code of each page element
+/// starts and at every line inside it. Mark origins are paths such as
+/// `page.children[0]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub code: String,
+    pub marks: Vec<Mark>,
+}
+
 pub fn generate_react(document: &Document) -> Result<String, CodegenError> {
-    ReactGenerator::generate(document)
+    Ok(ReactGenerator::generate(document, &Actions::default(), &Components::default())?.code)
 }
 
-pub fn generate_router(name: &str, imports: &str, routes: &str) -> String {
-    format!(
-        r#"import React from 'react'
-import ReactDOM from 'react-dom/client'
-import {{ BrowserRouter, Routes, Route }} from 'react-router-dom'
-import './index.css'
+/// Like [`generate_react`], importing the project actions the pages call.
+pub fn generate_react_with_actions(
+    document: &Document,
+    actions: &Actions,
+) -> Result<String, CodegenError> {
+    Ok(ReactGenerator::generate(document, actions, &Components::default())?.code)
+}
 
-{}
+/// Like [`generate_react_with_actions`], placing project `components` and
+/// keeping the marks source maps are built from.
+pub fn generate_react_mapped(
+    document: &Document,
+    actions: &Actions,
+    components: &Components,
+) -> Result<Output, CodegenError> {
+    ReactGenerator::generate(document, actions, components)
+}
 
-ReactDOM.createRoot(document.getElementById('root')!).render(
-  <React.StrictMode>
-    <BrowserRouter>
-      <Routes>
-{}
-      </Routes>
-    </BrowserRouter>
-  </React.StrictMode>,
-)
-"#,
-        imports, routes
-    )
+/// Generates `src/components/<Name>.tsx` for `component`, with the marks
+/// source maps are built from.
+pub fn generate_component(
+    component: &Component,
+    actions: &Actions,
+    components: &Components,
+) -> Result<Output, CodegenError> {
+    ReactGenerator::generate_component(component, actions, components)
+}
+
+/// Path of the `page:` mapping of the `i`th page of `document` in its
+/// source: `page` for a single page file, `pages[i].page` otherwise.
+pub(crate) fn page_path(document: &Document, i: usize) -> String {
+    match document.pages.len() {
+        1 => "page".to_string(),
+        _ => format!("pages[{}].page", i),
+    }
+}
+
+/// A route as written in `main.tsx`: its URL path, relative to the route
+/// around it, what it renders and the routes nested in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRoute {
+    /// Empty for index routes and layouts that don't add to the path.
+    pub path: String,
+    pub index: bool,
+    pub target: RouteTarget,
+    pub children: Vec<PageRoute>,
+}
+
+/// What a route renders.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteTarget {
+    /// A page or layout component, and the module it is imported from.
+    /// A `lazy` page is loaded only when it is first shown.
+    Page {
+        component: String,
+        module: String,
+        lazy: bool,
+    },
+    /// A redirect to the path.
+    Redirect(String),
+}
+
+/// What shows while the page of a lazy route loads.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteFallback {
+    Spinner(nwl_shared::SpinnerElement),
+    /// A page component, and the module it is imported from.
+    Page {
+        component: String,
+        module: String,
+    },
+}
+
+impl Default for RouteFallback {
+    fn default() -> Self {
+        RouteFallback::Spinner(nwl_shared::SpinnerElement {
+            size: None,
+            label: None,
+            style: Vec::new(),
+        })
+    }
+}
+
+/// A page component `main.tsx` renders, the module it is imported from,
+/// and whether that module is only loaded when the page is first shown.
+struct RoutedPage<'r> {
+    component: &'r str,
+    module: &'r str,
+    lazy: bool,
+}
+
+/// Generates `src/main.tsx`, rendering the app with a route per page,
+/// nested as the routes of `nwl.yaml` are. Lazy pages are imported with
+/// `React.lazy`, and show `fallback` while they load.
+pub fn generate_router(routes: &[PageRoute], fallback: &RouteFallback) -> String {
+    let mut router = vec!["BrowserRouter", "Routes", "Route"];
+    let mut pages = Vec::new();
+    // The fallback shows while the other pages load, so it can't be lazy
+    // itself.
+    if let RouteFallback::Page { component, module } = fallback {
+        pages.push(RoutedPage {
+            component,
+            module,
+            lazy: false,
+        });
+    }
+    collect_targets(routes, &mut router, &mut pages);
+    let mut imports = vec![
+        Import::new("react").default("React"),
+        Import::new("react-dom/client").default("ReactDOM"),
+        Import::new("react-router-dom").names(router),
+        Import::new("./index.css"),
+    ];
+    imports.extend(
+        pages
+            .iter()
+            .filter(|page| !page.lazy)
+            .map(|page| Import::new(page.module).default(page.component)),
+    );
+
+    let mut statements: Vec<Statement> = pages
+        .iter()
+        .filter(|page| page.lazy)
+        .map(|page| Statement::Declare {
+            mutable: false,
+            pattern: page.component.to_string(),
+            init: Expr::call(
+                "React.lazy",
+                vec![Expr::arrow(
+                    &[],
+                    Expr::call("import", vec![Expr::Raw(escape::js_string(page.module))]),
+                )],
+            ),
+        })
+        .collect();
+    if pages.iter().any(|page| page.lazy) {
+        let fallback: Expr = match fallback {
+            RouteFallback::Spinner(spinner) => ReactGenerator {
+                state: HashMap::new(),
+            }
+            .generate_spinner(spinner)
+            .expect("spinners always generate")
+            .into(),
+            RouteFallback::Page { component, .. } => Element::new(component).into(),
+        };
+        statements.push(Statement::Declare {
+            mutable: false,
+            pattern: "fallback".to_string(),
+            init: fallback,
+        });
+    }
+
+    let app = Element::new("React.StrictMode").child(
+        Element::new("BrowserRouter")
+            .child(Element::new("Routes").children(route_elements(routes, &pages))),
+    );
+    statements.push(Statement::from(Expr::call(
+        "ReactDOM.createRoot(document.getElementById(\"root\")!).render",
+        vec![app.into()],
+    )));
+
+    let code = print::print_module(&Module {
+        imports,
+        statements,
+    });
+    if cfg!(test) {
+        if let Err(error) = verify::parse_tsx(&code) {
+            panic!("generated main.tsx does not parse: {}\n\n{}", error, code);
+        }
+    }
+    code
+}
+
+/// Adds `Navigate` to the names `main.tsx` imports from React Router when
+/// `routes` redirect, and each page component they render to `pages`,
+/// once. A page is lazy only when every route rendering it is, since any
+/// other route imports it into the main bundle anyway.
+fn collect_targets<'r>(
+    routes: &'r [PageRoute],
+    router: &mut Vec<&str>,
+    pages: &mut Vec<RoutedPage<'r>>,
+) {
+    for route in routes {
+        match &route.target {
+            RouteTarget::Page {
+                component,
+                module,
+                lazy,
+            } => match pages.iter_mut().find(|page| page.module == module) {
+                Some(page) => page.lazy &= *lazy,
+                None => pages.push(RoutedPage {
+                    component,
+                    module,
+                    lazy: *lazy,
+                }),
+            },
+            RouteTarget::Redirect(_) => {
+                if !router.contains(&"Navigate") {
+                    router.push("Navigate");
+                }
+            }
+        }
+        collect_targets(&route.children, router, pages);
+    }
+}
+
+/// The `<Route>` elements of `routes`, with their nested routes inside.
+/// Lazy `pages` are rendered in a `Suspense` showing the fallback.
+fn route_elements(routes: &[PageRoute], pages: &[RoutedPage]) -> Vec<Node> {
+    routes
+        .iter()
+        .map(|route| {
+            let mut element = Element::new("Route");
+            if route.index {
+                element = element.flag("index");
+            }
+            if !route.path.is_empty() {
+                element = element.attr("path", &route.path);
+            }
+            let target = match &route.target {
+                RouteTarget::Page {
+                    component, module, ..
+                } => {
+                    let page = Element::new(component);
+                    if pages.iter().any(|page| page.module == module && page.lazy) {
+                        Element::new("React.Suspense")
+                            .prop("fallback", Expr::raw("fallback"))
+                            .child(page)
+                    } else {
+                        page
+                    }
+                }
+                RouteTarget::Redirect(to) => {
+                    Element::new("Navigate").attr("to", to).flag("replace")
+                }
+            };
+            element
+                .prop("element", target)
+                .children(route_elements(&route.children, pages))
+                .into()
+        })
+        .collect()
 }
 
 impl From<CodegenError> for nwl_shared::CompileError {
@@ -2122,11 +2758,13 @@ mod tests {
                     }),
                     Element::Button(ButtonElement {
                         content: "Click Me".to_string(),
-                        onClick: Some("handleClick()".to_string()),
+                        onClick: Some(Handler::from("handleClick()")),
                         style: vec!["bg-blue-500".to_string(), "text-white".to_string()],
                     }),
                 ],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         }
     }
@@ -2155,7 +2793,9 @@ mod tests {
                     content: "Test Heading".to_string(),
                     style: vec!["text-xl".to_string(), "font-semibold".to_string()],
                 })],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         };
         let document = Document { pages: vec![page] };
@@ -2173,10 +2813,12 @@ mod tests {
                 style: vec![],
                 children: vec![Element::Button(ButtonElement {
                     content: "Submit".to_string(),
-                    onClick: Some("handleSubmit()".to_string()),
+                    onClick: Some(Handler::from("handleSubmit()")),
                     style: vec!["bg-primary".to_string()],
                 })],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         };
         let document = Document { pages: vec![page] };
@@ -2200,7 +2842,9 @@ mod tests {
                     content: "Title".to_string(),
                     style: vec![],
                 })],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         };
         let document = Document { pages: vec![page] };
@@ -2241,7 +2885,9 @@ mod tests {
                     content: "Welcome".to_string(),
                     style: vec![],
                 })],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         };
         let page2 = Page {
@@ -2253,7 +2899,9 @@ mod tests {
                     content: "About Us".to_string(),
                     style: vec![],
                 })],
+                params: vec![],
                 state: vec![],
+                computed: vec![],
             },
         };
         let document = Document {
@@ -2268,15 +2916,155 @@ mod tests {
 
     #[test]
     fn test_generate_router() {
-        let imports = "import Home from './home';\nimport About from './about';".to_string();
-        let routes = "        <Route path=\"/\" element={<Home />}} />\n        <Route path=\"/about\" element={<About />}} />\n".to_string();
-        let router = generate_router("TestApp", &imports, &routes);
-        assert!(router.contains("import React from 'react'"));
-        assert!(router.contains("BrowserRouter"));
-        assert!(router.contains("Home"));
-        assert!(router.contains("About"));
-        assert!(router.contains("\"/\""));
-        assert!(router.contains("\"/about\""));
+        let route = |path: &str, component: &str| PageRoute {
+            path: path.to_string(),
+            index: false,
+            target: RouteTarget::Page {
+                component: component.to_string(),
+                module: format!("./{}", component.to_lowercase()),
+                lazy: false,
+            },
+            children: Vec::new(),
+        };
+        let router = generate_router(
+            &[route("/", "Home"), route("/about", "About")],
+            &RouteFallback::default(),
+        );
+        assert_eq!(
+            router,
+            r#"import React from "react";
+import ReactDOM from "react-dom/client";
+import { BrowserRouter, Routes, Route } from "react-router-dom";
+import "./index.css";
+import Home from "./home";
+import About from "./about";
+
+ReactDOM.createRoot(document.getElementById("root")!).render(
+  <React.StrictMode>
+    <BrowserRouter>
+      <Routes>
+        <Route path="/" element={<Home />} />
+        <Route path="/about" element={<About />} />
+      </Routes>
+    </BrowserRouter>
+  </React.StrictMode>,
+);
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_nested_router() {
+        let page = |path: &str, component: &str, children: Vec<PageRoute>| PageRoute {
+            path: path.to_string(),
+            index: false,
+            target: RouteTarget::Page {
+                component: component.to_string(),
+                module: format!("./{}", component.to_lowercase()),
+                lazy: false,
+            },
+            children,
+        };
+        let home = PageRoute {
+            index: true,
+            ..page("", "Home", Vec::new())
+        };
+        let redirect = PageRoute {
+            path: "old-about".to_string(),
+            index: false,
+            target: RouteTarget::Redirect("/about".to_string()),
+            children: Vec::new(),
+        };
+        let router = generate_router(
+            &[
+                page(
+                    "/",
+                    "Shell",
+                    vec![home, page("about", "About", Vec::new()), redirect],
+                ),
+                page("*", "NotFound", Vec::new()),
+            ],
+            &RouteFallback::default(),
+        );
+        assert!(router.contains(
+            "import { BrowserRouter, Routes, Route, Navigate } from \"react-router-dom\";"
+        ));
+        assert!(router.contains(
+            r#"        <Route path="/" element={<Shell />}>
+          <Route index element={<Home />} />
+          <Route path="about" element={<About />} />
+          <Route path="old-about" element={<Navigate to="/about" replace />} />
+        </Route>
+        <Route path="*" element={<NotFound />} />
+"#
+        ));
+    }
+
+    #[test]
+    fn test_generate_lazy_router() {
+        let page = |path: &str, component: &str, lazy: bool| PageRoute {
+            path: path.to_string(),
+            index: false,
+            target: RouteTarget::Page {
+                component: component.to_string(),
+                module: format!("./{}", component.to_lowercase()),
+                lazy,
+            },
+            children: Vec::new(),
+        };
+        let routes = [
+            page("/", "Home", false),
+            page("/reports", "Reports", true),
+            page("/reports/all", "Reports", true),
+            page("/about", "About", true),
+            page("/info", "About", false),
+        ];
+        let router = generate_router(&routes, &RouteFallback::default());
+        assert!(router.contains(
+            r#"import Home from "./home";
+import About from "./about";
+
+const Reports = React.lazy(() => import("./reports"));
+
+const fallback = (
+  <div className="flex items-center justify-center">
+"#
+        ));
+        // `About` is also served eagerly, so it is never split out.
+        assert!(router.contains(
+            r#"        <Route path="/" element={<Home />} />
+        <Route
+          path="/reports"
+          element={
+            <React.Suspense fallback={fallback}>
+              <Reports />
+            </React.Suspense>
+          }
+        />
+"#
+        ));
+        assert!(router.contains(r#"<Route path="/about" element={<About />} />"#));
+
+        let router = generate_router(
+            &routes[..2],
+            &RouteFallback::Page {
+                component: "Loading".to_string(),
+                module: "./loading".to_string(),
+            },
+        );
+        assert!(router.contains(
+            r#"import Loading from "./loading";
+import Home from "./home";
+
+const Reports = React.lazy(() => import("./reports"));
+
+const fallback = <Loading />;
+"#
+        ));
+
+        // Without lazy routes, there is nothing to wait for.
+        let router = generate_router(&routes[..1], &RouteFallback::default());
+        assert!(!router.contains("fallback"));
     }
 
     #[test]
@@ -2291,11 +3079,13 @@ mod tests {
                     style: vec![],
                     onClick: None,
                 })],
+                params: vec![],
                 state: vec![StateDefinition {
                     name: "count".to_string(),
                     value_type: None,
                     initial: Some(serde_yaml::Value::Number(0.into())),
                 }],
+                computed: vec![],
             },
         };
         let document = Document { pages: vec![page] };
@@ -2306,13 +3096,402 @@ mod tests {
     }
 
     #[test]
-    fn test_format_layout_props() {
+    fn test_generate_typed_state() {
+        let yaml = r#"
+page:
+  name: Profile
+  state:
+    - name: user
+      type: "{ name: string, tags: string[] } | null"
+      initial:
+        name: "Ada \"Countess\""
+        tags: [math]
+    - name: size
+      type: "'sm' | 'lg'"
+    - name: items
+      initial: []
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        let expected = r#"  const [user, setUser] = useState<{ name: string; tags: string[] } | null>({
+    name: 'Ada "Countess"',
+    tags: ["math"],
+  });
+  const [size, setSize] = useState<"sm" | "lg">("sm");
+  const [items, setItems] = useState<any[]>([]);
+"#;
+        assert!(result.contains(expected), "{}", result);
+    }
+
+    #[test]
+    fn test_generate_conditional_elements() {
+        let yaml = r#"
+page:
+  name: Gate
+  state:
+    - name: loggedIn
+      initial: false
+    - name: count
+      initial: 0
+  children:
+    - heading: Welcome back
+      if: loggedIn
+      else:
+        - button: Log in
+    - text: "{count} items"
+      if: count
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        let expected = r#"  return (
+    <>
+      {loggedIn ? (
+        <h1>Welcome back</h1>
+      ) : (
+        <>
+          <button>Log in</button>
+        </>
+      )}
+      {count ? <p>{count} items</p> : null}
+    </>
+  );
+"#;
+        assert!(result.contains(expected), "{}", result);
+    }
+
+    #[test]
+    fn test_generate_computed_values() {
+        let yaml = r#"
+page:
+  name: Cart
+  state:
+    - name: items
+      type: "{ price: number }[]"
+    - name: rate
+      initial: 0.2
+  computed:
+    - name: total
+      value: subtotal + tax
+    - name: tax
+      value: subtotal * rate
+      type: number
+    - name: subtotal
+      value: "items.reduce((sum, item) => sum + item.price, 0)"
+      deps: [items]
+    - name: summary
+      value: "{ total, count: items.length }"
+  children:
+    - text: "Total: {total}"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        assert!(result.starts_with("import React, { useState, useMemo } from \"react\";"));
+        let expected = r#"  const subtotal = useMemo(
+    () => items.reduce((sum, item) => sum + item.price, 0),
+    [items],
+  );
+  const tax = useMemo<number>(() => subtotal * rate, [subtotal, rate]);
+  const total = useMemo(() => subtotal + tax, [subtotal, tax]);
+  const summary = useMemo(
+    () => ({ total, count: items.length }),
+    [total, items],
+  );
+"#;
+        assert!(result.contains(expected), "{}", result);
+    }
+
+    #[test]
+    fn test_generate_expressions() {
+        let yaml = r#"
+page:
+  name: Inbox
+  state:
+    - name: unread_count
+      initial: 0
+    - name: user_name
+      initial: ""
+  computed:
+    - name: shout
+      value: "upper(user_name) + '!'"
+  children:
+    - text: "{len(user_name)} letters, {max(unread_count, 1)} or more"
+      if: "unread_count == 0 || user_name != 'root'"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        assert!(
+            result.contains("useMemo(() => userName.toUpperCase() + \"!\", [userName])"),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains("{unreadCount === 0 || userName !== \"root\" ? ("),
+            "{}",
+            result
+        );
+        assert!(
+            result.contains(
+                "<p>\n          {userName.length} letters, {Math.max(unreadCount, 1)} or more\n        </p>"
+            ),
+            "{}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_generate_escapes_content() {
+        let yaml = r#"
+page:
+  name: Escapes
+  state:
+    - name: note
+      initial: "say \"hi\" \\ {not} </p>"
+  children:
+    - text: "1 < 2 && 3 > 2 } &copy; {note}"
+    - image:
+        src: /cat.png
+        alt: "A \"cat\""
+    - input:
+        placeholder: "C:\\path"
+    - email:
+        address: team@example.com
+        subject: "Q&A?"
+    - copy-button:
+        content: "Note: {note}"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        let expected = [
+            r#"useState<string>('say "hi" \\ {not} </p>')"#,
+            "<p>1 &lt; 2 && 3 &gt; 2 &#125; &amp;copy; {note}</p>",
+            r#"<img src="/cat.png" alt={'A "cat"'} />"#,
+            r#"<input placeholder="C:\path" />"#,
+            r#"href="mailto:team@example.com?subject=Q%26A%3F""#,
+            "navigator.clipboard.writeText(`Note: ${note}`)",
+        ];
+        for expected in expected {
+            assert!(result.contains(expected), "{}\n{}", expected, result);
+        }
+    }
+
+    #[test]
+    fn test_generate_action_imports() {
+        let mut actions = Actions::default();
+        for name in ["save", "load", "track"] {
+            actions.add(crate::actions::Action::new(
+                &nwl_shared::ActionDefinition {
+                    name: name.to_string(),
+                    inputs: vec![],
+                    handler: String::new(),
+                },
+                vec![],
+            ));
+        }
+        let yaml = r#"
+page:
+  name: Editor
+  children:
+    - form:
+        onSubmit: "track()"
+        children:
+          - button: {content: Save, onClick: "save()"}
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let document = Document { pages: vec![page] };
+        let result = generate_react_with_actions(&document, &actions).unwrap();
+        assert!(result.starts_with(
+            "import React from \"react\";\nimport { save, track } from \"./actions\";\n\nexport default"
+        ));
+        assert!(result.contains("onClick={() => save()}"));
+
+        let result = generate_react(&document).unwrap();
+        assert!(result.starts_with("import React from \"react\";\n\nexport default"));
+    }
+
+    #[test]
+    fn test_generate_module_imports() {
+        let yaml = r#"
+component:
+  name: Dashboard
+  children:
+    - react: ./widgets/Chart
+    - react: ./widgets/Chart
+      if: detailed
+    - react: ./widgets/Chart
+      export: Legend
+    - react: ./widgets/Chart
+      as: Sparkline
+    - react: recharts
+      export: Tooltip
+"#;
+        let file: nwl_shared::ComponentFile = serde_yaml::from_str(yaml).unwrap();
+        let component = Component::new(
+            &file.component,
+            crate::source_index::SourceIndex::new(yaml, None),
+        );
+        let code = generate_component(&component, &Actions::default(), &Components::default())
+            .unwrap()
+            .code;
+        assert!(code.starts_with(
+            "import React from \"react\";\n\
+             import Chart, { Legend } from \"../widgets/Chart\";\n\
+             import Sparkline from \"../widgets/Chart\";\n\
+             import { Tooltip } from \"recharts\";\n"
+        ));
+        assert!(code.contains("{detailed ? <Chart /> : null}"));
+    }
+
+    #[test]
+    fn test_generate_structured_handlers() {
+        let yaml = r#"
+page:
+  name: Todos
+  state:
+    - name: todos
+      type: string[]
+    - name: draft
+      initial: ""
+    - name: menu_open
+      initial: false
+  children:
+    - button:
+        content: Add
+        onClick:
+          - append: todos
+            value: draft
+          - set: draft
+            to: ""
+    - button:
+        content: Menu
+        onClick: {toggle: menu_open}
+    - form:
+        onSubmit:
+          - call: save
+            with: [todos, "draft.trim()"]
+          - navigate: /done
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        let expected = r#"      <button
+        onClick={() => {
+          setTodos([...todos, draft]);
+          setDraft("");
+        }}
+      >
+        Add
+      </button>
+      <button onClick={() => setMenuOpen(!menuOpen)}>Menu</button>
+      <form
+        onSubmit={(e) => {
+          e.preventDefault();
+          save(todos, draft.trim());
+          navigate("/done");
+        }}
+      />
+"#;
+        assert!(result.contains(expected), "{}", result);
+        assert!(result.contains("  const navigate = useNavigate();\n"));
+    }
+
+    #[test]
+    fn test_generate_data_list() {
+        let yaml = r#"
+page:
+  name: Dashboard
+  state:
+    - name: projects
+      type: "{ id: number, name: string }[]"
+  children:
+    - element: list
+      data: projects
+      key: id
+      children:
+        - heading: "{project.name}"
+      empty:
+        - text: No projects yet
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        assert!(result.contains("{projects.length === 0 && ("));
+        assert!(result.contains("<p>No projects yet</p>"));
+        assert!(result.contains("{projects.map((project) => ("));
+        assert!(
+            result.contains("<div key={project.id} className=\"p-2 border-b last:border-b-0\">")
+        );
+        assert!(result.contains("<h1>{project.name}</h1>"));
+    }
+
+    #[test]
+    fn test_generate_typed_bindings() {
+        let yaml = r#"
+page:
+  name: Settings
+  state:
+    - name: age
+      type: number | null
+    - name: notify
+      initial: true
+    - name: due
+      type: string | null
+    - name: attachments
+      type: File[]
+    - name: rating
+      initial: 3
+  children:
+    - element: input
+      bind: age
+    - element: checkbox
+      bind: notify
+    - element: date-input
+      bind: due
+    - element: file-upload
+      bind: attachments
+      multiple: true
+    - element: radio-group
+      bind: rating
+      options:
+        - value: "1"
+        - value: "3"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let result = generate_react(&Document { pages: vec![page] }).unwrap();
+        let expected = r#"      <input
+        type="number"
+        value={age ?? ""}
+        onChange={(e) =>
+          setAge(e.target.value === "" ? null : Number(e.target.value))
+        }
+      />
+      <input
+        type="checkbox"
+        checked={notify}
+        onChange={(e) => setNotify(e.target.checked)}
+      />
+      <input
+        type="date"
+        value={due ?? ""}
+        onChange={(e) => setDue(e.target.value || null)}
+      />
+      <input
+        type="file"
+        multiple
+        onChange={(e) => setAttachments(Array.from(e.target.files ?? []))}
+      />
+"#;
+        assert!(result.contains(expected), "{}", result);
+        assert!(result.contains(
+            "            checked={rating === 3}\n            onChange={() => setRating(3)}\n"
+        ));
+    }
+
+    #[test]
+    fn test_layout_class() {
         let layout = Layout {
             layout_type: LayoutType::Column,
             columns: None,
             properties: vec!["items-center".to_string(), "gap-4".to_string()],
         };
-        let result = ReactGenerator::format_layout_props(&layout);
+        let result = ReactGenerator::layout_class(&layout);
         assert!(result.contains("flex flex-col"));
         assert!(result.contains("items-center"));
         assert!(result.contains("gap-4"));
@@ -0,0 +1,1068 @@
+//! Prints the syntax tree as TypeScript and JSX, formatted the way
+//! Prettier formats it with its default options: 80 columns, two-space
+//! indentation, double quotes, semicolons and trailing commas.
+
+use super::doc::{
+    broken_group, concat, conditional_group, fill, group, hardline, if_break, indent, join, line,
+    nil, softline, source, text, will_break, Doc, Mark,
+};
+use super::jsx::{Attribute, Body, Element, Expr, Import, Module, Node, Statement, Value};
+use crate::escape;
+
+const WIDTH: usize = 80;
+
+/// JSX whitespace that would otherwise be lost at the start or end of a
+/// line.
+const RAW_SPACE: &str = "{\" \"}";
+
+pub fn print_module(module: &Module) -> String {
+    print_module_marked(module).0
+}
+
+/// Like [`print_module`], also returning where the code of each element
+/// with an origin starts, and every line inside it.
+pub fn print_module_marked(module: &Module) -> (String, Vec<Mark>) {
+    let mut parts = Vec::new();
+    for import in &module.imports {
+        parts.push(import_doc(import));
+        parts.push(hardline());
+    }
+    for (i, statement) in module.statements.iter().enumerate() {
+        if i > 0 || !module.imports.is_empty() {
+            parts.push(hardline());
+        }
+        parts.push(statement_doc(statement));
+        parts.push(hardline());
+    }
+    super::doc::print_marked(concat(parts), WIDTH)
+}
+
+fn import_doc(import: &Import) -> Doc {
+    let source = text(escape::js_string(&import.source));
+    let mut clause = Vec::new();
+    if let Some(default) = &import.default {
+        clause.push(text(default.as_str()));
+    }
+    if !import.names.is_empty() {
+        clause.push(group(concat(vec![
+            text("{"),
+            indent(concat(vec![
+                line(),
+                join(
+                    concat(vec![text(","), line()]),
+                    import
+                        .names
+                        .iter()
+                        .map(|name| text(name.as_str()))
+                        .collect(),
+                ),
+            ])),
+            if_break(text(","), nil()),
+            line(),
+            text("}"),
+        ])));
+    }
+    if clause.is_empty() {
+        return concat(vec![text("import "), source, text(";")]);
+    }
+    concat(vec![
+        text("import "),
+        join(text(", "), clause),
+        text(" from "),
+        source,
+        text(";"),
+    ])
+}
+
+fn statement_doc(statement: &Statement) -> Doc {
+    match statement {
+        Statement::Expression(expr) => concat(vec![expression(expr, Parent::Statement), text(";")]),
+        Statement::Declare {
+            mutable,
+            pattern,
+            init,
+        } => concat(vec![
+            text(if *mutable { "let " } else { "const " }),
+            text(pattern.as_str()),
+            text(" = "),
+            expression(init, Parent::Other),
+            text(";"),
+        ]),
+        Statement::Return(None) => text("return;"),
+        Statement::Return(Some(expr)) => concat(vec![
+            text("return "),
+            expression(expr, Parent::Other),
+            text(";"),
+        ]),
+        Statement::If { test, then } => concat(vec![
+            text("if ("),
+            group(expression(test, Parent::Other)),
+            text(") "),
+            block(then),
+        ]),
+        Statement::Component {
+            name,
+            parameters,
+            props,
+            body,
+        } => concat(vec![
+            text(format!("export default function {}", name)),
+            parameters_doc(parameters, props.as_deref()),
+            text(" "),
+            block(body),
+        ]),
+        Statement::Interface { name, fields } => {
+            let fields: Vec<Doc> = fields
+                .iter()
+                .map(|field| {
+                    text(format!(
+                        "{}{}: {};",
+                        field.name,
+                        if field.optional { "?" } else { "" },
+                        field.ty
+                    ))
+                })
+                .collect();
+            concat(vec![
+                text(format!("export interface {} ", name)),
+                if fields.is_empty() {
+                    text("{}")
+                } else {
+                    concat(vec![
+                        text("{"),
+                        indent(concat(vec![hardline(), join(hardline(), fields)])),
+                        hardline(),
+                        text("}"),
+                    ])
+                },
+            ])
+        }
+    }
+}
+
+/// `()`, or destructured props `({ a, b = 1 }: Props)` that break one per
+/// line when they don't fit.
+fn parameters_doc(parameters: &[(String, Option<Expr>)], props: Option<&str>) -> Doc {
+    if parameters.is_empty() {
+        return text("()");
+    }
+    let parameters = parameters
+        .iter()
+        .map(|(name, default)| match default {
+            Some(default) => concat(vec![
+                text(format!("{} = ", name)),
+                expression(default, Parent::Other),
+            ]),
+            None => text(name.as_str()),
+        })
+        .collect();
+    let props = props
+        .map(|props| format!(": {}", props))
+        .unwrap_or_default();
+    group(concat(vec![
+        text("({"),
+        indent(concat(vec![
+            line(),
+            join(concat(vec![text(","), line()]), parameters),
+        ])),
+        if_break(text(","), nil()),
+        line(),
+        text(format!("}}{})", props)),
+    ]))
+}
+
+fn block(statements: &[Statement]) -> Doc {
+    if statements.is_empty() {
+        return text("{}");
+    }
+    concat(vec![
+        text("{"),
+        indent(concat(vec![
+            hardline(),
+            join(hardline(), statements.iter().map(statement_doc).collect()),
+        ])),
+        hardline(),
+        text("}"),
+    ])
+}
+
+/// What an expression is printed inside, which decides whether JSX gets
+/// parentheses and how arrow functions break.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parent {
+    Statement,
+    /// `{…}` in JSX.
+    Container,
+    /// An argument of a call. `expanded` is the last argument printed to
+    /// hug the call's parentheses; `in_container` is a call in `{…}`.
+    Argument {
+        expanded: bool,
+        in_container: bool,
+    },
+    /// Anywhere else, such as an initializer or the test of a condition.
+    Other,
+}
+
+fn expression(expr: &Expr, parent: Parent) -> Doc {
+    match expr {
+        Expr::Raw(code) => raw(code),
+        Expr::Jsx(node) => match parent {
+            Parent::Other => parenthesized(node_doc(node), false),
+            _ => node_doc(node),
+        },
+        Expr::Call { callee, arguments } => concat(vec![
+            expression(callee, Parent::Other),
+            call_arguments(arguments, parent == Parent::Container),
+        ]),
+        Expr::Arrow { parameters, body } => arrow(parameters, body, parent),
+        Expr::Conditional {
+            test,
+            consequent,
+            alternate,
+        } => conditional(test, consequent, alternate),
+        Expr::And(left, right) => {
+            let left = expression(left, Parent::Other);
+            if let Expr::Jsx(node) = &**right {
+                return group(concat(vec![
+                    left,
+                    text(" && "),
+                    parenthesized(node_doc(node), false),
+                ]));
+            }
+            let right = concat(vec![line(), expression(right, Parent::Other)]);
+            let right = if parent == Parent::Container {
+                right
+            } else {
+                indent(right)
+            };
+            group(concat(vec![left, text(" &&"), right]))
+        }
+        Expr::Array(items) if items.is_empty() => text("[]"),
+        Expr::Array(items) => group(concat(vec![
+            text("["),
+            indent(concat(vec![
+                softline(),
+                join(
+                    concat(vec![text(","), line()]),
+                    items
+                        .iter()
+                        .map(|item| expression(item, Parent::Other))
+                        .collect(),
+                ),
+            ])),
+            if_break(text(","), nil()),
+            softline(),
+            text("]"),
+        ])),
+        Expr::Object(properties) if properties.is_empty() => text("{}"),
+        Expr::Object(properties) => group(concat(vec![
+            text("{"),
+            indent(concat(vec![
+                line(),
+                join(
+                    concat(vec![text(","), line()]),
+                    properties
+                        .iter()
+                        .map(|(key, value)| {
+                            concat(vec![
+                                text(format!("{}: ", key)),
+                                expression(value, Parent::Other),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ])),
+            if_break(text(","), nil()),
+            line(),
+            text("}"),
+        ])),
+    }
+}
+
+/// Code used as written. Its lines keep their relative indentation.
+fn raw(code: &str) -> Doc {
+    let lines: Vec<Doc> = code.lines().map(|line| text(line.trim_end())).collect();
+    match lines.len() {
+        0 => nil(),
+        1 => lines.into_iter().next().unwrap_or_else(nil),
+        _ => join(hardline(), lines),
+    }
+}
+
+/// JSX that moves onto its own lines between parentheses when it breaks.
+fn parenthesized(jsx: Doc, broken: bool) -> Doc {
+    let contents = concat(vec![
+        if_break(text("("), nil()),
+        indent(concat(vec![softline(), jsx])),
+        softline(),
+        if_break(text(")"), nil()),
+    ]);
+    if broken {
+        broken_group(contents)
+    } else {
+        group(contents)
+    }
+}
+
+fn call_arguments(arguments: &[Expr], in_container: bool) -> Doc {
+    if arguments.is_empty() {
+        return text("()");
+    }
+    let printed: Vec<Doc> = arguments
+        .iter()
+        .map(|argument| {
+            expression(
+                argument,
+                Parent::Argument {
+                    expanded: false,
+                    in_container,
+                },
+            )
+        })
+        .collect();
+    let comma = || concat(vec![text(","), line()]);
+    let all_broken_out = broken_group(concat(vec![
+        text("("),
+        indent(concat(vec![
+            line(),
+            join(comma(), printed.clone()),
+            text(","),
+        ])),
+        line(),
+        text(")"),
+    ]));
+
+    if !should_group_last(arguments) {
+        let contents = concat(vec![
+            text("("),
+            indent(concat(vec![softline(), join(comma(), printed.clone())])),
+            if_break(text(","), nil()),
+            softline(),
+            text(")"),
+        ]);
+        return if printed.iter().any(will_break) {
+            broken_group(contents)
+        } else {
+            group(contents)
+        };
+    }
+
+    // The last argument, such as a callback, hugs the parentheses when the
+    // ones before it fit on the line.
+    let (last, init) = printed.split_last().expect("arguments are not empty");
+    if init.iter().any(will_break) {
+        return all_broken_out;
+    }
+    let expanded = expression(
+        &arguments[arguments.len() - 1],
+        Parent::Argument {
+            expanded: true,
+            in_container,
+        },
+    );
+    let mut head = vec![text("(")];
+    for argument in init {
+        head.push(argument.clone());
+        head.push(text(", "));
+    }
+    let hugged = |last: Doc| {
+        let mut parts = head.clone();
+        parts.push(last);
+        parts.push(text(")"));
+        concat(parts)
+    };
+    let chosen = conditional_group(vec![
+        hugged(expanded.clone()),
+        hugged(broken_group(expanded)),
+        all_broken_out,
+    ]);
+    if will_break(last) {
+        // Whatever contains a call that is sure to break breaks too.
+        concat(vec![chosen, broken_group(nil())])
+    } else {
+        chosen
+    }
+}
+
+/// Whether the last argument of a call can be printed hugging the call's
+/// parentheses, as Prettier does for callbacks and literals.
+fn should_group_last(arguments: &[Expr]) -> bool {
+    let Some(last) = arguments.last() else {
+        return false;
+    };
+    let could_expand = match last {
+        Expr::Object(properties) => !properties.is_empty(),
+        Expr::Array(items) => !items.is_empty(),
+        Expr::Arrow { .. } => true,
+        _ => false,
+    };
+    let penultimate = arguments.len().checked_sub(2).map(|i| &arguments[i]);
+    let same_kind =
+        penultimate.is_some_and(|p| std::mem::discriminant(p) == std::mem::discriminant(last));
+    // `useMemo(() => …, [deps])` keeps its dependencies with the callback.
+    let hook_with_dependencies = arguments.len() == 2
+        && matches!(penultimate, Some(Expr::Arrow { .. }))
+        && matches!(last, Expr::Array(_));
+    could_expand && !same_kind && !hook_with_dependencies
+}
+
+fn arrow(parameters: &[String], body: &Body, parent: Parent) -> Doc {
+    let signature = text(format!("({}) =>", parameters.join(", ")));
+    let body = match body {
+        Body::Block(statements) => {
+            return group(concat(vec![signature, text(" "), block(statements)]))
+        }
+        Body::Expression(body) => body,
+    };
+    match body {
+        Expr::Jsx(node) => {
+            // A callback rendering JSX from `{items.map(…)}` always puts the
+            // JSX on its own lines.
+            let broken = matches!(
+                parent,
+                Parent::Argument {
+                    in_container: true,
+                    ..
+                }
+            );
+            group(concat(vec![
+                signature,
+                text(" "),
+                parenthesized(node_doc(node), broken),
+            ]))
+        }
+        Expr::Array(_) | Expr::Object(_) | Expr::Arrow { .. } => group(concat(vec![
+            signature,
+            text(" "),
+            expression(body, Parent::Other),
+        ])),
+        _ => {
+            let trailing = match parent {
+                Parent::Argument { expanded: true, .. } => {
+                    concat(vec![if_break(text(","), nil()), softline()])
+                }
+                Parent::Container => softline(),
+                _ => nil(),
+            };
+            group(concat(vec![
+                signature,
+                group(concat(vec![
+                    indent(concat(vec![line(), expression(body, Parent::Other)])),
+                    trailing,
+                ])),
+            ]))
+        }
+    }
+}
+
+fn conditional(test: &Expr, consequent: &Expr, alternate: &Expr) -> Doc {
+    let test = expression(test, Parent::Other);
+    if !consequent.is_jsx() && !alternate.is_jsx() {
+        return group(concat(vec![
+            test,
+            indent(concat(vec![
+                line(),
+                text("? "),
+                expression(consequent, Parent::Other),
+                line(),
+                text(": "),
+                expression(alternate, Parent::Other),
+            ])),
+        ]));
+    }
+
+    // With JSX, each branch moves between parentheses on its own lines.
+    let is_nil =
+        |expr: &Expr| matches!(expr, Expr::Raw(code) if code == "null" || code == "undefined");
+    let wrap = |expr: &Expr| {
+        let doc = expression(expr, Parent::Statement);
+        if is_nil(expr) || matches!(expr, Expr::Conditional { .. }) {
+            doc
+        } else {
+            concat(vec![
+                if_break(text("("), nil()),
+                indent(concat(vec![softline(), doc])),
+                softline(),
+                if_break(text(")"), nil()),
+            ])
+        }
+    };
+    group(concat(vec![
+        test,
+        text(" ? "),
+        wrap(consequent),
+        text(" : "),
+        wrap(alternate),
+    ]))
+}
+
+fn node_doc(node: &Node) -> Doc {
+    match node {
+        Node::Element(element) => element_doc(element),
+        Node::Fragment(children) => jsx_element(None, &[], children),
+        Node::Text(content) => text(escape::jsx_text(content)),
+        Node::Expression(expr) => container(expr, true),
+    }
+}
+
+/// `{expr}` as a JSX child or attribute value.
+fn container(expr: &Expr, in_element: bool) -> Doc {
+    let inline = match expr {
+        Expr::Call { .. } | Expr::Arrow { .. } | Expr::Array(_) | Expr::Object(_) => true,
+        Expr::Raw(code) => code.starts_with('`'),
+        Expr::Conditional { .. } | Expr::And(..) => in_element,
+        _ => false,
+    };
+    let expr = expression(expr, Parent::Container);
+    if inline {
+        group(concat(vec![text("{"), expr, text("}")]))
+    } else {
+        group(concat(vec![
+            text("{"),
+            indent(concat(vec![softline(), expr])),
+            softline(),
+            text("}"),
+        ]))
+    }
+}
+
+fn element_doc(element: &Element) -> Doc {
+    let doc = jsx_element(Some(&element.name), &element.attributes, &element.children);
+    match &element.origin {
+        Some(origin) => source(origin.as_str(), doc),
+        None => doc,
+    }
+}
+
+fn attribute_doc(attribute: &Attribute) -> Doc {
+    let name = attribute.name.as_str();
+    match &attribute.value {
+        Value::True => text(name),
+        Value::String(value) => match escape::jsx_string(value) {
+            Some(literal) => text(format!("{}={}", name, literal)),
+            None => concat(vec![
+                text(format!("{}=", name)),
+                container(&Expr::Raw(escape::js_string(value)), false),
+            ]),
+        },
+        Value::Expression(expr) => concat(vec![text(format!("{}=", name)), container(expr, false)]),
+    }
+}
+
+fn opening_doc(name: Option<&str>, attributes: &[Attribute], self_closing: bool) -> Doc {
+    let Some(name) = name else {
+        return text("<>");
+    };
+    let end = if self_closing { " />" } else { ">" };
+    if attributes.is_empty() {
+        return text(format!("<{}{}", name, end));
+    }
+    // A single string attribute stays on the tag's line, however long.
+    if let [attribute] = attributes {
+        if let Value::String(value) = &attribute.value {
+            if escape::jsx_string(value).is_some() {
+                return group(concat(vec![
+                    text(format!("<{} ", name)),
+                    attribute_doc(attribute),
+                    text(end),
+                ]));
+            }
+        }
+    }
+    let attributes = attributes
+        .iter()
+        .flat_map(|attribute| [line(), attribute_doc(attribute)])
+        .collect();
+    group(concat(vec![
+        text(format!("<{}", name)),
+        indent(concat(attributes)),
+        if self_closing {
+            concat(vec![line(), text("/>")])
+        } else {
+            concat(vec![softline(), text(">")])
+        },
+    ]))
+}
+
+/// A piece of JSX children, before they are laid out as a fill.
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Content(Doc),
+    Empty,
+    /// Between two words of text.
+    Line,
+    Softline,
+    Hardline,
+    /// A space that becomes `{" "}` when it falls at a line break.
+    Whitespace,
+}
+
+impl Part {
+    fn is_line_or_empty(&self) -> bool {
+        matches!(
+            self,
+            Part::Empty | Part::Line | Part::Softline | Part::Hardline
+        )
+    }
+
+    fn to_doc(&self) -> Doc {
+        match self {
+            Part::Content(doc) => doc.clone(),
+            Part::Empty => text(""),
+            Part::Line => line(),
+            Part::Softline => softline(),
+            Part::Hardline => hardline(),
+            Part::Whitespace => if_break(concat(vec![text(RAW_SPACE), softline()]), text(" ")),
+        }
+    }
+}
+
+/// An element, or a fragment without a name, laid out the way Prettier
+/// lays out JSX: on one line when it fits and has at most one attribute,
+/// expression or text run, and otherwise with its children on their own
+/// lines, wrapping text like a paragraph.
+fn jsx_element(name: Option<&str>, attributes: &[Attribute], children: &[Node]) -> Doc {
+    let children = normalize(children);
+    if children.is_empty() {
+        return match name {
+            Some(_) => opening_doc(name, attributes, true),
+            None => text("<></>"),
+        };
+    }
+    let opening = opening_doc(name, attributes, false);
+    let closing = text(match name {
+        Some(name) => format!("</{}>", name),
+        None => "</>".to_string(),
+    });
+
+    let contains_tag = children
+        .iter()
+        .any(|child| matches!(child, Node::Element(_) | Node::Fragment(_)));
+    let expressions = children
+        .iter()
+        .filter(|child| matches!(child, Node::Expression(_)))
+        .count();
+    let contains_text = children.iter().any(|child| matches!(child, Node::Text(_)));
+    let mut forced_break =
+        will_break(&opening) || contains_tag || attributes.len() > 1 || expressions > 1;
+
+    let mut parts = children_parts(&children);
+    clean_separators(&mut parts, contains_text);
+
+    let mut multiline = Vec::with_capacity(parts.len());
+    for (i, part) in parts.iter().enumerate() {
+        if *part == Part::Whitespace {
+            let leading = i == 1 && parts[0] == Part::Empty;
+            let after_break =
+                i >= 2 && parts[i - 1] == Part::Empty && parts[i - 2] == Part::Hardline;
+            if leading && parts.len() == 2 {
+                multiline.push(text(RAW_SPACE));
+                continue;
+            }
+            if leading {
+                multiline.push(concat(vec![text(RAW_SPACE), hardline()]));
+                continue;
+            }
+            if i == parts.len() - 1 || after_break {
+                multiline.push(text(RAW_SPACE));
+                continue;
+            }
+        }
+        let doc = part.to_doc();
+        forced_break |= will_break(&doc);
+        multiline.push(doc);
+    }
+
+    let content = if contains_text {
+        fill(multiline)
+    } else {
+        broken_group(concat(multiline))
+    };
+    let multiline_element = group(concat(vec![
+        opening.clone(),
+        indent(concat(vec![hardline(), content])),
+        hardline(),
+        closing.clone(),
+    ]));
+    if forced_break {
+        return multiline_element;
+    }
+
+    let mut flat = vec![opening];
+    flat.extend(parts.iter().map(Part::to_doc));
+    flat.push(closing);
+    conditional_group(vec![group(concat(flat)), multiline_element])
+}
+
+/// Merges adjacent text and collapses its whitespace, which JSX, like
+/// HTML, only reads as a separator.
+fn normalize(children: &[Node]) -> Vec<Node> {
+    let mut normalized: Vec<Node> = Vec::with_capacity(children.len());
+    for child in children {
+        match (child, normalized.last_mut()) {
+            (Node::Text(content), Some(Node::Text(previous))) => previous.push_str(content),
+            _ => normalized.push(child.clone()),
+        }
+    }
+    normalized
+        .into_iter()
+        .filter_map(|child| match child {
+            Node::Text(content) => {
+                let collapsed: String = split_words(&content).join_whitespace();
+                (!collapsed.is_empty()).then_some(Node::Text(collapsed))
+            }
+            child => Some(child),
+        })
+        .collect()
+}
+
+fn is_jsx_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\n' | '\r' | '\t')
+}
+
+/// Splits text on runs of whitespace, keeping each run, like JavaScript's
+/// `text.split(/([ \n\r\t]+)/)`: words are at even indices and an empty
+/// first or last word marks leading or trailing whitespace.
+fn split_words(text: &str) -> Words<'_> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_whitespace = false;
+    for (i, c) in text.char_indices() {
+        if is_jsx_whitespace(c) != in_whitespace {
+            words.push(&text[start..i]);
+            start = i;
+            in_whitespace = !in_whitespace;
+        }
+    }
+    words.push(&text[start..]);
+    if in_whitespace {
+        words.push("");
+    }
+    Words(words)
+}
+
+struct Words<'a>(Vec<&'a str>);
+
+impl Words<'_> {
+    /// The text with each run of whitespace as a single space.
+    fn join_whitespace(&self) -> String {
+        self.0
+            .iter()
+            .enumerate()
+            .map(|(i, word)| if i % 2 == 1 { " " } else { word })
+            .collect()
+    }
+}
+
+/// Whether `node` is an element printed as self-closing.
+fn is_self_closing(node: &Node) -> bool {
+    matches!(node, Node::Element(element) if normalize(&element.children).is_empty())
+}
+
+/// The separator after `word` when no whitespace follows it: text may
+/// break away from an element with no space in between, except from a
+/// self-closing one, which always does unless the word is a single
+/// character.
+fn separator_without_whitespace(word: &str, child: &Node, next: Option<&Node>) -> Part {
+    if is_self_closing(child) || next.is_some_and(is_self_closing) {
+        if word.chars().count() == 1 {
+            Part::Softline
+        } else {
+            Part::Hardline
+        }
+    } else {
+        Part::Softline
+    }
+}
+
+fn children_parts(children: &[Node]) -> Vec<Part> {
+    let mut parts = Vec::new();
+    for (i, child) in children.iter().enumerate() {
+        let next = children.get(i + 1);
+        let Node::Text(content) = child else {
+            parts.push(Part::Content(node_doc(child)));
+            match next {
+                Some(Node::Text(next_text)) => {
+                    let trimmed = next_text.trim_matches(is_jsx_whitespace);
+                    let first_word = split_words(trimmed).0[0];
+                    parts.push(separator_without_whitespace(first_word, child, next));
+                }
+                _ => parts.push(Part::Hardline),
+            }
+            continue;
+        };
+
+        let Words(words) = split_words(content);
+        let mut words = &words[..];
+        if words[0].is_empty() {
+            parts.push(Part::Empty);
+            parts.push(Part::Whitespace);
+            words = &words[2..];
+        }
+        let mut trailing_whitespace = false;
+        if words.last() == Some(&"") {
+            words = &words[..words.len() - 1];
+            if let Some((_, rest)) = words.split_last() {
+                words = rest;
+                trailing_whitespace = true;
+            }
+        }
+        let Some(last_word) = words.last() else {
+            continue;
+        };
+        for (j, word) in words.iter().enumerate() {
+            if j % 2 == 1 {
+                parts.push(Part::Line);
+            } else {
+                parts.push(Part::Content(text(escape::jsx_text(word))));
+            }
+        }
+        if trailing_whitespace {
+            parts.push(Part::Whitespace);
+        } else {
+            parts.push(separator_without_whitespace(last_word, child, next));
+        }
+    }
+    parts
+}
+
+/// Drops the redundant separators that arise between text and other
+/// children, and any lines at either end.
+fn clean_separators(parts: &mut Vec<Part>, contains_text: bool) {
+    let mut i = parts.len().saturating_sub(2) as isize;
+    while i >= 0 {
+        let at = |k: usize| parts.get(k);
+        let k = i as usize;
+        let empty_next = at(k + 1) == Some(&Part::Empty);
+        let current = at(k);
+        let after = at(k + 2);
+        let is_line = |part: Option<&Part>| matches!(part, Some(Part::Hardline | Part::Softline));
+
+        let pair_of_empty = current == Some(&Part::Empty) && empty_next;
+        let pair_of_hardlines =
+            current == Some(&Part::Hardline) && empty_next && after == Some(&Part::Hardline);
+        let line_then_whitespace =
+            is_line(current) && empty_next && after == Some(&Part::Whitespace);
+        let whitespace_then_line =
+            current == Some(&Part::Whitespace) && empty_next && is_line(after);
+        let double_whitespace =
+            current == Some(&Part::Whitespace) && empty_next && after == Some(&Part::Whitespace);
+        let hard_and_soft = empty_next
+            && matches!(
+                (current, after),
+                (Some(Part::Softline), Some(Part::Hardline))
+                    | (Some(Part::Hardline), Some(Part::Softline))
+            );
+
+        if (pair_of_hardlines && contains_text)
+            || pair_of_empty
+            || line_then_whitespace
+            || double_whitespace
+        {
+            parts.drain(k..k + 2);
+        } else if whitespace_then_line || hard_and_soft {
+            parts.drain(k + 1..(k + 3).min(parts.len()));
+        }
+        i -= 1;
+    }
+
+    while parts.last().is_some_and(Part::is_line_or_empty) {
+        parts.pop();
+    }
+    while parts.len() > 1 && parts[0].is_line_or_empty() && parts[1].is_line_or_empty() {
+        parts.drain(..2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::jsx::Field;
+
+    fn print_jsx(node: impl Into<Node>) -> String {
+        super::super::doc::print_marked(node_doc(&node.into()), WIDTH).0
+    }
+
+    #[test]
+    fn test_short_elements_stay_on_one_line() {
+        let element = Element::new("p")
+            .child(Node::expression("count"))
+            .text(" items");
+        assert_eq!(print_jsx(element), "<p>{count} items</p>");
+        assert_eq!(print_jsx(Element::new("br")), "<br />");
+        assert_eq!(print_jsx(Node::Fragment(vec![])), "<></>");
+    }
+
+    #[test]
+    fn test_nested_elements_break() {
+        let element = Element::new("label")
+            .class("flex")
+            .child(Element::new("input").attr("type", "checkbox"))
+            .text(" Remember me");
+        assert_eq!(
+            print_jsx(element),
+            "<label className=\"flex\">\n  <input type=\"checkbox\" /> Remember me\n</label>"
+        );
+    }
+
+    #[test]
+    fn test_long_text_wraps() {
+        let words = "lorem ipsum dolor sit amet ".repeat(4);
+        let element = Element::new("p")
+            .text(words.trim())
+            .child(Element::new("br"));
+        assert_eq!(
+            print_jsx(element),
+            "<p>\n  lorem ipsum dolor sit amet lorem ipsum dolor sit amet lorem ipsum dolor sit\n  amet lorem ipsum dolor sit amet\n  <br />\n</p>"
+        );
+    }
+
+    #[test]
+    fn test_whitespace_at_line_breaks() {
+        let element = Element::new("p")
+            .child(Element::new("b").text("Note:"))
+            .text(" ")
+            .child(Node::expression("message"));
+        assert_eq!(print_jsx(element), "<p>\n  <b>Note:</b> {message}\n</p>");
+    }
+
+    #[test]
+    fn test_attributes_break_one_per_line() {
+        let element = Element::new("input")
+            .attr("type", "text")
+            .attr("placeholder", "Search the catalogue for anything at all")
+            .prop("value", "query");
+        assert_eq!(
+            print_jsx(element),
+            "<input\n  type=\"text\"\n  placeholder=\"Search the catalogue for anything at all\"\n  value={query}\n/>"
+        );
+        let element = Element::new("img").attr("alt", "A \"cat\"");
+        assert_eq!(print_jsx(element), "<img alt={'A \"cat\"'} />");
+    }
+
+    #[test]
+    fn test_conditionals() {
+        let short = Expr::conditional("open", Element::new("p").text("Hi"), Expr::null());
+        assert_eq!(
+            print_jsx(Node::expression(short)),
+            "{open ? <p>Hi</p> : null}"
+        );
+
+        let long = Expr::conditional(
+            "open",
+            Element::new("h1").text("Welcome back"),
+            Node::Fragment(vec![Element::new("button").text("Log in").into()]),
+        );
+        assert_eq!(
+            print_jsx(Node::expression(long)),
+            "{open ? (\n  <h1>Welcome back</h1>\n) : (\n  <>\n    <button>Log in</button>\n  </>\n)}"
+        );
+    }
+
+    #[test]
+    fn test_callbacks_hug_the_call() {
+        let map = Expr::call(
+            "items.map",
+            vec![Expr::arrow(
+                &["item"],
+                Element::new("li")
+                    .prop("key", "item.id")
+                    .child(Node::expression("item.name")),
+            )],
+        );
+        assert_eq!(
+            print_jsx(Element::new("ul").child(Node::expression(map))),
+            "<ul>\n  {items.map((item) => (\n    <li key={item.id}>{item.name}</li>\n  ))}\n</ul>"
+        );
+    }
+
+    #[test]
+    fn test_handlers() {
+        let button = Element::new("button")
+            .prop(
+                "onClick",
+                Expr::block(
+                    &[],
+                    vec![
+                        Statement::from(Expr::raw("a()")),
+                        Statement::from(Expr::raw("b()")),
+                    ],
+                ),
+            )
+            .text("Go");
+        assert_eq!(
+            print_jsx(button),
+            "<button\n  onClick={() => {\n    a();\n    b();\n  }}\n>\n  Go\n</button>"
+        );
+        let long = "setSelectedProjectIdentifier(projectIdentifiers[currentIndex + 1])";
+        let button = Element::new("button").prop("onClick", Expr::arrow(&[], long));
+        assert_eq!(
+            print_jsx(button),
+            format!("<button\n  onClick={{() =>\n    {}\n  }}\n/>", long)
+        );
+    }
+
+    #[test]
+    fn test_print_module() {
+        let module = Module {
+            imports: vec![
+                Import::new("react").default("React").names(["useState"]),
+                Import::new("./index.css"),
+            ],
+            statements: vec![Statement::Component {
+                name: "Home".to_string(),
+                parameters: Vec::new(),
+                props: None,
+                body: vec![
+                    Statement::constant(
+                        "[count, setCount]",
+                        Expr::call("useState<number>", vec![Expr::raw("0")]),
+                    ),
+                    Statement::Return(Some(Element::new("h1").text("Home").into())),
+                ],
+            }],
+        };
+        assert_eq!(
+            print_module(&module),
+            "import React, { useState } from \"react\";\nimport \"./index.css\";\n\nexport default function Home() {\n  const [count, setCount] = useState<number>(0);\n  return <h1>Home</h1>;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_print_component_with_props() {
+        let component = |parameters: Vec<(String, Option<Expr>)>| Module {
+            imports: Vec::new(),
+            statements: vec![
+                Statement::Interface {
+                    name: "CardProps".to_string(),
+                    fields: vec![Field {
+                        name: "title".to_string(),
+                        optional: false,
+                        ty: "string".to_string(),
+                    }],
+                },
+                Statement::Component {
+                    name: "Card".to_string(),
+                    parameters,
+                    props: Some("CardProps".to_string()),
+                    body: vec![Statement::Return(Some(Expr::null()))],
+                },
+            ],
+        };
+        assert_eq!(
+            print_module(&component(vec![
+                ("title".to_string(), None),
+                ("count".to_string(), Some(Expr::raw("0"))),
+            ])),
+            "export interface CardProps {\n  title: string;\n}\n\nexport default function Card({ title, count = 0 }: CardProps) {\n  return null;\n}\n"
+        );
+        let long: Vec<(String, Option<Expr>)> =
+            ["title", "subtitle", "description", "footer", "children"]
+                .iter()
+                .map(|name| (name.to_string(), None))
+                .collect();
+        assert!(print_module(&component(long)).contains(
+            "export default function Card({\n  title,\n  subtitle,\n  description,\n  footer,\n  children,\n}: CardProps) {\n"
+        ));
+    }
+}
@@ -0,0 +1,259 @@
+//! Components declared in a project's `components/*.yaml` files. Each
+//! compiles to a React component of its own in `src/components/`, which
+//! pages and other components place with `use:`.
+
+use crate::codegen::ReactGenerator;
+use crate::source_index::SourceIndex;
+use crate::types::{self, StateType};
+use nwl_shared::{ComponentDefinition, Element, PropDefinition, Span, StateDefinition};
+
+/// A declared prop of a component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prop {
+    pub name: String,
+    pub ty: StateType,
+    pub default: Option<serde_yaml::Value>,
+}
+
+impl Prop {
+    /// Reads a prop declaration. A `type:` that doesn't parse is reported
+    /// while validating the component; until then the prop is untyped.
+    pub fn new(definition: &PropDefinition) -> Self {
+        Prop {
+            name: definition.name.clone(),
+            ty: types::state_type(&Prop::as_state(definition)).unwrap_or(StateType::Unknown),
+            default: definition.default.clone(),
+        }
+    }
+
+    /// A prop declaration read as a state declaration, whose `initial`
+    /// value is the default. The two are typed the same way.
+    pub fn as_state(definition: &PropDefinition) -> StateDefinition {
+        StateDefinition {
+            name: definition.name.clone(),
+            value_type: definition.value_type.clone(),
+            initial: definition.default.clone(),
+        }
+    }
+
+    /// Whether every `use:` of the component has to give the prop.
+    pub fn is_required(&self) -> bool {
+        self.default.is_none()
+    }
+}
+
+/// A registered component, ready to be used by pages and generated.
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub name: String,
+    pub props: Vec<Prop>,
+    pub definition: ComponentDefinition,
+    /// The file the component is declared in, for diagnostics and source
+    /// maps.
+    pub index: SourceIndex,
+}
+
+impl Component {
+    pub fn new(definition: &ComponentDefinition, index: SourceIndex) -> Self {
+        Component {
+            name: definition.name.clone(),
+            props: definition.props.iter().map(Prop::new).collect(),
+            definition: definition.clone(),
+            index,
+        }
+    }
+
+    pub fn prop(&self, name: &str) -> Option<&Prop> {
+        self.props.iter().find(|prop| prop.name == name)
+    }
+
+    /// Names of the component's named slots.
+    pub fn slots(&self) -> &[String] {
+        &self.definition.slots
+    }
+
+    /// Whether the component renders the `children` of `use:`, with a
+    /// `slot` that has no name.
+    pub fn has_children(&self) -> bool {
+        fn renders_children(elements: &[Element]) -> bool {
+            elements.iter().any(|element| match element {
+                Element::Slot(slot) => slot.name.is_none(),
+                Element::Conditional(conditional) => {
+                    renders_children(std::slice::from_ref(&conditional.element))
+                        || renders_children(&conditional.otherwise)
+                }
+                Element::List(list) => {
+                    renders_children(&list.children) || renders_children(&list.empty)
+                }
+                _ => {
+                    renders_children(element.children())
+                        || element
+                            .slots()
+                            .into_iter()
+                            .any(|(_, children)| renders_children(children))
+                }
+            })
+        }
+        renders_children(&self.definition.children)
+    }
+
+    /// Name of the generated React component and of its module.
+    pub fn module(&self) -> String {
+        ReactGenerator::to_pascal_case(&self.name)
+    }
+
+    /// Where the component's name is declared, for notes pointing back at
+    /// it.
+    pub fn span(&self) -> Option<Span> {
+        self.index
+            .value_span("component.name")
+            .map(|span| span.with_path(self.index.path().map(Into::into)))
+    }
+}
+
+/// Every component of a project, in declaration order.
+#[derive(Debug, Clone, Default)]
+pub struct Components {
+    components: Vec<Component>,
+}
+
+impl Components {
+    pub fn add(&mut self, component: Component) {
+        self.components.push(component);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Component> {
+        self.components
+            .iter()
+            .find(|component| component.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Component> {
+        self.components.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    /// Names of the components `elements` place with `use:`, in
+    /// declaration order.
+    pub fn used_by(&self, elements: &[Element]) -> Vec<&str> {
+        let used: Vec<&str> = uses(elements, "")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        self.components
+            .iter()
+            .map(|component| component.name.as_str())
+            .filter(|name| used.contains(name))
+            .collect()
+    }
+}
+
+/// The components `use:` elements among `elements` place, with the path
+/// of each `use:` below `path`, such as `page.children`.
+pub fn uses<'e>(elements: &'e [Element], path: &str) -> Vec<(&'e str, String)> {
+    let mut uses = Vec::new();
+    walk(elements, path, &mut |element, path| {
+        if let Element::Use(instance) = element {
+            uses.push((instance.component.as_str(), path.to_string()));
+        }
+    });
+    uses
+}
+
+/// Calls `visit` with every element among `elements` and nested in them,
+/// and its path below `path`. A conditional element is visited as the
+/// element it guards, which shares its path.
+pub(crate) fn walk<'e>(
+    elements: &'e [Element],
+    path: &str,
+    visit: &mut impl FnMut(&'e Element, &str),
+) {
+    for (i, element) in elements.iter().enumerate() {
+        let path = format!("{}[{}]", path, i);
+        let element = match element {
+            Element::Conditional(conditional) => {
+                walk(&conditional.otherwise, &format!("{}.else", path), visit);
+                conditional.element.as_ref()
+            }
+            element => element,
+        };
+        visit(element, &path);
+        if let Element::List(list) = element {
+            walk(&list.children, &format!("{}.children", path), visit);
+            walk(&list.empty, &format!("{}.empty", path), visit);
+        }
+        walk(element.children(), &format!("{}.children", path), visit);
+        for (key, children) in element.slots() {
+            walk(children, &format!("{}.{}", path, key), visit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::ComponentFile;
+
+    fn component(source: &str) -> Component {
+        let file: ComponentFile = serde_yaml::from_str(source).unwrap();
+        Component::new(&file.component, SourceIndex::new(source, None))
+    }
+
+    #[test]
+    fn test_props_and_slots() {
+        let card = component(
+            r#"
+component:
+  name: ProfileCard
+  props:
+    - name: title
+      type: string
+    - name: count
+      default: 0
+  slots: [actions]
+  children:
+    - card:
+        children:
+          - slot: actions
+          - slot:
+            if: count > 0
+"#,
+        );
+        assert_eq!(card.module(), "ProfileCard");
+        assert!(card.prop("title").unwrap().is_required());
+        let count = card.prop("count").unwrap();
+        assert_eq!(count.ty, StateType::Number);
+        assert!(!count.is_required());
+        assert_eq!(card.slots(), ["actions"]);
+        assert!(card.has_children());
+        assert_eq!(card.span().map(|span| span.line), Some(3));
+    }
+
+    #[test]
+    fn test_used_by() {
+        let mut components = Components::default();
+        for name in ["Header", "Footer", "Avatar"] {
+            components.add(component(&format!("component:\n  name: {}\n", name)));
+        }
+        let file: ComponentFile = serde_yaml::from_str(
+            r#"
+component:
+  name: Layout
+  children:
+    - use: Footer
+      slots:
+        links:
+          - use: Avatar
+            if: signedIn
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            components.used_by(&file.component.children),
+            vec!["Footer", "Avatar"]
+        );
+    }
+}
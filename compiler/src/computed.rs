@@ -0,0 +1,114 @@
+//! Dependencies between a page's `computed:` values.
+
+use crate::expression::reads;
+use nwl_shared::ComputedDefinition;
+
+/// The names `value` depends on: its declared `deps`, or every name its
+/// expression reads.
+pub fn dependencies(value: &ComputedDefinition) -> Vec<&str> {
+    match &value.deps {
+        Some(deps) => deps.iter().map(String::as_str).collect(),
+        None => {
+            let mut names = Vec::new();
+            for (_, name) in reads(&value.value) {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            names
+        }
+    }
+}
+
+/// Orders computed values so each follows the computed values it depends
+/// on, as their `const` declarations must. Also returns every dependency
+/// cycle found, as indices into `computed`; the order ignores the edges
+/// that close them.
+pub fn order(computed: &[ComputedDefinition]) -> (Vec<usize>, Vec<Vec<usize>>) {
+    let mut sorter = Sorter {
+        computed,
+        marks: vec![Mark::New; computed.len()],
+        stack: Vec::new(),
+        order: Vec::new(),
+        cycles: Vec::new(),
+    };
+    for i in 0..computed.len() {
+        sorter.visit(i);
+    }
+    (sorter.order, sorter.cycles)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    New,
+    Active,
+    Done,
+}
+
+struct Sorter<'a> {
+    computed: &'a [ComputedDefinition],
+    marks: Vec<Mark>,
+    stack: Vec<usize>,
+    order: Vec<usize>,
+    cycles: Vec<Vec<usize>>,
+}
+
+impl Sorter<'_> {
+    fn visit(&mut self, i: usize) {
+        match self.marks[i] {
+            Mark::Done => return,
+            Mark::Active => {
+                let start = self.stack.iter().position(|&j| j == i).unwrap_or(0);
+                self.cycles.push(self.stack[start..].to_vec());
+                return;
+            }
+            Mark::New => {}
+        }
+        self.marks[i] = Mark::Active;
+        self.stack.push(i);
+        for name in dependencies(&self.computed[i]) {
+            if let Some(j) = self.computed.iter().position(|value| value.name == name) {
+                self.visit(j);
+            }
+        }
+        self.stack.pop();
+        self.marks[i] = Mark::Done;
+        self.order.push(i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computed(name: &str, value: &str) -> ComputedDefinition {
+        ComputedDefinition {
+            name: name.to_string(),
+            value: value.to_string(),
+            deps: None,
+            value_type: None,
+        }
+    }
+
+    #[test]
+    fn test_order_and_cycles() {
+        let values = vec![
+            computed("total", "subtotal + tax"),
+            computed("tax", "subtotal * rate"),
+            computed("subtotal", "items.length"),
+        ];
+        let (sorted, cycles) = order(&values);
+        assert_eq!(sorted, vec![2, 1, 0]);
+        assert!(cycles.is_empty());
+
+        let values = vec![
+            computed("a", "b + 1"),
+            computed("b", "c + 1"),
+            computed("c", "a + 1"),
+            computed("d", "d"),
+        ];
+        let (sorted, cycles) = order(&values);
+        assert_eq!(sorted.len(), 4);
+        assert_eq!(cycles, vec![vec![0, 1, 2], vec![3]]);
+    }
+}
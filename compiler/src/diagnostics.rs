@@ -0,0 +1,317 @@
+use crate::source_index::SourceIndex;
+use nwl_shared::{Diagnostic, Span};
+use std::path::Path;
+
+/// Stable codes for every diagnostic the compiler emits.
+pub mod codes {
+    pub const YAML_SYNTAX: &str = "E0001";
+    pub const INVALID_STRUCTURE: &str = "E0002";
+    pub const UNSUPPORTED_YAML: &str = "E0003";
+
+    pub const DUPLICATE_STATE: &str = "E0101";
+    pub const UNDECLARED_STATE: &str = "E0102";
+    pub const GRID_WITHOUT_COLUMNS: &str = "E0103";
+    pub const EMPTY_OPTIONS: &str = "E0104";
+    pub const DUPLICATE_OPTION: &str = "E0105";
+    pub const MODAL_WITHOUT_BIND: &str = "E0106";
+    pub const INVALID_TYPE: &str = "E0107";
+    pub const TYPE_MISMATCH: &str = "E0108";
+    pub const BIND_TYPE_MISMATCH: &str = "E0109";
+    pub const INVALID_LIST: &str = "E0110";
+    pub const LIST_DATA_NOT_ARRAY: &str = "E0111";
+    pub const UNKNOWN_FIELD: &str = "E0112";
+    pub const COMPUTED_CYCLE: &str = "E0113";
+    pub const MISSING_DEPENDENCY: &str = "E0114";
+    pub const BIND_TO_COMPUTED: &str = "E0115";
+    pub const DUPLICATE_ACTION: &str = "E0116";
+    pub const UNKNOWN_ACTION: &str = "E0117";
+    pub const ACTION_ARGUMENTS: &str = "E0118";
+    pub const INVALID_EXPRESSION: &str = "E0119";
+    pub const UNKNOWN_FUNCTION: &str = "E0120";
+    pub const FUNCTION_ARGUMENTS: &str = "E0121";
+    pub const SCRIPT_URL: &str = "E0122";
+    pub const PAGE_NOT_FOUND: &str = "E0123";
+    pub const UNKNOWN_COMPONENT: &str = "E0124";
+    pub const DUPLICATE_COMPONENT: &str = "E0125";
+    pub const COMPONENT_PROPS: &str = "E0126";
+    pub const UNKNOWN_SLOT: &str = "E0127";
+    pub const COMPONENT_CYCLE: &str = "E0128";
+    pub const UNKNOWN_MODULE: &str = "E0129";
+    pub const INVALID_IMPORT: &str = "E0130";
+    pub const ROUTE_PARAMS: &str = "E0131";
+    pub const INVALID_ROUTE: &str = "E0132";
+    pub const UNKNOWN_ROUTE: &str = "E0133";
+    pub const INVALID_NAME: &str = "E0134";
+
+    pub const INVALID_OUTPUT: &str = "E0901";
+}
+
+/// Messages produced by serde when the YAML is well-formed but does not
+/// match the shape of the NWL types.
+const STRUCTURE_ERRORS: &[&str] = &[
+    "missing field",
+    "unknown field",
+    "unknown variant",
+    "invalid type",
+    "invalid value",
+    "invalid length",
+    "duplicate field",
+];
+
+/// Converts a `serde_yaml` error into a located diagnostic, capturing the
+/// offending source line so it can be rendered on its own later.
+pub fn from_yaml_error(error: &serde_yaml::Error, source: &str, path: Option<&Path>) -> Diagnostic {
+    let (yaml_path, message) = split_yaml_message(&error.to_string());
+
+    let code = if STRUCTURE_ERRORS
+        .iter()
+        .any(|prefix| message.starts_with(prefix))
+    {
+        codes::INVALID_STRUCTURE
+    } else {
+        codes::YAML_SYNTAX
+    };
+
+    let mut span = error.location().map(|location| {
+        let length = source
+            .lines()
+            .nth(location.line().saturating_sub(1))
+            .map(|line| token_length(line, location.column()))
+            .unwrap_or(1);
+        Span::new(location.line(), location.column()).with_length(length)
+    });
+
+    let unknown = Unknown::parse(&message);
+    let mut diagnostic = match &unknown {
+        Some(unknown) => {
+            Diagnostic::error(code, format!("unknown {} `{}`", unknown.kind, unknown.name))
+        }
+        None => Diagnostic::error(code, message),
+    };
+
+    if let Some(unknown) = &unknown {
+        // Inside elements serde only knows the position of the enclosing
+        // sequence, so find the offending key or value in the source.
+        let within = yaml_path.as_deref().unwrap_or("");
+        let index = SourceIndex::new(source, None);
+        let located = match unknown.kind {
+            "field" => locate_unknown_field(&index, within, unknown),
+            _ => locate_unknown_variant(&index, within, unknown),
+        };
+        if let Some(located) = located {
+            span = Some(located);
+        }
+
+        let expected: Vec<&str> = unknown.expected.iter().map(String::as_str).collect();
+        diagnostic = match suggest(&unknown.name, &expected) {
+            Some(suggestion) => diagnostic
+                .with_label(format!("unknown {}", unknown.kind))
+                .with_help(format!("did you mean `{}`?", suggestion)),
+            None if expected.is_empty() => {
+                diagnostic.with_label(format!("unknown {}", unknown.kind))
+            }
+            None => {
+                let names: Vec<String> =
+                    expected.iter().map(|name| format!("`{}`", name)).collect();
+                let note = match names.as_slice() {
+                    [single] => format!("expected {}", single),
+                    _ => format!("expected one of {}", names.join(", ")),
+                };
+                diagnostic
+                    .with_label(format!("unknown {}", unknown.kind))
+                    .with_note(note)
+            }
+        };
+    }
+
+    if let Some(span) = span {
+        diagnostic = diagnostic
+            .with_span(span)
+            .with_path(path.map(Path::to_path_buf))
+            .with_source(source);
+    }
+    if let Some(yaml_path) = yaml_path {
+        diagnostic = diagnostic.with_note(format!("while parsing `{}`", yaml_path));
+    }
+    diagnostic
+}
+
+/// Returns the candidate closest to `name`, if any is close enough to be a
+/// plausible typo. Case-only differences always match.
+pub fn suggest<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let lower = name.to_lowercase();
+    if let Some(exact) = candidates.iter().find(|c| c.to_lowercase() == lower) {
+        return Some(exact);
+    }
+
+    let threshold = name.chars().count().max(3) / 3;
+    candidates
+        .iter()
+        .map(|candidate| {
+            (
+                strsim::osa_distance(&lower, &candidate.to_lowercase()),
+                *candidate,
+            )
+        })
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// An `unknown field` / `unknown variant` error as rendered by serde.
+#[derive(Debug, PartialEq)]
+struct Unknown {
+    kind: &'static str,
+    name: String,
+    expected: Vec<String>,
+}
+
+impl Unknown {
+    fn parse(message: &str) -> Option<Unknown> {
+        let (kind, rest) = if let Some(rest) = message.strip_prefix("unknown field `") {
+            ("field", rest)
+        } else if let Some(rest) = message.strip_prefix("unknown variant `") {
+            ("variant", rest)
+        } else {
+            return None;
+        };
+
+        let (name, expected) = rest.split_once('`')?;
+        let expected = expected
+            .split('`')
+            .skip(1)
+            .step_by(2)
+            .map(str::to_string)
+            .collect();
+
+        Some(Unknown {
+            kind,
+            name: name.to_string(),
+            expected,
+        })
+    }
+}
+
+/// Finds the occurrence of the unknown key below `within` that most likely
+/// belongs to the struct serde was deserializing, scoring each candidate by
+/// how many of its sibling keys are expected fields of that struct.
+fn locate_unknown_field(index: &SourceIndex, within: &str, unknown: &Unknown) -> Option<Span> {
+    let mut best: Option<(isize, &Span)> = None;
+    for (path, span) in index.keys_named(within, &unknown.name) {
+        let parent = &path[..path.len() - unknown.name.len() - 1];
+        let score: isize = index
+            .child_keys(parent)
+            .iter()
+            .filter(|key| **key != unknown.name && **key != "element")
+            .map(|key| {
+                if unknown.expected.iter().any(|e| e == key) {
+                    1
+                } else {
+                    -1
+                }
+            })
+            .sum();
+        if best.is_none_or(|(top, _)| score > top) {
+            best = Some((score, span));
+        }
+    }
+    best.map(|(_, span)| span.clone())
+}
+
+/// Finds the first value spelling the unknown variant below `within`, or the
+/// key of a keyed element (`- headng: {…}`), whichever comes first.
+fn locate_unknown_variant(index: &SourceIndex, within: &str, unknown: &Unknown) -> Option<Span> {
+    index
+        .values_equal(within, &unknown.name)
+        .into_iter()
+        .chain(index.keys_named(within, &unknown.name))
+        .map(|(_, span)| span)
+        .min_by_key(|span| (span.line, span.column))
+        .cloned()
+}
+
+/// Width of the scalar starting at the 1-based `column`, so the caret
+/// underlines the whole offending word rather than its first character.
+fn token_length(line: &str, column: usize) -> usize {
+    line.chars()
+        .skip(column.saturating_sub(1))
+        .take_while(|c| !c.is_whitespace() && *c != ':' && *c != ',')
+        .count()
+        .max(1)
+}
+
+/// Splits serde_yaml's `"<path>: <message> at line L column C"` rendering
+/// into the YAML path (if any) and the bare message.
+fn split_yaml_message(raw: &str) -> (Option<String>, String) {
+    let without_location = match raw.rfind(" at line ") {
+        Some(index) if raw[index..].contains(" column ") => &raw[..index],
+        _ => raw,
+    };
+
+    if let Some((prefix, rest)) = without_location.split_once(": ") {
+        let looks_like_path =
+            !prefix.is_empty() && !prefix.contains(char::is_whitespace) && !prefix.contains('`');
+        if looks_like_path {
+            return (Some(prefix.to_string()), rest.to_string());
+        }
+    }
+
+    (None, without_location.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::Page;
+
+    #[test]
+    fn test_split_yaml_message() {
+        let (path, message) =
+            split_yaml_message("page.children: missing field `content` at line 4 column 5");
+        assert_eq!(path.as_deref(), Some("page.children"));
+        assert_eq!(message, "missing field `content`");
+
+        let (path, message) = split_yaml_message("did not find expected key at line 2 column 3");
+        assert_eq!(path, None);
+        assert_eq!(message, "did not find expected key");
+    }
+
+    #[test]
+    fn test_from_yaml_error_renders_snippet() {
+        let source = "page:\n  name: X\n  children:\n    - element: headng\n      content: hi\n";
+        let error = serde_yaml::from_str::<Page>(source).unwrap_err();
+        let diagnostic = from_yaml_error(&error, source, Some(Path::new("pages/x.yaml")));
+
+        assert_eq!(diagnostic.code, codes::INVALID_STRUCTURE);
+        let rendered = diagnostic.to_string();
+        assert!(rendered.starts_with("error[E0002]: unknown variant `headng`"));
+        assert!(rendered.contains("--> pages/x.yaml:4:16"));
+        assert!(rendered.contains("4 |     - element: headng"));
+        assert!(rendered.contains("  |                ^^^^^^ unknown variant"));
+        assert!(rendered.contains("= help: did you mean `heading`?"));
+    }
+
+    #[test]
+    fn test_unknown_field_points_at_key() {
+        let source = "page:\n  name: X\n  children:\n    - element: text\n      content: a\n    - element: button\n      content: Go\n      onclik: go()\n";
+        let error = serde_yaml::from_str::<Page>(source).unwrap_err();
+        let diagnostic = from_yaml_error(&error, source, None);
+
+        assert_eq!(diagnostic.message, "unknown field `onclik`");
+        let span = diagnostic.span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (8, 7, 6));
+        assert_eq!(diagnostic.help.as_deref(), Some("did you mean `onClick`?"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let fields = ["content", "onClick", "style"];
+        assert_eq!(suggest("onclik", &fields), Some("onClick"));
+        assert_eq!(suggest("STYLE", &fields), Some("style"));
+        assert_eq!(suggest("size", &fields), None);
+        assert_eq!(
+            suggest("placholder", &["placeholder", "bind"]),
+            Some("placeholder")
+        );
+    }
+}
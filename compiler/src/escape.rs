@@ -0,0 +1,155 @@
+//! Escaping of page content into the generated code. Everything copied
+//! from YAML into a component goes through one of these, depending on
+//! where it lands: JSX text, a JSX attribute or a JavaScript string.
+
+/// A JavaScript string literal, double-quoted unless the string holds
+/// more double quotes than single ones, as Prettier prints strings.
+pub fn js_string(s: &str) -> String {
+    let doubles = s.matches('"').count();
+    let singles = s.matches('\'').count();
+    let quote = if doubles > singles { '\'' } else { '"' };
+    let mut literal = String::with_capacity(s.len() + 2);
+    literal.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            '\u{8}' => literal.push_str("\\b"),
+            '\u{c}' => literal.push_str("\\f"),
+            // JavaScript strings can't hold the line separators raw.
+            '\u{2028}' => literal.push_str("\\u2028"),
+            '\u{2029}' => literal.push_str("\\u2029"),
+            c if c == quote => {
+                literal.push('\\');
+                literal.push(c);
+            }
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push(quote);
+    literal
+}
+
+/// Literal text as JSX children. Characters JSX would read as markup or
+/// as the start of an expression are written as HTML entities.
+pub fn jsx_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => escaped.push_str("&#123;"),
+            '}' => escaped.push_str("&#125;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' if starts_entity(&text[i..]) => escaped.push_str("&amp;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A quoted JSX attribute value. JSX strings have no escapes, so values a
+/// quoted string can't hold give `None`, to be passed as a JavaScript
+/// string instead.
+pub fn jsx_string(value: &str) -> Option<String> {
+    let literal = !value.contains(['"', '\n', '\r'])
+        && !value
+            .char_indices()
+            .any(|(i, c)| c == '&' && starts_entity(&value[i..]));
+    literal.then(|| format!("\"{}\"", value))
+}
+
+/// Percent-encodes text for use in a URL query, such as the subject of a
+/// `mailto:` link.
+pub fn url_component(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Whether a URL runs script when followed, such as `javascript:alert(1)`.
+/// Browsers ignore leading whitespace and control characters and tabs or
+/// line breaks anywhere in the scheme, so those are ignored here too.
+pub fn is_script_url(url: &str) -> bool {
+    let scheme: String = url
+        .trim_start_matches(|c: char| c <= ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .take_while(|&c| c != ':')
+        .collect();
+    scheme.eq_ignore_ascii_case("javascript") && url.contains(':')
+}
+
+/// Whether `text` starts with something JSX decodes as an entity, such as
+/// `&amp;` or `&#38;`.
+fn starts_entity(text: &str) -> bool {
+    let Some(end) = text.find(';') else {
+        return false;
+    };
+    let name = &text[1..end];
+    match name.strip_prefix('#') {
+        Some(code) => code.strip_prefix(['x', 'X']).map_or(
+            !code.is_empty() && code.chars().all(|c| c.is_ascii_digit()),
+            |hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        ),
+        None => !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsx_text() {
+        assert_eq!(jsx_text("a < b && c > d"), "a &lt; b && c &gt; d");
+        assert_eq!(jsx_text("{not code}"), "&#123;not code&#125;");
+        assert_eq!(jsx_text("AT&T &copy; &#169;"), "AT&T &amp;copy; &amp;#169;");
+        assert_eq!(jsx_text("it's \"quoted\" \\n"), "it's \"quoted\" \\n");
+    }
+
+    #[test]
+    fn test_js_string() {
+        assert_eq!(js_string("it's"), "\"it's\"");
+        assert_eq!(js_string("Say \"hi\""), "'Say \"hi\"'");
+        assert_eq!(js_string("\"it's\""), "'\"it\\'s\"'");
+        assert_eq!(
+            js_string("a\\b\nc\u{1}\u{2028}"),
+            "\"a\\\\b\\nc\\u0001\\u2028\""
+        );
+    }
+
+    #[test]
+    fn test_jsx_string() {
+        assert_eq!(jsx_string("A cat").as_deref(), Some("\"A cat\""));
+        assert_eq!(jsx_string("C:\\cats").as_deref(), Some("\"C:\\cats\""));
+        assert_eq!(jsx_string("Say \"hi\""), None);
+        assert_eq!(jsx_string("&amp;"), None);
+        assert_eq!(jsx_string("a\nb"), None);
+    }
+
+    #[test]
+    fn test_url_component() {
+        assert_eq!(url_component("Hi & bye?"), "Hi%20%26%20bye%3F");
+        assert_eq!(url_component("café"), "caf%C3%A9");
+    }
+
+    #[test]
+    fn test_is_script_url() {
+        assert!(is_script_url("javascript:alert(1)"));
+        assert!(is_script_url(" JavaScript:void(0)"));
+        assert!(is_script_url("java\tscript:alert(1)"));
+        assert!(!is_script_url("/javascript/intro"));
+        assert!(!is_script_url("https://example.com/?q=javascript:"));
+        assert!(!is_script_url("javascript"));
+    }
+}
@@ -0,0 +1,366 @@
+//! Expressions embedded in pages. Conditions, computed values, handler
+//! steps and `{…}` interpolations are written in the NWL expression
+//! language, which is parsed, checked against the page's names and emitted
+//! as JavaScript. Script handlers stay plain JavaScript and only get the
+//! lightweight scanning below.
+
+mod ast;
+mod check;
+mod emit;
+mod parser;
+
+pub use ast::{BinaryOp, Expr, ExprKind, Ident, Property, UnaryOp};
+pub use check::{check, check_interpolation, join, unknown_field, Problem, Scope};
+pub use emit::{emit, emit_string, emit_text};
+pub use parser::{parse, parse_text, Segment, SyntaxError};
+
+/// Names an expression may use without declaring them.
+const GLOBALS: &[&str] = &[
+    "true",
+    "false",
+    "null",
+    "undefined",
+    "typeof",
+    "instanceof",
+    "in",
+    "new",
+    "NaN",
+    "Infinity",
+    "Math",
+    "Number",
+    "String",
+    "Boolean",
+    "Array",
+    "Date",
+    "JSON",
+    "Object",
+    "Promise",
+    "console",
+    "window",
+    "document",
+    "navigator",
+    "location",
+    "history",
+    "localStorage",
+    "sessionStorage",
+    "alert",
+    "confirm",
+    "prompt",
+    "fetch",
+    "setTimeout",
+    "clearTimeout",
+    "setInterval",
+    "clearInterval",
+    "parseInt",
+    "parseFloat",
+    "encodeURIComponent",
+    "decodeURIComponent",
+];
+
+/// The character offset and name of every state, computed value or list
+/// item an NWL expression reads. Expressions that don't parse are scanned
+/// as JavaScript instead.
+pub fn reads(source: &str) -> Vec<(usize, &str)> {
+    match parse(source) {
+        Ok(expr) => expr.references(),
+        Err(_) => references(source),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    /// A name, with its character offset and whether it follows a `.`.
+    Name(usize, &'a str, bool),
+    Punct(char),
+    Arrow,
+    Literal,
+}
+
+/// Yields the character offset and name of every variable an expression
+/// reads, skipping literals, property names, parameters of arrow functions
+/// and well-known globals.
+pub fn references(expression: &str) -> Vec<(usize, &str)> {
+    let tokens = tokenize(expression);
+    let parameters = parameters(&tokens);
+
+    // `key:` right after `{` or `,` names a property of an object literal.
+    let is_key = |i: usize| {
+        matches!(tokens.get(i + 1), Some(Token::Punct(':')))
+            && i > 0
+            && matches!(tokens[i - 1], Token::Punct('{') | Token::Punct(','))
+    };
+
+    tokens
+        .iter()
+        .enumerate()
+        .filter_map(|(i, token)| match token {
+            Token::Name(offset, name, false)
+                if !GLOBALS.contains(name) && !parameters.contains(name) && !is_key(i) =>
+            {
+                Some((*offset, *name))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// A call of a plain function, such as `save(draft, 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call<'a> {
+    /// Character offset of the function name.
+    pub offset: usize,
+    pub name: &'a str,
+    /// Character offset and trimmed text of every argument.
+    pub arguments: Vec<(usize, &'a str)>,
+}
+
+/// Yields every call of a function by its bare name, skipping method calls,
+/// constructors, arrow function parameters and well-known globals.
+pub fn calls(expression: &str) -> Vec<Call<'_>> {
+    let tokens = tokenize(expression);
+    let parameters = parameters(&tokens);
+    let chars: Vec<(usize, char)> = expression.char_indices().collect();
+
+    let mut found = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let Token::Name(offset, name, false) = *token else {
+            continue;
+        };
+        let is_call = matches!(tokens.get(i + 1), Some(Token::Punct('(')))
+            && !matches!(tokens[..i].last(), Some(Token::Name(_, "new", _)))
+            && !GLOBALS.contains(&name)
+            && !parameters.contains(&name);
+        if !is_call {
+            continue;
+        }
+        let open = (offset + name.chars().count()..chars.len())
+            .find(|&j| chars[j].1 == '(')
+            .unwrap_or(chars.len());
+        found.push(Call {
+            offset,
+            name,
+            arguments: arguments(expression, &chars, open + 1),
+        });
+    }
+    found
+}
+
+/// Splits a script handler into its statements at every `;` outside
+/// brackets and strings, dropping empty ones.
+pub fn statements(script: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    let mut depth = 0;
+    let mut from = 0;
+    let mut chars = script.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            quote @ ('"' | '\'' | '`') => {
+                while let Some((_, next)) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == quote {
+                        break;
+                    }
+                }
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ';' if depth == 0 => {
+                found.push(&script[from..i]);
+                from = i + 1;
+            }
+            _ => {}
+        }
+    }
+    found.push(&script[from..]);
+    found
+        .into_iter()
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// Splits the argument list starting at character `start` on its top-level
+/// commas.
+fn arguments<'a>(
+    expression: &'a str,
+    chars: &[(usize, char)],
+    start: usize,
+) -> Vec<(usize, &'a str)> {
+    let byte = |j: usize| chars.get(j).map_or(expression.len(), |&(index, _)| index);
+    let mut found = Vec::new();
+    let mut push = |from: usize, to: usize| {
+        let text = &expression[byte(from)..byte(to)];
+        let trimmed = text.trim();
+        if !trimmed.is_empty() {
+            let leading = text[..text.len() - text.trim_start().len()].chars().count();
+            found.push((from + leading, trimmed));
+        }
+    };
+
+    let mut depth = 0;
+    let mut from = start;
+    let mut j = start;
+    while j < chars.len() {
+        match chars[j].1 {
+            quote @ ('"' | '\'' | '`') => {
+                j += 1;
+                while j < chars.len() && chars[j].1 != quote {
+                    if chars[j].1 == '\\' {
+                        j += 1;
+                    }
+                    j += 1;
+                }
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => break,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                push(from, j);
+                from = j + 1;
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+    push(from, j);
+    found
+}
+
+/// Names bound as parameters of the arrow functions in `tokens`.
+fn parameters<'a>(tokens: &[Token<'a>]) -> Vec<&'a str> {
+    let mut parameters = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if *token != Token::Arrow {
+            continue;
+        }
+        match tokens[..i].last() {
+            Some(Token::Name(_, name, _)) => parameters.push(*name),
+            Some(Token::Punct(')')) => {
+                let mut depth = 0;
+                for token in tokens[..i].iter().rev() {
+                    match token {
+                        Token::Punct(')') => depth += 1,
+                        Token::Punct('(') => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        Token::Name(_, name, false) => parameters.push(*name),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    parameters
+}
+
+fn tokenize(expression: &str) -> Vec<Token<'_>> {
+    let chars: Vec<(usize, char)> = expression.char_indices().collect();
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c == '"' || c == '\'' || c == '`' {
+            i += 1;
+            while i < chars.len() && chars[i].1 != c {
+                if chars[i].1 == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Literal);
+        } else if is_name(c) {
+            let mut end = i;
+            while end < chars.len() && is_name(chars[end].1) {
+                end += 1;
+            }
+            if c.is_ascii_digit() {
+                tokens.push(Token::Literal);
+            } else {
+                let stop = chars.get(end).map_or(expression.len(), |&(index, _)| index);
+                let after_dot = matches!(tokens.last(), Some(Token::Punct('.')));
+                tokens.push(Token::Name(i, &expression[start..stop], after_dot));
+            }
+            i = end;
+        } else if c == '=' && chars.get(i + 1).is_some_and(|&(_, next)| next == '>') {
+            tokens.push(Token::Arrow);
+            i += 2;
+        } else {
+            if !c.is_whitespace() {
+                tokens.push(Token::Punct(c));
+            }
+            i += 1;
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statements() {
+        assert_eq!(
+            statements("save(title, 2); setTitle('')"),
+            ["save(title, 2)", "setTitle('')"]
+        );
+        assert_eq!(
+            statements("alert('a; b'); if (x) { a(); b() };"),
+            ["alert('a; b')", "if (x) { a(); b() }"]
+        );
+        assert_eq!(statements("setOpen(false)"), ["setOpen(false)"]);
+    }
+
+    #[test]
+    fn test_references() {
+        let names = |expression| {
+            references(expression)
+                .into_iter()
+                .map(|(_, name)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names("items.filter((item, i) => item.done && i < limit).length"),
+            vec!["items", "limit"]
+        );
+        assert_eq!(
+            names("user?.name ?? 'guest' + count"),
+            vec!["user", "count"]
+        );
+        assert_eq!(
+            names("typeof x === \"undefined\" || 1.5e3 > y"),
+            vec!["x", "y"]
+        );
+        assert_eq!(
+            names("{ total, count: n ? a : b }"),
+            vec!["total", "n", "a", "b"]
+        );
+        assert_eq!(references("a + é + b"), vec![(0, "a"), (8, "b")]);
+    }
+
+    #[test]
+    fn test_calls() {
+        let found = calls("save(draft, { id: 1, tags: [a, b] }, 'x, y'); console.log(f(1))");
+        let names: Vec<&str> = found.iter().map(|call| call.name).collect();
+        assert_eq!(names, vec!["save", "f"]);
+        assert_eq!(
+            found[0].arguments,
+            vec![
+                (5, "draft"),
+                (12, "{ id: 1, tags: [a, b] }"),
+                (37, "'x, y'")
+            ]
+        );
+        assert_eq!(found[1].arguments, vec![(60, "1")]);
+
+        assert!(calls("items.map((fn) => fn()); new Thing()").is_empty());
+        assert_eq!(calls("reset( )")[0].arguments, vec![]);
+    }
+}
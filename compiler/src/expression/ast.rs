@@ -0,0 +1,261 @@
+//! Syntax tree of NWL expressions.
+
+/// Global names expressions may read without declaring them.
+pub(super) const CONSTANTS: &[&str] = &[
+    "NaN", "Infinity", "Math", "JSON", "Number", "String", "Boolean", "Date", "Object", "Array",
+];
+
+/// Built-in functions, with their minimum and maximum number of arguments.
+pub(super) const BUILTINS: &[(&str, usize, usize)] = &[
+    ("len", 1, 1),
+    ("upper", 1, 1),
+    ("lower", 1, 1),
+    ("trim", 1, 1),
+    ("round", 1, 1),
+    ("floor", 1, 1),
+    ("ceil", 1, 1),
+    ("abs", 1, 1),
+    ("min", 1, usize::MAX),
+    ("max", 1, usize::MAX),
+    ("str", 1, 1),
+    ("num", 1, 1),
+];
+
+/// An expression, with the character offset and length of its source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr<'a> {
+    pub kind: ExprKind<'a>,
+    pub offset: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind<'a> {
+    /// A number, as written.
+    Number(&'a str),
+    String(String),
+    Boolean(bool),
+    Null,
+    Undefined,
+    /// A state, computed value, list item or function parameter.
+    Name(&'a str),
+    Array(Vec<Expr<'a>>),
+    Object(Vec<Property<'a>>),
+    /// `object.name`, or `object?.name` when `optional`.
+    Member {
+        object: Box<Expr<'a>>,
+        name: Ident<'a>,
+        optional: bool,
+    },
+    /// `object[index]`, or `object?.[index]` when `optional`.
+    Index {
+        object: Box<Expr<'a>>,
+        index: Box<Expr<'a>>,
+        optional: bool,
+    },
+    /// A call of a built-in function or of a method.
+    Call {
+        callee: Box<Expr<'a>>,
+        arguments: Vec<Expr<'a>>,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr<'a>>,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr<'a>>,
+        right: Box<Expr<'a>>,
+    },
+    /// `test ? consequent : alternate`.
+    Conditional {
+        test: Box<Expr<'a>>,
+        consequent: Box<Expr<'a>>,
+        alternate: Box<Expr<'a>>,
+    },
+    /// `item => …` or `(total, item) => …`, passed to methods such as `map`.
+    Lambda {
+        parameters: Vec<Ident<'a>>,
+        body: Box<Expr<'a>>,
+    },
+}
+
+/// A name written in the source, with its character offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ident<'a> {
+    pub name: &'a str,
+    pub offset: usize,
+}
+
+/// An entry of an object literal. `{ total }` is shorthand for
+/// `{ total: total }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property<'a> {
+    pub key: Ident<'a>,
+    pub value: Expr<'a>,
+    pub shorthand: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Not,
+    Negate,
+    TypeOf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    /// `==` or `===`; both compare strictly.
+    Equal,
+    /// `!=` or `!==`; both compare strictly.
+    NotEqual,
+    And,
+    Or,
+    Coalesce,
+}
+
+impl UnaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            UnaryOp::Not => "!",
+            UnaryOp::Negate => "-",
+            UnaryOp::TypeOf => "typeof",
+        }
+    }
+}
+
+impl BinaryOp {
+    /// The operator as written in JavaScript.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Remainder => "%",
+            BinaryOp::Less => "<",
+            BinaryOp::LessEqual => "<=",
+            BinaryOp::Greater => ">",
+            BinaryOp::GreaterEqual => ">=",
+            BinaryOp::Equal => "===",
+            BinaryOp::NotEqual => "!==",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::Coalesce => "??",
+        }
+    }
+
+    /// JavaScript's precedence of the operator; higher binds tighter.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Coalesce | BinaryOp::Or => 3,
+            BinaryOp::And => 4,
+            BinaryOp::Equal | BinaryOp::NotEqual => 8,
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => 9,
+            BinaryOp::Add | BinaryOp::Subtract => 11,
+            BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Remainder => 12,
+        }
+    }
+}
+
+impl<'a> Expr<'a> {
+    /// JavaScript's precedence of the expression, for deciding where the
+    /// emitter needs parentheses.
+    pub fn precedence(&self) -> u8 {
+        match &self.kind {
+            ExprKind::Lambda { .. } => 1,
+            ExprKind::Conditional { .. } => 2,
+            ExprKind::Binary { op, .. } => op.precedence(),
+            ExprKind::Unary { .. } => 14,
+            ExprKind::Member { .. } | ExprKind::Index { .. } | ExprKind::Call { .. } => 17,
+            _ => 18,
+        }
+    }
+
+    /// Whether the expression is a literal such as `1`, `'low'` or `null`.
+    pub fn is_literal(&self) -> bool {
+        matches!(
+            self.kind,
+            ExprKind::Number(_)
+                | ExprKind::String(_)
+                | ExprKind::Boolean(_)
+                | ExprKind::Null
+                | ExprKind::Undefined
+        )
+    }
+
+    /// Yields the character offset and name of every state, computed value
+    /// or list item the expression reads, in source order.
+    pub fn references(&self) -> Vec<(usize, &'a str)> {
+        let mut found = Vec::new();
+        self.collect_references(&mut Vec::new(), &mut found);
+        found
+    }
+
+    fn collect_references(&self, locals: &mut Vec<&'a str>, found: &mut Vec<(usize, &'a str)>) {
+        match &self.kind {
+            ExprKind::Name(name) => {
+                if !locals.contains(name) && !CONSTANTS.contains(name) {
+                    found.push((self.offset, *name));
+                }
+            }
+            ExprKind::Array(items) => {
+                for item in items {
+                    item.collect_references(locals, found);
+                }
+            }
+            ExprKind::Object(properties) => {
+                for property in properties {
+                    property.value.collect_references(locals, found);
+                }
+            }
+            ExprKind::Member { object, .. } => object.collect_references(locals, found),
+            ExprKind::Index { object, index, .. } => {
+                object.collect_references(locals, found);
+                index.collect_references(locals, found);
+            }
+            ExprKind::Call { callee, arguments } => {
+                if !matches!(callee.kind, ExprKind::Name(_)) {
+                    callee.collect_references(locals, found);
+                }
+                for argument in arguments {
+                    argument.collect_references(locals, found);
+                }
+            }
+            ExprKind::Unary { operand, .. } => operand.collect_references(locals, found),
+            ExprKind::Binary { left, right, .. } => {
+                left.collect_references(locals, found);
+                right.collect_references(locals, found);
+            }
+            ExprKind::Conditional {
+                test,
+                consequent,
+                alternate,
+            } => {
+                test.collect_references(locals, found);
+                consequent.collect_references(locals, found);
+                alternate.collect_references(locals, found);
+            }
+            ExprKind::Lambda { parameters, body } => {
+                let depth = locals.len();
+                locals.extend(parameters.iter().map(|parameter| parameter.name));
+                body.collect_references(locals, found);
+                locals.truncate(depth);
+            }
+            ExprKind::Number(_)
+            | ExprKind::String(_)
+            | ExprKind::Boolean(_)
+            | ExprKind::Null
+            | ExprKind::Undefined => {}
+        }
+    }
+}
@@ -0,0 +1,1086 @@
+//! Name resolution and type inference for NWL expressions.
+
+use super::ast::{BinaryOp, Expr, ExprKind, Ident, UnaryOp, BUILTINS, CONSTANTS};
+use crate::diagnostics::{codes, suggest};
+use crate::types::{Field, StateType};
+use nwl_shared::Diagnostic;
+
+/// The names an expression may read: the page's state and computed values
+/// and the items of enclosing lists.
+pub trait Scope {
+    /// The type of `name`, or `None` when nothing by that name is declared.
+    fn lookup(&self, name: &str) -> Option<StateType>;
+    /// Every name in scope, for suggestions.
+    fn names(&self) -> Vec<&str>;
+}
+
+/// A problem found in an expression, pointing at a character range of the
+/// text it was parsed from.
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub diagnostic: Diagnostic,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Methods of arrays, with their minimum and maximum number of arguments.
+const ARRAY_METHODS: &[(&str, usize, usize)] = &[
+    ("map", 1, 1),
+    ("filter", 1, 1),
+    ("find", 1, 1),
+    ("findIndex", 1, 1),
+    ("some", 1, 1),
+    ("every", 1, 1),
+    ("reduce", 2, 2),
+    ("includes", 1, 1),
+    ("indexOf", 1, 1),
+    ("join", 0, 1),
+    ("slice", 0, 2),
+    ("concat", 1, 1),
+    ("at", 1, 1),
+    ("toString", 0, 0),
+];
+
+const STRING_METHODS: &[(&str, usize, usize)] = &[
+    ("toUpperCase", 0, 0),
+    ("toLowerCase", 0, 0),
+    ("trim", 0, 0),
+    ("trimStart", 0, 0),
+    ("trimEnd", 0, 0),
+    ("includes", 1, 1),
+    ("startsWith", 1, 1),
+    ("endsWith", 1, 1),
+    ("indexOf", 1, 1),
+    ("split", 1, 1),
+    ("slice", 1, 2),
+    ("replace", 2, 2),
+    ("replaceAll", 2, 2),
+    ("padStart", 1, 2),
+    ("padEnd", 1, 2),
+    ("at", 1, 1),
+    ("toString", 0, 0),
+];
+
+const NUMBER_METHODS: &[(&str, usize, usize)] = &[("toFixed", 0, 1), ("toString", 0, 0)];
+
+const OTHER_METHODS: &[(&str, usize, usize)] = &[("toString", 0, 0)];
+
+/// Resolves every name `expr` reads against `scope` and infers its type.
+/// `source` is the text the offsets of `expr` point into, and `subject`
+/// names the expression in messages, e.g. `condition`.
+pub fn check(
+    expr: &Expr,
+    source: &str,
+    scope: &dyn Scope,
+    subject: &str,
+) -> (StateType, Vec<Problem>) {
+    let mut checker = Checker {
+        source: source.chars().collect(),
+        scope,
+        subject,
+        locals: Vec::new(),
+        problems: Vec::new(),
+    };
+    let ty = checker.expr(expr);
+    (ty, checker.problems)
+}
+
+/// Like [`check`] for an expression interpolated in text, which must also
+/// be a value text can show: React throws on objects rendered as children.
+pub fn check_interpolation(
+    expr: &Expr,
+    source: &str,
+    scope: &dyn Scope,
+) -> (StateType, Vec<Problem>) {
+    let mut checker = Checker {
+        source: source.chars().collect(),
+        scope,
+        subject: "interpolation",
+        locals: Vec::new(),
+        problems: Vec::new(),
+    };
+    let ty = checker.expr(expr);
+    if !is_text(&ty) {
+        let help = match &ty {
+            StateType::Object(fields) if !fields.is_empty() => format!(
+                "interpolate one of its fields, such as `{{{}.{}}}`",
+                checker.snippet(expr),
+                fields[0].name
+            ),
+            _ => "interpolate a string, number or boolean instead".to_string(),
+        };
+        let diagnostic =
+            Diagnostic::error(codes::TYPE_MISMATCH, format!("cannot show {} as text", ty))
+                .with_label(checker.label(expr, &ty))
+                .with_help(help);
+        checker.problem(diagnostic, expr);
+    }
+    (ty, checker.problems)
+}
+
+/// Whether a value of type `ty` can be shown in text. Objects and files
+/// can't, nor lists of them.
+fn is_text(ty: &StateType) -> bool {
+    match ty {
+        StateType::Object(_) | StateType::File => false,
+        StateType::Array(item) | StateType::Nullable(item) => is_text(item),
+        _ => true,
+    }
+}
+
+/// The diagnostic for reading `field` of `owner`, a value of type `ty`
+/// that has no such field.
+pub fn unknown_field(owner: &str, ty: &StateType, field: &str) -> Diagnostic {
+    let diagnostic = Diagnostic::error(
+        codes::UNKNOWN_FIELD,
+        format!("no field `{}` on `{}`", field, owner),
+    )
+    .with_label(format!("`{}` is {}", owner, ty));
+    match suggest(field, &ty.field_names()) {
+        Some(candidate) => diagnostic.with_help(format!("did you mean `{}`?", candidate)),
+        None => diagnostic,
+    }
+}
+
+/// The type of a value that is either an `a` or a `b`, as far as it is
+/// known.
+pub fn join(a: &StateType, b: &StateType) -> StateType {
+    match (a, b) {
+        _ if a == b => a.clone(),
+        (StateType::Unknown, _) | (_, StateType::Unknown) => StateType::Unknown,
+        (StateType::Union(members), StateType::Union(others)) => {
+            let mut members = members.clone();
+            for other in others {
+                if !members.contains(other) {
+                    members.push(other.clone());
+                }
+            }
+            StateType::Union(members)
+        }
+        (StateType::Nullable(inner), other) | (other, StateType::Nullable(inner)) => {
+            let other = match other {
+                StateType::Nullable(other) => other.as_ref(),
+                other => other,
+            };
+            match (inner.as_ref(), other) {
+                (StateType::Unknown, other) | (other, StateType::Unknown) => {
+                    StateType::Nullable(Box::new(other.clone()))
+                }
+                (inner, other) => StateType::Nullable(Box::new(join(inner, other))),
+            }
+        }
+        (StateType::Array(a), StateType::Array(b)) => StateType::Array(Box::new(join(a, b))),
+        _ if a.accepts(b) => a.clone(),
+        _ if b.accepts(a) => b.clone(),
+        _ => StateType::Unknown,
+    }
+}
+
+/// `ty` without `| null`.
+fn non_null(ty: &StateType) -> &StateType {
+    match ty {
+        StateType::Nullable(inner) => inner,
+        ty => ty,
+    }
+}
+
+/// Whether `expr` continues an optional chain such as `a?.b`, which stops
+/// evaluating when the value before `?.` is null.
+fn short_circuits(expr: &Expr) -> bool {
+    match &expr.kind {
+        ExprKind::Member {
+            object, optional, ..
+        }
+        | ExprKind::Index {
+            object, optional, ..
+        } => *optional || short_circuits(object),
+        ExprKind::Call { callee, .. } => short_circuits(callee),
+        _ => false,
+    }
+}
+
+fn is_string(ty: &StateType) -> bool {
+    matches!(ty, StateType::String | StateType::Union(_))
+}
+
+fn is_number(ty: &StateType) -> bool {
+    matches!(ty, StateType::Number | StateType::Unknown)
+}
+
+struct Checker<'s, 'a> {
+    source: Vec<char>,
+    scope: &'s dyn Scope,
+    subject: &'s str,
+    /// Parameters of the enclosing functions, innermost last.
+    locals: Vec<(&'a str, StateType)>,
+    problems: Vec<Problem>,
+}
+
+impl<'s, 'a> Checker<'s, 'a> {
+    fn expr(&mut self, expr: &Expr<'a>) -> StateType {
+        match &expr.kind {
+            ExprKind::Number(_) => StateType::Number,
+            ExprKind::String(value) => StateType::Union(vec![value.clone()]),
+            ExprKind::Boolean(_) => StateType::Boolean,
+            ExprKind::Null | ExprKind::Undefined => {
+                StateType::Nullable(Box::new(StateType::Unknown))
+            }
+            ExprKind::Name(name) => self.name(name, expr),
+            ExprKind::Array(items) => {
+                let mut item = None;
+                for expr in items {
+                    let ty = self.expr(expr);
+                    item = Some(match item {
+                        Some(item) => join(&item, &ty),
+                        None => ty,
+                    });
+                }
+                StateType::Array(Box::new(item.unwrap_or(StateType::Unknown)))
+            }
+            ExprKind::Object(properties) => StateType::Object(
+                properties
+                    .iter()
+                    .map(|property| Field {
+                        name: property.key.name.to_string(),
+                        ty: self.expr(&property.value),
+                        optional: false,
+                    })
+                    .collect(),
+            ),
+            ExprKind::Member {
+                object,
+                name,
+                optional,
+            } => {
+                let ty = self.expr(object);
+                self.member(object, &ty, name, *optional)
+            }
+            ExprKind::Index {
+                object,
+                index,
+                optional,
+            } => {
+                let ty = self.expr(object);
+                let item = self.index(object, &ty, index);
+                match ty {
+                    StateType::Nullable(_) if *optional => StateType::Nullable(Box::new(item)),
+                    _ => item,
+                }
+            }
+            ExprKind::Call { callee, arguments } => self.call(callee, arguments),
+            ExprKind::Unary { op, operand } => {
+                let ty = self.expr(operand);
+                match op {
+                    UnaryOp::Not => StateType::Boolean,
+                    UnaryOp::TypeOf => StateType::String,
+                    UnaryOp::Negate => {
+                        if !is_number(&ty) {
+                            self.operator_mismatch("-", operand, &ty);
+                        }
+                        StateType::Number
+                    }
+                }
+            }
+            ExprKind::Binary { op, left, right } => self.binary(expr, *op, left, right),
+            ExprKind::Conditional {
+                test,
+                consequent,
+                alternate,
+            } => {
+                self.expr(test);
+                let consequent = self.expr(consequent);
+                let alternate = self.expr(alternate);
+                join(&consequent, &alternate)
+            }
+            ExprKind::Lambda { parameters, body } => {
+                self.problem(
+                    Diagnostic::error(
+                        codes::INVALID_EXPRESSION,
+                        "functions can only be passed to methods such as `map`",
+                    )
+                    .with_label("not allowed here"),
+                    expr,
+                );
+                self.lambda(parameters, body, &[]);
+                StateType::Unknown
+            }
+        }
+    }
+
+    fn name(&mut self, name: &'a str, expr: &Expr<'a>) -> StateType {
+        if let Some((_, ty)) = self.locals.iter().rev().find(|(local, _)| *local == name) {
+            return ty.clone();
+        }
+        if let Some(ty) = self.scope.lookup(name) {
+            // A state that starts as `null` without a declared type can
+            // hold anything.
+            return match ty {
+                StateType::Nullable(inner) if *inner == StateType::Unknown => StateType::Unknown,
+                ty => ty,
+            };
+        }
+        match name {
+            "NaN" | "Infinity" => return StateType::Number,
+            _ if CONSTANTS.contains(&name) => return StateType::Unknown,
+            _ => {}
+        }
+
+        let mut diagnostic = Diagnostic::error(
+            codes::UNDECLARED_STATE,
+            format!("{} refers to undeclared state `{}`", self.subject, name),
+        )
+        .with_label("not declared in `state:`");
+        let mut candidates = self.scope.names();
+        candidates.extend(self.locals.iter().map(|(local, _)| *local));
+        diagnostic = match suggest(name, &candidates) {
+            Some(candidate) => diagnostic.with_help(format!("did you mean `{}`?", candidate)),
+            None => diagnostic.with_help(format!(
+                "add `- name: {}` to the page's `state:` list",
+                name
+            )),
+        };
+        self.problem(diagnostic, expr);
+        StateType::Unknown
+    }
+
+    fn member(
+        &mut self,
+        object: &Expr<'a>,
+        ty: &StateType,
+        name: &Ident<'a>,
+        optional: bool,
+    ) -> StateType {
+        let owner = non_null(ty);
+        if *owner == StateType::Unknown {
+            return StateType::Unknown;
+        }
+        let Some(field) = owner.field(name.name) else {
+            let diagnostic = unknown_field(&self.snippet(object), owner, name.name);
+            self.problem_at(diagnostic, name.offset, name.name.chars().count());
+            return StateType::Unknown;
+        };
+        if !ty.is_nullable() {
+            return field;
+        }
+        // `a?.b.c` stops at `a?.` when `a` is null, so `.c` is safe there.
+        if !optional && !short_circuits(object) {
+            let snippet = self.snippet(object);
+            let diagnostic =
+                Diagnostic::error(codes::TYPE_MISMATCH, format!("`{}` may be null", snippet))
+                    .with_label(self.label(object, ty))
+                    .with_help(format!(
+                        "read the field with `{}?.{}`, which is null while `{}` is",
+                        snippet, name.name, snippet
+                    ));
+            self.problem(diagnostic, object);
+        }
+        if field.is_nullable() {
+            field
+        } else {
+            StateType::Nullable(Box::new(field))
+        }
+    }
+
+    fn index(&mut self, object: &Expr<'a>, ty: &StateType, index: &Expr<'a>) -> StateType {
+        match non_null(ty) {
+            StateType::Array(item) => {
+                self.expect(index, &StateType::Number, "an index");
+                item.as_ref().clone()
+            }
+            ty if is_string(ty) => {
+                self.expect(index, &StateType::Number, "an index");
+                StateType::String
+            }
+            owner @ StateType::Object(_) => match &index.kind {
+                ExprKind::String(key) => owner.field(key).unwrap_or_else(|| {
+                    let diagnostic = unknown_field(&self.snippet(object), owner, key);
+                    self.problem(diagnostic, index);
+                    StateType::Unknown
+                }),
+                _ => {
+                    self.expr(index);
+                    StateType::Unknown
+                }
+            },
+            StateType::Unknown => {
+                self.expr(index);
+                StateType::Unknown
+            }
+            owner => {
+                self.expr(index);
+                let diagnostic =
+                    Diagnostic::error(codes::TYPE_MISMATCH, format!("cannot index into {}", owner))
+                        .with_label(self.label(object, owner));
+                self.problem(diagnostic, object);
+                StateType::Unknown
+            }
+        }
+    }
+
+    fn call(&mut self, callee: &Expr<'a>, arguments: &[Expr<'a>]) -> StateType {
+        match &callee.kind {
+            ExprKind::Name(name) if !self.locals.iter().any(|(local, _)| local == name) => {
+                match BUILTINS.iter().find(|(builtin, _, _)| builtin == name) {
+                    Some(&(_, min, max)) if self.arity(name, callee, min, max, arguments) => {
+                        self.builtin(name, arguments)
+                    }
+                    Some(_) => {
+                        self.loose(arguments);
+                        StateType::Unknown
+                    }
+                    None => {
+                        let builtins: Vec<&str> =
+                            BUILTINS.iter().map(|(name, _, _)| *name).collect();
+                        let mut diagnostic = Diagnostic::error(
+                            codes::UNKNOWN_FUNCTION,
+                            format!("cannot find function `{}`", name),
+                        )
+                        .with_label("not a built-in function");
+                        diagnostic = match suggest(name, &builtins) {
+                            Some(candidate) => {
+                                diagnostic.with_help(format!("did you mean `{}`?", candidate))
+                            }
+                            None => diagnostic.with_help(format!(
+                                "expressions can call {}; use an event handler step for anything else",
+                                builtins.join(", ")
+                            )),
+                        };
+                        self.problem(diagnostic, callee);
+                        self.loose(arguments);
+                        StateType::Unknown
+                    }
+                }
+            }
+            ExprKind::Member { object, name, .. } => {
+                let ty = self.expr(object);
+                self.method(object, &ty, name, arguments)
+            }
+            _ => {
+                self.problem(
+                    Diagnostic::error(
+                        codes::INVALID_EXPRESSION,
+                        "only built-in functions and methods can be called",
+                    )
+                    .with_label("not a function"),
+                    callee,
+                );
+                self.loose(arguments);
+                StateType::Unknown
+            }
+        }
+    }
+
+    fn builtin(&mut self, name: &str, arguments: &[Expr<'a>]) -> StateType {
+        match name {
+            "len" => {
+                let ty = self.expr(&arguments[0]);
+                let measurable = matches!(non_null(&ty), StateType::Array(_) | StateType::Unknown)
+                    || is_string(non_null(&ty));
+                if !measurable {
+                    self.problem(
+                        Diagnostic::error(
+                            codes::TYPE_MISMATCH,
+                            "`len` expects a string or an array",
+                        )
+                        .with_label(self.label(&arguments[0], &ty)),
+                        &arguments[0],
+                    );
+                }
+                StateType::Number
+            }
+            "upper" | "lower" | "trim" => {
+                self.expect(&arguments[0], &StateType::String, &format!("`{}`", name));
+                StateType::String
+            }
+            "str" => {
+                self.expr(&arguments[0]);
+                StateType::String
+            }
+            "num" => {
+                self.expr(&arguments[0]);
+                StateType::Number
+            }
+            _ => {
+                for argument in arguments {
+                    self.expect(argument, &StateType::Number, &format!("`{}`", name));
+                }
+                StateType::Number
+            }
+        }
+    }
+
+    fn method(
+        &mut self,
+        object: &Expr<'a>,
+        ty: &StateType,
+        name: &Ident<'a>,
+        arguments: &[Expr<'a>],
+    ) -> StateType {
+        let receiver = non_null(ty);
+        let methods = match receiver {
+            StateType::Unknown => {
+                self.loose(arguments);
+                return StateType::Unknown;
+            }
+            StateType::Array(_) => ARRAY_METHODS,
+            StateType::Number => NUMBER_METHODS,
+            receiver if is_string(receiver) => STRING_METHODS,
+            _ => OTHER_METHODS,
+        };
+        let Some(&(_, min, max)) = methods.iter().find(|(method, _, _)| *method == name.name)
+        else {
+            let owner = self.snippet(object);
+            let mut diagnostic = Diagnostic::error(
+                codes::UNKNOWN_FUNCTION,
+                format!("no method `{}` on `{}`", name.name, owner),
+            )
+            .with_label(format!("`{}` is {}", owner, receiver));
+            let names: Vec<&str> = methods.iter().map(|(method, _, _)| *method).collect();
+            if let Some(candidate) = suggest(name.name, &names) {
+                diagnostic = diagnostic.with_help(format!("did you mean `{}`?", candidate));
+            }
+            self.problem_at(diagnostic, name.offset, name.name.chars().count());
+            self.loose(arguments);
+            return StateType::Unknown;
+        };
+        let callee = Expr {
+            kind: ExprKind::Name(name.name),
+            offset: name.offset,
+            length: name.name.chars().count(),
+        };
+        if !self.arity(name.name, &callee, min, max, arguments) {
+            self.loose(arguments);
+            return StateType::Unknown;
+        }
+
+        let method = format!("`{}`", name.name);
+        let text = || StateType::String;
+        match (receiver, name.name) {
+            (_, "toString") => text(),
+            (StateType::Array(item), _) => {
+                let item = item.as_ref();
+                let number = StateType::Number;
+                match name.name {
+                    "map" => {
+                        let body = self.callback(name.name, &arguments[0], &[item, &number]);
+                        StateType::Array(Box::new(body))
+                    }
+                    "filter" => {
+                        self.callback(name.name, &arguments[0], &[item, &number]);
+                        StateType::Array(Box::new(item.clone()))
+                    }
+                    "find" => {
+                        self.callback(name.name, &arguments[0], &[item, &number]);
+                        StateType::Nullable(Box::new(item.clone()))
+                    }
+                    "findIndex" => {
+                        self.callback(name.name, &arguments[0], &[item, &number]);
+                        StateType::Number
+                    }
+                    "some" | "every" => {
+                        self.callback(name.name, &arguments[0], &[item, &number]);
+                        StateType::Boolean
+                    }
+                    "reduce" => {
+                        let initial = self.expr(&arguments[1]);
+                        let body =
+                            self.callback(name.name, &arguments[0], &[&initial, item, &number]);
+                        join(&initial, &body)
+                    }
+                    "includes" | "indexOf" => {
+                        self.expect(&arguments[0], item, &method);
+                        if name.name == "includes" {
+                            StateType::Boolean
+                        } else {
+                            StateType::Number
+                        }
+                    }
+                    "join" => {
+                        self.expect_all(arguments, &StateType::String, &method);
+                        text()
+                    }
+                    "concat" => {
+                        self.expect(&arguments[0], receiver, &method);
+                        receiver.clone()
+                    }
+                    "at" => {
+                        self.expect(&arguments[0], &StateType::Number, &method);
+                        StateType::Nullable(Box::new(item.clone()))
+                    }
+                    _ => {
+                        self.expect_all(arguments, &StateType::Number, &method);
+                        receiver.clone()
+                    }
+                }
+            }
+            (StateType::Number, _) => {
+                self.expect_all(arguments, &StateType::Number, &method);
+                text()
+            }
+            (_, "includes" | "startsWith" | "endsWith") => {
+                self.expect_all(arguments, &StateType::String, &method);
+                StateType::Boolean
+            }
+            (_, "indexOf") => {
+                self.expect_all(arguments, &StateType::String, &method);
+                StateType::Number
+            }
+            (_, "split") => {
+                self.expect_all(arguments, &StateType::String, &method);
+                StateType::Array(Box::new(StateType::String))
+            }
+            (_, "replace" | "replaceAll") => {
+                self.expect_all(arguments, &StateType::String, &method);
+                text()
+            }
+            (_, "padStart" | "padEnd") => {
+                self.expect(&arguments[0], &StateType::Number, &method);
+                self.expect_all(&arguments[1..], &StateType::String, &method);
+                text()
+            }
+            _ => {
+                self.expect_all(arguments, &StateType::Number, &method);
+                text()
+            }
+        }
+    }
+
+    /// Checks the function passed to an array method, whose parameters
+    /// receive `parameters`, and returns the type of its body.
+    fn callback(
+        &mut self,
+        method: &str,
+        argument: &Expr<'a>,
+        parameters: &[&StateType],
+    ) -> StateType {
+        if let ExprKind::Lambda {
+            parameters: names,
+            body,
+        } = &argument.kind
+        {
+            return self.lambda(names, body, parameters);
+        }
+        let ty = self.expr(argument);
+        self.problem(
+            Diagnostic::error(
+                codes::TYPE_MISMATCH,
+                format!("`{}` expects a function", method),
+            )
+            .with_label(self.label(argument, &ty))
+            .with_help("pass a function such as `item => item.done`"),
+            argument,
+        );
+        StateType::Unknown
+    }
+
+    fn lambda(&mut self, names: &[Ident<'a>], body: &Expr<'a>, types: &[&StateType]) -> StateType {
+        let depth = self.locals.len();
+        for (i, name) in names.iter().enumerate() {
+            let ty = types.get(i).map_or(StateType::Unknown, |ty| (*ty).clone());
+            self.locals.push((name.name, ty));
+        }
+        let ty = self.expr(body);
+        self.locals.truncate(depth);
+        ty
+    }
+
+    /// Checks arguments whose types aren't known, such as those of a
+    /// function that doesn't exist.
+    fn loose(&mut self, arguments: &[Expr<'a>]) {
+        for argument in arguments {
+            match &argument.kind {
+                ExprKind::Lambda { parameters, body } => {
+                    self.lambda(parameters, body, &[]);
+                }
+                _ => {
+                    self.expr(argument);
+                }
+            }
+        }
+    }
+
+    /// Reports calls with too few or too many arguments.
+    fn arity(
+        &mut self,
+        name: &str,
+        callee: &Expr<'a>,
+        min: usize,
+        max: usize,
+        arguments: &[Expr<'a>],
+    ) -> bool {
+        let count = arguments.len();
+        if (min..=max).contains(&count) {
+            return true;
+        }
+        let plural = |n: usize| if n == 1 { "argument" } else { "arguments" };
+        let expected = if min == max {
+            format!("{} {}", min, plural(min))
+        } else if max == usize::MAX {
+            format!("at least {} {}", min, plural(min))
+        } else if min == 0 {
+            format!("at most {} {}", max, plural(max))
+        } else {
+            format!("{} to {} arguments", min, max)
+        };
+        let diagnostic = Diagnostic::error(
+            codes::FUNCTION_ARGUMENTS,
+            format!(
+                "`{}` takes {} but {} {} supplied",
+                name,
+                expected,
+                count,
+                if count == 1 { "was" } else { "were" }
+            ),
+        )
+        .with_label(format!("expected {}", expected));
+        self.problem(diagnostic, callee);
+        false
+    }
+
+    fn binary(
+        &mut self,
+        expr: &Expr<'a>,
+        op: BinaryOp,
+        left: &Expr<'a>,
+        right: &Expr<'a>,
+    ) -> StateType {
+        let l = self.expr(left);
+        let r = self.expr(right);
+        match op {
+            BinaryOp::And if l == StateType::Boolean && r == StateType::Boolean => {
+                StateType::Boolean
+            }
+            BinaryOp::And => StateType::Unknown,
+            BinaryOp::Or | BinaryOp::Coalesce => join(non_null(&l), &r),
+            BinaryOp::Add => {
+                for (operand, ty) in [(left, &l), (right, &r)] {
+                    if !is_number(ty) && !is_string(ty) {
+                        self.operator_mismatch("+", operand, ty);
+                    }
+                }
+                if is_string(&l) || is_string(&r) {
+                    StateType::String
+                } else if l == StateType::Number && r == StateType::Number {
+                    StateType::Number
+                } else {
+                    StateType::Unknown
+                }
+            }
+            BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Remainder => {
+                for (operand, ty) in [(left, &l), (right, &r)] {
+                    if !is_number(ty) {
+                        self.operator_mismatch(op.symbol(), operand, ty);
+                    }
+                }
+                StateType::Number
+            }
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual => {
+                let (a, b) = (non_null(&l), non_null(&r));
+                let comparable = *a == StateType::Unknown
+                    || *b == StateType::Unknown
+                    || (*a == StateType::Number && *b == StateType::Number)
+                    || (is_string(a) && is_string(b));
+                if !comparable {
+                    self.problem(
+                        Diagnostic::error(
+                            codes::TYPE_MISMATCH,
+                            format!("cannot compare {} with {}", l, r),
+                        )
+                        .with_label(format!(
+                            "`{}` compares two numbers or two strings",
+                            op.symbol()
+                        )),
+                        expr,
+                    );
+                }
+                StateType::Boolean
+            }
+            BinaryOp::Equal | BinaryOp::NotEqual => {
+                self.equality(expr, (left, &l), (right, &r));
+                StateType::Boolean
+            }
+        }
+    }
+
+    /// Reports comparisons between values that can never be equal, such as
+    /// a union state and a string that isn't one of its members.
+    fn equality(
+        &mut self,
+        expr: &Expr<'a>,
+        (left, l): (&Expr<'a>, &StateType),
+        (right, r): (&Expr<'a>, &StateType),
+    ) {
+        let (a, b) = (non_null(l), non_null(r));
+        let overlapping = match (a, b) {
+            (StateType::Union(members), StateType::Union(others)) => {
+                others.iter().any(|other| members.contains(other))
+            }
+            _ => a.accepts(b) || b.accepts(a),
+        };
+        if overlapping {
+            return;
+        }
+        // A literal reads better as written than as its one-member type.
+        let describe = |operand: &Expr, ty: &StateType| {
+            if operand.is_literal() {
+                format!("`{}`", self.snippet(operand))
+            } else {
+                ty.to_string()
+            }
+        };
+        let mut diagnostic = Diagnostic::error(
+            codes::TYPE_MISMATCH,
+            format!(
+                "cannot compare {} with {}",
+                describe(left, l),
+                describe(right, r)
+            ),
+        );
+        let literal = match (a, b) {
+            (StateType::Union(members), StateType::Union(_)) if right.is_literal() => {
+                Some((right, members))
+            }
+            (StateType::Union(_), StateType::Union(members)) if left.is_literal() => {
+                Some((left, members))
+            }
+            _ => None,
+        };
+        let Some((literal, members)) = literal else {
+            diagnostic = diagnostic.with_label("these values are never equal");
+            return self.problem(diagnostic, expr);
+        };
+        let union = StateType::Union(members.clone());
+        diagnostic = diagnostic.with_label(format!("`{}` is not {}", self.snippet(literal), union));
+        if let ExprKind::String(value) = &literal.kind {
+            let members: Vec<&str> = members.iter().map(String::as_str).collect();
+            if let Some(candidate) = suggest(value, &members) {
+                diagnostic = diagnostic.with_help(format!("did you mean '{}'?", candidate));
+            }
+        }
+        self.problem(diagnostic, literal);
+    }
+
+    fn operator_mismatch(&mut self, op: &str, operand: &Expr<'a>, ty: &StateType) {
+        let diagnostic = Diagnostic::error(
+            codes::TYPE_MISMATCH,
+            format!("`{}` cannot be applied to {}", op, ty),
+        )
+        .with_label(self.label(operand, ty));
+        self.problem(diagnostic, operand);
+    }
+
+    /// Checks that `expr` has a type `expected` accepts, where `what` names
+    /// what expects it, e.g. `` `join` ``.
+    fn expect(&mut self, expr: &Expr<'a>, expected: &StateType, what: &str) {
+        let ty = self.expr(expr);
+        if expected.accepts(&ty) {
+            return;
+        }
+        let label = if expr.is_literal() {
+            format!("`{}` is not {}", self.snippet(expr), expected)
+        } else {
+            self.label(expr, &ty)
+        };
+        let diagnostic = Diagnostic::error(
+            codes::TYPE_MISMATCH,
+            format!("{} expects {}", what, expected),
+        )
+        .with_label(label);
+        self.problem(diagnostic, expr);
+    }
+
+    fn expect_all(&mut self, arguments: &[Expr<'a>], expected: &StateType, what: &str) {
+        for argument in arguments {
+            self.expect(argument, expected, what);
+        }
+    }
+
+    /// Describes the type of `expr` for a label, quoting its source when it
+    /// is short enough to read at a glance.
+    fn label(&self, expr: &Expr<'a>, ty: &StateType) -> String {
+        let snippet = self.snippet(expr);
+        if snippet.chars().count() <= 24 {
+            format!("`{}` is {}", snippet, ty)
+        } else {
+            format!("this is {}", ty)
+        }
+    }
+
+    fn snippet(&self, expr: &Expr) -> String {
+        let end = (expr.offset + expr.length).min(self.source.len());
+        self.source[expr.offset.min(end)..end].iter().collect()
+    }
+
+    fn problem(&mut self, diagnostic: Diagnostic, expr: &Expr) {
+        self.problem_at(diagnostic, expr.offset, expr.length);
+    }
+
+    fn problem_at(&mut self, diagnostic: Diagnostic, offset: usize, length: usize) {
+        self.problems.push(Problem {
+            diagnostic,
+            offset,
+            length,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::parse;
+    use std::collections::HashMap;
+
+    struct Page(HashMap<&'static str, StateType>);
+
+    impl Scope for Page {
+        fn lookup(&self, name: &str) -> Option<StateType> {
+            self.0.get(name).cloned()
+        }
+
+        fn names(&self) -> Vec<&str> {
+            self.0.keys().copied().collect()
+        }
+    }
+
+    fn page() -> Page {
+        let ty = |source| StateType::parse(source).unwrap();
+        Page(HashMap::from([
+            ("count", StateType::Number),
+            ("draft", StateType::String),
+            ("done", StateType::Boolean),
+            ("size", ty("'sm' | 'lg'")),
+            ("user", ty("{ name: string, tags: string[] } | null")),
+            ("items", ty("{ title: string, price: number }[]")),
+        ]))
+    }
+
+    fn check_source(source: &str) -> (StateType, Vec<Problem>) {
+        check(&parse(source).unwrap(), source, &page(), "condition")
+    }
+
+    fn infer(source: &str) -> String {
+        let (ty, problems) = check_source(source);
+        assert!(problems.is_empty(), "{:?}", problems);
+        ty.to_ts()
+    }
+
+    fn problems(source: &str) -> Vec<(String, usize, usize)> {
+        check_source(source)
+            .1
+            .into_iter()
+            .map(|problem| (problem.diagnostic.message, problem.offset, problem.length))
+            .collect()
+    }
+
+    #[test]
+    fn test_infer_types() {
+        assert_eq!(infer("count + 1"), "number");
+        assert_eq!(infer("'Total: ' + count"), "string");
+        assert_eq!(infer("done ? 'on' : 'off'"), "\"on\" | \"off\"");
+        assert_eq!(infer("user?.name"), "string | null");
+        assert_eq!(infer("user?.name ?? 'guest'"), "string");
+        assert_eq!(infer("items.map(item => item.price)"), "number[]");
+        assert_eq!(
+            infer("items.reduce((sum, item) => sum + item.price, 0)"),
+            "number"
+        );
+        assert_eq!(
+            infer("items.find(i => i.price > count)"),
+            "{ title: string; price: number } | null"
+        );
+        assert_eq!(infer("len(draft.trim()) > 0 && !done"), "boolean");
+        assert_eq!(
+            infer("{ count, label: upper(draft) }"),
+            "{ count: number; label: string }"
+        );
+        assert_eq!(infer("items[0].title.split(' ')"), "string[]");
+        assert_eq!(infer("Math.max(count, 1)"), "any");
+    }
+
+    #[test]
+    fn test_names_are_resolved() {
+        assert_eq!(
+            problems("cont > 1 && items.some(item => item.titel)"),
+            vec![
+                (
+                    "condition refers to undeclared state `cont`".to_string(),
+                    0,
+                    4
+                ),
+                ("no field `titel` on `item`".to_string(), 36, 5),
+            ]
+        );
+        let (_, found) = check_source("cont > 1");
+        assert_eq!(
+            found[0].diagnostic.help.as_deref(),
+            Some("did you mean `count`?")
+        );
+        assert_eq!(
+            problems("user?.tags.joon(', ') + format(draft)"),
+            vec![
+                ("no method `joon` on `user?.tags`".to_string(), 11, 4),
+                ("cannot find function `format`".to_string(), 24, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nullable_fields_are_read_optionally() {
+        assert_eq!(
+            problems("user.name"),
+            vec![("`user` may be null".to_string(), 0, 4)]
+        );
+        let (ty, found) = check_source("user.name");
+        assert_eq!(ty.to_ts(), "string | null");
+        assert_eq!(
+            found[0].diagnostic.help.as_deref(),
+            Some("read the field with `user?.name`, which is null while `user` is")
+        );
+        assert_eq!(infer("user?.tags.length"), "number | null");
+    }
+
+    #[test]
+    fn test_types_are_checked() {
+        assert_eq!(
+            problems("count * draft"),
+            vec![("`*` cannot be applied to `string`".to_string(), 8, 5)]
+        );
+        assert_eq!(
+            problems("size == 'md'"),
+            vec![(
+                "cannot compare one of \"sm\" | \"lg\" with `'md'`".to_string(),
+                8,
+                4
+            )]
+        );
+        let (_, found) = check_source("size == 'md'");
+        assert_eq!(
+            found[0].diagnostic.label.as_deref(),
+            Some("`'md'` is not one of \"sm\" | \"lg\"")
+        );
+        assert_eq!(
+            problems("count > draft"),
+            vec![("cannot compare `number` with `string`".to_string(), 0, 13)]
+        );
+        assert_eq!(
+            problems("round(draft, 2)"),
+            vec![(
+                "`round` takes 1 argument but 2 were supplied".to_string(),
+                0,
+                5
+            )]
+        );
+        assert_eq!(
+            problems("items.map(count)"),
+            vec![("`map` expects a function".to_string(), 10, 5)]
+        );
+        assert_eq!(
+            problems("item => item"),
+            vec![(
+                "functions can only be passed to methods such as `map`".to_string(),
+                0,
+                12
+            )]
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Emits checked NWL expressions as JavaScript.
+
+use super::ast::{BinaryOp, Expr, ExprKind, UnaryOp, BUILTINS, CONSTANTS};
+use super::parser::Segment;
+use crate::escape::{js_string, jsx_text};
+
+/// Emits `expr` as a JavaScript expression. `rename` maps the names of
+/// states, computed values and list items to their generated variables.
+pub fn emit(expr: &Expr, rename: &dyn Fn(&str) -> String) -> String {
+    Emitter {
+        rename,
+        locals: Vec::new(),
+    }
+    .expr(expr)
+}
+
+/// Emits interpolated text as JSX children, with every expression in
+/// `{…}`.
+pub fn emit_text(segments: &[Segment], rename: &dyn Fn(&str) -> String) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => jsx_text(text),
+            Segment::Expression(expr) => format!("{{{}}}", emit(expr, rename)),
+        })
+        .collect()
+}
+
+/// Emits interpolated text as a JavaScript string: a string literal, or a
+/// template literal when the text has expressions.
+pub fn emit_string(segments: &[Segment], rename: &dyn Fn(&str) -> String) -> String {
+    if segments
+        .iter()
+        .all(|segment| matches!(segment, Segment::Text(_)))
+    {
+        let text: String = segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => *text,
+                Segment::Expression(_) => "",
+            })
+            .collect();
+        return js_string(&text);
+    }
+    let mut template = String::from("`");
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => {
+                for c in text.chars() {
+                    match c {
+                        '`' | '\\' | '$' => {
+                            template.push('\\');
+                            template.push(c);
+                        }
+                        c => template.push(c),
+                    }
+                }
+            }
+            Segment::Expression(expr) => {
+                template.push_str(&format!("${{{}}}", emit(expr, rename)));
+            }
+        }
+    }
+    template.push('`');
+    template
+}
+
+struct Emitter<'r, 'a> {
+    rename: &'r dyn Fn(&str) -> String,
+    /// Parameters of the enclosing functions.
+    locals: Vec<&'a str>,
+}
+
+impl<'a> Emitter<'_, 'a> {
+    fn expr(&mut self, expr: &Expr<'a>) -> String {
+        match &expr.kind {
+            ExprKind::Number(text) => text.to_string(),
+            ExprKind::String(value) => js_string(value),
+            ExprKind::Boolean(flag) => flag.to_string(),
+            ExprKind::Null => "null".to_string(),
+            ExprKind::Undefined => "undefined".to_string(),
+            ExprKind::Name(name) => self.name(name),
+            ExprKind::Array(items) => {
+                let items: Vec<String> = items.iter().map(|item| self.expr(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            ExprKind::Object(properties) if properties.is_empty() => "{}".to_string(),
+            ExprKind::Object(properties) => {
+                let entries: Vec<String> = properties
+                    .iter()
+                    .map(|property| {
+                        let value = self.expr(&property.value);
+                        if property.shorthand && value == property.key.name {
+                            value
+                        } else {
+                            format!("{}: {}", property.key.name, value)
+                        }
+                    })
+                    .collect();
+                format!("{{ {} }}", entries.join(", "))
+            }
+            ExprKind::Member {
+                object,
+                name,
+                optional,
+            } => format!(
+                "{}{}{}",
+                self.object(object),
+                if *optional { "?." } else { "." },
+                name.name
+            ),
+            ExprKind::Index {
+                object,
+                index,
+                optional,
+            } => format!(
+                "{}{}[{}]",
+                self.object(object),
+                if *optional { "?." } else { "" },
+                self.expr(index)
+            ),
+            ExprKind::Call { callee, arguments } => {
+                let builtin = match &callee.kind {
+                    ExprKind::Name(name) if !self.locals.contains(name) => BUILTINS
+                        .iter()
+                        .any(|(builtin, _, _)| builtin == name)
+                        .then_some(*name),
+                    _ => None,
+                };
+                match builtin {
+                    Some(name) => self.builtin(name, arguments),
+                    None => format!("{}({})", self.object(callee), self.arguments(arguments)),
+                }
+            }
+            ExprKind::Unary { op, operand } => {
+                // `- -x` would read as a decrement without the parentheses.
+                let nested = matches!(
+                    operand.kind,
+                    ExprKind::Unary {
+                        op: UnaryOp::Negate,
+                        ..
+                    }
+                );
+                let operand = self.wrap(operand, operand.precedence() < 14 || nested);
+                match op {
+                    UnaryOp::TypeOf => format!("typeof {}", operand),
+                    op => format!("{}{}", op.symbol(), operand),
+                }
+            }
+            ExprKind::Binary { op, left, right } => {
+                let precedence = op.precedence();
+                let left = self.wrap(
+                    left,
+                    left.precedence() < precedence || mixes_nullish(*op, left),
+                );
+                let right = self.wrap(
+                    right,
+                    right.precedence() <= precedence || mixes_nullish(*op, right),
+                );
+                format!("{} {} {}", left, op.symbol(), right)
+            }
+            ExprKind::Conditional {
+                test,
+                consequent,
+                alternate,
+            } => format!(
+                "{} ? {} : {}",
+                self.wrap(test, test.precedence() <= 2),
+                self.wrap(consequent, consequent.precedence() < 2),
+                self.wrap(alternate, alternate.precedence() < 2)
+            ),
+            ExprKind::Lambda { parameters, body } => {
+                let depth = self.locals.len();
+                self.locals
+                    .extend(parameters.iter().map(|parameter| parameter.name));
+                // A body starting with `{` would read as a block.
+                let body = self.wrap(body, matches!(body.kind, ExprKind::Object(_)));
+                self.locals.truncate(depth);
+                let parameters: Vec<&str> =
+                    parameters.iter().map(|parameter| parameter.name).collect();
+                format!("({}) => {}", parameters.join(", "), body)
+            }
+        }
+    }
+
+    fn name(&self, name: &str) -> String {
+        if self.locals.contains(&name) || CONSTANTS.contains(&name) {
+            name.to_string()
+        } else {
+            (self.rename)(name)
+        }
+    }
+
+    fn builtin(&mut self, name: &str, arguments: &[Expr<'a>]) -> String {
+        let method = match name {
+            "len" => return format!("{}.length", self.object(&arguments[0])),
+            "upper" => "toUpperCase",
+            "lower" => "toLowerCase",
+            "trim" => "trim",
+            "str" => return format!("String({})", self.arguments(arguments)),
+            "num" => return format!("Number({})", self.arguments(arguments)),
+            _ => return format!("Math.{}({})", name, self.arguments(arguments)),
+        };
+        format!("{}.{}()", self.object(&arguments[0]), method)
+    }
+
+    fn arguments(&mut self, arguments: &[Expr<'a>]) -> String {
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| self.expr(argument))
+            .collect();
+        arguments.join(", ")
+    }
+
+    /// Emits the object of a member access or call.
+    fn object(&mut self, object: &Expr<'a>) -> String {
+        let needs_parens = object.precedence() < 17
+            || matches!(object.kind, ExprKind::Number(_) | ExprKind::Object(_));
+        self.wrap(object, needs_parens)
+    }
+
+    fn wrap(&mut self, expr: &Expr<'a>, parenthesize: bool) -> String {
+        let emitted = self.expr(expr);
+        if parenthesize {
+            format!("({})", emitted)
+        } else {
+            emitted
+        }
+    }
+}
+
+/// JavaScript rejects `??` mixed with `&&` or `||` without parentheses.
+fn mixes_nullish(op: BinaryOp, operand: &Expr) -> bool {
+    let ExprKind::Binary { op: inner, .. } = operand.kind else {
+        return false;
+    };
+    match op {
+        BinaryOp::Coalesce => matches!(inner, BinaryOp::And | BinaryOp::Or),
+        BinaryOp::And | BinaryOp::Or => inner == BinaryOp::Coalesce,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::{parse, parse_text};
+
+    fn emitted(source: &str) -> String {
+        let rename = |name: &str| name.replace("_c", "C");
+        emit(&parse(source).unwrap(), &rename)
+    }
+
+    #[test]
+    fn test_emit() {
+        assert_eq!(emitted("item_count == 0"), "itemCount === 0");
+        assert_eq!(emitted("(a + b) * c - (d - e)"), "(a + b) * c - (d - e)");
+        assert_eq!(emitted("a ?? (b || c)"), "a ?? (b || c)");
+        assert_eq!(
+            emitted("!(a && b) ? 'yes' : \"no\""),
+            "!(a && b) ? \"yes\" : \"no\""
+        );
+        assert_eq!(emitted("- -x"), "-(-x)");
+        assert_eq!(
+            emitted("items.reduce((sum, item) => sum + item.price, 0)"),
+            "items.reduce((sum, item) => sum + item.price, 0)"
+        );
+        assert_eq!(
+            emitted("items.map(item => { item, n: len(item.tags) })"),
+            "items.map((item) => ({ item, n: item.tags.length }))"
+        );
+        assert_eq!(
+            emitted("{ total, count: item_count }"),
+            "{ total, count: itemCount }"
+        );
+        assert_eq!(emitted("{ item_count }"), "{ item_count: itemCount }");
+        assert_eq!(
+            emitted("upper(first + last) + max(a, 1) + str(n)"),
+            "(first + last).toUpperCase() + Math.max(a, 1) + String(n)"
+        );
+        assert_eq!(emitted("user?.tags?.[0]"), "user?.tags?.[0]");
+        assert_eq!(
+            emitted("typeof x === 'undefined'"),
+            "typeof x === \"undefined\""
+        );
+    }
+
+    #[test]
+    fn test_emit_text() {
+        let rename = |name: &str| name.to_string();
+        let segments = parse_text("Hello {user.name}, {len(items)} left").unwrap();
+        assert_eq!(
+            emit_text(&segments, &rename),
+            "Hello {user.name}, {items.length} left"
+        );
+        let segments = parse_text("a < b } \"c\"").unwrap();
+        assert_eq!(emit_text(&segments, &rename), "a &lt; b &#125; \"c\"");
+    }
+
+    #[test]
+    fn test_emit_string() {
+        let rename = |name: &str| name.to_string();
+        let segments = parse_text("It's \"$5\"").unwrap();
+        assert_eq!(emit_string(&segments, &rename), "'It\\'s \"$5\"'");
+        let segments = parse_text("`cost` {total}$").unwrap();
+        assert_eq!(emit_string(&segments, &rename), "`\\`cost\\` ${total}\\$`");
+    }
+}
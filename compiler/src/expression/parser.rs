@@ -0,0 +1,760 @@
+//! Parser for NWL expressions and for text with `{expression}`
+//! interpolations.
+
+use super::ast::{BinaryOp, Expr, ExprKind, Ident, Property, UnaryOp};
+use nwl_shared::RESERVED_WORDS;
+
+/// An expression that could not be parsed, with the 0-based character
+/// offset and length of the offending token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// A piece of interpolated text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Expression(Expr<'a>),
+}
+
+/// Operators, longest first so `===` isn't read as `==`.
+const PUNCTUATION: &[&str] = &[
+    "===", "!==", "...", "?.", "??", "&&", "||", "==", "!=", "<=", ">=", "=>", "(", ")", "[", "]",
+    "{", "}", ",", ".", "?", ":", "!", "+", "-", "*", "/", "%", "<", ">", "=",
+];
+
+pub fn parse(source: &str) -> Result<Expr<'_>, SyntaxError> {
+    parse_at(source, 0)
+}
+
+/// Parses `source`, whose first character is at character `base` of the
+/// string it was taken from.
+fn parse_at(source: &str, base: usize) -> Result<Expr<'_>, SyntaxError> {
+    let mut parser = Parser {
+        tokens: tokenize(source, base)?,
+        position: 0,
+    };
+    let expr = parser.expression()?;
+    let token = parser.peek();
+    if token.kind != TokenKind::End {
+        return Err(parser.unexpected(token));
+    }
+    Ok(expr)
+}
+
+/// Splits `text` into plain text and the expressions interpolated in it
+/// with `{…}`. Offsets of the expressions are relative to `text`.
+pub fn parse_text(text: &str) -> Result<Vec<Segment<'_>>, SyntaxError> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte = |i: usize| chars.get(i).map_or(text.len(), |&(index, _)| index);
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].1 != '{' {
+            i += 1;
+            continue;
+        }
+        let close = closing_brace(&chars, i + 1).ok_or_else(|| SyntaxError {
+            message: "this `{` is never closed".to_string(),
+            offset: i,
+            length: 1,
+        })?;
+        if start < i {
+            segments.push(Segment::Text(&text[byte(start)..byte(i)]));
+        }
+        let inner = &text[byte(i + 1)..byte(close)];
+        if inner.trim().is_empty() {
+            return Err(SyntaxError {
+                message: "expected an expression between `{` and `}`".to_string(),
+                offset: i,
+                length: close - i + 1,
+            });
+        }
+        segments.push(Segment::Expression(parse_at(inner, i + 1)?));
+        start = close + 1;
+        i = close + 1;
+    }
+    if start < chars.len() {
+        segments.push(Segment::Text(&text[byte(start)..]));
+    }
+    Ok(segments)
+}
+
+/// Finds the `}` matching a `{` that ends just before character `from`,
+/// skipping nested braces and string literals.
+fn closing_brace(chars: &[(usize, char)], from: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i].1 {
+            quote @ ('"' | '\'') => {
+                i += 1;
+                while i < chars.len() && chars[i].1 != quote {
+                    if chars[i].1 == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind<'a> {
+    Number(&'a str),
+    String(String),
+    Name(&'a str),
+    Punct(&'static str),
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    offset: usize,
+    length: usize,
+}
+
+fn tokenize(source: &str, base: usize) -> Result<Vec<Token<'_>>, SyntaxError> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let byte = |i: usize| chars.get(i).map_or(source.len(), |&(index, _)| index);
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+    let is_digit = |i: usize| chars.get(i).is_some_and(|&(_, c)| c.is_ascii_digit());
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let start = i;
+        let kind = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() || (c == '.' && is_digit(i + 1)) {
+            while is_digit(i) || chars.get(i).is_some_and(|&(_, c)| c == '.') {
+                i += 1;
+            }
+            if chars.get(i).is_some_and(|&(_, c)| c == 'e' || c == 'E') {
+                let sign = chars.get(i + 1).is_some_and(|&(_, c)| c == '+' || c == '-');
+                if is_digit(i + 1) || (sign && is_digit(i + 2)) {
+                    i += if sign { 2 } else { 1 };
+                    while is_digit(i) {
+                        i += 1;
+                    }
+                }
+            }
+            let text = &source[byte(start)..byte(i)];
+            if text.matches('.').count() > 1 || chars.get(i).is_some_and(|&(_, c)| is_name(c)) {
+                while chars.get(i).is_some_and(|&(_, c)| is_name(c) || c == '.') {
+                    i += 1;
+                }
+                return Err(SyntaxError {
+                    message: format!("invalid number `{}`", &source[byte(start)..byte(i)]),
+                    offset: base + start,
+                    length: i - start,
+                });
+            }
+            TokenKind::Number(text)
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            let mut value = String::new();
+            loop {
+                let Some(&(_, next)) = chars.get(i) else {
+                    return Err(SyntaxError {
+                        message: "this string is never closed".to_string(),
+                        offset: base + start,
+                        length: i - start,
+                    });
+                };
+                i += 1;
+                match next {
+                    _ if next == c => break,
+                    '\\' => {
+                        let Some(&(_, escaped)) = chars.get(i) else {
+                            continue;
+                        };
+                        i += 1;
+                        value.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            'r' => '\r',
+                            '0' => '\0',
+                            other => other,
+                        });
+                    }
+                    other => value.push(other),
+                }
+            }
+            TokenKind::String(value)
+        } else if c == '`' {
+            return Err(SyntaxError {
+                message: "template strings are not supported; join strings with `+`".to_string(),
+                offset: base + start,
+                length: 1,
+            });
+        } else if is_name(c) {
+            while chars.get(i).is_some_and(|&(_, c)| is_name(c)) {
+                i += 1;
+            }
+            TokenKind::Name(&source[byte(start)..byte(i)])
+        } else {
+            let rest = &source[byte(i)..];
+            let punct = PUNCTUATION
+                .iter()
+                .find(|punct| rest.starts_with(**punct))
+                // `a?.5:1` is a conditional, not optional chaining.
+                .filter(|punct| **punct != "?." || !is_digit(i + 2))
+                .or_else(|| (c == '?').then_some(&"?"))
+                .ok_or_else(|| SyntaxError {
+                    message: format!("unexpected character `{}`", c),
+                    offset: base + start,
+                    length: 1,
+                })?;
+            i += punct.chars().count();
+            TokenKind::Punct(punct)
+        };
+        tokens.push(Token {
+            kind,
+            offset: base + start,
+            length: i - start,
+        });
+    }
+    tokens.push(Token {
+        kind: TokenKind::End,
+        offset: base + chars.len(),
+        length: 1,
+    });
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Token<'a> {
+        self.tokens[self.position].clone()
+    }
+
+    fn peek_at(&self, ahead: usize) -> &TokenKind<'a> {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.position + ahead).min(last)].kind
+    }
+
+    fn next(&mut self) -> Token<'a> {
+        let token = self.peek();
+        if token.kind != TokenKind::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> Option<Token<'a>> {
+        if matches!(self.peek_at(0), TokenKind::Punct(p) if *p == punct) {
+            Some(self.next())
+        } else {
+            None
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<Token<'a>, SyntaxError> {
+        match self.eat(punct) {
+            Some(token) => Ok(token),
+            None => {
+                let token = self.peek();
+                let found = match &token.kind {
+                    TokenKind::End => "the end of the expression".to_string(),
+                    _ => format!("`{}`", describe(&token.kind)),
+                };
+                Err(SyntaxError {
+                    message: format!("expected `{}`, found {}", punct, found),
+                    offset: token.offset,
+                    length: token.length,
+                })
+            }
+        }
+    }
+
+    fn unexpected(&self, token: Token<'a>) -> SyntaxError {
+        let message = match &token.kind {
+            TokenKind::End => "unexpected end of expression".to_string(),
+            TokenKind::Punct("=") => "expressions cannot assign; use `==` to compare".to_string(),
+            kind => format!("unexpected `{}`", describe(kind)),
+        };
+        SyntaxError {
+            message,
+            offset: token.offset,
+            length: token.length,
+        }
+    }
+
+    fn node(&self, kind: ExprKind<'a>, offset: usize) -> Expr<'a> {
+        let previous = &self.tokens[self.position.saturating_sub(1)];
+        Expr {
+            kind,
+            offset,
+            length: (previous.offset + previous.length).saturating_sub(offset),
+        }
+    }
+
+    /// A full expression: a conditional, or anything binding tighter.
+    fn expression(&mut self) -> Result<Expr<'a>, SyntaxError> {
+        let start = self.peek().offset;
+        let test = self.binary(1)?;
+        if self.eat("?").is_none() {
+            return Ok(test);
+        }
+        let consequent = self.expression()?;
+        self.expect(":")?;
+        let alternate = self.expression()?;
+        Ok(self.node(
+            ExprKind::Conditional {
+                test: Box::new(test),
+                consequent: Box::new(consequent),
+                alternate: Box::new(alternate),
+            },
+            start,
+        ))
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Result<Expr<'a>, SyntaxError> {
+        let start = self.peek().offset;
+        let mut left = self.unary()?;
+        while let Some(op) = binary_op(self.peek_at(0)) {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            self.next();
+            let right = self.binary(op.precedence() + 1)?;
+            left = self.node(
+                ExprKind::Binary {
+                    op,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+                start,
+            );
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr<'a>, SyntaxError> {
+        let token = self.peek();
+        let op = match token.kind {
+            TokenKind::Punct("!") => UnaryOp::Not,
+            TokenKind::Punct("-") => UnaryOp::Negate,
+            TokenKind::Name("typeof") => UnaryOp::TypeOf,
+            _ => return self.postfix(),
+        };
+        self.next();
+        let operand = self.unary()?;
+        Ok(self.node(
+            ExprKind::Unary {
+                op,
+                operand: Box::new(operand),
+            },
+            token.offset,
+        ))
+    }
+
+    fn postfix(&mut self) -> Result<Expr<'a>, SyntaxError> {
+        let start = self.peek().offset;
+        let mut expr = self.primary()?;
+        loop {
+            let kind = if self.eat(".").is_some() {
+                ExprKind::Member {
+                    object: Box::new(expr),
+                    name: self.property_name()?,
+                    optional: false,
+                }
+            } else if self.eat("?.").is_some() {
+                if self.eat("[").is_some() {
+                    let index = self.expression()?;
+                    self.expect("]")?;
+                    ExprKind::Index {
+                        object: Box::new(expr),
+                        index: Box::new(index),
+                        optional: true,
+                    }
+                } else {
+                    ExprKind::Member {
+                        object: Box::new(expr),
+                        name: self.property_name()?,
+                        optional: true,
+                    }
+                }
+            } else if self.eat("[").is_some() {
+                let index = self.expression()?;
+                self.expect("]")?;
+                ExprKind::Index {
+                    object: Box::new(expr),
+                    index: Box::new(index),
+                    optional: false,
+                }
+            } else if self.eat("(").is_some() {
+                ExprKind::Call {
+                    callee: Box::new(expr),
+                    arguments: self.list(")")?,
+                }
+            } else {
+                return Ok(expr);
+            };
+            expr = self.node(kind, start);
+        }
+    }
+
+    fn property_name(&mut self) -> Result<Ident<'a>, SyntaxError> {
+        let token = self.next();
+        match token.kind {
+            TokenKind::Name(name) => Ok(Ident {
+                name,
+                offset: token.offset,
+            }),
+            _ => Err(SyntaxError {
+                message: "expected a field name after `.`".to_string(),
+                offset: token.offset,
+                length: token.length,
+            }),
+        }
+    }
+
+    /// Comma-separated expressions up to `close`, allowing a trailing comma.
+    fn list(&mut self, close: &str) -> Result<Vec<Expr<'a>>, SyntaxError> {
+        let mut items = Vec::new();
+        while self.eat(close).is_none() {
+            items.push(self.expression()?);
+            if self.eat(",").is_none() {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn primary(&mut self) -> Result<Expr<'a>, SyntaxError> {
+        if self.is_lambda() {
+            return self.lambda();
+        }
+        let token = self.next();
+        let kind = match token.kind {
+            TokenKind::Number(text) => ExprKind::Number(text),
+            TokenKind::String(ref value) => ExprKind::String(value.clone()),
+            TokenKind::Name("true") => ExprKind::Boolean(true),
+            TokenKind::Name("false") => ExprKind::Boolean(false),
+            TokenKind::Name("null") => ExprKind::Null,
+            TokenKind::Name("undefined") => ExprKind::Undefined,
+            TokenKind::Name(name) if RESERVED_WORDS.contains(&name) => {
+                return Err(SyntaxError {
+                    message: format!("`{}` is not supported in expressions", name),
+                    offset: token.offset,
+                    length: token.length,
+                })
+            }
+            TokenKind::Name(name) => ExprKind::Name(name),
+            TokenKind::Punct("(") => {
+                let inner = self.expression()?;
+                self.expect(")")?;
+                return Ok(inner);
+            }
+            TokenKind::Punct("[") => ExprKind::Array(self.list("]")?),
+            TokenKind::Punct("{") => ExprKind::Object(self.properties()?),
+            _ => {
+                return Err(match token.kind {
+                    TokenKind::End => SyntaxError {
+                        message: "expected an expression".to_string(),
+                        offset: token.offset,
+                        length: token.length,
+                    },
+                    _ => self.unexpected(token),
+                })
+            }
+        };
+        Ok(self.node(kind, token.offset))
+    }
+
+    fn properties(&mut self) -> Result<Vec<Property<'a>>, SyntaxError> {
+        let mut properties = Vec::new();
+        while self.eat("}").is_none() {
+            let token = self.next();
+            let TokenKind::Name(name) = token.kind else {
+                return Err(SyntaxError {
+                    message: "expected a field name".to_string(),
+                    offset: token.offset,
+                    length: token.length,
+                });
+            };
+            let key = Ident {
+                name,
+                offset: token.offset,
+            };
+            let (value, shorthand) = if self.eat(":").is_some() {
+                (self.expression()?, false)
+            } else {
+                let value = Expr {
+                    kind: ExprKind::Name(name),
+                    offset: token.offset,
+                    length: token.length,
+                };
+                (value, true)
+            };
+            properties.push(Property {
+                key,
+                value,
+                shorthand,
+            });
+            if self.eat(",").is_none() {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(properties)
+    }
+
+    /// Whether the next tokens start an arrow function: `x =>` or
+    /// `(a, b) =>`.
+    fn is_lambda(&self) -> bool {
+        match self.peek_at(0) {
+            TokenKind::Name(_) => *self.peek_at(1) == TokenKind::Punct("=>"),
+            TokenKind::Punct("(") => {
+                let mut ahead = 1;
+                loop {
+                    match self.peek_at(ahead) {
+                        TokenKind::Punct(")") => {
+                            return *self.peek_at(ahead + 1) == TokenKind::Punct("=>")
+                        }
+                        TokenKind::Name(_) => {}
+                        _ => return false,
+                    }
+                    match self.peek_at(ahead + 1) {
+                        TokenKind::Punct(",") => ahead += 2,
+                        TokenKind::Punct(")") => ahead += 1,
+                        _ => return false,
+                    }
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn lambda(&mut self) -> Result<Expr<'a>, SyntaxError> {
+        let start = self.peek().offset;
+        let parenthesized = self.eat("(").is_some();
+        let mut parameters = Vec::new();
+        while let TokenKind::Name(name) = self.peek().kind {
+            let token = self.next();
+            parameters.push(Ident {
+                name,
+                offset: token.offset,
+            });
+            if !parenthesized || self.eat(",").is_none() {
+                break;
+            }
+        }
+        if parenthesized {
+            self.expect(")")?;
+        }
+        self.expect("=>")?;
+        let body = self.expression()?;
+        Ok(self.node(
+            ExprKind::Lambda {
+                parameters,
+                body: Box::new(body),
+            },
+            start,
+        ))
+    }
+}
+
+fn binary_op(kind: &TokenKind) -> Option<BinaryOp> {
+    let TokenKind::Punct(punct) = kind else {
+        return None;
+    };
+    Some(match *punct {
+        "+" => BinaryOp::Add,
+        "-" => BinaryOp::Subtract,
+        "*" => BinaryOp::Multiply,
+        "/" => BinaryOp::Divide,
+        "%" => BinaryOp::Remainder,
+        "<" => BinaryOp::Less,
+        "<=" => BinaryOp::LessEqual,
+        ">" => BinaryOp::Greater,
+        ">=" => BinaryOp::GreaterEqual,
+        "==" | "===" => BinaryOp::Equal,
+        "!=" | "!==" => BinaryOp::NotEqual,
+        "&&" => BinaryOp::And,
+        "||" => BinaryOp::Or,
+        "??" => BinaryOp::Coalesce,
+        _ => return None,
+    })
+}
+
+fn describe<'a>(kind: &'a TokenKind<'a>) -> &'a str {
+    match kind {
+        TokenKind::Number(text) | TokenKind::Name(text) => text,
+        TokenKind::Punct(punct) => punct,
+        TokenKind::String(_) => "string",
+        TokenKind::End => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders the tree with explicit parentheses.
+    fn tree(expr: &Expr) -> String {
+        match &expr.kind {
+            ExprKind::Number(text) | ExprKind::Name(text) => text.to_string(),
+            ExprKind::String(value) => format!("'{}'", value),
+            ExprKind::Boolean(flag) => flag.to_string(),
+            ExprKind::Null => "null".to_string(),
+            ExprKind::Undefined => "undefined".to_string(),
+            ExprKind::Array(items) => {
+                format!("[{}]", items.iter().map(tree).collect::<Vec<_>>().join(" "))
+            }
+            ExprKind::Object(properties) => format!(
+                "{{{}}}",
+                properties
+                    .iter()
+                    .map(|p| format!("{}={}", p.key.name, tree(&p.value)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            ExprKind::Member { object, name, .. } => format!("{}.{}", tree(object), name.name),
+            ExprKind::Index { object, index, .. } => format!("{}[{}]", tree(object), tree(index)),
+            ExprKind::Call { callee, arguments } => format!(
+                "{}({})",
+                tree(callee),
+                arguments.iter().map(tree).collect::<Vec<_>>().join(" ")
+            ),
+            ExprKind::Unary { op, operand } => {
+                let space = if *op == UnaryOp::TypeOf { " " } else { "" };
+                format!("({}{}{})", op.symbol(), space, tree(operand))
+            }
+            ExprKind::Binary { op, left, right } => {
+                format!("({} {} {})", tree(left), op.symbol(), tree(right))
+            }
+            ExprKind::Conditional {
+                test,
+                consequent,
+                alternate,
+            } => format!(
+                "({} ? {} : {})",
+                tree(test),
+                tree(consequent),
+                tree(alternate)
+            ),
+            ExprKind::Lambda { parameters, body } => format!(
+                "(({}) => {})",
+                parameters
+                    .iter()
+                    .map(|p| p.name)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                tree(body)
+            ),
+        }
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let parsed = |source| tree(&parse(source).unwrap());
+        assert_eq!(parsed("a + b * c - d"), "((a + (b * c)) - d)");
+        assert_eq!(
+            parsed("!done && count >= 1 || x ?? 'y'"),
+            "((((!done) && (count >= 1)) || x) ?? 'y')"
+        );
+        assert_eq!(parsed("a ? b : c ? d : e"), "(a ? b : (c ? d : e))");
+        assert_eq!(parsed("-(a - b) == 3"), "((-(a - b)) === 3)");
+        assert_eq!(
+            parsed("items.filter(item => item.done)[0]?.name"),
+            "items.filter(((item) => item.done))[0].name"
+        );
+        assert_eq!(
+            parsed("items.reduce((sum, item) => sum + item.price, 0)"),
+            "items.reduce(((sum item) => (sum + item.price)) 0)"
+        );
+        assert_eq!(
+            parsed("{ total, count: len([1, 2.5e3,]) }"),
+            "{total=total count=len([1 2.5e3])}"
+        );
+        assert_eq!(
+            parsed("typeof x !== \"undefined\""),
+            "((typeof x) !== 'undefined')"
+        );
+    }
+
+    #[test]
+    fn test_parse_offsets() {
+        let expr = parse("user.name + 'é' + count").unwrap();
+        let ExprKind::Binary { right, .. } = &expr.kind else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!((right.offset, right.length), (18, 5));
+        assert_eq!(expr.references(), vec![(0, "user"), (18, "count")]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |source| {
+            let error = parse(source).unwrap_err();
+            (error.message, error.offset, error.length)
+        };
+        assert_eq!(
+            error("count = 1"),
+            (
+                "expressions cannot assign; use `==` to compare".to_string(),
+                6,
+                1
+            )
+        );
+        assert_eq!(error("a + "), ("expected an expression".to_string(), 4, 1));
+        assert_eq!(
+            error("f(a, b"),
+            (
+                "expected `)`, found the end of the expression".to_string(),
+                6,
+                1
+            )
+        );
+        assert_eq!(
+            error("'open"),
+            ("this string is never closed".to_string(), 0, 5)
+        );
+        assert_eq!(
+            error("new Date()"),
+            ("`new` is not supported in expressions".to_string(), 0, 3)
+        );
+        assert_eq!(error("a b"), ("unexpected `b`".to_string(), 2, 1));
+    }
+
+    #[test]
+    fn test_parse_text() {
+        let segments = parse_text("Hi {user.name}, you have {len(items)} {'}'}").unwrap();
+        assert_eq!(segments.len(), 6);
+        assert_eq!(segments[0], Segment::Text("Hi "));
+        let Segment::Expression(expr) = &segments[3] else {
+            panic!("expected an expression");
+        };
+        assert_eq!((expr.offset, expr.length), (26, 10));
+        assert_eq!(
+            parse_text("No braces").unwrap(),
+            vec![Segment::Text("No braces")]
+        );
+
+        let error = parse_text("Total: {total").unwrap_err();
+        assert_eq!((error.offset, error.length), (7, 1));
+        let error = parse_text("{a} and {b +}").unwrap_err();
+        assert_eq!(error.offset, 12);
+    }
+}
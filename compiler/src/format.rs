@@ -0,0 +1,1005 @@
+//! `nwl fmt`: rewrites the YAML files of a project into one layout, so that
+//! diffs show only what changed. Keys follow the order their types declare
+//! them in; elements start with their tag and end with their children. Lists of scalars are written
+//! `[a, b]` when they fit on a line, and every other collection in block
+//! style. Comments and single blank lines stay with the keys and items
+//! they were written next to.
+//!
+//! The formatter works on the YAML events of the file rather than on its
+//! types, so nothing but layout changes. As a safety net, the output is
+//! parsed again and must mean exactly what the input did.
+
+use crate::diagnostics::codes;
+use crate::{parse_actions, parse_component, parse_project, parse_source, CompilerError, FileKind};
+use nwl_shared::{
+    ActionFile, ComponentFile, Diagnostic, Document, Element, FileSchema, ProjectConfig, Span,
+};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::Path;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+const INDENT: usize = 2;
+/// Lists of scalars longer than this are written one item per line.
+const WIDTH: usize = 80;
+
+/// Formats `source`, a file of `kind`. Fails when it doesn't parse as one,
+/// or when it uses YAML that can't be kept as written: anchors, aliases,
+/// tags and keys that aren't scalars.
+pub fn format_source(
+    source: &str,
+    kind: FileKind,
+    path: Option<&Path>,
+) -> Result<String, CompilerError> {
+    let parsed = Parsed::new(source, kind, path)?;
+    let lines: Vec<&str> = source.lines().collect();
+    let mut tree = Tree::build(source, &lines, path)?;
+
+    let schema = kind.schema();
+    match &mut tree.root {
+        // Each page of a `pages:` document is a page file of its own.
+        Some(Node::Mapping(entries)) if is_document(entries) => {
+            for entry in entries.iter_mut() {
+                if let Node::Sequence(items) = &mut entry.value {
+                    for item in items {
+                        order(&mut item.value, Some(schema.root()), &schema);
+                    }
+                }
+            }
+        }
+        Some(root) => order(root, Some(schema.root()), &schema),
+        None => {}
+    }
+
+    let output = Printer::print(&tree);
+    if Parsed::new(&output, kind, path).ok().as_ref() != Some(&parsed) {
+        let file = path.map_or("the file".to_string(), |path| {
+            format!("`{}`", path.display())
+        });
+        return Err(CompilerError::InvalidOutput(vec![Diagnostic::error(
+            codes::INVALID_OUTPUT,
+            format!(
+                "internal formatter error: formatting {} would change its meaning",
+                file
+            ),
+        )
+        .with_help("this is a bug in the NWL formatter; please report it")]));
+    }
+    Ok(output)
+}
+
+/// What a file means, to check that formatting kept it.
+#[derive(PartialEq)]
+enum Parsed {
+    Page(Document),
+    Project(ProjectConfig),
+    Actions(ActionFile),
+    Component(ComponentFile),
+}
+
+impl Parsed {
+    fn new(source: &str, kind: FileKind, path: Option<&Path>) -> Result<Self, CompilerError> {
+        Ok(match kind {
+            FileKind::Page => Parsed::Page(parse_source(source, path)?),
+            FileKind::Project => Parsed::Project(parse_project(source, path)?),
+            FileKind::Actions => Parsed::Actions(parse_actions(source, path)?),
+            FileKind::Component => Parsed::Component(parse_component(source, path)?),
+        })
+    }
+}
+
+fn is_document(entries: &[Entry]) -> bool {
+    entries.iter().any(|entry| entry.key.value == "pages")
+        && !entries.iter().any(|entry| entry.key.value == "page")
+}
+
+enum Node {
+    Scalar(Scalar),
+    Mapping(Vec<Entry>),
+    Sequence(Vec<Item>),
+}
+
+struct Scalar {
+    value: String,
+    text: Text,
+    /// The line the scalar starts on, or its header's for block scalars.
+    line: usize,
+}
+
+enum Text {
+    /// The scalar as written, when it fits on its line, or quoted again.
+    Inline(String),
+    /// A `|` or `>` scalar: its header and its lines, without their
+    /// indentation.
+    Block { header: String, lines: Vec<String> },
+}
+
+struct Entry {
+    key: Scalar,
+    value: Node,
+    /// Index of the entry's comments in [`Tree::notes`].
+    notes: usize,
+}
+
+struct Item {
+    value: Node,
+    notes: usize,
+}
+
+/// The comments and blank lines that go with an entry or an item.
+#[derive(Default)]
+struct Notes {
+    /// Comment lines above it, and the blank lines among them as empty
+    /// strings.
+    leading: Vec<String>,
+    /// The comment at the end of its first line.
+    trailing: Option<String>,
+}
+
+struct Tree {
+    root: Option<Node>,
+    notes: Vec<Notes>,
+    /// Comment lines after everything else.
+    trailing: Vec<String>,
+}
+
+impl Tree {
+    fn build(source: &str, lines: &[&str], path: Option<&Path>) -> Result<Tree, CompilerError> {
+        let mut builder = Builder {
+            lines,
+            comments: scan(lines).into(),
+            notes: Vec::new(),
+            leading: Vec::new(),
+            anchors: (usize::MAX, Vec::new()),
+            stack: Vec::new(),
+            root: None,
+            error: None,
+        };
+        Parser::new_from_str(source)
+            .load(&mut builder, false)
+            .map_err(|error| {
+                let diagnostic = Diagnostic::error(codes::YAML_SYNTAX, error.to_string());
+                CompilerError::Parse(Box::new(diagnostic))
+            })?;
+        if let Some((message, mark)) = builder.error {
+            let span =
+                Span::new(mark.line(), mark.col() + 1).with_path(path.map(Path::to_path_buf));
+            let diagnostic = Diagnostic::error(codes::UNSUPPORTED_YAML, message)
+                .with_span(span)
+                .with_source(source)
+                .with_help("write the value out in full");
+            return Err(CompilerError::Parse(Box::new(diagnostic)));
+        }
+        builder.flush(usize::MAX);
+        Ok(Tree {
+            root: builder.root,
+            notes: builder.notes,
+            trailing: builder.leading,
+        })
+    }
+}
+
+struct Comment {
+    line: usize,
+    text: String,
+    /// Whether the comment is alone on its line.
+    full_line: bool,
+}
+
+/// Finds the comments of a file, skipping `#` inside quotes and block
+/// scalars.
+fn scan(lines: &[&str]) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    let mut quote = None;
+    // Lines of a block scalar are indented past this column.
+    let mut block: Option<usize> = None;
+    for (number, line) in lines.iter().enumerate() {
+        if let Some(parent) = block {
+            if line.trim().is_empty() || indentation(line) > parent {
+                continue;
+            }
+            block = None;
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let start = comment_start(&chars, &mut quote);
+        let code: String = chars[..start.unwrap_or(chars.len())].iter().collect();
+        if let Some(start) = start {
+            comments.push(Comment {
+                line: number,
+                text: chars[start..]
+                    .iter()
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string(),
+                full_line: code.trim().is_empty(),
+            });
+        }
+        if quote.is_none() {
+            block = block_header(&code).map(|(_, parent)| parent);
+        }
+    }
+    comments
+}
+
+/// Where the comment on a line starts, if it has one. `quote` is the quote
+/// of a scalar still open at the start of the line, and at its end.
+fn comment_start(chars: &[char], quote: &mut Option<char>) -> Option<usize> {
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match *quote {
+            Some('\'') if c == '\'' => {
+                // `''` is a quote inside the scalar.
+                if chars.get(i + 1) == Some(&'\'') {
+                    i += 1;
+                } else {
+                    *quote = None;
+                }
+            }
+            Some('"') if c == '\\' => i += 1,
+            Some('"') if c == '"' => *quote = None,
+            Some(_) => {}
+            None if c == '#' && (i == 0 || chars[i - 1].is_whitespace()) => return Some(i),
+            None if matches!(c, '\'' | '"') && opens_scalar(&chars[..i]) => *quote = Some(c),
+            None => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Whether a quote after `before` opens a quoted scalar, rather than being
+/// part of a plain one.
+fn opens_scalar(before: &[char]) -> bool {
+    match before.last() {
+        None => true,
+        Some(c) if c.is_whitespace() => matches!(
+            before.iter().rev().find(|c| !c.is_whitespace()),
+            None | Some(':' | '-' | '[' | '{' | ',' | '?')
+        ),
+        Some(c) => matches!(c, '[' | '{' | ','),
+    }
+}
+
+/// The header of the block scalar `code` ends with, like `|` or `>-`, and
+/// the column the scalar's lines are indented past.
+fn block_header(code: &str) -> Option<(String, usize)> {
+    let code = code.trim_end();
+    let start = code.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let header = &code[start..];
+    let mut chars = header.chars();
+    if !matches!(chars.next(), Some('|' | '>'))
+        || !chars.all(|c| matches!(c, '+' | '-' | '1'..='9'))
+    {
+        return None;
+    }
+    let before = code[..start].trim_end();
+    let indent = indentation(before);
+    let parent = if before.ends_with(':') {
+        // The key's column, after the dashes of any items it starts.
+        let mut column = indent;
+        let mut rest = before.trim_start();
+        while let Some(after) = rest.strip_prefix("- ") {
+            let after = after.trim_start();
+            column += rest.len() - after.len();
+            rest = after;
+        }
+        column
+    } else if before.ends_with('-') {
+        before.chars().count() - 1
+    } else {
+        return None;
+    };
+    Some((header.to_string(), parent))
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// A collection being read.
+enum Frame {
+    Mapping {
+        entries: Vec<Entry>,
+        /// The key whose value is being read, and its notes.
+        key: Option<(Scalar, usize)>,
+        flow: bool,
+        /// Whether the mapping is an item whose notes are still to be
+        /// taken, at its first key.
+        item: bool,
+    },
+    Sequence {
+        items: Vec<Item>,
+        /// The notes of the item being read.
+        item: Option<usize>,
+        flow: bool,
+    },
+}
+
+struct Builder<'a> {
+    lines: &'a [&'a str],
+    comments: VecDeque<Comment>,
+    notes: Vec<Notes>,
+    /// Comment lines waiting for the next entry or item.
+    leading: Vec<String>,
+    /// The notes taken on the last line that had any, and whether each
+    /// is inside a flow collection.
+    anchors: (usize, Vec<(usize, bool)>),
+    stack: Vec<Frame>,
+    root: Option<Node>,
+    error: Option<(String, Marker)>,
+}
+
+impl Builder<'_> {
+    /// Takes the notes of an entry or item starting on `line`.
+    fn take_notes(&mut self, line: usize) -> usize {
+        self.flush(line);
+        let mut leading = std::mem::take(&mut self.leading);
+        if self.follows_blank(line) {
+            leading.push(String::new());
+        }
+        self.notes.push(Notes {
+            leading,
+            trailing: None,
+        });
+        let id = self.notes.len() - 1;
+        let flow = match self.stack.last() {
+            Some(Frame::Mapping { flow, .. } | Frame::Sequence { flow, .. }) => *flow,
+            None => false,
+        };
+        if self.anchors.0 != line {
+            self.anchors = (line, Vec::new());
+        }
+        self.anchors.1.push((id, flow));
+        id
+    }
+
+    /// Hands out the comments above `line`: those ending a line go to the
+    /// innermost entry on it outside flow collections, others wait for the
+    /// next entry or item.
+    fn flush(&mut self, line: usize) {
+        while self
+            .comments
+            .front()
+            .is_some_and(|comment| comment.line < line)
+        {
+            let comment = self.comments.pop_front().expect("checked above");
+            let (anchor_line, anchors) = &self.anchors;
+            let owner = anchors
+                .iter()
+                .rev()
+                .find(|(_, flow)| !flow)
+                .or(anchors.first())
+                .filter(|_| *anchor_line == comment.line && !comment.full_line);
+            if let Some((id, _)) = owner {
+                self.notes[*id].trailing = Some(comment.text);
+                continue;
+            }
+            if self.follows_blank(comment.line) {
+                self.leading.push(String::new());
+            }
+            self.leading.push(comment.text);
+        }
+    }
+
+    fn follows_blank(&self, line: usize) -> bool {
+        line > 0 && self.lines[line - 1].trim().is_empty()
+    }
+
+    fn fail(&mut self, message: &str, mark: Marker) {
+        self.error.get_or_insert((message.to_string(), mark));
+    }
+
+    /// Starts a node: when it is an item, takes the item's notes.
+    fn begin(&mut self, line: usize) {
+        if let Some(Frame::Sequence { item: None, .. }) = self.stack.last() {
+            let id = self.take_notes(line);
+            if let Some(Frame::Sequence { item, .. }) = self.stack.last_mut() {
+                *item = Some(id);
+            }
+        }
+    }
+
+    /// Adds a finished node to the collection holding it.
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(Frame::Mapping { entries, key, .. }) => {
+                let (key, notes) = key.take().expect("values follow their key");
+                entries.push(Entry {
+                    key,
+                    value: node,
+                    notes,
+                });
+            }
+            Some(Frame::Sequence { items, item, .. }) => items.push(Item {
+                value: node,
+                notes: item.take().expect("items are begun"),
+            }),
+            None => self.root = Some(node),
+        }
+    }
+
+    fn key(&mut self, scalar: Scalar) {
+        let line = scalar.line;
+        let len = self.stack.len();
+        if let Some(Frame::Mapping {
+            item: item @ true, ..
+        }) = self.stack.last_mut()
+        {
+            *item = false;
+            let id = self.take_notes(line);
+            if let Some(Frame::Sequence { item, .. }) = self.stack.get_mut(len - 2) {
+                *item = Some(id);
+            }
+        }
+        let id = self.take_notes(line);
+        if let Some(Frame::Mapping { key, .. }) = self.stack.last_mut() {
+            *key = Some((scalar, id));
+        }
+    }
+
+    fn at(&self, mark: Marker) -> Option<char> {
+        self.lines.get(mark.line() - 1)?.chars().nth(mark.col())
+    }
+
+    fn expects_key(&self) -> bool {
+        matches!(self.stack.last(), Some(Frame::Mapping { key: None, .. }))
+    }
+
+    fn scalar(&self, value: String, style: TScalarStyle, mark: Marker) -> Option<Scalar> {
+        let line = mark.line() - 1;
+        if matches!(style, TScalarStyle::Literal | TScalarStyle::Folded) {
+            return self.block(value, line, mark.col());
+        }
+        let chars: Vec<char> = self
+            .lines
+            .get(line)
+            .map(|text| text.chars().skip(mark.col()).collect())
+            .unwrap_or_default();
+        let end = match style {
+            TScalarStyle::SingleQuoted => closing_quote(&chars, '\''),
+            TScalarStyle::DoubleQuoted => closing_quote(&chars, '"'),
+            _ => {
+                let len = value.chars().count();
+                (chars.len() >= len && chars[..len].iter().copied().eq(value.chars()))
+                    .then_some(len)
+            }
+        };
+        // Scalars written over several lines are quoted on one.
+        let text = match end {
+            Some(end) => chars[..end].iter().collect(),
+            None => serde_json::to_string(&value).expect("strings serialize"),
+        };
+        Some(Scalar {
+            value,
+            text: Text::Inline(text),
+            line,
+        })
+    }
+
+    /// A block scalar whose first line is `first`, indented by `indent`.
+    fn block(&self, value: String, first: usize, indent: usize) -> Option<Scalar> {
+        let line = (0..first.min(self.lines.len()))
+            .rev()
+            .find(|&line| !self.lines[line].trim().is_empty())?;
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        let end = comment_start(&chars, &mut None).unwrap_or(chars.len());
+        let (header, _) = block_header(&chars[..end].iter().collect::<String>())?;
+        let mut lines: Vec<String> = self.lines[line + 1..]
+            .iter()
+            .take_while(|text| text.trim().is_empty() || indentation(text) >= indent)
+            .map(|text| text.get(indent..).unwrap_or_default().to_string())
+            .collect();
+        if !header.contains('+') {
+            while lines.last().is_some_and(|text| text.trim().is_empty()) {
+                lines.pop();
+            }
+        }
+        Some(Scalar {
+            value,
+            text: Text::Block { header, lines },
+            line,
+        })
+    }
+}
+
+/// The length of the quoted scalar `chars` starts with, when it ends on
+/// the same line.
+fn closing_quote(chars: &[char], quote: char) -> Option<usize> {
+    let mut i = 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if quote == '"' => i += 1,
+            c if c == quote => {
+                if quote == '\'' && chars.get(i + 1) == Some(&'\'') {
+                    i += 1;
+                } else {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+impl MarkedEventReceiver for Builder<'_> {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        match event {
+            Event::Scalar(_, _, anchor, tag) | Event::SequenceStart(anchor, tag)
+                if anchor > 0 || tag.is_some() =>
+            {
+                self.fail("anchors and tags can't be formatted", mark)
+            }
+            Event::MappingStart(anchor, tag) if anchor > 0 || tag.is_some() => {
+                self.fail("anchors and tags can't be formatted", mark)
+            }
+            Event::Alias(_) => self.fail("aliases can't be formatted", mark),
+            Event::Scalar(value, style, ..) => {
+                let Some(scalar) = self.scalar(value, style, mark) else {
+                    return self.fail("this block scalar can't be formatted", mark);
+                };
+                if self.expects_key() {
+                    self.key(scalar);
+                } else {
+                    self.begin(scalar.line);
+                    self.push(Node::Scalar(scalar));
+                }
+            }
+            Event::SequenceStart(..) | Event::MappingStart(..) if self.expects_key() => {
+                self.fail("only scalar keys can be formatted", mark)
+            }
+            Event::SequenceStart(..) => {
+                self.begin(mark.line() - 1);
+                let flow = self.at(mark) == Some('[');
+                self.stack.push(Frame::Sequence {
+                    items: Vec::new(),
+                    item: None,
+                    flow,
+                });
+            }
+            Event::MappingStart(..) => {
+                // Block mappings are marked after their first key, so an
+                // item's notes wait for the key.
+                let flow = self.at(mark) == Some('{');
+                let item = matches!(self.stack.last(), Some(Frame::Sequence { .. }));
+                if flow {
+                    self.begin(mark.line() - 1);
+                }
+                self.stack.push(Frame::Mapping {
+                    entries: Vec::new(),
+                    key: None,
+                    flow,
+                    item: item && !flow,
+                });
+            }
+            Event::SequenceEnd => {
+                if let Some(Frame::Sequence { items, .. }) = self.stack.pop() {
+                    self.push(Node::Sequence(items));
+                }
+            }
+            Event::MappingEnd => {
+                if let Some(Frame::Mapping { entries, .. }) = self.stack.pop() {
+                    self.push(Node::Mapping(entries));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Puts the keys of every mapping under `schema` in the order the schema
+/// declares them. Elements start with their tag and condition and end with
+/// their children, then `else:`. Keys the schema doesn't know keep their
+/// order, after the rest.
+fn order(node: &mut Node, schema: Option<&Value>, file: &FileSchema) {
+    match node {
+        Node::Scalar(_) => {}
+        Node::Sequence(items) => {
+            let schema = schema.and_then(|schema| file.items(schema));
+            for item in items {
+                order(&mut item.value, schema, file);
+            }
+        }
+        Node::Mapping(entries) => {
+            let ranked: Vec<(usize, Option<&Value>)> = {
+                let keys: Vec<(&str, &str)> = entries
+                    .iter()
+                    .map(|entry| {
+                        let value = match &entry.value {
+                            Node::Scalar(scalar) => scalar.value.as_str(),
+                            _ => "",
+                        };
+                        (entry.key.value.as_str(), value)
+                    })
+                    .collect();
+                let properties = schema
+                    .map(|schema| file.properties(schema, &keys))
+                    .unwrap_or_default();
+                let tag = schema
+                    .filter(|schema| file.is_element(schema))
+                    .and_then(|_| {
+                        keys.iter()
+                            .map(|(key, _)| *key)
+                            .find(|key| *key == "element")
+                            .or_else(|| {
+                                keys.iter()
+                                    .map(|(key, _)| *key)
+                                    .find(|key| Element::TAGS.contains(key))
+                            })
+                    });
+                let holds_elements = |schema: &Value| {
+                    file.is_element(schema)
+                        || file
+                            .items(schema)
+                            .is_some_and(|items| file.is_element(items))
+                };
+                keys.iter()
+                    .map(|(key, _)| {
+                        let position = properties.iter().position(|(name, _)| name == key);
+                        let property = position.map(|position| properties[position].1);
+                        let rank = match (tag, *key, position) {
+                            (Some(tag), key, _) if key == tag => 0,
+                            (Some(_), "if" | "show", _) => 1,
+                            (Some(_), "else", _) => usize::MAX,
+                            (_, _, None) => usize::MAX - 1,
+                            // Children come after the element's own properties.
+                            (Some(_), _, Some(position))
+                                if property.is_some_and(holds_elements) =>
+                            {
+                                properties.len() + position + 2
+                            }
+                            (_, _, Some(position)) => position + 2,
+                        };
+                        (rank, property)
+                    })
+                    .collect()
+            };
+            let mut sorted: Vec<_> = ranked.into_iter().zip(entries.drain(..)).collect();
+            sorted.sort_by_key(|((rank, _), _)| *rank);
+            for ((_, schema), mut entry) in sorted {
+                order(&mut entry.value, schema, file);
+                entries.push(entry);
+            }
+        }
+    }
+}
+
+struct Printer<'a> {
+    notes: &'a [Notes],
+    out: String,
+}
+
+impl Printer<'_> {
+    fn print(tree: &Tree) -> String {
+        let mut printer = Printer {
+            notes: &tree.notes,
+            out: String::new(),
+        };
+        match &tree.root {
+            Some(Node::Mapping(entries)) if !entries.is_empty() => {
+                printer.entries(entries, 0, None, None)
+            }
+            Some(Node::Sequence(items)) if !items.is_empty() => printer.items(items, 0),
+            Some(root) => {
+                printer.value(root, 0, None);
+                printer.out = printer.out.trim_start().to_string();
+            }
+            None => {}
+        }
+        printer.comments(&tree.trailing, 0, printer.out.is_empty());
+        let mut out = printer.out.trim_end().to_string();
+        out.push('\n');
+        out
+    }
+
+    /// Writes comment lines, and the blank lines among them unless they
+    /// come `first` in their collection.
+    fn comments(&mut self, lines: &[String], indent: usize, first: bool) {
+        let start = if first {
+            lines.iter().take_while(|line| line.is_empty()).count()
+        } else {
+            0
+        };
+        for line in &lines[start..] {
+            if !line.is_empty() {
+                self.out.push_str(&" ".repeat(indent));
+                self.out.push_str(line);
+            }
+            self.out.push('\n');
+        }
+    }
+
+    fn end_line(&mut self, comment: Option<&str>) {
+        if let Some(comment) = comment {
+            self.out.push(' ');
+            self.out.push_str(comment);
+        }
+        self.out.push('\n');
+    }
+
+    /// Writes the entries of a mapping. The first is written after `head`
+    /// when the mapping is an item, with the item's comment.
+    fn entries(
+        &mut self,
+        entries: &[Entry],
+        indent: usize,
+        mut head: Option<&str>,
+        item_comment: Option<&str>,
+    ) {
+        for (i, entry) in entries.iter().enumerate() {
+            let notes = &self.notes[entry.notes];
+            let mut comment = notes.trailing.as_deref();
+            match head.take() {
+                Some(head) => {
+                    self.out.push_str(head);
+                    comment = comment.or(item_comment);
+                }
+                None => {
+                    self.comments(&notes.leading, indent, i == 0);
+                    self.out.push_str(&" ".repeat(indent));
+                }
+            }
+            if let Text::Inline(key) = &entry.key.text {
+                self.out.push_str(key);
+            }
+            self.out.push(':');
+            self.value(&entry.value, indent, comment);
+        }
+    }
+
+    fn items(&mut self, items: &[Item], indent: usize) {
+        for (i, item) in items.iter().enumerate() {
+            let notes = &self.notes[item.notes];
+            self.comments(&notes.leading, indent, i == 0);
+            let head = format!("{}-", " ".repeat(indent));
+            let comment = notes.trailing.as_deref();
+            match &item.value {
+                Node::Mapping(entries) if !entries.is_empty() => {
+                    let head = format!("{} ", head);
+                    self.entries(entries, indent + INDENT, Some(&head), comment);
+                }
+                value => {
+                    self.out.push_str(&head);
+                    self.value(value, indent, comment);
+                }
+            }
+        }
+    }
+
+    /// Writes the rest of the line of a key or a dash, and the lines of its
+    /// value.
+    fn value(&mut self, node: &Node, indent: usize, comment: Option<&str>) {
+        match node {
+            Node::Scalar(Scalar {
+                text: Text::Inline(text),
+                ..
+            }) => {
+                if !text.is_empty() {
+                    self.out.push(' ');
+                    self.out.push_str(text);
+                }
+                self.end_line(comment);
+            }
+            Node::Scalar(Scalar {
+                text: Text::Block { header, lines },
+                ..
+            }) => {
+                self.out.push(' ');
+                self.out.push_str(header);
+                self.end_line(comment);
+                let indent = indent
+                    + header
+                        .chars()
+                        .find_map(|c| c.to_digit(10))
+                        .map_or(INDENT, |digit| digit as usize);
+                for line in lines {
+                    if !line.is_empty() {
+                        self.out.push_str(&" ".repeat(indent));
+                        self.out.push_str(line);
+                    }
+                    self.out.push('\n');
+                }
+            }
+            Node::Mapping(entries) if entries.is_empty() => {
+                self.out.push_str(" {}");
+                self.end_line(comment);
+            }
+            Node::Mapping(entries) => {
+                self.end_line(comment);
+                self.entries(entries, indent + INDENT, None, None);
+            }
+            Node::Sequence(items) if items.is_empty() => {
+                self.out.push_str(" []");
+                self.end_line(comment);
+            }
+            Node::Sequence(items) => match self.flow(items) {
+                Some(flow) if self.column() + 1 + flow.chars().count() <= WIDTH => {
+                    self.out.push(' ');
+                    self.out.push_str(&flow);
+                    self.end_line(comment);
+                }
+                _ => {
+                    self.end_line(comment);
+                    self.items(items, indent + INDENT);
+                }
+            },
+        }
+    }
+
+    /// A sequence of scalars written `[a, b]`, unless its items have
+    /// comments or can't be written that way.
+    fn flow(&self, items: &[Item]) -> Option<String> {
+        let mut texts = Vec::new();
+        for item in items {
+            let notes = &self.notes[item.notes];
+            if notes.trailing.is_some() || notes.leading.iter().any(|line| !line.is_empty()) {
+                return None;
+            }
+            let Node::Scalar(Scalar {
+                text: Text::Inline(text),
+                ..
+            }) = &item.value
+            else {
+                return None;
+            };
+            let quoted = text.starts_with(['\'', '"']);
+            if text.is_empty() || !quoted && text.contains([',', '[', ']', '{', '}']) {
+                return None;
+            }
+            texts.push(text.as_str());
+        }
+        Some(format!("[{}]", texts.join(", ")))
+    }
+
+    /// The column the next character is written at.
+    fn column(&self) -> usize {
+        let line = self.out.rsplit('\n').next().unwrap_or_default();
+        line.chars().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format_page(source: &str) -> String {
+        format_source(source, FileKind::Page, None).unwrap()
+    }
+
+    #[test]
+    fn test_orders_keys_and_styles_lists() {
+        let source = "page:
+  children:
+    - content: Save
+      style: [
+        px-4,
+        py-2
+      ]
+      onClick:
+      - set: draft
+        to: \"''\"
+      element: button
+    - if: open
+      heading: Hi
+  state:
+    - type: string
+      name: draft
+      initial: ''
+  name: Home
+";
+        let expected = "page:
+  name: Home
+  state:
+    - name: draft
+      type: string
+      initial: ''
+  children:
+    - element: button
+      content: Save
+      onClick:
+        - set: draft
+          to: \"''\"
+      style: [px-4, py-2]
+    - heading: Hi
+      if: open
+";
+        assert_eq!(format_page(source), expected);
+        assert_eq!(format_page(expected), expected);
+    }
+
+    #[test]
+    fn test_keeps_comments_and_blank_lines() {
+        let source = "# The home page.
+page:
+  name: Home # shown in the tab
+
+  # Typed by the user.
+  state:
+    - name: draft # empty at first
+      type: string
+  children:
+    - text: \"# not a comment\"
+    - element: text
+      # Placeholder.
+      content: Hi
+      style: # classes
+        - text-lg
+        # - text-red-500
+        - font-bold
+# The end.
+";
+        let expected = "# The home page.
+page:
+  name: Home # shown in the tab
+
+  # Typed by the user.
+  state:
+    - name: draft # empty at first
+      type: string
+  children:
+    - text: \"# not a comment\"
+    - element: text
+      # Placeholder.
+      content: Hi
+      style: # classes
+        - text-lg
+        # - text-red-500
+        - font-bold
+# The end.
+";
+        assert_eq!(format_page(source), expected);
+    }
+
+    #[test]
+    fn test_block_scalars_and_long_lists() {
+        let source = "action:
+  - handler: |-
+        // Saves the # of items.
+          return items;
+    inputs: [a]
+    name: save
+";
+        let actions = format_source(source, FileKind::Actions, None).unwrap();
+        assert_eq!(
+            actions,
+            "action:
+  - name: save
+    inputs: [a]
+    handler: |-
+      // Saves the # of items.
+        return items;
+"
+        );
+
+        let classes: Vec<String> = (0..12).map(|i| format!("class-{}", i)).collect();
+        let source = format!("page:\n  name: Home\n  style: [{}]\n", classes.join(", "));
+        let formatted = format_page(&source);
+        assert!(
+            formatted.contains("  style:\n    - class-0\n"),
+            "{}",
+            formatted
+        );
+    }
+
+    #[test]
+    fn test_rejects_what_it_cannot_keep() {
+        let source = "page:\n  name: &name Home\n";
+        let Err(CompilerError::Parse(diagnostic)) = format_source(source, FileKind::Page, None)
+        else {
+            panic!("anchors are formatted");
+        };
+        assert_eq!(diagnostic.code, codes::UNSUPPORTED_YAML);
+        assert_eq!(diagnostic.span.unwrap().line, 2);
+
+        assert!(matches!(
+            format_source("page: [", FileKind::Page, None),
+            Err(CompilerError::Parse(_))
+        ));
+    }
+}
@@ -1,55 +1,200 @@
+pub mod actions;
+pub mod binding;
 pub mod codegen;
+pub mod components;
+pub mod computed;
+pub mod diagnostics;
+pub mod escape;
+pub mod expression;
+pub mod format;
+pub mod modules;
+pub mod routes;
+pub mod source_index;
+pub mod source_map;
+pub mod types;
+pub mod validate;
+pub mod verify;
 
-use crate::codegen::{generate_react, generate_router, CodegenError};
-use nwl_shared::{Document, Page, ProjectConfig};
+use crate::actions::Actions;
+use crate::codegen::{
+    generate_actions, generate_component, generate_react, generate_react_mapped, generate_router,
+    CodegenError, PageRoute, RouteFallback, RouteTarget,
+};
+use crate::components::Components;
+use crate::diagnostics::codes;
+use crate::routes::RouteEntry;
+use crate::source_index::SourceIndex;
+use nwl_shared::{
+    ActionFile, ComponentFile, Diagnostic, Document, FileSchema, Page, ProjectConfig, RouteConfig,
+};
+use serde::de::DeserializeOwned;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, thiserror::Error)]
 pub enum CompilerError {
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
-    #[error("YAML parse error: {0}")]
-    Parse(#[from] serde_yaml::Error),
+    #[error("{0}")]
+    Parse(Box<Diagnostic>),
+    #[error("{}", render_diagnostics(.0))]
+    Semantic(Vec<Diagnostic>),
     #[error("Codegen error: {0}")]
     Codegen(#[from] CodegenError),
+    #[error("{}", render_diagnostics(.0))]
+    InvalidOutput(Vec<Diagnostic>),
+}
+
+/// Options for [`build_project`].
+#[derive(Debug, Clone, Default)]
+pub struct BuildOptions {
+    /// Parse every generated file and report code that does not parse as a
+    /// compiler bug, instead of writing it.
+    pub verify: bool,
+}
+
+/// Renders every diagnostic followed by a summary line, the way the CLI
+/// prints a failed build.
+fn render_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let mut rendered: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+    match errors {
+        0 => {}
+        1 => rendered.push("aborting due to 1 previous error".to_string()),
+        n => rendered.push(format!("aborting due to {} previous errors", n)),
+    }
+    rendered.join("\n\n")
 }
 
 pub fn compile_file(path: PathBuf) -> Result<String, CompilerError> {
-    let content = fs::read_to_string(path)?;
-    compile(&content)
+    let content = fs::read_to_string(&path)?;
+    let document = parse_source(&content, Some(&path))?;
+    check(validate::validate_document(
+        &document,
+        &SourceIndex::new(&content, Some(&path)),
+    ))?;
+    let output = generate_react(&document)?;
+    Ok(output)
 }
 
 pub fn compile(input: &str) -> Result<String, CompilerError> {
     let document = parse_yaml(input)?;
+    check(validate::validate_document(
+        &document,
+        &SourceIndex::new(input, None),
+    ))?;
     let output = generate_react(&document)?;
     Ok(output)
 }
 
 pub fn compile_page(input: &str) -> Result<String, CompilerError> {
-    let page: Page = serde_yaml::from_str(input)?;
+    let page = parse_page(input)?;
     let document = Document { pages: vec![page] };
+    check(validate::validate_document(
+        &document,
+        &SourceIndex::new(input, None),
+    ))?;
     let output = generate_react(&document)?;
     Ok(output)
 }
 
-pub fn parse_document(input: &str) -> Result<Document, serde_yaml::Error> {
-    serde_yaml::from_str(input)
+fn check(diagnostics: Vec<Diagnostic>) -> Result<(), CompilerError> {
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        Err(CompilerError::Semantic(diagnostics))
+    } else {
+        Ok(())
+    }
 }
 
-pub fn parse_page(input: &str) -> Result<Page, serde_yaml::Error> {
-    serde_yaml::from_str(input)
+pub fn parse_document(input: &str) -> Result<Document, CompilerError> {
+    deserialize(input, None)
+}
+
+pub fn parse_page(input: &str) -> Result<Page, CompilerError> {
+    deserialize(input, None)
 }
 
 pub fn parse_yaml(input: &str) -> Result<Document, CompilerError> {
-    match serde_yaml::from_str::<Page>(input) {
-        Ok(page) => return Ok(Document { pages: vec![page] }),
-        Err(e) => eprintln!("DEBUG: Page parse error: {}", e),
+    parse_source(input, None)
+}
+
+/// Parses either a single `page:` file or a multi-page `pages:` document,
+/// reporting errors against whichever shape the input actually declares.
+pub fn parse_source(input: &str, path: Option<&Path>) -> Result<Document, CompilerError> {
+    let value: serde_yaml::Value = deserialize(input, path)?;
+    let is_document = value
+        .as_mapping()
+        .is_some_and(|mapping| mapping.contains_key("pages") && !mapping.contains_key("page"));
+
+    if is_document {
+        deserialize(input, path)
+    } else {
+        let page: Page = deserialize(input, path)?;
+        Ok(Document { pages: vec![page] })
     }
-    serde_yaml::from_str::<Document>(input).map_err(Into::into)
 }
 
-pub fn build_project(project_dir: PathBuf) -> Result<(), CompilerError> {
+/// Parses a project's `nwl.yaml`.
+pub fn parse_project(input: &str, path: Option<&Path>) -> Result<ProjectConfig, CompilerError> {
+    deserialize(input, path)
+}
+
+/// Parses an `actions/*.yaml` file.
+pub fn parse_actions(input: &str, path: Option<&Path>) -> Result<ActionFile, CompilerError> {
+    deserialize(input, path)
+}
+
+/// Parses a `components/*.yaml` file.
+pub fn parse_component(input: &str, path: Option<&Path>) -> Result<ComponentFile, CompilerError> {
+    deserialize(input, path)
+}
+
+/// The kinds of YAML file in a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Page,
+    /// The project's `nwl.yaml`.
+    Project,
+    /// A file in `actions/`.
+    Actions,
+    /// A file in `components/`.
+    Component,
+}
+
+impl FileKind {
+    /// Tells the kind of a file from its path. Files outside a project, and
+    /// unsaved ones, are taken to be pages.
+    pub fn of(path: Option<&Path>) -> FileKind {
+        let Some(path) = path else {
+            return FileKind::Page;
+        };
+        if path.file_name().is_some_and(|name| name == "nwl.yaml") {
+            return FileKind::Project;
+        }
+        match path.parent().and_then(Path::file_name) {
+            Some(dir) if dir == "actions" => FileKind::Actions,
+            Some(dir) if dir == "components" => FileKind::Component,
+            _ => FileKind::Page,
+        }
+    }
+
+    /// The schema of files of this kind.
+    pub fn schema(self) -> FileSchema {
+        match self {
+            FileKind::Page => FileSchema::page(),
+            FileKind::Project => FileSchema::project(),
+            FileKind::Actions => FileSchema::action(),
+            FileKind::Component => FileSchema::component(),
+        }
+    }
+}
+
+fn deserialize<T: DeserializeOwned>(input: &str, path: Option<&Path>) -> Result<T, CompilerError> {
+    serde_yaml::from_str(input)
+        .map_err(|e| CompilerError::Parse(Box::new(diagnostics::from_yaml_error(&e, input, path))))
+}
+
+pub fn build_project(project_dir: PathBuf, options: &BuildOptions) -> Result<(), CompilerError> {
     let config_path = project_dir.join("nwl.yaml");
     if !config_path.exists() {
         return Err(CompilerError::IO(std::io::Error::new(
@@ -59,49 +204,418 @@ pub fn build_project(project_dir: PathBuf) -> Result<(), CompilerError> {
     }
 
     let config_content = fs::read_to_string(&config_path)?;
-    let config: ProjectConfig = serde_yaml::from_str(&config_content)?;
-
-    let mut import_statements = String::new();
-    let mut route_entries = String::new();
-
-    for (index, route) in config.routes.iter().enumerate() {
-        let page_path = project_dir.join(&route.page);
-        if !page_path.exists() {
-            return Err(CompilerError::IO(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                format!("Page file not found: {}", route.page),
-            )));
+    let config = parse_project(&config_content, Some(Path::new("nwl.yaml")))?;
+    let config_index = SourceIndex::new(&config_content, Some(Path::new("nwl.yaml")));
+
+    // Parse and validate the actions, components and every page before
+    // writing anything, so a single build reports all of their problems at
+    // once.
+    let (actions, mut diagnostics) = load_actions(&project_dir)?;
+    let (components, component_diagnostics) = load_components(&project_dir, &actions)?;
+    diagnostics.extend(component_diagnostics);
+    for component in components.iter() {
+        diagnostics.extend(routes::check_links(
+            &component.definition.children,
+            "component.children",
+            &component.index,
+            &config.routes,
+        ));
+    }
+    diagnostics.extend(validate::validate_project(&config, &config_index));
+    let entries = routes::flatten(&config.routes);
+    let fallback_page = config
+        .fallback
+        .as_ref()
+        .and_then(|fallback| fallback.page.as_deref());
+    // Each page file is compiled once, however many routes render it.
+    let mut pages: Vec<(&str, Page, SourceIndex)> = Vec::new();
+    for file in entries
+        .iter()
+        .filter_map(|entry| entry.route.file())
+        .chain(fallback_page)
+    {
+        if !pages.iter().any(|(loaded, ..)| *loaded == file) {
+            let page_path = project_dir.join(file);
+            if !page_path.exists() {
+                return Err(CompilerError::IO(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Page file not found: {}", file),
+                )));
+            }
+
+            let page_content = fs::read_to_string(&page_path)?;
+            let source_path = Path::new(file);
+            let page: Page = deserialize(&page_content, Some(source_path))?;
+            let index = SourceIndex::new(&page_content, Some(source_path));
+            diagnostics.extend(validate::validate_page(
+                &page.page_data,
+                "page",
+                &index,
+                &actions,
+                &components,
+            ));
+            diagnostics.extend(modules::check_modules(
+                &page.page_data.children,
+                "page.children",
+                &index,
+                &project_dir,
+            ));
+            diagnostics.extend(routes::check_links(
+                &page.page_data.children,
+                "page.children",
+                &index,
+                &config.routes,
+            ));
+            let routes: Vec<&RouteEntry> = entries
+                .iter()
+                .filter(|other| other.route.file() == Some(file))
+                .collect();
+            diagnostics.extend(validate::validate_params(
+                &page.page_data,
+                "page",
+                &routes,
+                &index,
+            ));
+            pages.push((file, page, index));
+        }
+    }
+    let loaded = |file: &str| {
+        let (_, page, _) = pages
+            .iter()
+            .find(|(loaded, ..)| *loaded == file)
+            .expect("every page file is loaded");
+        &page.page_data
+    };
+    for entry in &entries {
+        if let Some(file) = entry.route.file() {
+            diagnostics.extend(validate::validate_route(entry, loaded(file), &config_index));
         }
+    }
+    if let Some(file) = fallback_page {
+        diagnostics.extend(validate::validate_fallback(loaded(file), &config_index));
+    }
+    check(diagnostics)?;
+
+    // Generate everything before writing, so a failed self-check leaves the
+    // previous build in place.
+    let mut files = Vec::new();
+    let mut invalid = Vec::new();
 
-        let page_content = fs::read_to_string(&page_path)?;
-        let page: Page = serde_yaml::from_str(&page_content)?;
+    if !actions.is_empty() {
+        let code = generate_actions(&actions);
+        if options.verify {
+            if let Err(error) = verify::parse_ts(&code) {
+                invalid.push(
+                    Diagnostic::error(
+                        codes::INVALID_OUTPUT,
+                        "generated `src/actions.ts` does not parse",
+                    )
+                    .with_note(format!("src/actions.ts:{}", error))
+                    .with_help(
+                        "action handlers are copied into this file as written; check their syntax",
+                    ),
+                );
+            }
+        }
+        files.push(("actions.ts".to_string(), code));
+    }
+
+    for component in components.iter() {
+        let output = generate_component(component, &actions, &components)?;
+        let file = format!("{}.tsx", component.module());
+        if options.verify {
+            if let Err(error) = verify::verify_component(component, &output.code) {
+                invalid.push(
+                    error.to_diagnostic(&format!("src/components/{}", file), &component.index),
+                );
+            }
+        }
+        // Components are written to `src/components/`, two levels below
+        // `components/`.
+        let map_file = format!("{}.map", file);
+        let source = component
+            .index
+            .path()
+            .map(|path| format!("../../{}", path.display().to_string().replace('\\', "/")))
+            .unwrap_or_default();
+        let map = source_map::source_map(&file, &source, &component.index, &output.marks);
+        files.push((format!("components/{}", map_file), map));
+        files.push((
+            format!("components/{}", file),
+            output.code + &source_map::mapping_url_comment(&map_file),
+        ));
+    }
+
+    // The component each page file compiles to.
+    let mut page_components = Vec::new();
+
+    for (page_file, page, index) in pages {
         let component_name = page.page_data.name.clone();
         let document = Document { pages: vec![page] };
-        let component_code = generate_react(&document)?;
+        let output = generate_react_mapped(&document, &actions, &components)?;
+
+        let module = component_name.to_lowercase();
+        let file = format!("{}.tsx", module);
+        if options.verify {
+            if let Err(error) = verify::verify_document(&document, &output.code) {
+                invalid.push(error.to_diagnostic(&format!("src/{}", file), &index));
+            }
+        }
+        // Components are written to `src/`, next to `pages/`.
+        let map_file = format!("{}.map", file);
+        let source = format!("../{}", page_file.replace('\\', "/"));
+        let map = source_map::source_map(&file, &source, &index, &output.marks);
+        files.push((map_file.clone(), map));
+        files.push((
+            file,
+            output.code + &source_map::mapping_url_comment(&map_file),
+        ));
+
+        page_components.push((page_file, component_name, format!("./{}", module)));
+    }
+
+    let fallback = match fallback_page {
+        Some(file) => {
+            let (_, component, module) = page_components
+                .iter()
+                .find(|(page_file, ..)| *page_file == file)
+                .expect("the fallback page is compiled");
+            RouteFallback::Page {
+                component: component.clone(),
+                module: module.clone(),
+            }
+        }
+        None => config
+            .fallback
+            .as_ref()
+            .and_then(|fallback| fallback.spinner.clone())
+            .map(RouteFallback::Spinner)
+            .unwrap_or_default(),
+    };
+    let router_code = generate_router(
+        &page_routes(&config.routes, config.lazy, &page_components),
+        &fallback,
+    );
+    if options.verify {
+        if let Err(error) = verify::parse_tsx(&router_code) {
+            invalid.push(
+                Diagnostic::error(
+                    codes::INVALID_OUTPUT,
+                    "internal compiler error: generated `src/main.tsx` does not parse",
+                )
+                .with_note(format!("src/main.tsx:{}", error))
+                .with_help("this is a bug in the NWL compiler; please report it"),
+            );
+        }
+    }
+    files.push(("main.tsx".to_string(), router_code));
+
+    if !invalid.is_empty() {
+        return Err(CompilerError::InvalidOutput(invalid));
+    }
+    if !components.is_empty() {
+        fs::create_dir_all(project_dir.join("src").join("components"))?;
+    }
+    for (file, code) in files {
+        fs::write(project_dir.join("src").join(file), code)?;
+    }
+
+    Ok(())
+}
 
-        let component_file = format!("{}.tsx", component_name.to_lowercase());
-        let component_path = project_dir.join("src").join(&component_file);
+/// The routes of `main.tsx` for `routes`, rendering the components
+/// `page_components` pairs with page files, and the modules they're
+/// imported from. Routes without `lazy` of their own follow the project's.
+fn page_routes(
+    routes: &[RouteConfig],
+    lazy: Option<bool>,
+    page_components: &[(&str, String, String)],
+) -> Vec<PageRoute> {
+    routes
+        .iter()
+        .map(|route| {
+            let target = match (route.file(), &route.redirect) {
+                (Some(file), _) => {
+                    let (_, component, module) = page_components
+                        .iter()
+                        .find(|(page_file, ..)| *page_file == file)
+                        .expect("every page file is compiled");
+                    RouteTarget::Page {
+                        component: component.clone(),
+                        module: module.clone(),
+                        lazy: route.lazy.or(lazy) == Some(true),
+                    }
+                }
+                (None, Some(to)) => RouteTarget::Redirect(to.clone()),
+                (None, None) => unreachable!("routes without a target are rejected"),
+            };
+            PageRoute {
+                path: route.path.clone(),
+                index: route.index == Some(true),
+                target,
+                children: page_routes(&route.children, lazy, page_components),
+            }
+        })
+        .collect()
+}
+
+/// Parses and validates every `actions/*.yaml` file of a project, in file
+/// name order.
+pub fn load_actions(project_dir: &Path) -> Result<(Actions, Vec<Diagnostic>), CompilerError> {
+    let mut actions = Actions::default();
+    let mut diagnostics = Vec::new();
+    let Ok(entries) = fs::read_dir(project_dir.join("actions")) else {
+        return Ok((actions, diagnostics));
+    };
 
-        fs::write(&component_path, &component_code)?;
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    files.sort();
 
-        import_statements.push_str(&format!(
-            "import {} from './{}';\n",
-            component_name,
-            component_file.replace(".tsx", "")
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let source_path = file.strip_prefix(project_dir).unwrap_or(&file);
+        let action_file = parse_actions(&content, Some(source_path))?;
+        let index = SourceIndex::new(&content, Some(source_path));
+        diagnostics.extend(validate::validate_actions(
+            &action_file,
+            &index,
+            &mut actions,
         ));
-        route_entries.push_str(&format!(
-            "        <Route path=\"{}\" element={{<{} />}} />\n",
-            route.path, component_name
+    }
+    Ok((actions, diagnostics))
+}
+
+/// Parses every `components/*.yaml` file of a project, in file name order,
+/// then validates each component once all of them are known, so they can
+/// use each other regardless of order, and checks the modules it imports.
+/// Components that use themselves are reported last.
+pub fn load_components(
+    project_dir: &Path,
+    actions: &Actions,
+) -> Result<(Components, Vec<Diagnostic>), CompilerError> {
+    let mut components = Components::default();
+    let mut diagnostics = Vec::new();
+    let Ok(entries) = fs::read_dir(project_dir.join("components")) else {
+        return Ok((components, diagnostics));
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    files.sort();
+
+    let mut parsed = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let source_path = file.strip_prefix(project_dir).unwrap_or(&file);
+        let component_file = parse_component(&content, Some(source_path))?;
+        let index = SourceIndex::new(&content, Some(source_path));
+        diagnostics.extend(validate::register_component(
+            &component_file,
+            &index,
+            &mut components,
+        ));
+        parsed.push((component_file, index));
+    }
+    for (file, index) in &parsed {
+        diagnostics.extend(validate::validate_component(
+            &file.component,
+            index,
+            actions,
+            &components,
         ));
+        diagnostics.extend(modules::check_modules(
+            &file.component.children,
+            "component.children",
+            index,
+            project_dir,
+        ));
+    }
+    diagnostics.extend(validate::component_cycles(&components));
+    Ok((components, diagnostics))
+}
 
-        if index < config.routes.len() - 1 {
-            route_entries.push_str("\n");
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_source_reports_page_error() {
+        let source =
+            "page:\n  name: Broken\n  children:\n    - element: heading\n      style: [text-xl]\n";
+        let error = parse_source(source, Some(Path::new("pages/broken.yaml"))).unwrap_err();
+        let rendered = error.to_string();
+        assert!(rendered.contains("missing field `content`"));
+        assert!(rendered.contains("--> pages/broken.yaml:4:5"));
+        assert!(!rendered.contains("missing field `pages`"));
     }
 
-    let router_code = generate_router(&config.name, &import_statements, &route_entries);
-    let main_tsx_path = project_dir.join("src").join("main.tsx");
-    fs::write(&main_tsx_path, &router_code)?;
+    #[test]
+    fn test_compile_reports_every_semantic_error() {
+        let source = r#"page:
+  name: Broken
+  children:
+    - element: input
+      bind: email
+    - element: select
+      bind: country
+"#;
+        let error = compile(source).unwrap_err();
+        let CompilerError::Semantic(diagnostics) = &error else {
+            panic!("expected semantic error, got {:?}", error);
+        };
+        assert_eq!(diagnostics.len(), 3);
 
-    Ok(())
+        let rendered = error.to_string();
+        assert!(rendered.contains("undeclared state `email`"));
+        assert!(rendered.contains("undeclared state `country`"));
+        assert!(rendered.contains("`select` has no `options`"));
+        assert!(rendered.ends_with("aborting due to 3 previous errors"));
+    }
+
+    #[test]
+    fn test_unknown_nested_field_is_rejected() {
+        let source = r#"page:
+  name: Nav
+  children:
+    - element: nav
+      links:
+        - label: Home
+          hrf: "/"
+"#;
+        let error = parse_source(source, None).unwrap_err();
+        let rendered = error.to_string();
+        assert!(rendered.contains("unknown field `hrf`"));
+        assert!(rendered.contains("7 |           hrf: \"/\""));
+        assert!(rendered.contains("did you mean `href`?"));
+    }
+
+    #[test]
+    fn test_unknown_project_field_is_rejected() {
+        let source =
+            "name: Shop\nroutes:\n  - path: /\n    page: pages/home.yaml\n    lazzy: true\n";
+        let rendered = parse_project(source, None).unwrap_err().to_string();
+        assert!(rendered.contains("unknown field `lazzy`"));
+        assert!(rendered.contains("did you mean `lazy`?"));
+        assert!(parse_project("name: Shop\nsource: src\npages: pages\n", None).is_ok());
+    }
+
+    #[test]
+    fn test_parse_source_accepts_document() {
+        let source = "pages:\n  - page:\n      name: One\n  - page:\n      name: Two\n";
+        let document = parse_source(source, None).unwrap();
+        assert_eq!(document.pages.len(), 2);
+    }
 }
@@ -0,0 +1,206 @@
+//! Hand-written React components, rendered by `react` elements. Each is
+//! imported by the generated module from a file in the project's `src/`
+//! or from an installed package.
+
+use crate::codegen::ReactGenerator;
+use crate::components::walk;
+use crate::diagnostics::codes;
+use crate::source_index::SourceIndex;
+use nwl_shared::{Diagnostic, Element, ReactElement};
+use std::fs;
+use std::path::Path;
+
+/// Extensions a module path may leave out, as Vite resolves them.
+const EXTENSIONS: &[&str] = &["tsx", "ts", "jsx", "js"];
+
+/// The name `element`'s component is rendered by: its `as:`, its
+/// `export`, or the file name of its module in PascalCase.
+pub fn local_name(element: &ReactElement) -> String {
+    if let Some(name) = element.alias.as_ref().or(element.export.as_ref()) {
+        return name.clone();
+    }
+    let file = element.import.trim_end_matches('/');
+    let file = file.rsplit('/').next().unwrap_or(file);
+    let stem = file.split('.').next().unwrap_or(file);
+    ReactGenerator::to_pascal_case(stem)
+}
+
+/// Whether `module` names a file rather than a package.
+pub fn is_path(module: &str) -> bool {
+    module.starts_with('.') || module.starts_with('/')
+}
+
+/// `module`, written relative to `src/`, as imported by a file `depth`
+/// directories below it.
+pub fn relative_to(module: &str, depth: usize) -> String {
+    if !is_path(module) || depth == 0 {
+        return module.to_string();
+    }
+    let module = module.strip_prefix("./").unwrap_or(module);
+    format!("{}{}", "../".repeat(depth), module)
+}
+
+/// The `react` elements among `elements` and nested in them, with their
+/// path below `path`, such as `page.children`.
+pub fn imports<'e>(elements: &'e [Element], path: &str) -> Vec<(&'e ReactElement, String)> {
+    let mut imports = Vec::new();
+    walk(elements, path, &mut |element, path| {
+        if let Element::React(react) = element {
+            imports.push((react, path.to_string()));
+        }
+    });
+    imports
+}
+
+/// Reports `react` elements among `elements` whose module isn't in the
+/// project at `project_dir`: a path must name a file in `src/`, and a
+/// package must be a dependency in `package.json`.
+pub fn check_modules(
+    elements: &[Element],
+    path: &str,
+    index: &SourceIndex,
+    project_dir: &Path,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (element, path) in imports(elements, path) {
+        let module = element.import.as_str();
+        let diagnostic = if is_path(module) {
+            if resolve(&project_dir.join("src"), module) {
+                continue;
+            }
+            Diagnostic::error(
+                codes::UNKNOWN_MODULE,
+                format!("cannot find module `{}`", module),
+            )
+            .with_label("no such file in `src/`")
+            .with_help("module paths are relative to `src/`, e.g. `./widgets/Chart`")
+        } else {
+            let package = package_name(module);
+            if dependencies(project_dir).iter().any(|name| name == package) {
+                continue;
+            }
+            Diagnostic::error(
+                codes::UNKNOWN_MODULE,
+                format!("cannot find package `{}`", package),
+            )
+            .with_label("not a dependency in `package.json`")
+            .with_help(format!("install it with `npm install {}`", package))
+        };
+        diagnostics.push(index.locate(diagnostic, &format!("{}.import", path)));
+    }
+    diagnostics
+}
+
+/// Whether `module` names a file below `src`, with or without its
+/// extension, or a directory with an index file.
+fn resolve(src: &Path, module: &str) -> bool {
+    if module.starts_with('/') {
+        return false;
+    }
+    let file = src.join(module);
+    if file.is_file() {
+        return true;
+    }
+    EXTENSIONS.iter().any(|extension| {
+        let mut with_extension = file.clone().into_os_string();
+        with_extension.push(format!(".{}", extension));
+        Path::new(&with_extension).is_file() || file.join(format!("index.{}", extension)).is_file()
+    })
+}
+
+/// The package a bare module specifier imports from: `recharts` for
+/// `recharts/es6`, `@scope/name` for `@scope/name/sub`.
+fn package_name(module: &str) -> &str {
+    let segments = if module.starts_with('@') { 2 } else { 1 };
+    match module.match_indices('/').nth(segments - 1) {
+        Some((end, _)) => &module[..end],
+        None => module,
+    }
+}
+
+/// Names of the packages `package.json` depends on, in any of its
+/// dependency lists.
+fn dependencies(project_dir: &Path) -> Vec<String> {
+    let Some(manifest) = fs::read_to_string(project_dir.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+    else {
+        return Vec::new();
+    };
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .filter_map(|list| manifest.get(list)?.as_object())
+        .flat_map(|list| list.keys().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::Page;
+
+    fn react(source: &str) -> ReactElement {
+        match serde_yaml::from_str(source).unwrap() {
+            Element::React(element) => element,
+            element => panic!("expected a react element, got {:?}", element),
+        }
+    }
+
+    #[test]
+    fn test_local_name() {
+        assert_eq!(
+            local_name(&react("react: ./widgets/sales-chart.tsx")),
+            "SalesChart"
+        );
+        assert_eq!(
+            local_name(&react("{react: recharts, export: LineChart}")),
+            "LineChart"
+        );
+        assert_eq!(
+            local_name(&react("{react: recharts, export: LineChart, as: Line}")),
+            "Line"
+        );
+        assert_eq!(relative_to("./widgets/Chart", 1), "../widgets/Chart");
+        assert_eq!(relative_to("../shared/Chart", 1), "../../shared/Chart");
+        assert_eq!(relative_to("recharts", 1), "recharts");
+        assert_eq!(
+            package_name("@tanstack/react-table/build"),
+            "@tanstack/react-table"
+        );
+        assert_eq!(package_name("recharts/es6"), "recharts");
+    }
+
+    #[test]
+    fn test_check_modules() {
+        let source = r#"
+page:
+  name: Sales
+  children:
+    - react: ./widgets/Chart
+    - react: ./widgets/legend
+      export: Legend
+    - react: recharts/es6
+      export: LineChart
+    - card:
+        children:
+          - react: ./widgets/Table
+    - react: react-table
+"#;
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        // The golden pages' project has `src/widgets/` and a `package.json`.
+        let project = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+        let diagnostics =
+            check_modules(&page.page_data.children, "page.children", &index, &project);
+
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "cannot find module `./widgets/Table`",
+                "cannot find package `react-table`"
+            ]
+        );
+        assert_eq!(diagnostics[0].span.as_ref().map(|span| span.line), Some(12));
+    }
+}
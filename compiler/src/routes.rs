@@ -0,0 +1,480 @@
+//! The routes `nwl.yaml` serves pages at, nested below layout routes, and
+//! the parameters in their paths, such as `id` in `/project/:id`.
+
+use crate::components::walk;
+use crate::diagnostics::{codes, suggest};
+use crate::source_index::SourceIndex;
+use nwl_shared::{Diagnostic, Element, Handler, RouteConfig, Step};
+
+/// A route of `nwl.yaml` and where it sits among the routes around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteEntry<'r> {
+    pub route: &'r RouteConfig,
+    /// Path of the route in `nwl.yaml`, such as `routes[0].children[1]`.
+    pub key: String,
+    /// Whether the route is nested in a layout route.
+    pub nested: bool,
+    /// The URL path of the routes around it, joined.
+    pub parent_path: String,
+    /// The URL path the route is served at: its own path joined to
+    /// `parent_path`.
+    pub full_path: String,
+}
+
+/// Every route of `routes` and the routes nested in them, each before its
+/// children.
+pub fn flatten(routes: &[RouteConfig]) -> Vec<RouteEntry<'_>> {
+    let mut entries = Vec::new();
+    collect(routes, "routes", false, "", &mut entries);
+    entries
+}
+
+fn collect<'r>(
+    routes: &'r [RouteConfig],
+    key: &str,
+    nested: bool,
+    parent_path: &str,
+    entries: &mut Vec<RouteEntry<'r>>,
+) {
+    for (i, route) in routes.iter().enumerate() {
+        let key = format!("{}[{}]", key, i);
+        let full_path = join(parent_path, &route.path);
+        entries.push(RouteEntry {
+            route,
+            key: key.clone(),
+            nested,
+            parent_path: parent_path.to_string(),
+            full_path: full_path.clone(),
+        });
+        collect(
+            &route.children,
+            &format!("{}.children", key),
+            true,
+            &full_path,
+            entries,
+        );
+    }
+}
+
+/// `path` below `parent`, as React Router resolves nested paths.
+pub fn join(parent: &str, path: &str) -> String {
+    if path.is_empty() {
+        return parent.to_string();
+    }
+    if path.starts_with('/') || parent.is_empty() {
+        return path.to_string();
+    }
+    format!("{}/{}", parent.trim_end_matches('/'), path)
+}
+
+/// A `:name` segment of a route path, or `:name?` when it may be left
+/// out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteParam<'p> {
+    pub name: &'p str,
+    pub optional: bool,
+    /// Character offset of the segment in the path.
+    pub offset: usize,
+    /// Length of the segment in characters, including `:` and `?`.
+    pub length: usize,
+}
+
+/// Whether `href` is a path of the app, routed in the browser, rather than
+/// a URL of another site or a `mailto:` link.
+pub fn is_internal(href: &str) -> bool {
+    href.starts_with('/') && !href.starts_with("//")
+}
+
+/// Whether the URL path `path` is served at the route path `pattern`,
+/// such as `/projects/12` at `/projects/:id`. A `*` at the end of the
+/// pattern matches the rest of the path.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    for expected in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if expected == "*" {
+            return true;
+        }
+        match (expected.strip_prefix(':'), segments.next()) {
+            (Some(name), None) => {
+                if !name.ends_with('?') {
+                    return false;
+                }
+            }
+            (Some(_), Some(_)) => {}
+            (None, Some(segment)) if segment == expected => {}
+            (None, _) => return false,
+        }
+    }
+    segments.next().is_none()
+}
+
+/// The internal paths among `elements`, and those nested in them, link or
+/// navigate to, with their path below `path`: buttons' `onClick` paths,
+/// `navigate:` steps, `url` links and the links of navs, menus and
+/// breadcrumbs.
+pub fn links<'e>(
+    elements: &'e [Element],
+    path: &str,
+    index: &SourceIndex,
+) -> Vec<(&'e str, String)> {
+    let mut links = Vec::new();
+    walk(elements, path, &mut |element, path| {
+        for (key, href) in hrefs(element) {
+            links.push((href, format!("{}.{}", path, key)));
+        }
+        for (key, handler) in element.handlers() {
+            let Handler::Steps(steps) = handler else {
+                continue;
+            };
+            let handler_path = format!("{}.{}", path, key);
+            for (i, step) in steps.iter().enumerate() {
+                let Step::Navigate { navigate } = step else {
+                    continue;
+                };
+                // A single step may be written without the surrounding list.
+                let step_path =
+                    if steps.len() == 1 && index.has_key(&format!("{}.navigate", handler_path)) {
+                        format!("{}.navigate", handler_path)
+                    } else {
+                        format!("{}[{}].navigate", handler_path, i)
+                    };
+                links.push((navigate.as_str(), step_path));
+            }
+        }
+    });
+    links.retain(|(href, _)| is_internal(href));
+    links
+}
+
+/// The paths or URLs an element links to, keyed by their path relative to
+/// the element.
+fn hrefs(element: &Element) -> Vec<(String, &str)> {
+    let mut hrefs = Vec::new();
+    match element {
+        Element::Button(button) => {
+            if let Some(Handler::Script(href)) = &button.onClick {
+                hrefs.push(("onClick".to_string(), href.as_str()));
+            }
+        }
+        Element::Nav(nav) => {
+            for (i, link) in nav.links.iter().enumerate() {
+                hrefs.extend(
+                    link.href
+                        .iter()
+                        .map(|href| (format!("links[{}].href", i), href.as_str())),
+                );
+            }
+        }
+        Element::Menu(menu) => {
+            for (i, link) in menu.items.iter().enumerate() {
+                hrefs.extend(
+                    link.href
+                        .iter()
+                        .map(|href| (format!("items[{}].href", i), href.as_str())),
+                );
+            }
+        }
+        Element::Url(url) if !url.is_input() => {
+            hrefs.extend(
+                url.href
+                    .iter()
+                    .map(|href| ("href".to_string(), href.as_str())),
+            );
+        }
+        Element::Breadcrumb(breadcrumb) => {
+            for (i, item) in breadcrumb.items.iter().enumerate() {
+                hrefs.extend(
+                    item.href
+                        .iter()
+                        .map(|href| (format!("items[{}].href", i), href.as_str())),
+                );
+            }
+        }
+        _ => {}
+    }
+    hrefs
+}
+
+/// Reports the internal paths among `elements` that no route of `routes`
+/// serves. A catch-all `*` route doesn't count: it serves the paths that
+/// are missing.
+pub fn check_links(
+    elements: &[Element],
+    path: &str,
+    index: &SourceIndex,
+    routes: &[RouteConfig],
+) -> Vec<Diagnostic> {
+    links(elements, path, index)
+        .into_iter()
+        .filter_map(|(href, path)| {
+            unknown_route(href, routes).map(|diagnostic| index.locate(diagnostic, &path))
+        })
+        .collect()
+}
+
+/// An error for `href` when no route of `routes` serves it.
+pub fn unknown_route(href: &str, routes: &[RouteConfig]) -> Option<Diagnostic> {
+    let entries = flatten(routes);
+    let served: Vec<&str> = entries
+        .iter()
+        .map(|entry| entry.full_path.as_str())
+        .filter(|path| !matches!(*path, "*" | "/*"))
+        .collect();
+    if served.iter().any(|pattern| matches(pattern, href)) {
+        return None;
+    }
+    let path = href.split(['?', '#']).next().unwrap_or(href);
+    let filled: Vec<(String, String)> = served
+        .iter()
+        .map(|pattern| fill(pattern, path))
+        .filter(|(_, shown)| shown != path)
+        .collect();
+    let candidates: Vec<&str> = filled
+        .iter()
+        .map(|(compared, _)| compared.as_str())
+        .collect();
+    let shown = suggest(path, &candidates).and_then(|candidate| {
+        filled
+            .iter()
+            .find(|(compared, _)| compared == candidate)
+            .map(|(_, shown)| shown)
+    });
+    let help = match shown {
+        Some(candidate) => format!("did you mean `{}`?", candidate),
+        None => "add a route for it to `nwl.yaml`, or link to a full URL".to_string(),
+    };
+    Some(
+        Diagnostic::error(
+            codes::UNKNOWN_ROUTE,
+            format!("no route is served at `{}`", href),
+        )
+        .with_label("not a path of a route in `nwl.yaml`")
+        .with_help(help),
+    )
+}
+
+/// `pattern` with its parameters and `*` taken from the segments of `path`
+/// in the same places, so that `/product/12` is compared to
+/// `/products/12` rather than `/products/:id`. Returns the path to compare
+/// and the one to suggest, which keeps the required parameters `path` has
+/// no segment for: `/projects` is compared to `/projects`, but
+/// `/projects/:id` is suggested.
+fn fill(pattern: &str, path: &str) -> (String, String) {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let (mut compared, mut shown) = (Vec::new(), Vec::new());
+    for expected in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        let segment = segments.next();
+        if expected == "*" {
+            let rest: Vec<&str> = segment.into_iter().chain(segments.by_ref()).collect();
+            compared.extend(&rest);
+            shown.extend(rest);
+            break;
+        }
+        match (expected.starts_with(':'), segment) {
+            (true, Some(segment)) => {
+                compared.push(segment);
+                shown.push(segment);
+            }
+            (true, None) if !expected.ends_with('?') => shown.push(expected),
+            (true, None) => {}
+            (false, _) => {
+                compared.push(expected);
+                shown.push(expected);
+            }
+        }
+    }
+    (
+        format!("/{}", compared.join("/")),
+        format!("/{}", shown.join("/")),
+    )
+}
+
+/// The parameters of `path`, in the order they appear.
+pub fn params(path: &str) -> Vec<RouteParam<'_>> {
+    let mut params = Vec::new();
+    let mut offset = 0;
+    for segment in path.split('/') {
+        let length = segment.chars().count();
+        if let Some(name) = segment.strip_prefix(':') {
+            let (name, optional) = match name.strip_suffix('?') {
+                Some(name) => (name, true),
+                None => (name, false),
+            };
+            params.push(RouteParam {
+                name,
+                optional,
+                offset,
+                length,
+            });
+        }
+        offset += length + 1;
+    }
+    params
+}
+
+/// Whether `name` can name a route parameter: letters, digits, `_` and
+/// `-`, as React Router matches them.
+pub fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten() {
+        let config: nwl_shared::ProjectConfig = serde_yaml::from_str(
+            r#"
+name: Shop
+routes:
+  - path: /
+    layout: pages/shell.yaml
+    children:
+      - index: true
+        page: pages/home.yaml
+      - path: products/:id
+        page: pages/product.yaml
+  - path: "*"
+    page: pages/404.yaml
+"#,
+        )
+        .unwrap();
+        let entries: Vec<(String, bool, String)> = flatten(&config.routes)
+            .into_iter()
+            .map(|entry| (entry.key, entry.nested, entry.full_path))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("routes[0]".to_string(), false, "/".to_string()),
+                ("routes[0].children[0]".to_string(), true, "/".to_string()),
+                (
+                    "routes[0].children[1]".to_string(),
+                    true,
+                    "/products/:id".to_string()
+                ),
+                ("routes[1]".to_string(), false, "*".to_string()),
+            ]
+        );
+        assert_eq!(join("/account", "settings"), "/account/settings");
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("/", "/"));
+        assert!(matches("/about", "/about/"));
+        assert!(matches("/projects/:id", "/projects/12?tab=files"));
+        assert!(!matches("/projects/:id", "/projects"));
+        assert!(matches("/projects/:id/:tab?", "/projects/12"));
+        assert!(matches("/docs/*", "/docs/guide/intro"));
+        assert!(!matches("/about", "/about/team"));
+        assert!(is_internal("/about"));
+        assert!(!is_internal("//cdn.example.com/a.js"));
+        assert!(!is_internal("https://example.com"));
+    }
+
+    #[test]
+    fn test_check_links() {
+        let config: nwl_shared::ProjectConfig = serde_yaml::from_str(
+            r#"
+name: Shop
+routes:
+  - path: /
+    page: pages/home.yaml
+  - path: /products/:id
+    page: pages/product.yaml
+  - path: "*"
+    page: pages/404.yaml
+"#,
+        )
+        .unwrap();
+        let source = r#"
+page:
+  name: Home
+  children:
+    - element: nav
+      links:
+        - label: Products
+          href: /products/12
+        - label: Cart
+          href: /cart
+        - label: Docs
+          href: https://docs.example.com
+        - label: All products
+          href: /products
+    - url:
+        href: /nowhere
+    - button:
+        content: Product
+        onClick: /product/12
+    - form:
+        onSubmit:
+          navigate: /
+"#;
+        let page: nwl_shared::Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        let diagnostics = check_links(
+            &page.page_data.children,
+            "page.children",
+            &index,
+            &config.routes,
+        );
+
+        let messages: Vec<(&str, Option<&str>, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| {
+                (
+                    d.message.as_str(),
+                    d.help.as_deref(),
+                    d.span.as_ref().map(|span| span.line),
+                )
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "no route is served at `/cart`",
+                    Some("add a route for it to `nwl.yaml`, or link to a full URL"),
+                    Some(10)
+                ),
+                (
+                    "no route is served at `/products`",
+                    Some("did you mean `/products/:id`?"),
+                    Some(14)
+                ),
+                (
+                    "no route is served at `/nowhere`",
+                    Some("add a route for it to `nwl.yaml`, or link to a full URL"),
+                    Some(16)
+                ),
+                (
+                    "no route is served at `/product/12`",
+                    Some("did you mean `/products/12`?"),
+                    Some(19)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!(params("/"), []);
+        assert_eq!(params("/about"), []);
+        let found = params("/projects/:id/files/:tab?");
+        let names: Vec<(&str, bool, usize, usize)> = found
+            .iter()
+            .map(|param| (param.name, param.optional, param.offset, param.length))
+            .collect();
+        assert_eq!(names, [("id", false, 10, 3), ("tab", true, 20, 5)]);
+        assert!(is_param_name("project_id"));
+        assert!(!is_param_name(""));
+        assert!(is_param_name("project-id"));
+        assert!(!is_param_name("id?"));
+    }
+}
@@ -0,0 +1,443 @@
+use nwl_shared::{Diagnostic, Element, Span};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// Maps YAML node paths such as `page.children[2].bind` to the position of
+/// the key and value in the original source, so diagnostics produced after
+/// deserialization can still point at the offending line.
+///
+/// Paths use the same notation serde_yaml uses in its error messages.
+#[derive(Debug, Clone, Default)]
+pub struct SourceIndex {
+    path: Option<PathBuf>,
+    source: String,
+    keys: HashMap<String, Span>,
+    values: HashMap<String, Span>,
+    scalars: HashMap<String, String>,
+}
+
+impl SourceIndex {
+    pub fn new(source: &str, path: Option<&Path>) -> Self {
+        let mut builder = IndexBuilder::default();
+        let mut parser = Parser::new_from_str(source);
+        // Syntax errors are reported by serde_yaml before validation runs; a
+        // partial index is still useful for whatever did parse.
+        let _ = parser.load(&mut builder, false);
+
+        let mut index = SourceIndex {
+            path: path.map(Path::to_path_buf),
+            source: source.to_string(),
+            keys: builder.keys,
+            values: builder.values,
+            scalars: builder.scalars,
+        };
+        index.alias_keyed_elements();
+        index
+    }
+
+    /// Makes keyed elements (`- heading: {content: …}`) reachable through
+    /// the tagged paths used after parsing, so `children[0].content` finds
+    /// `children[0].heading.content` and `children[0].element` finds the tag.
+    /// The scalar body of `- text: "…"` is reachable as its `content`, and
+    /// that of `- use: Card` as its `component`.
+    fn alias_keyed_elements(&mut self) {
+        let mut key_aliases = Vec::new();
+        let mut content_aliases = Vec::new();
+        for (path, span) in &self.keys {
+            let (canonical, tag) = self.canonical_path(path);
+            if let (Some(item), Some(value), Some(text)) =
+                (&tag, self.values.get(path), self.scalars.get(path))
+            {
+                let tag = path.rsplit('.').next().unwrap_or_default();
+                let property = nwl_shared::Element::scalar_property(tag);
+                content_aliases.push((
+                    format!("{}.{}", item, property),
+                    value.clone(),
+                    text.clone(),
+                ));
+            }
+            if canonical != *path {
+                key_aliases.push((canonical, span.clone()));
+            } else if let Some(item) = tag {
+                key_aliases.push((format!("{}.element", item), span.clone()));
+            }
+        }
+        let mut value_aliases: Vec<(String, Span)> = self
+            .values
+            .iter()
+            .map(|(path, span)| (self.canonical_path(path).0, span.clone()))
+            .filter(|(canonical, _)| !self.values.contains_key(canonical))
+            .collect();
+        let scalar_aliases: Vec<(String, String)> = self
+            .scalars
+            .iter()
+            .map(|(path, text)| (self.canonical_path(path).0, text.clone()))
+            .filter(|(canonical, _)| !self.scalars.contains_key(canonical))
+            .collect();
+        for (alias, text) in scalar_aliases {
+            self.scalars.insert(alias, text);
+        }
+        for (alias, span, text) in content_aliases {
+            value_aliases.push((alias.clone(), span));
+            self.scalars.entry(alias).or_insert(text);
+        }
+
+        for (alias, span) in key_aliases {
+            self.keys.entry(alias).or_insert(span);
+        }
+        for (alias, span) in value_aliases {
+            self.values.entry(alias).or_insert(span);
+        }
+    }
+
+    /// Drops the tag segment of every keyed element along `path`. When the
+    /// path itself ends at the tag of a keyed element, also returns the
+    /// canonical path of that element.
+    fn canonical_path(&self, path: &str) -> (String, Option<String>) {
+        let mut raw = String::new();
+        let mut canonical = String::new();
+        let mut keyed_item = None;
+        for segment in split_path(path) {
+            let is_keyed_tag = is_element_item(&canonical)
+                && segment
+                    .strip_prefix('.')
+                    .is_some_and(|tag| Element::TAGS.contains(&tag))
+                && !self.keys.contains_key(&format!("{}.element", raw));
+            keyed_item = is_keyed_tag.then(|| canonical.clone());
+            raw.push_str(segment);
+            if !is_keyed_tag {
+                canonical.push_str(segment);
+            }
+        }
+        (canonical, keyed_item)
+    }
+
+    /// Position of the mapping key at `path`, falling back to the closest
+    /// ancestor that exists in the source.
+    pub fn key_span(&self, path: &str) -> Option<Span> {
+        self.lookup(path, &self.keys)
+    }
+
+    /// Position of the value at `path`, falling back to the closest ancestor
+    /// that exists in the source.
+    pub fn value_span(&self, path: &str) -> Option<Span> {
+        self.lookup(path, &self.values)
+    }
+
+    /// Whether the mapping key at `path` is literally present in the source.
+    pub fn has_key(&self, path: &str) -> bool {
+        self.keys.contains_key(path)
+    }
+
+    /// Every occurrence of the mapping key `name` below `within`, in source
+    /// order, as `(path, span)` pairs.
+    pub fn keys_named(&self, within: &str, name: &str) -> Vec<(&str, &Span)> {
+        let mut found: Vec<(&str, &Span)> = self
+            .keys
+            .iter()
+            .filter(|(path, _)| {
+                path.starts_with(within)
+                    && path.rsplit_once('.').is_some_and(|(_, key)| key == name)
+            })
+            .map(|(path, span)| (path.as_str(), span))
+            .collect();
+        found.sort_by_key(|(_, span)| (span.line, span.column));
+        found
+    }
+
+    /// Every scalar value equal to `text` below `within`, in source order.
+    pub fn values_equal(&self, within: &str, text: &str) -> Vec<(&str, &Span)> {
+        let mut found: Vec<(&str, &Span)> = self
+            .scalars
+            .iter()
+            .filter(|(path, value)| path.starts_with(within) && value.as_str() == text)
+            .filter_map(|(path, _)| Some((path.as_str(), self.values.get(path)?)))
+            .collect();
+        found.sort_by_key(|(_, span)| (span.line, span.column));
+        found
+    }
+
+    /// Names of the keys directly inside the mapping at `path`.
+    pub fn child_keys(&self, path: &str) -> Vec<&str> {
+        self.keys
+            .keys()
+            .filter_map(|key| key.strip_prefix(path)?.strip_prefix('.'))
+            .filter(|key| !key.contains(['.', '[']))
+            .collect()
+    }
+
+    /// Attaches the value position of `path` (and the surrounding source
+    /// line) to `diagnostic`.
+    pub fn locate(&self, diagnostic: Diagnostic, path: &str) -> Diagnostic {
+        match self.value_span(path) {
+            Some(span) => self.attach(diagnostic, span),
+            None => diagnostic,
+        }
+    }
+
+    /// Like [`SourceIndex::locate`] but points `length` characters starting at
+    /// the 0-based character `offset` inside the scalar at `path`, for errors
+    /// within a string such as a type or an expression. Falls back to the
+    /// whole value when the scalar spans several lines.
+    pub fn locate_in_value(
+        &self,
+        diagnostic: Diagnostic,
+        path: &str,
+        offset: usize,
+        length: usize,
+    ) -> Diagnostic {
+        match self.span_in_value(path, offset, length) {
+            Some(span) => self.attach(diagnostic, span),
+            None => self.locate(diagnostic, path),
+        }
+    }
+
+    /// Position of `length` characters starting at the 0-based character
+    /// `offset` inside the scalar at `path`, or `None` when the scalar isn't
+    /// written on a single line as-is, such as a block or escaped string.
+    pub fn span_in_value(&self, path: &str, offset: usize, length: usize) -> Option<Span> {
+        let (span, text) = (self.values.get(path)?, self.scalars.get(path)?);
+        let chars = text.chars().count();
+        let quote = match span.length.checked_sub(chars) {
+            Some(0) => 0,
+            Some(2) => 1,
+            _ => return None,
+        };
+        Some(
+            Span::new(span.line, span.column + quote + offset.min(chars))
+                .with_length(length.min(chars.saturating_sub(offset)).max(1)),
+        )
+    }
+
+    /// The text of the scalar at `path`.
+    pub fn scalar(&self, path: &str) -> Option<&str> {
+        self.scalars.get(path).map(String::as_str)
+    }
+
+    /// Like [`SourceIndex::locate`] but points at the key instead of the value.
+    pub fn locate_key(&self, diagnostic: Diagnostic, path: &str) -> Diagnostic {
+        match self.key_span(path) {
+            Some(span) => self.attach(diagnostic, span),
+            None => diagnostic,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn attach(&self, diagnostic: Diagnostic, span: Span) -> Diagnostic {
+        diagnostic
+            .with_span(span.with_path(self.path.clone()))
+            .with_source(&self.source)
+    }
+
+    fn lookup(&self, path: &str, table: &HashMap<String, Span>) -> Option<Span> {
+        let mut current = path;
+        loop {
+            let found = table
+                .get(current)
+                .or_else(|| self.values.get(current))
+                .or_else(|| self.keys.get(current));
+            if let Some(span) = found {
+                return Some(span.clone());
+            }
+            current = parent_path(current)?;
+        }
+    }
+}
+
+/// Splits `a.b[0].c` into `a`, `.b`, `[0]`, `.c`.
+fn split_path(path: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for (i, c) in path.char_indices().skip(1) {
+        if c == '.' || c == '[' {
+            segments.push(&path[start..i]);
+            start = i;
+        }
+    }
+    segments.push(&path[start..]);
+    segments
+}
+
+/// Whether `path` is an item of a list of elements, such as `children[0]`,
+/// rather than of any other sequence, such as the steps of a handler.
+fn is_element_item(path: &str) -> bool {
+    let Some(list) = path
+        .strip_suffix(']')
+        .and_then(|path| path.rsplit_once('['))
+        .map(|(list, _)| list)
+    else {
+        return false;
+    };
+    let (parent, key) = list.rsplit_once('.').unwrap_or(("", list));
+    matches!(key, "children" | "else" | "empty") || parent.ends_with(".slots")
+}
+
+fn parent_path(path: &str) -> Option<&str> {
+    let cut = path.rfind(['.', '['])?;
+    Some(&path[..cut])
+}
+
+enum Frame {
+    Mapping { path: String, key: Option<String> },
+    Sequence { path: String, index: usize },
+}
+
+#[derive(Default)]
+struct IndexBuilder {
+    stack: Vec<Frame>,
+    keys: HashMap<String, Span>,
+    values: HashMap<String, Span>,
+    scalars: HashMap<String, String>,
+}
+
+impl IndexBuilder {
+    /// Returns the path of the node about to be read, or `None` when the next
+    /// scalar is a mapping key.
+    fn next_value_path(&mut self) -> Option<String> {
+        match self.stack.last_mut() {
+            None => Some(String::new()),
+            Some(Frame::Mapping { path, key }) => key.take().map(|key| join(path, &key)),
+            Some(Frame::Sequence { path, index }) => {
+                let child = format!("{}[{}]", path, index);
+                *index += 1;
+                Some(child)
+            }
+        }
+    }
+}
+
+impl MarkedEventReceiver for IndexBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let span = Span::new(mark.line(), mark.col() + 1);
+        match event {
+            Event::Scalar(value, style, _, _) => {
+                let length = value.chars().count()
+                    + match style {
+                        TScalarStyle::SingleQuoted | TScalarStyle::DoubleQuoted => 2,
+                        _ => 0,
+                    };
+                let span = span.with_length(length);
+                match self.next_value_path() {
+                    Some(path) => {
+                        self.values.insert(path.clone(), span);
+                        self.scalars.insert(path, value);
+                    }
+                    None => {
+                        if let Some(Frame::Mapping { path, key }) = self.stack.last_mut() {
+                            self.keys.insert(join(path, &value), span);
+                            *key = Some(value);
+                        }
+                    }
+                }
+            }
+            Event::MappingStart(..) | Event::SequenceStart(..) => {
+                let path = self.next_value_path().unwrap_or_default();
+                self.values.insert(path.clone(), span);
+                self.stack
+                    .push(if matches!(event, Event::MappingStart(..)) {
+                        Frame::Mapping { path, key: None }
+                    } else {
+                        Frame::Sequence { path, index: 0 }
+                    });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+            }
+            Event::Alias(_) => {
+                self.next_value_path();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"page:
+  name: Test
+  children:
+    - element: heading
+      content: "Hello"
+    - element: input
+      bind: username
+"#;
+
+    #[test]
+    fn test_value_span() {
+        let index = SourceIndex::new(SOURCE, None);
+        let span = index.value_span("page.children[1].bind").unwrap();
+        assert_eq!((span.line, span.column, span.length), (7, 13, 8));
+
+        let span = index.value_span("page.children[0].content").unwrap();
+        assert_eq!((span.line, span.column, span.length), (5, 16, 7));
+    }
+
+    #[test]
+    fn test_keyed_element_aliases() {
+        let source = "page:\n  children:\n    - card:\n        children:\n          - input:\n              bind: username\n";
+        let index = SourceIndex::new(source, None);
+        let span = index
+            .value_span("page.children[0].children[0].bind")
+            .unwrap();
+        assert_eq!((span.line, span.column), (6, 21));
+        let span = index
+            .key_span("page.children[0].children[0].element")
+            .unwrap();
+        assert_eq!((span.line, span.column), (5, 13));
+        assert_eq!(
+            index.scalar("page.children[0].children[0].bind"),
+            Some("username")
+        );
+        let span = index
+            .span_in_value("page.children[0].children[0].bind", 0, 8)
+            .unwrap();
+        assert_eq!((span.line, span.column, span.length), (6, 21, 8));
+
+        let index = SourceIndex::new("page:\n  children:\n    - text: \"Hi {name}\"\n", None);
+        let span = index.value_span("page.children[0].content").unwrap();
+        assert_eq!((span.line, span.column, span.length), (3, 13, 11));
+        let span = index
+            .span_in_value("page.children[0].content", 4, 4)
+            .unwrap();
+        assert_eq!((span.line, span.column, span.length), (3, 18, 4));
+        assert_eq!(index.scalar("page.children[0].content"), Some("Hi {name}"));
+
+        // Steps share names with tags, but aren't elements.
+        let source = "page:\n  children:\n    - button:\n        content: Save\n        onClick:\n          - toggle: draft\n";
+        let index = SourceIndex::new(source, None);
+        let span = index
+            .value_span("page.children[0].onClick[0].toggle")
+            .unwrap();
+        assert_eq!((span.line, span.column, span.length), (6, 21, 5));
+    }
+
+    #[test]
+    fn test_key_span_and_fallback() {
+        let index = SourceIndex::new(SOURCE, None);
+        let span = index.key_span("page.children[1].bind").unwrap();
+        assert_eq!((span.line, span.column), (7, 7));
+
+        // Missing keys fall back to the enclosing element.
+        let span = index.key_span("page.children[1].placeholder").unwrap();
+        assert_eq!(span.line, 6);
+    }
+}
@@ -0,0 +1,109 @@
+//! Source maps from generated components back to page YAML, so browser
+//! devtools and bundler error overlays point at the element that produced
+//! a line.
+
+use crate::codegen::Mark;
+use crate::source_index::SourceIndex;
+use sourcemap::SourceMapBuilder;
+
+/// Builds a v3 source map for the generated `file` from the marks printed
+/// with it. `source` is the page file as referenced from the map, and
+/// `index` the page it was generated from; its text is embedded so the
+/// map works without access to the project.
+pub fn source_map(file: &str, source: &str, index: &SourceIndex, marks: &[Mark]) -> String {
+    let mut builder = SourceMapBuilder::new(Some(file));
+    let id = builder.add_source(source);
+    builder.set_source_contents(id, Some(index.source()));
+
+    for mark in marks {
+        // The tag of the element, or the element itself when the index
+        // has no tag for it.
+        let span = index
+            .key_span(&format!("{}.element", mark.origin))
+            .or_else(|| index.value_span(&mark.origin));
+        let Some(span) = span else {
+            continue;
+        };
+        builder.add_raw(
+            mark.line as u32,
+            mark.column as u32,
+            span.line.saturating_sub(1) as u32,
+            span.column.saturating_sub(1) as u32,
+            Some(id),
+            None,
+            false,
+        );
+    }
+
+    let mut json = Vec::new();
+    builder
+        .into_sourcemap()
+        .to_writer(&mut json)
+        .expect("writing to a Vec cannot fail");
+    String::from_utf8(json).expect("source maps are JSON")
+}
+
+/// The comment that links a generated file to its source map.
+pub fn mapping_url_comment(map_file: &str) -> String {
+    format!("//# sourceMappingURL={}\n", map_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Actions;
+    use crate::codegen::generate_react_mapped;
+    use crate::components::Components;
+    use nwl_shared::{Document, Page};
+    use sourcemap::SourceMap;
+    use std::path::Path;
+
+    #[test]
+    fn test_source_map_points_at_elements() {
+        let yaml = r#"page:
+  name: Home
+  state:
+    - name: count
+      initial: 0
+  children:
+    - element: heading
+      content: Welcome
+    - element: card
+      children:
+        - element: button
+          content: Add one
+          onClick:
+            - set: count
+              to: count + 1
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let document = Document { pages: vec![page] };
+        let output =
+            generate_react_mapped(&document, &Actions::default(), &Components::default()).unwrap();
+        let index = SourceIndex::new(yaml, Some(Path::new("pages/home.yaml")));
+        let json = source_map("home.tsx", "../pages/home.yaml", &index, &output.marks);
+
+        let map = SourceMap::from_slice(json.as_bytes()).unwrap();
+        assert_eq!(map.get_file(), Some("home.tsx"));
+        assert_eq!(map.get_source(0), Some("../pages/home.yaml"));
+        assert_eq!(map.get_source_contents(0), Some(yaml));
+
+        // 0-based YAML line of the element each generated line maps to.
+        let source_line = |needle: &str| {
+            let (line, text) = output
+                .code
+                .lines()
+                .enumerate()
+                .find(|(_, text)| text.contains(needle))
+                .unwrap();
+            let column = text.len() - text.trim_start().len();
+            let token = map.lookup_token(line as u32, column as u32).unwrap();
+            token.get_src_line()
+        };
+        assert_eq!(source_line("<h1>Welcome</h1>"), 6);
+        assert_eq!(source_line("<div>"), 8);
+        assert_eq!(source_line("<button"), 10);
+        assert_eq!(source_line("onClick="), 10);
+        assert_eq!(source_line("Add one"), 10);
+    }
+}
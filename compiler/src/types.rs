@@ -0,0 +1,708 @@
+use crate::escape::js_string;
+use nwl_shared::{ParamDefinition, StateDefinition};
+use serde_yaml::Value;
+use std::fmt;
+
+/// The type of a page state variable, either declared with `type:` or
+/// inferred from its `initial` value.
+///
+/// Declared types use a small subset of TypeScript syntax:
+///
+/// ```yaml
+/// state:
+///   - name: count
+///     type: number
+///   - name: tags
+///     type: string[]
+///   - name: priority
+///     type: "'low' | 'medium' | 'high'"
+///   - name: user
+///     type: "{ name: string, age: number } | null"
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateType {
+    String,
+    Number,
+    Boolean,
+    /// A browser `File`, as produced by `file-upload`.
+    File,
+    Array(Box<StateType>),
+    Object(Vec<Field>),
+    Nullable(Box<StateType>),
+    /// A union of string literals, e.g. `'low' | 'high'`.
+    Union(Vec<String>),
+    /// No type was declared and none could be inferred.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: StateType,
+    pub optional: bool,
+}
+
+/// A `type:` declaration that could not be parsed, with the 0-based
+/// character offset and length of the offending token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeSyntaxError {
+    pub message: String,
+    pub offset: usize,
+    pub length: usize,
+}
+
+impl StateType {
+    pub fn parse(source: &str) -> Result<StateType, TypeSyntaxError> {
+        let mut parser = TypeParser {
+            tokens: tokenize(source)?,
+            position: 0,
+            end: source.chars().count(),
+        };
+        let ty = parser.parse_union()?;
+        match parser.peek() {
+            None => Ok(ty),
+            Some((token, offset)) => Err(TypeSyntaxError {
+                message: format!("unexpected `{}` in type", token),
+                offset,
+                length: token.to_string().chars().count(),
+            }),
+        }
+    }
+
+    /// Infers a type from an initial value. Empty arrays and `null` carry no
+    /// element information and infer to `any[]` / `any`.
+    pub fn infer(value: &Value) -> StateType {
+        match value {
+            Value::String(_) => StateType::String,
+            Value::Number(_) => StateType::Number,
+            Value::Bool(_) => StateType::Boolean,
+            Value::Sequence(items) => {
+                let mut types = items.iter().map(StateType::infer);
+                let first = types.next().unwrap_or(StateType::Unknown);
+                if types.all(|ty| ty == first) {
+                    StateType::Array(Box::new(first))
+                } else {
+                    StateType::Array(Box::new(StateType::Unknown))
+                }
+            }
+            Value::Mapping(mapping) => StateType::Object(
+                mapping
+                    .iter()
+                    .map(|(key, value)| Field {
+                        name: key_to_string(key),
+                        ty: StateType::infer(value),
+                        optional: false,
+                    })
+                    .collect(),
+            ),
+            Value::Tagged(tagged) => StateType::infer(&tagged.value),
+            Value::Null => StateType::Unknown,
+        }
+    }
+
+    /// Checks that `value` is assignable to this type. On mismatch returns a
+    /// message naming the offending part of the value (`initial.user.age`).
+    pub fn check(&self, value: &Value) -> Result<(), String> {
+        self.check_at(value, "initial")
+    }
+
+    /// Like [`StateType::check`], calling the value `name` in messages.
+    pub fn check_named(&self, value: &Value, name: &str) -> Result<(), String> {
+        self.check_at(value, name)
+    }
+
+    fn check_at(&self, value: &Value, path: &str) -> Result<(), String> {
+        let mismatch = || {
+            Err(format!(
+                "`{}` is {}, expected {}",
+                path,
+                describe(value),
+                self
+            ))
+        };
+        match (self, value) {
+            (StateType::Unknown, _) => Ok(()),
+            (StateType::Nullable(_), Value::Null) => Ok(()),
+            (StateType::Nullable(inner), _) => inner.check_at(value, path),
+            (StateType::String, Value::String(_))
+            | (StateType::Number, Value::Number(_))
+            | (StateType::Boolean, Value::Bool(_)) => Ok(()),
+            (StateType::Union(members), Value::String(s)) if members.contains(s) => Ok(()),
+            (StateType::Array(item), Value::Sequence(items)) => {
+                for (i, value) in items.iter().enumerate() {
+                    item.check_at(value, &format!("{}[{}]", path, i))?;
+                }
+                Ok(())
+            }
+            (StateType::Object(fields), Value::Mapping(mapping)) => {
+                for field in fields {
+                    match mapping.get(field.name.as_str()) {
+                        Some(value) => field
+                            .ty
+                            .check_at(value, &format!("{}.{}", path, field.name))?,
+                        None if field.optional || matches!(field.ty, StateType::Nullable(_)) => {}
+                        None => {
+                            return Err(format!("`{}` is missing field `{}`", path, field.name))
+                        }
+                    }
+                }
+                for key in mapping.keys() {
+                    let key = key_to_string(key);
+                    if !fields.iter().any(|field| field.name == key) {
+                        return Err(format!("`{}` has unexpected field `{}`", path, key));
+                    }
+                }
+                Ok(())
+            }
+            (_, Value::Tagged(tagged)) => self.check_at(&tagged.value, path),
+            _ => mismatch(),
+        }
+    }
+
+    /// The value a state of this type starts with when no `initial` is given.
+    pub fn default_value(&self) -> Value {
+        match self {
+            StateType::String => Value::String(String::new()),
+            StateType::Number => Value::Number(0.into()),
+            StateType::Boolean => Value::Bool(false),
+            StateType::Array(_) => Value::Sequence(Vec::new()),
+            StateType::Object(fields) => Value::Mapping(
+                fields
+                    .iter()
+                    .filter(|field| !field.optional)
+                    .map(|field| (Value::String(field.name.clone()), field.ty.default_value()))
+                    .collect(),
+            ),
+            StateType::Union(members) => Value::String(members[0].clone()),
+            StateType::File | StateType::Nullable(_) | StateType::Unknown => Value::Null,
+        }
+    }
+
+    /// The TypeScript spelling of this type.
+    pub fn to_ts(&self) -> String {
+        match self {
+            StateType::String => "string".to_string(),
+            StateType::Number => "number".to_string(),
+            StateType::Boolean => "boolean".to_string(),
+            StateType::File => "File".to_string(),
+            StateType::Unknown => "any".to_string(),
+            StateType::Array(item) => match item.as_ref() {
+                StateType::Nullable(_) | StateType::Union(_) => format!("({})[]", item.to_ts()),
+                _ => format!("{}[]", item.to_ts()),
+            },
+            StateType::Object(fields) if fields.is_empty() => "Record<string, never>".to_string(),
+            StateType::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|field| {
+                        format!(
+                            "{}{}: {}",
+                            js_key(&field.name),
+                            if field.optional { "?" } else { "" },
+                            field.ty.to_ts()
+                        )
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            StateType::Nullable(inner) if **inner == StateType::Unknown => "any".to_string(),
+            StateType::Nullable(inner) => format!("{} | null", inner.to_ts()),
+            StateType::Union(members) => members
+                .iter()
+                .map(|member| js_string(member))
+                .collect::<Vec<_>>()
+                .join(" | "),
+        }
+    }
+
+    /// The type of `value.name` for a value of this type, or `None` when it
+    /// has no such field. Types that aren't known precisely, including
+    /// nullable ones, allow any field.
+    pub fn field(&self, name: &str) -> Option<StateType> {
+        match self {
+            StateType::Object(fields) => fields
+                .iter()
+                .find(|field| field.name == name)
+                .map(|field| field.ty.clone()),
+            StateType::String | StateType::Array(_) | StateType::Union(_) if name == "length" => {
+                Some(StateType::Number)
+            }
+            StateType::File if name == "name" || name == "type" => Some(StateType::String),
+            StateType::File if name == "size" => Some(StateType::Number),
+            StateType::Nullable(_) | StateType::Unknown => Some(StateType::Unknown),
+            _ => None,
+        }
+    }
+
+    /// The names `field` accepts, for suggestions.
+    pub fn field_names(&self) -> Vec<&str> {
+        match self {
+            StateType::Object(fields) => fields.iter().map(|field| field.name.as_str()).collect(),
+            StateType::String | StateType::Array(_) | StateType::Union(_) => vec!["length"],
+            StateType::File => vec!["name", "size", "type"],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The type of the items of an array type, or `None` for non-arrays.
+    pub fn item(&self) -> Option<StateType> {
+        match self {
+            StateType::Array(item) => Some(item.as_ref().clone()),
+            StateType::Unknown => Some(StateType::Unknown),
+            _ => None,
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, StateType::Nullable(_) | StateType::Unknown)
+    }
+
+    /// Whether a value of type `other` can be passed where this type is
+    /// expected. Types that aren't known precisely are accepted.
+    pub fn accepts(&self, other: &StateType) -> bool {
+        match (self, other) {
+            (StateType::Unknown, _) | (_, StateType::Unknown) => true,
+            (StateType::Nullable(inner), StateType::Nullable(other)) => inner.accepts(other),
+            (StateType::Nullable(inner), other) => inner.accepts(other),
+            (_, StateType::Nullable(_)) => false,
+            (StateType::String, StateType::Union(_)) => true,
+            (StateType::Union(members), StateType::Union(values)) => {
+                values.iter().all(|value| members.contains(value))
+            }
+            (StateType::Array(item), StateType::Array(other)) => item.accepts(other),
+            (StateType::Object(fields), StateType::Object(others)) => fields.iter().all(|field| {
+                match others.iter().find(|other| other.name == field.name) {
+                    Some(other) => field.ty.accepts(&other.ty),
+                    None => field.optional,
+                }
+            }),
+            _ => self == other,
+        }
+    }
+}
+
+impl fmt::Display for StateType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateType::Union(_) => write!(f, "one of {}", self.to_ts()),
+            _ => write!(f, "`{}`", self.to_ts()),
+        }
+    }
+}
+
+/// The declared type of `state`, or the type inferred from its initial value.
+pub fn state_type(state: &StateDefinition) -> Result<StateType, TypeSyntaxError> {
+    match (&state.value_type, &state.initial) {
+        (Some(declared), _) => StateType::parse(declared),
+        (None, Some(initial)) => Ok(StateType::infer(initial)),
+        (None, None) => Ok(StateType::Unknown),
+    }
+}
+
+/// The declared type of a route parameter, the type inferred from its
+/// default, or `string`.
+pub fn param_type(param: &ParamDefinition) -> Result<StateType, TypeSyntaxError> {
+    match (&param.value_type, &param.default) {
+        (Some(declared), _) => StateType::parse(declared),
+        (None, Some(default)) => Ok(StateType::infer(default)),
+        (None, None) => Ok(StateType::String),
+    }
+}
+
+/// The value `state` starts with: its `initial`, or the default for its type.
+pub fn initial_value(state: &StateDefinition, ty: &StateType) -> Value {
+    state.initial.clone().unwrap_or_else(|| ty.default_value())
+}
+
+/// Renders a YAML value as a JavaScript literal.
+pub fn js_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => js_string(s),
+        Value::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(js_literal).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Mapping(mapping) if mapping.is_empty() => "{}".to_string(),
+        Value::Mapping(mapping) => {
+            let entries: Vec<String> = mapping
+                .iter()
+                .map(|(key, value)| {
+                    format!("{}: {}", js_key(&key_to_string(key)), js_literal(value))
+                })
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        Value::Tagged(tagged) => js_literal(&tagged.value),
+    }
+}
+
+/// Whether `name` is an ASCII JavaScript identifier.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// An object key, quoted only when it is not a valid identifier.
+pub(crate) fn js_key(key: &str) -> String {
+    if is_identifier(key) {
+        key.to_string()
+    } else {
+        js_string(key)
+    }
+}
+
+pub(crate) fn key_to_string(key: &Value) -> String {
+    match key {
+        Value::String(s) => s.clone(),
+        other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("the boolean `{}`", b),
+        Value::Number(n) => format!("the number `{}`", n),
+        Value::String(s) => format!("the string {}", js_string(s)),
+        Value::Sequence(_) => "an array".to_string(),
+        Value::Mapping(_) => "an object".to_string(),
+        Value::Tagged(tagged) => describe(&tagged.value),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Str(s) => write!(f, "{}", js_string(s)),
+            Token::Punct(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, TypeSyntaxError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else if c == '\'' || c == '"' {
+            let start = i;
+            i += 1;
+            let mut value = String::new();
+            while i < chars.len() && chars[i] != c {
+                value.push(chars[i]);
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err(TypeSyntaxError {
+                    message: "unterminated string literal in type".to_string(),
+                    offset: start,
+                    length: 1,
+                });
+            }
+            i += 1;
+            tokens.push((Token::Str(value), start));
+        } else if "{}[]()<>|:;,?".contains(c) {
+            tokens.push((Token::Punct(c), i));
+            i += 1;
+        } else {
+            return Err(TypeSyntaxError {
+                message: format!("unexpected character `{}` in type", c),
+                offset: i,
+                length: 1,
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+struct TypeParser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    end: usize,
+}
+
+impl TypeParser {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens
+            .get(self.position)
+            .map(|(token, offset)| (token, *offset))
+    }
+
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if matches!(self.peek(), Some((Token::Punct(c), _)) if *c == punct) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), TypeSyntaxError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", punct)))
+        }
+    }
+
+    fn error(&self, message: String) -> TypeSyntaxError {
+        TypeSyntaxError {
+            message,
+            offset: self.peek().map(|(_, offset)| offset).unwrap_or(self.end),
+            length: 1,
+        }
+    }
+
+    fn parse_union(&mut self) -> Result<StateType, TypeSyntaxError> {
+        let start = self.peek().map(|(_, offset)| offset).unwrap_or(self.end);
+        self.eat('|');
+        let mut members = vec![self.parse_postfix()?];
+        while self.eat('|') {
+            members.push(self.parse_postfix()?);
+        }
+        if members.len() == 1 {
+            return Ok(members.remove(0).into_type());
+        }
+
+        let nullable = members.iter().any(|member| member.is_null());
+        members.retain(|member| !member.is_null());
+
+        let ty = if members.len() == 1 {
+            members.remove(0)
+        } else if members
+            .iter()
+            .all(|member| matches!(member, Parsed::Literal(_)))
+        {
+            Parsed::Type(StateType::Union(
+                members
+                    .into_iter()
+                    .filter_map(|member| match member {
+                        Parsed::Literal(s) => Some(s),
+                        _ => None,
+                    })
+                    .collect(),
+            ))
+        } else {
+            return Err(TypeSyntaxError {
+                message: "unions may only combine string literals and `null`".to_string(),
+                offset: start,
+                length: 1,
+            });
+        };
+
+        let ty = ty.into_type();
+        Ok(if nullable {
+            StateType::Nullable(Box::new(ty))
+        } else {
+            ty
+        })
+    }
+
+    fn parse_postfix(&mut self) -> Result<Parsed, TypeSyntaxError> {
+        let mut ty = self.parse_primary()?;
+        while self.eat('[') {
+            self.expect(']')?;
+            ty = Parsed::Type(StateType::Array(Box::new(ty.into_type())));
+        }
+        Ok(ty)
+    }
+
+    fn parse_primary(&mut self) -> Result<Parsed, TypeSyntaxError> {
+        let Some((token, offset)) = self.next() else {
+            return Err(TypeSyntaxError {
+                message: "expected a type".to_string(),
+                offset: self.end,
+                length: 1,
+            });
+        };
+        match token {
+            Token::Ident(name) => match name.as_str() {
+                "string" => Ok(Parsed::Type(StateType::String)),
+                "number" => Ok(Parsed::Type(StateType::Number)),
+                "boolean" => Ok(Parsed::Type(StateType::Boolean)),
+                "File" => Ok(Parsed::Type(StateType::File)),
+                "any" | "unknown" => Ok(Parsed::Type(StateType::Unknown)),
+                "null" => Ok(Parsed::Null),
+                "Array" => {
+                    self.expect('<')?;
+                    let item = self.parse_union()?;
+                    self.expect('>')?;
+                    Ok(Parsed::Type(StateType::Array(Box::new(item))))
+                }
+                _ => Err(TypeSyntaxError {
+                    message: format!("unknown type `{}`", name),
+                    offset,
+                    length: name.chars().count(),
+                }),
+            },
+            Token::Str(value) => Ok(Parsed::Literal(value)),
+            Token::Punct('(') => {
+                let ty = self.parse_union()?;
+                self.expect(')')?;
+                Ok(Parsed::Type(ty))
+            }
+            Token::Punct('{') => {
+                let mut fields = Vec::new();
+                while !self.eat('}') {
+                    let name = match self.next() {
+                        Some((Token::Ident(name), _)) | Some((Token::Str(name), _)) => name,
+                        _ => {
+                            self.position -= 1;
+                            return Err(self.error("expected a field name".to_string()));
+                        }
+                    };
+                    let optional = self.eat('?');
+                    self.expect(':')?;
+                    let ty = self.parse_union()?;
+                    fields.push(Field { name, ty, optional });
+                    if !self.eat(',') && !self.eat(';') {
+                        self.expect('}')?;
+                        break;
+                    }
+                }
+                Ok(Parsed::Type(StateType::Object(fields)))
+            }
+            other => Err(TypeSyntaxError {
+                message: format!("unexpected `{}` in type", other),
+                offset,
+                length: other.to_string().chars().count(),
+            }),
+        }
+    }
+}
+
+/// Intermediate result while parsing unions, which need to tell string
+/// literals and `null` apart from complete types.
+enum Parsed {
+    Type(StateType),
+    Literal(String),
+    Null,
+}
+
+impl Parsed {
+    fn is_null(&self) -> bool {
+        matches!(self, Parsed::Null)
+    }
+
+    fn into_type(self) -> StateType {
+        match self {
+            Parsed::Type(ty) => ty,
+            Parsed::Literal(value) => StateType::Union(vec![value]),
+            Parsed::Null => StateType::Nullable(Box::new(StateType::Unknown)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaml(source: &str) -> Value {
+        serde_yaml::from_str(source).unwrap()
+    }
+
+    #[test]
+    fn test_parse_types() {
+        assert_eq!(StateType::parse("number").unwrap(), StateType::Number);
+        assert_eq!(StateType::parse("string[]").unwrap().to_ts(), "string[]");
+        assert_eq!(
+            StateType::parse("'low' | 'medium' | 'high'")
+                .unwrap()
+                .to_ts(),
+            "\"low\" | \"medium\" | \"high\""
+        );
+        assert_eq!(
+            StateType::parse("{ name: string, tags?: string[] } | null")
+                .unwrap()
+                .to_ts(),
+            "{ name: string; tags?: string[] } | null"
+        );
+        assert_eq!(
+            StateType::parse("Array<number | null>").unwrap().to_ts(),
+            "(number | null)[]"
+        );
+        assert_eq!(StateType::parse("File[]").unwrap().to_ts(), "File[]");
+
+        let error = StateType::parse("strng").unwrap_err();
+        assert_eq!(error.offset, 0);
+        assert_eq!(error.message, "unknown type `strng`");
+        assert_eq!(error.length, 5);
+        let error = StateType::parse("{ name: string").unwrap_err();
+        assert_eq!(error.offset, 14);
+    }
+
+    #[test]
+    fn test_infer_and_check() {
+        let value = yaml("{ name: Ada, tags: [a, b], age: 36 }");
+        let ty = StateType::infer(&value);
+        assert_eq!(ty.to_ts(), "{ name: string; tags: string[]; age: number }");
+        assert!(ty.check(&value).is_ok());
+
+        let ty = StateType::parse("{ name: string, age: number }").unwrap();
+        let error = ty.check(&yaml("{ name: Ada, age: old }")).unwrap_err();
+        assert_eq!(
+            error,
+            "`initial.age` is the string \"old\", expected `number`"
+        );
+
+        let ty = StateType::parse("'a' | 'b'").unwrap();
+        assert!(ty.check(&yaml("a")).is_ok());
+        assert!(ty.check(&yaml("c")).is_err());
+        assert_eq!(ty.default_value(), yaml("a"));
+    }
+
+    #[test]
+    fn test_accepts() {
+        let ty = |source| StateType::parse(source).unwrap();
+        assert!(ty("string | null").accepts(&ty("string")));
+        assert!(!ty("string").accepts(&ty("string | null")));
+        assert!(ty("string").accepts(&ty("'a' | 'b'")));
+        assert!(!ty("'a' | 'b'").accepts(&ty("'a' | 'c'")));
+        assert!(ty("{ id: number, note?: string }[]").accepts(&ty("{ id: number }[]")));
+        assert!(!ty("number").accepts(&ty("string")));
+        assert!(ty("number").accepts(&StateType::Unknown));
+    }
+
+    #[test]
+    fn test_js_literal() {
+        let value = yaml("{ name: \"Say \\\"hi\\\"\", list: [1, true, null], \"my-key\": {} }");
+        assert_eq!(
+            js_literal(&value),
+            "{ name: 'Say \"hi\"', list: [1, true, null], \"my-key\": {} }"
+        );
+    }
+}
@@ -0,0 +1,3796 @@
+use crate::actions::{Action, ActionInput, Actions};
+use crate::binding::{BindError, Control};
+use crate::codegen::ReactGenerator;
+use crate::components::{self, Component, Components, Prop};
+use crate::computed;
+use crate::diagnostics::{codes, suggest};
+use crate::escape;
+use crate::expression::{self, calls, parse, parse_text, Problem, Scope, Segment, SyntaxError};
+use crate::modules;
+use crate::routes::{self, RouteEntry};
+use crate::source_index::SourceIndex;
+use crate::types::{self, StateType, TypeSyntaxError};
+use nwl_shared::{
+    ActionFile, ComponentDefinition, ComponentFile, ComputedDefinition, Diagnostic, Document,
+    Element, Handler, Layout, LayoutType, ListElement, PageData, ParamDefinition, ProjectConfig,
+    PropDefinition, ReactElement, SlotElement, StateDefinition, Step, UseElement, RESERVED_WORDS,
+};
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// Semantic checks that run between parsing and codegen. Unlike parsing,
+/// validation never stops at the first problem: every issue in the page is
+/// collected so it can be fixed in a single pass.
+pub struct Validator<'a> {
+    index: &'a SourceIndex,
+    actions: &'a Actions,
+    components: &'a Components,
+    /// Named slots of the component being checked, or `None` on a page.
+    slots: Option<Vec<String>>,
+    /// Names the generated module binds besides the components `react`
+    /// elements import, with what each names.
+    taken: Vec<(String, String)>,
+    /// Names `react` elements import components as, with the module and
+    /// export each is imported from.
+    imported: HashMap<String, (String, Option<String>)>,
+    state: HashMap<String, DeclaredState>,
+    /// Item names of the enclosing `list` templates, innermost last.
+    items: Vec<(String, StateType)>,
+    diagnostics: Vec<Diagnostic>,
+    references: Vec<Reference>,
+}
+
+/// A place a page names one of its states or computed values, or a
+/// component one of its props.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub name: String,
+    /// Path of the scalar the name is written in, or of the mapping key
+    /// when `key` is set.
+    pub path: String,
+    /// Character offset of the name inside the scalar.
+    pub offset: usize,
+    /// Whether the name is a mapping key, such as a field of a form's
+    /// `validation`.
+    pub key: bool,
+    /// Whether this is the `name:` of the `state:` or `computed:` entry.
+    pub declaration: bool,
+}
+
+/// What validating a page or component found.
+#[derive(Debug, Clone, Default)]
+pub struct PageAnalysis {
+    pub diagnostics: Vec<Diagnostic>,
+    /// Every place the page names its states and computed values, in the
+    /// order they were checked. Script handlers are JavaScript and aren't
+    /// included.
+    pub references: Vec<Reference>,
+}
+
+/// Element properties rendered as JSX text, where `{expression}` is
+/// interpolated.
+const INTERPOLATED: &[&str] = &["content", "label", "title"];
+
+/// Names the generated code takes for React Router, with what each names.
+const ROUTER_NAMES: &[(&str, &str)] = &[
+    ("useNavigate", "React Router's `useNavigate`"),
+    ("useParams", "React Router's `useParams`"),
+    ("Link", "React Router's `Link`"),
+    ("NavLink", "React Router's `NavLink`"),
+    ("Navigate", "React Router's `Navigate`"),
+    ("Outlet", "React Router's `Outlet`"),
+    ("navigate", "the function handlers navigate with"),
+    ("routeParams", "the page's route parameters as text"),
+];
+
+/// A name declared in `state:`, `computed:`, a page's `params:` or a
+/// component's `props:`.
+struct DeclaredState {
+    index: usize,
+    /// `None` when the declared type failed to parse; it was reported once
+    /// already, so bindings to the state aren't checked again.
+    ty: Option<StateType>,
+    kind: Kind,
+}
+
+/// Where a name expressions can read is declared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    State,
+    Computed,
+    Prop,
+    Param,
+}
+
+impl Kind {
+    /// The key of the list the name is declared in.
+    fn section(self) -> &'static str {
+        match self {
+            Kind::State => "state",
+            Kind::Computed => "computed",
+            Kind::Prop => "props",
+            Kind::Param => "params",
+        }
+    }
+}
+
+/// The names expressions on a page can read.
+struct PageScope<'v> {
+    state: &'v HashMap<String, DeclaredState>,
+    items: &'v [(String, StateType)],
+}
+
+impl Scope for PageScope<'_> {
+    fn lookup(&self, name: &str) -> Option<StateType> {
+        if let Some((_, ty)) = self.items.iter().rev().find(|(item, _)| item == name) {
+            return Some(ty.clone());
+        }
+        let state = self.state.get(name)?;
+        Some(state.ty.clone().unwrap_or(StateType::Unknown))
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.state
+            .keys()
+            .map(String::as_str)
+            .chain(self.items.iter().map(|(item, _)| item.as_str()))
+            .collect()
+    }
+}
+
+pub fn validate_document(document: &Document, index: &SourceIndex) -> Vec<Diagnostic> {
+    let actions = Actions::default();
+    let components = Components::default();
+    let mut diagnostics = Vec::new();
+    if document.pages.len() == 1 {
+        diagnostics.extend(validate_page(
+            &document.pages[0].page_data,
+            "page",
+            index,
+            &actions,
+            &components,
+        ));
+    } else {
+        for (i, page) in document.pages.iter().enumerate() {
+            let path = format!("pages[{}].page", i);
+            diagnostics.extend(validate_page(
+                &page.page_data,
+                &path,
+                index,
+                &actions,
+                &components,
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Validates a single page whose `page:` mapping lives at `path` in the
+/// source. Event handlers may call any of `actions`, and `use:` may place
+/// any of `components`.
+pub fn validate_page(
+    page: &PageData,
+    path: &str,
+    index: &SourceIndex,
+    actions: &Actions,
+    components: &Components,
+) -> Vec<Diagnostic> {
+    analyze_page(page, path, index, actions, components).diagnostics
+}
+
+/// Like [`validate_page`], but also returns where the page names its
+/// states, for editors to navigate and rename them.
+pub fn analyze_page(
+    page: &PageData,
+    path: &str,
+    index: &SourceIndex,
+    actions: &Actions,
+    components: &Components,
+) -> PageAnalysis {
+    let mut validator = Validator::new(index, actions, components);
+    validator.check_page(page, path);
+    validator.analysis()
+}
+
+/// Checks the routes of `config`, the `nwl.yaml` indexed by `index`: each
+/// renders exactly one of a page, a layout around nested routes or a
+/// redirect, has a path written the way its place calls for, and is
+/// served at a path no other route is.
+pub fn validate_project(config: &ProjectConfig, index: &SourceIndex) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let entries = routes::flatten(&config.routes);
+    let mut served: Vec<(&str, String)> = Vec::new();
+    for entry in &entries {
+        let route = entry.route;
+        let key = entry.key.as_str();
+        let targets: Vec<&str> = [
+            ("page", route.page.is_some()),
+            ("layout", route.layout.is_some()),
+            ("redirect", route.redirect.is_some()),
+        ]
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(target, _)| *target)
+        .collect();
+        let is_index = route.index == Some(true);
+
+        let mut error = |diagnostic: Diagnostic, path: String, key: bool| {
+            diagnostics.push(if key {
+                index.locate_key(diagnostic, &path)
+            } else {
+                index.locate(diagnostic, &path)
+            });
+        };
+        match targets.as_slice() {
+            [] => error(
+                Diagnostic::error(codes::INVALID_ROUTE, "route has nothing to render")
+                    .with_label("no `page`, `layout` or `redirect`")
+                    .with_help("give it a `page:`, a `layout:` with `children:`, or a `redirect:`"),
+                key.to_string(),
+                false,
+            ),
+            [first, second, ..] => error(
+                Diagnostic::error(
+                    codes::INVALID_ROUTE,
+                    format!("route has both `{}` and `{}`", first, second),
+                )
+                .with_label("a route renders only one of these")
+                .with_help(if *second == "redirect" {
+                    "remove `redirect:`, or move it to a route of its own"
+                } else {
+                    "to show the page inside the layout, move it under `children:` with `index: true`"
+                }),
+                format!("{}.{}", key, second),
+                true,
+            ),
+            _ => {}
+        }
+        if route.layout.is_some() && route.children.is_empty() {
+            error(
+                Diagnostic::error(codes::INVALID_ROUTE, "layout route has no `children`")
+                    .with_label("nothing would show at its `outlet`")
+                    .with_help(
+                        "add the routes it lays out under `children:`, or make it a `page:`",
+                    ),
+                format!("{}.layout", key),
+                true,
+            );
+        } else if !route.children.is_empty() && route.layout.is_none() {
+            error(
+                Diagnostic::error(codes::INVALID_ROUTE, "only layout routes have `children`")
+                    .with_label("nested routes need a page to show in")
+                    .with_help("set `layout:` to a page with an `outlet` element"),
+                format!("{}.children", key),
+                true,
+            );
+        }
+
+        if is_index {
+            if !route.path.is_empty() {
+                error(
+                    Diagnostic::error(codes::INVALID_ROUTE, "index route has a `path`")
+                        .with_label("index routes are served at the path of the route around them")
+                        .with_help("remove `index: true`, or remove the `path`"),
+                    format!("{}.path", key),
+                    true,
+                );
+            }
+            if route.layout.is_some() {
+                error(
+                    Diagnostic::error(codes::INVALID_ROUTE, "index route has a `layout`")
+                        .with_label("index routes can't have nested routes")
+                        .with_help("use a `page:`, and nest it under the layout route instead"),
+                    format!("{}.index", key),
+                    true,
+                );
+            }
+        } else if route.path.is_empty() {
+            // A layout without a path wraps its children without adding to
+            // their paths.
+            if route.layout.is_none() {
+                error(
+                    Diagnostic::error(codes::INVALID_ROUTE, "route has no `path`")
+                        .with_label("not served anywhere")
+                        .with_help("add a `path:`, or `index: true` to serve it at the path of the route around it"),
+                    key.to_string(),
+                    false,
+                );
+            }
+        } else if entry.nested && route.path.starts_with('/') {
+            error(
+                Diagnostic::error(codes::INVALID_ROUTE, "nested route path starts with `/`")
+                    .with_label("paths of nested routes are relative to the route around them")
+                    .with_help(format!("write `{}`", route.path.trim_start_matches('/'))),
+                format!("{}.path", key),
+                false,
+            );
+        } else if !entry.nested && !route.path.starts_with('/') && route.path != "*" {
+            error(
+                Diagnostic::error(codes::INVALID_ROUTE, "route path doesn't start with `/`")
+                    .with_label("paths of top-level routes are absolute")
+                    .with_help(format!("write `/{}`", route.path)),
+                format!("{}.path", key),
+                false,
+            );
+        }
+
+        if route.lazy.is_some() && route.file().is_none() && route.redirect.is_some() {
+            error(
+                Diagnostic::error(codes::INVALID_ROUTE, "redirect route has `lazy`")
+                    .with_label("there is no page to load")
+                    .with_help("remove `lazy`"),
+                format!("{}.lazy", key),
+                true,
+            );
+        }
+
+        if let Some(to) = route
+            .redirect
+            .as_deref()
+            .filter(|to| routes::is_internal(to))
+        {
+            if let Some(diagnostic) = routes::unknown_route(to, &config.routes) {
+                diagnostics.push(index.locate(diagnostic, &format!("{}.redirect", key)));
+            }
+        }
+
+        // Layouts share their path with the routes nested in them.
+        if route.layout.is_some() || targets.is_empty() {
+            continue;
+        }
+        let at = if is_index {
+            format!("{}.index", key)
+        } else {
+            format!("{}.path", key)
+        };
+        if let Some((_, first)) = served.iter().find(|(path, _)| *path == entry.full_path) {
+            let note = match index.value_span(first) {
+                Some(span) => format!("first served on line {}", span.line),
+                None => "first served by another route".to_string(),
+            };
+            let diagnostic = Diagnostic::error(
+                codes::INVALID_ROUTE,
+                format!("more than one route is served at `{}`", entry.full_path),
+            )
+            .with_label("served again here")
+            .with_note(note);
+            diagnostics.push(index.locate(diagnostic, &at));
+        } else {
+            served.push((&entry.full_path, at));
+        }
+    }
+
+    if let Some(fallback) = &config.fallback {
+        match (&fallback.page, &fallback.spinner) {
+            (None, None) => diagnostics.push(
+                index.locate(
+                    Diagnostic::error(codes::INVALID_ROUTE, "fallback has nothing to render")
+                        .with_label("no `page` or `spinner`")
+                        .with_help(
+                            "give it a `page:`, or a `spinner:` such as `spinner: { label: Loading... }`",
+                        ),
+                    "fallback",
+                ),
+            ),
+            (Some(_), Some(_)) => diagnostics.push(
+                index.locate_key(
+                    Diagnostic::error(
+                        codes::INVALID_ROUTE,
+                        "fallback has both `page` and `spinner`",
+                    )
+                    .with_label("a fallback renders only one of these")
+                    .with_help("remove one of them"),
+                    "fallback.spinner",
+                ),
+            ),
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// Checks the page `nwl.yaml` shows while lazy routes load, `page`,
+/// reporting in `index` of `nwl.yaml`. It shows for every lazy route, so
+/// it can't rely on the params of any of them.
+pub fn validate_fallback(page: &PageData, index: &SourceIndex) -> Vec<Diagnostic> {
+    page.params
+        .iter()
+        .filter(|param| param.default.is_none())
+        .map(|param| {
+            let diagnostic = Diagnostic::error(
+                codes::ROUTE_PARAMS,
+                format!(
+                    "fallback page reads param `{}` without a `default`",
+                    param.name
+                ),
+            )
+            .with_label("the fallback shows while any lazy route loads")
+            .with_help(format!(
+                "give `{}` a `default`, or use a page without `params`",
+                param.name
+            ));
+            index.locate(diagnostic, "fallback.page")
+        })
+        .collect()
+}
+
+/// Checks `entry`, a route of `nwl.yaml` indexed by `index`, against
+/// `page`, the page or layout it renders: each `:param` of its own path
+/// must be declared in the page's `params:`, and a layout must have an
+/// `outlet` for its nested routes.
+pub fn validate_route(entry: &RouteEntry, page: &PageData, index: &SourceIndex) -> Vec<Diagnostic> {
+    let route = entry.route;
+    let file = route.file().unwrap_or_default();
+    let path = format!("{}.path", entry.key);
+    let mut diagnostics = Vec::new();
+    let mut seen: Vec<&str> = routes::params(&entry.parent_path)
+        .iter()
+        .map(|param| param.name)
+        .collect();
+    for param in routes::params(&route.path) {
+        let diagnostic = if !routes::is_param_name(param.name) {
+            Diagnostic::error(
+                codes::ROUTE_PARAMS,
+                format!("invalid route parameter `:{}`", param.name),
+            )
+            .with_label("parameters are named with letters, digits, `_` and `-`")
+        } else if seen.contains(&param.name) {
+            Diagnostic::error(
+                codes::ROUTE_PARAMS,
+                format!("route parameter `{}` appears more than once", param.name),
+            )
+            .with_label("appears again here")
+        } else if !page
+            .params
+            .iter()
+            .any(|declared| declared.name == param.name)
+        {
+            Diagnostic::error(
+                codes::ROUTE_PARAMS,
+                format!(
+                    "page `{}` does not declare route parameter `{}`",
+                    page.name, param.name
+                ),
+            )
+            .with_label("not in the page's `params:`")
+            .with_help(format!(
+                "add `- name: {}` to `params:` in `{}`",
+                param.name, file
+            ))
+        } else {
+            seen.push(param.name);
+            continue;
+        };
+        seen.push(param.name);
+        diagnostics.push(index.locate_in_value(diagnostic, &path, param.offset, param.length));
+    }
+
+    let mut outlets = 0;
+    components::walk(&page.children, "", &mut |element, _| {
+        if matches!(element, Element::Outlet(_)) {
+            outlets += 1;
+        }
+    });
+    if route.layout.is_some() && outlets == 0 {
+        diagnostics.push(
+            index.locate(
+                Diagnostic::error(
+                    codes::INVALID_ROUTE,
+                    format!("layout page `{}` has no `outlet`", page.name),
+                )
+                .with_label("its nested routes would never show")
+                .with_help(format!(
+                    "add `- outlet:` where nested pages go in `{}`",
+                    file
+                )),
+                &format!("{}.layout", entry.key),
+            ),
+        );
+    }
+    diagnostics
+}
+
+/// Checks the `params:` of `page`, whose `page:` mapping lives at `path`,
+/// against `routes`, the routes of `nwl.yaml` rendering it: the URL of
+/// each route must set every param that has no `default`.
+pub fn validate_params(
+    page: &PageData,
+    path: &str,
+    routes: &[&RouteEntry],
+    index: &SourceIndex,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (i, param) in page.params.iter().enumerate() {
+        if param.default.is_some() {
+            continue;
+        }
+        for route in routes {
+            if routes::params(&route.full_path)
+                .iter()
+                .any(|segment| segment.name == param.name)
+            {
+                continue;
+            }
+            let diagnostic = Diagnostic::error(
+                codes::ROUTE_PARAMS,
+                format!(
+                    "route `{}` has no parameter `{}`",
+                    route.full_path, param.name
+                ),
+            )
+            .with_label("never set by the URL")
+            .with_help(format!(
+                "add `:{}` to the route's path in `nwl.yaml`, or give the param a `default`",
+                param.name
+            ));
+            diagnostics.push(index.locate(diagnostic, &format!("{}.params[{}].name", path, i)));
+        }
+    }
+    diagnostics
+}
+/// Validates an `actions/*.yaml` file and adds its actions to `actions`,
+/// reporting names already declared by this or an earlier file.
+pub fn validate_actions(
+    file: &ActionFile,
+    index: &SourceIndex,
+    actions: &mut Actions,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (i, definition) in file.actions.iter().enumerate() {
+        let path = format!("action[{}]", i);
+        let mut inputs: Vec<ActionInput> = Vec::new();
+        for (j, source) in definition.inputs.iter().enumerate() {
+            let input_path = format!("{}.inputs[{}]", path, j);
+            let input = match ActionInput::parse(source) {
+                Ok(input) => input,
+                Err(error) => {
+                    let diagnostic = Diagnostic::error(
+                        codes::INVALID_TYPE,
+                        format!(
+                            "invalid input `{}` for action `{}`",
+                            source.trim(),
+                            definition.name
+                        ),
+                    )
+                    .with_label(error.message)
+                    .with_help("write inputs as `name` or `name: type`, e.g. `count: number`");
+                    diagnostics.push(index.locate_in_value(
+                        diagnostic,
+                        &input_path,
+                        error.offset,
+                        error.length,
+                    ));
+                    continue;
+                }
+            };
+            if inputs.iter().any(|other| other.name == input.name) {
+                diagnostics.push(
+                    index.locate(
+                        Diagnostic::error(
+                            codes::DUPLICATE_ACTION,
+                            format!(
+                                "input `{}` of action `{}` is declared more than once",
+                                input.name, definition.name
+                            ),
+                        )
+                        .with_label("declared again here"),
+                        &input_path,
+                    ),
+                );
+                continue;
+            }
+            inputs.push(input);
+        }
+
+        let name_path = format!("{}.name", path);
+        if let Some(first) = actions.get(&definition.name) {
+            let mut diagnostic = Diagnostic::error(
+                codes::DUPLICATE_ACTION,
+                format!("action `{}` is declared more than once", definition.name),
+            )
+            .with_label("declared again here");
+            if let Some(span) = &first.span {
+                diagnostic = diagnostic.with_note(format!("first declared at {}", location(span)));
+            }
+            diagnostics.push(index.locate(diagnostic, &name_path));
+            continue;
+        }
+
+        let mut action = Action::new(definition, inputs);
+        action.span = index
+            .value_span(&name_path)
+            .map(|span| span.with_path(index.path().map(Into::into)));
+        actions.add(action);
+    }
+    diagnostics
+}
+
+/// Validates the component of a `components/*.yaml` file, the way
+/// [`validate_page`] validates a page. Its props are read like state.
+pub fn validate_component(
+    component: &ComponentDefinition,
+    index: &SourceIndex,
+    actions: &Actions,
+    components: &Components,
+) -> Vec<Diagnostic> {
+    analyze_component(component, index, actions, components).diagnostics
+}
+
+/// Like [`validate_component`], but also returns where the component
+/// names its props and states.
+pub fn analyze_component(
+    component: &ComponentDefinition,
+    index: &SourceIndex,
+    actions: &Actions,
+    components: &Components,
+) -> PageAnalysis {
+    let mut validator = Validator::new(index, actions, components);
+    validator.check_component(component, "component");
+    validator.analysis()
+}
+
+/// Adds the component of a `components/*.yaml` file to `components`,
+/// reporting a name already declared by this or an earlier file. Its body
+/// is checked by [`validate_component`] once every component is known.
+pub fn register_component(
+    file: &ComponentFile,
+    index: &SourceIndex,
+    components: &mut Components,
+) -> Vec<Diagnostic> {
+    let name = &file.component.name;
+    let Some(first) = components.get(name) else {
+        components.add(Component::new(&file.component, index.clone()));
+        return Vec::new();
+    };
+    let mut diagnostic = Diagnostic::error(
+        codes::DUPLICATE_COMPONENT,
+        format!("component `{}` is declared more than once", name),
+    )
+    .with_label("declared again here");
+    if let Some(span) = first.span() {
+        diagnostic = diagnostic.with_note(format!("first declared at {}", location(&span)));
+    }
+    vec![index.locate(diagnostic, "component.name")]
+}
+
+/// Reports components that place themselves with `use:`, directly or
+/// through other components, as they would render forever. Each cycle is
+/// reported once, at the first of its components.
+pub fn component_cycles(components: &Components) -> Vec<Diagnostic> {
+    let all: Vec<&Component> = components.iter().collect();
+    let position = |name: &str| all.iter().position(|component| component.name == name);
+    let edges: Vec<Vec<(usize, String)>> = all
+        .iter()
+        .map(|component| {
+            components::uses(&component.definition.children, "component.children")
+                .into_iter()
+                .filter_map(|(name, path)| Some((position(name)?, path)))
+                .collect()
+        })
+        .collect();
+
+    let mut diagnostics = Vec::new();
+    for (start, component) in all.iter().enumerate() {
+        for (next, path) in &edges[start] {
+            // Cycles through an earlier component were reported with it.
+            let Some(mut cycle) = cycle_back(&edges, *next, start, &mut vec![false; all.len()])
+            else {
+                continue;
+            };
+            if cycle.iter().any(|&i| i < start) {
+                continue;
+            }
+            cycle.insert(0, start);
+            let names: Vec<String> = cycle
+                .iter()
+                .map(|&i| format!("`{}`", all[i].name))
+                .collect();
+            let message = if cycle.len() == 2 {
+                format!("component `{}` uses itself", component.name)
+            } else {
+                format!("component `{}` uses itself through a cycle", component.name)
+            };
+            let diagnostic = Diagnostic::error(codes::COMPONENT_CYCLE, message)
+                .with_label(names.join(" → "))
+                .with_help(
+                    "a component can't contain itself; pass the inner part in a slot instead",
+                );
+            diagnostics.push(
+                component
+                    .index
+                    .locate(diagnostic, &format!("{}.component", path)),
+            );
+            break;
+        }
+    }
+    diagnostics
+}
+
+/// A path of components from `from` that uses `to`, ending with `to`.
+fn cycle_back(
+    edges: &[Vec<(usize, String)>],
+    from: usize,
+    to: usize,
+    visited: &mut Vec<bool>,
+) -> Option<Vec<usize>> {
+    if from == to {
+        return Some(vec![to]);
+    }
+    if std::mem::replace(&mut visited[from], true) {
+        return None;
+    }
+    edges[from].iter().find_map(|(next, _)| {
+        let mut path = cycle_back(edges, *next, to, visited)?;
+        path.insert(0, from);
+        Some(path)
+    })
+}
+
+/// `file:line` of a span, or just the line when it has no file.
+fn location(span: &nwl_shared::Span) -> String {
+    match &span.path {
+        Some(path) => format!("{}:{}", path.display(), span.line),
+        None => format!("line {}", span.line),
+    }
+}
+
+impl<'a> Validator<'a> {
+    fn new(index: &'a SourceIndex, actions: &'a Actions, components: &'a Components) -> Self {
+        Validator {
+            index,
+            actions,
+            components,
+            slots: None,
+            taken: Vec::new(),
+            imported: HashMap::new(),
+            state: HashMap::new(),
+            items: Vec::new(),
+            diagnostics: Vec::new(),
+            references: Vec::new(),
+        }
+    }
+
+    fn analysis(self) -> PageAnalysis {
+        PageAnalysis {
+            diagnostics: self.diagnostics,
+            references: self.references,
+        }
+    }
+
+    fn check_page(&mut self, page: &PageData, path: &str) {
+        self.take_names(format!("page `{}`", page.name), &page.name, &page.children);
+        self.check_params(&page.params, path);
+        self.check_state(&page.state, path);
+        self.check_computed(&page.computed, path);
+
+        if let Some(layout) = &page.layout {
+            self.check_layout(layout, &format!("{}.layout", path));
+        }
+        for (i, class) in page.style.iter().enumerate() {
+            self.check_text(class, &format!("{}.style[{}]", path, i));
+        }
+
+        self.check_children(&page.children, &format!("{}.children", path));
+    }
+
+    /// Checks a component like a page, with its props declared first so
+    /// that state and computed values can start from them.
+    fn check_component(&mut self, component: &ComponentDefinition, path: &str) {
+        self.take_names(
+            format!("component `{}`", component.name),
+            &component.name,
+            &component.children,
+        );
+        for (i, prop) in component.props.iter().enumerate() {
+            let prop_path = format!("{}.props[{}]", path, i);
+            let ty = self.check_prop_type(prop, &prop_path);
+            self.declaration(&prop.name, &format!("{}.name", prop_path));
+            self.declare(
+                &prop.name,
+                DeclaredState {
+                    index: i,
+                    ty,
+                    kind: Kind::Prop,
+                },
+                path,
+            );
+        }
+
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for (i, slot) in component.slots.iter().enumerate() {
+            let problem = if slot == "children" {
+                Some("`children` is the slot without a name; use `slot:` with no name instead")
+            } else if seen.insert(slot, i).is_some() {
+                Some("each slot needs a different name")
+            } else if component.props.iter().any(|prop| &prop.name == slot) {
+                Some("slots and props share the generated component's props; rename one")
+            } else {
+                None
+            };
+            if let Some(help) = problem {
+                self.error(
+                    Diagnostic::error(
+                        codes::DUPLICATE_COMPONENT,
+                        format!("slot `{}` clashes with another name", slot),
+                    )
+                    .with_label("declared again here")
+                    .with_help(help),
+                    &format!("{}.slots[{}]", path, i),
+                );
+            }
+        }
+        self.slots = Some(component.slots.clone());
+
+        self.check_state(&component.state, path);
+        self.check_computed(&component.computed, path);
+        self.check_children(&component.children, &format!("{}.children", path));
+    }
+
+    /// Records the names the module generated for a page or component
+    /// binds: its own, `React`, the ones it uses with React Router, and the
+    /// components it places.
+    fn take_names(&mut self, what: String, name: &str, children: &[Element]) {
+        self.taken = vec![
+            (ReactGenerator::to_pascal_case(name), what),
+            ("React".to_string(), "`React`".to_string()),
+        ];
+        for (name, what) in ROUTER_NAMES {
+            self.taken.push((name.to_string(), what.to_string()));
+        }
+        for name in self.components.used_by(children) {
+            self.taken.push((
+                ReactGenerator::to_pascal_case(name),
+                format!("component `{}`", name),
+            ));
+        }
+    }
+
+    /// Declares a page's route parameters. Their values are URL text, so
+    /// they can only have types that text converts to.
+    fn check_params(&mut self, params: &[ParamDefinition], page_path: &str) {
+        for (i, param) in params.iter().enumerate() {
+            let path = format!("{}.params[{}]", page_path, i);
+            let name_path = format!("{}.name", path);
+            if !routes::is_param_name(&param.name) {
+                self.error(
+                    Diagnostic::error(
+                        codes::ROUTE_PARAMS,
+                        format!("invalid parameter name `{}`", param.name),
+                    )
+                    .with_label("route parameters are named with letters, digits, `_` and `-`"),
+                    &name_path,
+                );
+            }
+            let ty = self.check_param_type(param, &path);
+            self.declaration(&param.name, &name_path);
+            self.declare(
+                &param.name,
+                DeclaredState {
+                    index: i,
+                    ty,
+                    kind: Kind::Param,
+                },
+                page_path,
+            );
+        }
+    }
+
+    /// Resolves the type of a route parameter the way
+    /// [`Self::check_prop_type`] does, rejecting types URL text can't be
+    /// converted to.
+    fn check_param_type(&mut self, param: &ParamDefinition, path: &str) -> Option<StateType> {
+        let ty = match types::param_type(param) {
+            Ok(ty) => ty,
+            Err(error) => {
+                self.invalid_type(
+                    format!("invalid type for param `{}`", param.name),
+                    error,
+                    path,
+                );
+                return None;
+            }
+        };
+        if !matches!(
+            ty,
+            StateType::String | StateType::Number | StateType::Boolean | StateType::Union(_)
+        ) {
+            let key = if param.value_type.is_some() {
+                "type"
+            } else {
+                "default"
+            };
+            self.error(
+                Diagnostic::error(
+                    codes::INVALID_TYPE,
+                    format!("invalid type for param `{}`", param.name),
+                )
+                .with_label(format!("{} can't be read from a URL", ty))
+                .with_help("use string, number, boolean or a union of string literals"),
+                &format!("{}.{}", path, key),
+            );
+            return None;
+        }
+
+        if let Some(default) = &param.default {
+            if let Err(message) = ty.check_named(default, "default") {
+                self.error(
+                    Diagnostic::error(
+                        codes::TYPE_MISMATCH,
+                        format!(
+                            "default value of param `{}` does not match its type",
+                            param.name
+                        ),
+                    )
+                    .with_label(message)
+                    .with_note(format!("`{}` is declared as {}", param.name, ty)),
+                    &format!("{}.default", path),
+                );
+            }
+        }
+        Some(ty)
+    }
+
+    fn check_state(&mut self, state: &[StateDefinition], path: &str) {
+        for (i, state) in state.iter().enumerate() {
+            let state_path = format!("{}.state[{}]", path, i);
+            let ty = self.check_state_type(state, &state_path);
+            self.declaration(&state.name, &format!("{}.name", state_path));
+            self.declare(
+                &state.name,
+                DeclaredState {
+                    index: i,
+                    ty,
+                    kind: Kind::State,
+                },
+                path,
+            );
+        }
+    }
+
+    /// Records a state, computed value or prop, reporting names declared
+    /// twice and names the generated code can't declare. Names are
+    /// compared as generated, so `user-name` and `user_name` clash.
+    fn declare(&mut self, name: &str, declared: DeclaredState, page_path: &str) {
+        let generated = ReactGenerator::to_camel_case(name);
+        let declared_path = format!(
+            "{}.{}[{}].name",
+            page_path,
+            declared.kind.section(),
+            declared.index
+        );
+        if RESERVED_WORDS.contains(&generated.as_str()) {
+            self.error(
+                Diagnostic::error(
+                    codes::INVALID_NAME,
+                    format!("`{}` can't be used as a name", name),
+                )
+                .with_label(format!("`{}` is a reserved word in JavaScript", generated))
+                .with_help("choose another name"),
+                &declared_path,
+            );
+            self.state.insert(name.to_string(), declared);
+            return;
+        }
+        if let Some((_, what)) = self.taken.iter().find(|(taken, _)| *taken == generated) {
+            self.error(
+                Diagnostic::error(
+                    codes::DUPLICATE_STATE,
+                    format!("the name `{}` is already taken", generated),
+                )
+                .with_label(format!("also the name of {}", what))
+                .with_help("choose another name"),
+                &declared_path,
+            );
+            self.state.insert(name.to_string(), declared);
+            return;
+        }
+        let Some((first_name, first)) = self
+            .state
+            .iter()
+            .find(|(other, _)| ReactGenerator::to_camel_case(other) == generated)
+        else {
+            self.state.insert(name.to_string(), declared);
+            return;
+        };
+        let note = match self.index.value_span(&format!(
+            "{}.{}[{}].name",
+            page_path,
+            first.kind.section(),
+            first.index
+        )) {
+            Some(span) => format!("first declared on line {}", span.line),
+            None => format!(
+                "first declared as {} #{}",
+                first.kind.section(),
+                first.index + 1
+            ),
+        };
+        let message = if first.kind != Kind::State || declared.kind != Kind::State {
+            format!("`{}` is declared more than once", name)
+        } else {
+            format!("state `{}` is declared more than once", name)
+        };
+        let label = if first_name == name {
+            "declared again here".to_string()
+        } else {
+            format!(
+                "`{}` is also named `{}` in the generated code",
+                first_name, generated
+            )
+        };
+        self.error(
+            Diagnostic::error(codes::DUPLICATE_STATE, message)
+                .with_label(label)
+                .with_note(note),
+            &declared_path,
+        );
+    }
+
+    /// Resolves the type of a state entry, reporting unparsable `type:`
+    /// declarations and `initial` values that don't match them.
+    fn check_state_type(&mut self, state: &StateDefinition, path: &str) -> Option<StateType> {
+        let ty = match types::state_type(state) {
+            Ok(ty) => ty,
+            Err(error) => {
+                self.invalid_type(
+                    format!("invalid type for state `{}`", state.name),
+                    error,
+                    path,
+                );
+                return None;
+            }
+        };
+
+        if let Some(initial) = &state.initial {
+            if let Err(message) = ty.check(initial) {
+                self.error(
+                    Diagnostic::error(
+                        codes::TYPE_MISMATCH,
+                        format!(
+                            "initial value of state `{}` does not match its type",
+                            state.name
+                        ),
+                    )
+                    .with_label(message)
+                    .with_note(format!("`{}` is declared as {}", state.name, ty)),
+                    &format!("{}.initial", path),
+                );
+            }
+        }
+        Some(ty)
+    }
+
+    /// Resolves the type of a prop the way [`Self::check_state_type`] does,
+    /// with `default` in place of `initial`.
+    fn check_prop_type(&mut self, prop: &PropDefinition, path: &str) -> Option<StateType> {
+        let ty = match types::state_type(&Prop::as_state(prop)) {
+            Ok(ty) => ty,
+            Err(error) => {
+                self.invalid_type(
+                    format!("invalid type for prop `{}`", prop.name),
+                    error,
+                    path,
+                );
+                return None;
+            }
+        };
+
+        if let Some(default) = &prop.default {
+            if let Err(message) = ty.check_named(default, "default") {
+                self.error(
+                    Diagnostic::error(
+                        codes::TYPE_MISMATCH,
+                        format!(
+                            "default value of prop `{}` does not match its type",
+                            prop.name
+                        ),
+                    )
+                    .with_label(message)
+                    .with_note(format!("`{}` is declared as {}", prop.name, ty)),
+                    &format!("{}.default", path),
+                );
+            }
+        }
+        Some(ty)
+    }
+
+    fn invalid_type(&mut self, message: String, error: TypeSyntaxError, path: &str) {
+        let diagnostic = Diagnostic::error(codes::INVALID_TYPE, message)
+            .with_label(error.message)
+            .with_help(
+                "use string, number, boolean, `T[]`, `{ field: T }`, `T | null` \
+                 or a union of string literals like `'a' | 'b'`",
+            );
+        self.diagnostics.push(self.index.locate_in_value(
+            diagnostic,
+            &format!("{}.type", path),
+            error.offset,
+            error.length,
+        ));
+    }
+
+    fn check_computed(&mut self, computed: &[ComputedDefinition], page_path: &str) {
+        // Declare every value first: computed values may refer to ones
+        // declared after them.
+        for (i, value) in computed.iter().enumerate() {
+            let path = format!("{}.computed[{}]", page_path, i);
+            let ty = match value.value_type.as_deref().map(StateType::parse) {
+                Some(Ok(ty)) => Some(ty),
+                Some(Err(error)) => {
+                    let message = format!("invalid type for computed value `{}`", value.name);
+                    self.invalid_type(message, error, &path);
+                    None
+                }
+                None => Some(StateType::Unknown),
+            };
+            self.declaration(&value.name, &format!("{}.name", path));
+            self.declare(
+                &value.name,
+                DeclaredState {
+                    index: i,
+                    ty,
+                    kind: Kind::Computed,
+                },
+                page_path,
+            );
+        }
+
+        // Infer the type of each value after the values it reads, but report
+        // problems in declaration order.
+        let (order, cycles) = computed::order(computed);
+        let mut problems = vec![Vec::new(); computed.len()];
+        for i in order {
+            let value = &computed[i];
+            let value_path = format!("{}.computed[{}].value", page_path, i);
+            let subject = format!("computed value `{}`", value.name);
+            let start = self.diagnostics.len();
+            if let Some(ty) = self.check_expression(&value.value, &value_path, &subject) {
+                self.infer_computed(value, i, ty, &value_path);
+            }
+            problems[i] = self.diagnostics.split_off(start);
+        }
+
+        for (i, value) in computed.iter().enumerate() {
+            self.diagnostics.append(&mut problems[i]);
+            let path = format!("{}.computed[{}]", page_path, i);
+            let value_path = format!("{}.value", path);
+            let subject = format!("computed value `{}`", value.name);
+
+            let Some(deps) = &value.deps else { continue };
+            for (j, dep) in deps.iter().enumerate() {
+                let dep_path = format!("{}.deps[{}]", path, j);
+                self.reference(dep, &dep_path, 0);
+                if !self.state.contains_key(dep) {
+                    let diagnostic = Diagnostic::error(
+                        codes::UNDECLARED_STATE,
+                        format!("dependency refers to undeclared state `{}`", dep),
+                    )
+                    .with_label("not declared in `state:` or `computed:`");
+                    self.error(diagnostic, &dep_path);
+                }
+            }
+            let mut missing: Vec<&str> = Vec::new();
+            for (offset, name) in expression::reads(&value.value) {
+                if !self.state.contains_key(name)
+                    || deps.iter().any(|dep| dep == name)
+                    || missing.contains(&name)
+                {
+                    continue;
+                }
+                missing.push(name);
+                let diagnostic = Diagnostic::error(
+                    codes::MISSING_DEPENDENCY,
+                    format!(
+                        "{} reads `{}` but does not list it in `deps`",
+                        subject, name
+                    ),
+                )
+                .with_label("not in `deps`")
+                .with_help(format!("add `{}` to `deps`", name));
+                self.diagnostics.push(self.index.locate_in_value(
+                    diagnostic,
+                    &value_path,
+                    offset,
+                    name.chars().count(),
+                ));
+            }
+        }
+
+        for cycle in cycles {
+            let names: Vec<String> = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|&i| format!("`{}`", computed[i].name))
+                .collect();
+            let first = &computed[cycle[0]];
+            let message = if cycle.len() == 1 {
+                format!("computed value `{}` depends on itself", first.name)
+            } else {
+                format!(
+                    "computed value `{}` depends on itself through a cycle",
+                    first.name
+                )
+            };
+            self.error(
+                Diagnostic::error(codes::COMPUTED_CYCLE, message)
+                    .with_label(names.join(" → "))
+                    .with_help(
+                        "compute the shared part in a separate value that depends on neither",
+                    ),
+                &format!("{}.computed[{}].name", page_path, cycle[0]),
+            );
+        }
+    }
+
+    /// Records the inferred type of computed value `i`, or checks it
+    /// against the declared one.
+    fn infer_computed(
+        &mut self,
+        value: &ComputedDefinition,
+        i: usize,
+        inferred: StateType,
+        path: &str,
+    ) {
+        let Some(state) = self
+            .state
+            .get_mut(&value.name)
+            .filter(|state| state.kind == Kind::Computed && state.index == i)
+        else {
+            return;
+        };
+        let Some(declared) = state.ty.clone() else {
+            return;
+        };
+        if value.value_type.is_none() {
+            state.ty = Some(inferred);
+        } else if !declared.accepts(&inferred) {
+            self.error(
+                Diagnostic::error(codes::TYPE_MISMATCH, "mismatched types")
+                    .with_label(format!("this value is {}", inferred))
+                    .with_note(format!("`{}` is declared as {}", value.name, declared)),
+                path,
+            );
+        }
+    }
+
+    /// Checks the elements of the sequence at `path`, such as
+    /// `page.children` or a list's `empty`.
+    fn check_children(&mut self, children: &[Element], path: &str) {
+        for (i, child) in children.iter().enumerate() {
+            self.check_element(child, &format!("{}[{}]", path, i));
+        }
+    }
+
+    fn check_element(&mut self, element: &Element, path: &str) {
+        if let Element::Conditional(conditional) = element {
+            let key = if self.index.has_key(&format!("{}.show", path)) {
+                "show"
+            } else {
+                "if"
+            };
+            self.check_expression(
+                &conditional.condition,
+                &format!("{}.{}", path, key),
+                "condition",
+            );
+            self.check_element(&conditional.element, path);
+            for (i, child) in conditional.otherwise.iter().enumerate() {
+                self.check_element(child, &format!("{}.else[{}]", path, i));
+            }
+            return;
+        }
+
+        match element {
+            Element::Use(instance) => return self.check_use(instance, path),
+            Element::Slot(slot) => return self.check_slot(slot, path),
+            Element::React(react) => return self.check_react(react, path),
+            Element::Outlet(_) => return self.check_outlet(path),
+            _ => {}
+        }
+
+        if let Ok(value) = serde_yaml::to_value(element) {
+            self.check_interpolations(&value, path);
+        }
+
+        if let Some(bind) = element.bind() {
+            // `modal` also accepts the older `isOpen` spelling.
+            let key = if self.index.has_key(&format!("{}.isOpen", path)) {
+                "isOpen"
+            } else {
+                "bind"
+            };
+            let bind_path = format!("{}.{}", path, key);
+            self.reference(bind, &bind_path, 0);
+            if let Some(kind) = self
+                .state
+                .get(bind)
+                .map(|state| state.kind)
+                .filter(|kind| *kind != Kind::State)
+            {
+                self.read_only(kind, "be bound to", bind, &bind_path, key);
+            } else {
+                self.check_state_reference(bind, &bind_path, key);
+                self.check_binding(element, bind, &bind_path);
+            }
+        }
+
+        for (key, handler) in element.handlers() {
+            self.check_handler(handler, &format!("{}.{}", path, key));
+        }
+
+        for (key, url) in urls(element) {
+            self.check_url(url, &format!("{}.{}", path, key));
+        }
+
+        match element {
+            Element::List(list) => self.check_list(list, path),
+            Element::Layout(layout) => {
+                self.check_layout(&layout.layout, &format!("{}.layout", path))
+            }
+            Element::Select(select) if select.options.is_empty() => {
+                self.empty_options(element, path)
+            }
+            Element::RadioGroup(radio) if radio.options.is_empty() => {
+                self.empty_options(element, path)
+            }
+            Element::Tabs(tabs) => {
+                if tabs.options.is_empty() {
+                    self.empty_options(element, path);
+                }
+                let mut seen = HashMap::new();
+                for (i, tab) in tabs.options.iter().enumerate() {
+                    if seen.insert(tab.value.as_str(), i).is_some() {
+                        self.error(
+                            Diagnostic::error(
+                                codes::DUPLICATE_OPTION,
+                                format!("tab id `{}` is used more than once", tab.value),
+                            )
+                            .with_label("duplicate id"),
+                            &format!("{}.options[{}].id", path, i),
+                        );
+                    }
+                }
+            }
+            Element::Modal(modal) if modal.bind.is_none() => {
+                self.error_at_key(
+                    Diagnostic::error(codes::MODAL_WITHOUT_BIND, "`modal` has no `bind`")
+                        .with_label("this modal can never be opened or closed")
+                        .with_help("bind it to a boolean state, e.g. `bind: isModalOpen`"),
+                    &format!("{}.element", path),
+                );
+            }
+            Element::Form(form) => {
+                if let Some(validation) = &form.validation {
+                    let mut fields: Vec<&String> = validation.keys().collect();
+                    fields.sort();
+                    for field in fields {
+                        let field_path = format!("{}.validation.{}", path, field);
+                        if self.state.contains_key(field.as_str()) {
+                            self.references.push(Reference {
+                                name: field.clone(),
+                                path: field_path,
+                                offset: 0,
+                                key: true,
+                                declaration: false,
+                            });
+                        } else {
+                            self.error_at_key(
+                                Diagnostic::error(
+                                    codes::UNDECLARED_STATE,
+                                    format!("validation refers to undeclared state `{}`", field),
+                                )
+                                .with_label("not declared in `state:`")
+                                .with_help(format!(
+                                    "add `- name: {}` to the page's `state:` list",
+                                    field
+                                )),
+                                &field_path,
+                            );
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        self.check_children(element.children(), &format!("{}.children", path));
+    }
+
+    /// Checks a `use:` against the component it places: its props must be
+    /// declared and typed to match, and its slots declared. What it gives
+    /// the slots is checked even when the component is unknown.
+    fn check_use(&mut self, instance: &UseElement, path: &str) {
+        let components = self.components;
+        let component = components.get(&instance.component);
+        if component.is_none() {
+            let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+            let diagnostic = Diagnostic::error(
+                codes::UNKNOWN_COMPONENT,
+                format!("cannot find component `{}`", instance.component),
+            )
+            .with_label("not declared in `components/`");
+            let diagnostic = match suggest(&instance.component, &names) {
+                Some(candidate) => diagnostic.with_help(format!("did you mean `{}`?", candidate)),
+                None => diagnostic.with_help(format!(
+                    "declare it in a file in `components/`, e.g. `component: {{ name: {} }}`",
+                    instance.component
+                )),
+            };
+            self.error(diagnostic, &format!("{}.component", path));
+        }
+
+        let mut names: Vec<&String> = instance.props.keys().collect();
+        names.sort();
+        for name in names {
+            let prop_path = format!("{}.props.{}", path, name);
+            let value = &instance.props[name];
+            let Some(component) = component else {
+                self.check_prop_value(value, &prop_path);
+                continue;
+            };
+            let Some(prop) = component.prop(name) else {
+                let candidates: Vec<&str> =
+                    component.props.iter().map(|p| p.name.as_str()).collect();
+                let diagnostic = Diagnostic::error(
+                    codes::COMPONENT_PROPS,
+                    format!("component `{}` has no prop `{}`", component.name, name),
+                )
+                .with_label("not declared in `props:`");
+                let diagnostic = match suggest(name, &candidates) {
+                    Some(candidate) => {
+                        diagnostic.with_help(format!("did you mean `{}`?", candidate))
+                    }
+                    None => diagnostic,
+                };
+                self.error_at_key(diagnostic, &prop_path);
+                self.check_prop_value(value, &prop_path);
+                continue;
+            };
+            let mismatch = match self.check_prop_value(value, &prop_path) {
+                Some(found) if !prop.ty.accepts(&found) => Some(format!("this value is {}", found)),
+                Some(_) => None,
+                None => prop.ty.check_named(value, name).err(),
+            };
+            if let Some(label) = mismatch {
+                self.error(
+                    Diagnostic::error(codes::TYPE_MISMATCH, "mismatched types")
+                        .with_label(label)
+                        .with_note(format!(
+                            "`{}` is declared as {} in component `{}`",
+                            name, prop.ty, component.name
+                        )),
+                    &prop_path,
+                );
+            }
+        }
+        if let Some(component) = component {
+            let missing: Vec<&str> = component
+                .props
+                .iter()
+                .filter(|prop| prop.is_required() && !instance.props.contains_key(&prop.name))
+                .map(|prop| prop.name.as_str())
+                .collect();
+            if !missing.is_empty() {
+                let list: Vec<String> = missing.iter().map(|name| format!("`{}`", name)).collect();
+                let (noun, label) = if missing.len() == 1 {
+                    ("prop", "it has no default value")
+                } else {
+                    ("props", "they have no default value")
+                };
+                self.error_at_key(
+                    Diagnostic::error(
+                        codes::COMPONENT_PROPS,
+                        format!(
+                            "component `{}` is missing {} {}",
+                            component.name,
+                            noun,
+                            list.join(", ")
+                        ),
+                    )
+                    .with_label(label)
+                    .with_help(format!("set {} under `props:`", list.join(" and "))),
+                    &format!("{}.element", path),
+                );
+            }
+        }
+
+        let mut slots: Vec<(&String, &Vec<Element>)> = instance.slots.iter().collect();
+        slots.sort_by_key(|(name, _)| *name);
+        for (name, children) in slots {
+            let slot_path = format!("{}.slots.{}", path, name);
+            if let Some(component) = component.filter(|c| !c.slots().contains(name)) {
+                let candidates: Vec<&str> = component.slots().iter().map(String::as_str).collect();
+                let diagnostic = Diagnostic::error(
+                    codes::UNKNOWN_SLOT,
+                    format!("component `{}` has no slot `{}`", component.name, name),
+                )
+                .with_label("not declared in `slots:`");
+                let diagnostic = match suggest(name, &candidates) {
+                    Some(candidate) => {
+                        diagnostic.with_help(format!("did you mean `{}`?", candidate))
+                    }
+                    None => diagnostic,
+                };
+                self.error_at_key(diagnostic, &slot_path);
+            }
+            self.check_children(children, &slot_path);
+        }
+
+        let children_path = format!("{}.children", path);
+        if let Some(component) = component {
+            if !instance.children.is_empty() && !component.has_children() {
+                self.error_at_key(
+                    Diagnostic::error(
+                        codes::UNKNOWN_SLOT,
+                        format!("component `{}` does not render children", component.name),
+                    )
+                    .with_label("these elements are never shown")
+                    .with_help("add a `slot` without a name where the component shows them"),
+                    &children_path,
+                );
+            }
+        }
+        self.check_children(&instance.children, &children_path);
+    }
+
+    /// Checks a value `use:` gives a prop, returning its type when it is a
+    /// string: a single `{expression}` has the expression's type, and
+    /// other text is a string. Other values are literals.
+    fn check_prop_value(&mut self, value: &Value, path: &str) -> Option<StateType> {
+        let Value::String(text) = value else {
+            return None;
+        };
+        match parse_text(text).as_deref() {
+            Ok([Segment::Expression(expr)]) => {
+                let (ty, problems) = expression::check(expr, text, &self.scope(), "prop");
+                self.report(problems, path);
+                self.expression_references(expr, path);
+                Some(ty)
+            }
+            Ok(segments) if segments.iter().all(|s| matches!(s, Segment::Text(_))) => None,
+            _ => {
+                self.check_text(text, path);
+                Some(StateType::String)
+            }
+        }
+    }
+
+    /// Checks a `react` element: its component must be imported under a
+    /// name JSX renders as a component that nothing else in the module
+    /// uses, and its props must have names JSX accepts. Its event handlers
+    /// may read `value`, the argument the component calls them with.
+    fn check_react(&mut self, react: &ReactElement, path: &str) {
+        let export = react.export.as_deref();
+        if let Some(export) = export.filter(|export| !types::is_identifier(export)) {
+            self.error(
+                Diagnostic::error(
+                    codes::INVALID_IMPORT,
+                    format!("invalid export name `{}`", export),
+                )
+                .with_label("not a JavaScript identifier"),
+                &format!("{}.export", path),
+            );
+        }
+
+        let name = modules::local_name(react);
+        let key = match (&react.alias, export) {
+            (Some(_), _) => "as",
+            (None, Some(_)) => "export",
+            (None, None) => "import",
+        };
+        let name_path = format!("{}.{}", path, key);
+        let problem = if !types::is_identifier(&name) {
+            // An invalid export was reported above.
+            (key != "export").then_some("not a JavaScript identifier".to_string())
+        } else if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            Some("JSX renders lowercase names as HTML tags".to_string())
+        } else {
+            None
+        };
+        if let Some(label) = problem {
+            self.error(
+                Diagnostic::error(
+                    codes::INVALID_IMPORT,
+                    format!("`{}` can't be rendered as a component", name),
+                )
+                .with_label(label)
+                .with_help("import it under another name with `as:`, e.g. `as: Chart`"),
+                &name_path,
+            );
+        } else if let Some((_, what)) = self.taken.iter().find(|(taken, _)| *taken == name) {
+            let diagnostic = Diagnostic::error(
+                codes::INVALID_IMPORT,
+                format!("the name `{}` is already taken", name),
+            )
+            .with_label(format!("also the name of {}", what))
+            .with_help("import it under another name with `as:`");
+            self.error(diagnostic, &name_path);
+        } else if let Some((module, export)) = self.imported.get(&name) {
+            if *module != react.import || export.as_deref() != react.export.as_deref() {
+                let label = match export {
+                    Some(export) => format!("also `{}` from `{}`", export, module),
+                    None => format!("also the default export of `{}`", module),
+                };
+                let diagnostic = Diagnostic::error(
+                    codes::INVALID_IMPORT,
+                    format!("the name `{}` is already taken", name),
+                )
+                .with_label(label)
+                .with_help("import one of them under another name with `as:`");
+                self.error(diagnostic, &name_path);
+            }
+        } else {
+            self.imported
+                .insert(name, (react.import.clone(), react.export.clone()));
+        }
+
+        let mut props: Vec<(&String, &Value)> = react.props.iter().collect();
+        props.sort_by_key(|(name, _)| *name);
+        for (name, value) in props {
+            let prop_path = format!("{}.props.{}", path, name);
+            self.check_prop_name(name, &prop_path);
+            self.check_prop_value(value, &prop_path);
+        }
+
+        let mut events: Vec<(&String, &Handler)> = react.events.iter().collect();
+        events.sort_by_key(|(name, _)| *name);
+        self.items.push(("value".to_string(), StateType::Unknown));
+        for (name, handler) in events {
+            let event_path = format!("{}.events.{}", path, name);
+            if react.props.contains_key(name) {
+                self.error_at_key(
+                    Diagnostic::error(
+                        codes::COMPONENT_PROPS,
+                        format!("prop `{}` is set more than once", name),
+                    )
+                    .with_label("also set under `props:`")
+                    .with_help("keep the handler or the value, not both"),
+                    &event_path,
+                );
+            } else {
+                self.check_prop_name(name, &event_path);
+            }
+            self.check_handler(handler, &event_path);
+        }
+        self.items.pop();
+
+        self.check_children(&react.children, &format!("{}.children", path));
+    }
+
+    /// Reports a prop name JSX can't write as an attribute.
+    fn check_prop_name(&mut self, name: &str, path: &str) {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-'));
+        if !valid {
+            self.error_at_key(
+                Diagnostic::error(
+                    codes::COMPONENT_PROPS,
+                    format!("invalid prop name `{}`", name),
+                )
+                .with_label("not a JSX attribute name")
+                .with_help("prop names are identifiers such as `onSelect` or `aria-label`"),
+                path,
+            );
+        }
+    }
+
+    /// Checks that a `slot` is inside a component that declares it.
+    fn check_slot(&mut self, slot: &SlotElement, path: &str) {
+        let Some(slots) = &self.slots else {
+            self.error_at_key(
+                Diagnostic::error(codes::UNKNOWN_SLOT, "`slot` is only allowed in a component")
+                    .with_label("pages have nothing to fill it with")
+                    .with_help("move these elements into a file in `components/`"),
+                &format!("{}.element", path),
+            );
+            return;
+        };
+        let Some(name) = &slot.name else { return };
+        if slots.contains(name) {
+            return;
+        }
+        let candidates: Vec<&str> = slots.iter().map(String::as_str).collect();
+        let help = match suggest(name, &candidates) {
+            Some(candidate) => format!("did you mean `{}`?", candidate),
+            None => format!("add `{}` to the component's `slots:`", name),
+        };
+        self.error(
+            Diagnostic::error(
+                codes::UNKNOWN_SLOT,
+                format!("slot `{}` is not declared", name),
+            )
+            .with_label("not declared in `slots:`")
+            .with_help(help),
+            &format!("{}.name", path),
+        );
+    }
+
+    /// Outlets render nested routes, which only layout pages have.
+    fn check_outlet(&mut self, path: &str) {
+        if self.slots.is_some() {
+            self.error_at_key(
+                Diagnostic::error(codes::INVALID_ROUTE, "`outlet` is only allowed in a page")
+                    .with_label("nested routes render in the layout page around them")
+                    .with_help("put the `outlet` in the layout page, next to this component"),
+                &format!("{}.element", path),
+            );
+        }
+    }
+
+    fn check_list(&mut self, list: &ListElement, path: &str) {
+        let Some(data) = &list.data else {
+            let template = [
+                ("children", !list.children.is_empty()),
+                ("empty", !list.empty.is_empty()),
+                ("as", list.alias.is_some()),
+                ("key", list.key.is_some()),
+            ];
+            for (field, _) in template.iter().filter(|(_, present)| *present) {
+                self.error_at_key(
+                    Diagnostic::error(codes::INVALID_LIST, format!("`{}` needs `data`", field))
+                        .with_label("only used when rendering a state array")
+                        .with_help("add `data: <state>` naming an array state"),
+                    &format!("{}.{}", path, field),
+                );
+            }
+            return;
+        };
+
+        if !list.items.is_empty() {
+            self.error_at_key(
+                Diagnostic::error(codes::INVALID_LIST, "`list` has both `items` and `data`")
+                    .with_label("static items can't be mixed with `data`")
+                    .with_help("move the static items into the state's `initial` value"),
+                &format!("{}.items", path),
+            );
+        }
+
+        let data_path = format!("{}.data", path);
+        // `project.tags` reads a field of a state or of an enclosing item.
+        let ty = if data.contains('.') {
+            self.check_expression(data, &data_path, "data")
+        } else {
+            self.reference(data, &data_path, 0);
+            self.check_state_reference(data, &data_path, "data");
+            self.state.get(data).and_then(|state| state.ty.clone())
+        };
+        let item = match ty.as_ref().map(|ty| (ty, ty.item())) {
+            Some((_, Some(item))) => item,
+            Some((ty, None)) => {
+                self.error(
+                    Diagnostic::error(
+                        codes::LIST_DATA_NOT_ARRAY,
+                        format!("`data` refers to `{}`, which is not an array", data),
+                    )
+                    .with_label(format!("`{}` is {}", data, ty))
+                    .with_help("`data` needs an array, such as a state declared as `T[]`"),
+                    &data_path,
+                );
+                StateType::Unknown
+            }
+            None => StateType::Unknown,
+        };
+
+        let name = list.item_name();
+        if let Some(key) = &list.key {
+            if item.field(key).is_none() {
+                self.diagnostics.push(self.index.locate_in_value(
+                    expression::unknown_field(&name, &item, key),
+                    &format!("{}.key", path),
+                    0,
+                    key.chars().count(),
+                ));
+            }
+        }
+
+        self.check_children(&list.empty, &format!("{}.empty", path));
+        self.items.push((name, item));
+        self.check_children(&list.children, &format!("{}.children", path));
+        self.items.pop();
+    }
+
+    /// Parses and checks the expression in the scalar at `path`, returning
+    /// its type, or `None` when it doesn't parse. `subject` names the
+    /// expression in messages.
+    fn check_expression(&mut self, source: &str, path: &str, subject: &str) -> Option<StateType> {
+        let expr = match parse(source) {
+            Ok(expr) => expr,
+            Err(error) => {
+                self.syntax_error(subject, error, path);
+                return None;
+            }
+        };
+        let (ty, problems) = expression::check(&expr, source, &self.scope(), subject);
+        self.report(problems, path);
+        self.expression_references(&expr, path);
+        Some(ty)
+    }
+
+    fn scope(&self) -> PageScope<'_> {
+        PageScope {
+            state: &self.state,
+            items: &self.items,
+        }
+    }
+
+    fn syntax_error(&mut self, subject: &str, error: SyntaxError, path: &str) {
+        let diagnostic =
+            Diagnostic::error(codes::INVALID_EXPRESSION, format!("invalid {}", subject))
+                .with_label(error.message);
+        self.diagnostics.push(self.index.locate_in_value(
+            diagnostic,
+            path,
+            error.offset,
+            error.length,
+        ));
+    }
+
+    /// Reports problems found in the expression in the scalar at `path`.
+    fn report(&mut self, problems: Vec<Problem>, path: &str) {
+        for problem in problems {
+            self.diagnostics.push(self.index.locate_in_value(
+                problem.diagnostic,
+                path,
+                problem.offset,
+                problem.length,
+            ));
+        }
+    }
+
+    /// Checks an event handler. Steps must target declared states of a
+    /// fitting type and call actions with their declared inputs; script
+    /// handlers may only call actions and state setters.
+    fn check_handler(&mut self, handler: &Handler, path: &str) {
+        let steps = match handler {
+            Handler::Script(script) => return self.check_script(script, path),
+            Handler::Steps(steps) => steps,
+        };
+        for (i, step) in steps.iter().enumerate() {
+            // A single step may be written without the surrounding list.
+            let step_path =
+                if steps.len() == 1 && self.index.has_key(&format!("{}.{}", path, step.kind())) {
+                    path.to_string()
+                } else {
+                    format!("{}[{}]", path, i)
+                };
+            self.check_step(step, &step_path);
+        }
+    }
+
+    fn check_step(&mut self, step: &Step, path: &str) {
+        match step {
+            Step::Navigate { navigate } => self.check_url(navigate, &format!("{}.navigate", path)),
+            Step::Set { set, to } => {
+                let ty = self.check_assignment(set, path, "set");
+                let to_path = format!("{}.to", path);
+                // An empty `to:` stores the empty string.
+                let (value, found) = if to.trim().is_empty() {
+                    ("\"\"", Some(StateType::Union(vec![String::new()])))
+                } else {
+                    (to.trim(), self.check_expression(to, &to_path, "`to`"))
+                };
+                if let (Some(ty), Some(found)) = (ty, found) {
+                    let note = format!("`{}` is {}", set, ty);
+                    self.check_value(value, &found, &ty, &to_path, &note);
+                }
+            }
+            Step::Toggle { toggle } => {
+                let ty = self.check_assignment(toggle, path, "toggle");
+                if let Some(ty) = ty.filter(|ty| !StateType::Boolean.accepts(ty)) {
+                    self.error(
+                        Diagnostic::error(
+                            codes::TYPE_MISMATCH,
+                            format!("`toggle` needs a boolean state, but `{}` is {}", toggle, ty),
+                        )
+                        .with_label(format!("`{}` is {}", toggle, ty))
+                        .with_help(format!("use `set: {}` with `to:` instead", toggle)),
+                        &format!("{}.toggle", path),
+                    );
+                }
+            }
+            Step::Append { append, value } => {
+                let ty = self.check_assignment(append, path, "append");
+                let value_path = format!("{}.value", path);
+                let found = self.check_expression(value, &value_path, "`value`");
+                match ty.as_ref().map(|ty| (ty, ty.item())) {
+                    Some((_, Some(item))) => {
+                        if let Some(found) = found {
+                            let note = format!("items of `{}` are {}", append, item);
+                            self.check_value(value.trim(), &found, &item, &value_path, &note);
+                        }
+                    }
+                    Some((ty, None)) => self.error(
+                        Diagnostic::error(
+                            codes::TYPE_MISMATCH,
+                            format!("`append` needs an array state, but `{}` is {}", append, ty),
+                        )
+                        .with_label(format!("`{}` is {}", append, ty)),
+                        &format!("{}.append", path),
+                    ),
+                    None => {}
+                }
+            }
+            Step::Call { call, with } => {
+                let call_path = format!("{}.call", path);
+                let mut arguments = Vec::new();
+                for (i, argument) in with.iter().enumerate() {
+                    let argument_path = format!("{}.with[{}]", path, i);
+                    let ty = self.check_expression(argument, &argument_path, "argument");
+                    arguments.push((argument_path, 0, argument.trim(), ty));
+                }
+                let Some(action) = self.actions.get(call) else {
+                    self.unknown_action(call, &call_path, 0, &[]);
+                    return;
+                };
+                self.check_call(action, (&call_path, 0), &arguments);
+            }
+        }
+    }
+
+    /// Checks that a step may assign `name`, returning its type when known.
+    fn check_assignment(&mut self, name: &str, path: &str, key: &str) -> Option<StateType> {
+        let target = format!("{}.{}", path, key);
+        self.reference(name, &target, 0);
+        match self.state.get(name) {
+            Some(state) if state.kind != Kind::State => {
+                let kind = state.kind;
+                self.read_only(kind, "change", name, &target, key);
+                None
+            }
+            Some(state) => state.ty.clone(),
+            None => {
+                self.check_state_reference(name, &target, key);
+                None
+            }
+        }
+    }
+
+    /// Reports `key` trying to `verb` a computed value or prop.
+    fn read_only(&mut self, kind: Kind, verb: &str, name: &str, path: &str, key: &str) {
+        let diagnostic = if kind == Kind::Prop {
+            Diagnostic::error(
+                codes::BIND_TO_COMPUTED,
+                format!("`{}` cannot {} prop `{}`", key, verb, name),
+            )
+            .with_label("props are read-only")
+            .with_help("props are set by `use:`; keep values that change in `state:`")
+        } else if kind == Kind::Param {
+            Diagnostic::error(
+                codes::BIND_TO_COMPUTED,
+                format!("`{}` cannot {} param `{}`", key, verb, name),
+            )
+            .with_label("params are read from the URL")
+            .with_help("navigate to another URL to change it, or keep a copy in `state:`")
+        } else {
+            let help = if verb == "change" {
+                "change one of the states it is computed from"
+            } else {
+                "bind to one of the states it is computed from"
+            };
+            Diagnostic::error(
+                codes::BIND_TO_COMPUTED,
+                format!("`{}` cannot {} computed value `{}`", key, verb, name),
+            )
+            .with_label("computed values are read-only")
+            .with_help(help)
+        };
+        self.error(diagnostic, path);
+    }
+
+    /// Checks that a value of type `found` a step stores fits `expected`.
+    fn check_value(
+        &mut self,
+        value: &str,
+        found: &StateType,
+        expected: &StateType,
+        path: &str,
+        note: &str,
+    ) {
+        if expected.accepts(found) {
+            return;
+        }
+        self.error(
+            Diagnostic::error(codes::TYPE_MISMATCH, "mismatched types")
+                .with_label(mismatch_label(value, found, expected))
+                .with_note(note.to_string()),
+            path,
+        );
+    }
+
+    /// Checks the functions a script handler calls: project actions must get
+    /// one argument per declared input, and anything else must be the setter
+    /// of a declared state.
+    fn check_script(&mut self, script: &str, path: &str) {
+        let setters = self.setters();
+        for call in calls(script) {
+            if let Some(action) = self.actions.get(call.name) {
+                let arguments: Vec<_> = call
+                    .arguments
+                    .iter()
+                    .map(|&(offset, argument)| {
+                        let ty = self.argument_type(argument);
+                        (path.to_string(), offset, argument, ty)
+                    })
+                    .collect();
+                self.check_call(action, (path, call.offset), &arguments);
+                continue;
+            }
+            let known = setters.iter().any(|setter| setter == call.name)
+                || self.state.contains_key(call.name)
+                || self.items.iter().any(|(item, _)| item == call.name);
+            if !known {
+                self.unknown_action(call.name, path, call.offset, &setters);
+            }
+        }
+    }
+
+    /// Setter names of the page's states, as generated by `useState`.
+    fn setters(&self) -> Vec<String> {
+        self.state
+            .iter()
+            .filter(|(_, state)| state.kind == Kind::State)
+            .map(|(name, _)| format!("set{}", ReactGenerator::to_pascal_case(name)))
+            .collect()
+    }
+
+    fn unknown_action(&mut self, name: &str, path: &str, offset: usize, setters: &[String]) {
+        let mut diagnostic = Diagnostic::error(
+            codes::UNKNOWN_ACTION,
+            format!("cannot find action `{}`", name),
+        )
+        .with_label("not declared in `actions/`");
+        let candidates: Vec<&str> = self
+            .actions
+            .iter()
+            .map(|action| action.name.as_str())
+            .chain(setters.iter().map(String::as_str))
+            .collect();
+        diagnostic = match suggest(name, &candidates) {
+            Some(candidate) => diagnostic.with_help(format!("did you mean `{}`?", candidate)),
+            None => diagnostic.with_help(format!(
+                "declare it under `action:` in a file in `actions/`, e.g. `- name: {}`",
+                name
+            )),
+        };
+        self.diagnostics.push(self.index.locate_in_value(
+            diagnostic,
+            path,
+            offset,
+            name.chars().count(),
+        ));
+    }
+
+    /// Checks the arguments of a call to `action`. The name and every
+    /// argument are given as the path of the scalar they are written in and
+    /// their character offset inside it; arguments also carry their type
+    /// when it is known.
+    fn check_call(
+        &mut self,
+        action: &Action,
+        (path, offset): (&str, usize),
+        arguments: &[(String, usize, &str, Option<StateType>)],
+    ) {
+        if arguments.len() != action.inputs.len() {
+            let plural = |n: usize, word: &str| {
+                if n == 1 {
+                    format!("1 {}", word)
+                } else {
+                    format!("{} {}s", n, word)
+                }
+            };
+            let mut diagnostic = Diagnostic::error(
+                codes::ACTION_ARGUMENTS,
+                format!(
+                    "action `{}` takes {} but {} {} supplied",
+                    action.name,
+                    plural(action.inputs.len(), "input"),
+                    plural(arguments.len(), "argument"),
+                    if arguments.len() == 1 { "was" } else { "were" }
+                ),
+            )
+            .with_label(format!("expected `{}`", action.signature()));
+            if let Some(span) = &action.span {
+                diagnostic = diagnostic.with_note(format!(
+                    "`{}` is declared at {}",
+                    action.name,
+                    location(span)
+                ));
+            }
+            self.diagnostics.push(self.index.locate_in_value(
+                diagnostic,
+                path,
+                offset,
+                action.name.chars().count(),
+            ));
+            return;
+        }
+
+        for (input, (path, offset, argument, found)) in action.inputs.iter().zip(arguments) {
+            let Some(found) = found else {
+                continue;
+            };
+            if input.ty.accepts(found) {
+                continue;
+            }
+            let label = mismatch_label(argument, found, &input.ty);
+            self.diagnostics.push(
+                self.index.locate_in_value(
+                    Diagnostic::error(
+                        codes::TYPE_MISMATCH,
+                        format!(
+                            "action `{}` expects `{}` to be {}",
+                            action.name, input.name, input.ty
+                        ),
+                    )
+                    .with_label(label),
+                    path,
+                    *offset,
+                    argument.chars().count(),
+                ),
+            );
+        }
+    }
+
+    /// The type of an argument in a script handler that is a state, a list
+    /// item or a literal. Other JavaScript expressions aren't typed.
+    fn argument_type(&self, argument: &str) -> Option<StateType> {
+        if let Some(state) = self.state.get(argument) {
+            return state.ty.clone();
+        }
+        if let Some((_, ty)) = self.items.iter().rev().find(|(item, _)| item == argument) {
+            return Some(ty.clone());
+        }
+        let ty = match argument {
+            "true" | "false" => StateType::Boolean,
+            "null" => StateType::Nullable(Box::new(StateType::Unknown)),
+            _ => {
+                let quote = argument.chars().next()?;
+                let inner = argument.get(1..argument.len().saturating_sub(1));
+                match inner {
+                    Some(inner)
+                        if matches!(quote, '"' | '\'')
+                            && argument.len() >= 2
+                            && argument.ends_with(quote)
+                            && !inner.contains(quote) =>
+                    {
+                        StateType::Union(vec![inner.to_string()])
+                    }
+                    _ if argument.parse::<f64>().is_ok() => StateType::Number,
+                    _ => return None,
+                }
+            }
+        };
+        Some(ty)
+    }
+
+    /// Checks the expressions interpolated in the text properties of an
+    /// element.
+    fn check_interpolations(&mut self, value: &Value, path: &str) {
+        match value {
+            Value::Mapping(mapping) => {
+                for (key, value) in mapping {
+                    let Some(key) = key.as_str() else { continue };
+                    if key == "children" || key == "empty" {
+                        continue;
+                    }
+                    let path = format!("{}.{}", path, key);
+                    match value {
+                        Value::String(text) if INTERPOLATED.contains(&key) => {
+                            self.check_text(text, &path)
+                        }
+                        Value::Sequence(classes) if key == "style" => {
+                            for (i, class) in classes.iter().enumerate() {
+                                if let Some(class) = class.as_str() {
+                                    self.check_text(class, &format!("{}[{}]", path, i));
+                                }
+                            }
+                        }
+                        _ => self.check_interpolations(value, &path),
+                    }
+                }
+            }
+            Value::Sequence(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    self.check_interpolations(value, &format!("{}[{}]", path, i));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_text(&mut self, text: &str, path: &str) {
+        let segments = match parse_text(text) {
+            Ok(segments) => segments,
+            Err(error) => return self.syntax_error("interpolation", error, path),
+        };
+        for segment in &segments {
+            if let Segment::Expression(expr) = segment {
+                let (_, problems) = expression::check_interpolation(expr, text, &self.scope());
+                self.report(problems, path);
+                self.expression_references(expr, path);
+            }
+        }
+    }
+
+    /// Rejects links and images whose URL would run script.
+    fn check_url(&mut self, url: &str, path: &str) {
+        if escape::is_script_url(url) {
+            self.error(
+                Diagnostic::error(codes::SCRIPT_URL, "`javascript:` URLs are not allowed")
+                    .with_label("this URL would run script when followed")
+                    .with_help("run code from an event handler such as `onClick` instead"),
+                path,
+            );
+        }
+    }
+
+    fn check_layout(&mut self, layout: &Layout, path: &str) {
+        if layout.layout_type == LayoutType::Grid && layout.columns.is_none() {
+            self.error(
+                Diagnostic::error(
+                    codes::GRID_WITHOUT_COLUMNS,
+                    "grid layout is missing `columns`",
+                )
+                .with_label("grid declared here")
+                .with_help("add `columns: N` to the layout"),
+                &format!("{}.type", path),
+            );
+        }
+    }
+
+    /// Records that the scalar at `path` names `name` at `offset`, when it
+    /// is a declared state or computed value not hidden by a list item.
+    fn reference(&mut self, name: &str, path: &str, offset: usize) {
+        if !self.state.contains_key(name) || self.items.iter().any(|(item, _)| item == name) {
+            return;
+        }
+        self.references.push(Reference {
+            name: name.to_string(),
+            path: path.to_string(),
+            offset,
+            key: false,
+            declaration: false,
+        });
+    }
+
+    fn declaration(&mut self, name: &str, path: &str) {
+        self.references.push(Reference {
+            name: name.to_string(),
+            path: path.to_string(),
+            offset: 0,
+            key: false,
+            declaration: true,
+        });
+    }
+
+    fn expression_references(&mut self, expr: &expression::Expr, path: &str) {
+        for (offset, name) in expr.references() {
+            self.reference(name, path, offset);
+        }
+    }
+
+    fn check_state_reference(&mut self, name: &str, path: &str, key: &str) {
+        if !self.state.contains_key(name) {
+            self.error(
+                Diagnostic::error(
+                    codes::UNDECLARED_STATE,
+                    format!("`{}` refers to undeclared state `{}`", key, name),
+                )
+                .with_label("not declared in `state:`")
+                .with_help(format!(
+                    "add `- name: {}` to the page's `state:` list",
+                    name
+                )),
+                path,
+            );
+        }
+    }
+
+    /// Checks that the type of a bound state can hold the element's value.
+    fn check_binding(&mut self, element: &Element, name: &str, path: &str) {
+        let Some(control) = Control::of(element) else {
+            return;
+        };
+        let Some(ty) = self.state.get(name).and_then(|state| state.ty.as_ref()) else {
+            return;
+        };
+        let (label, help) = match control.conversion(ty) {
+            Ok(_) => return,
+            Err(BindError::Incompatible) => (
+                format!("`{}` is {}", name, ty),
+                format!("`{}` needs {}", element.tag(), control.expected()),
+            ),
+            Err(BindError::UnknownOption(option)) => (
+                format!("option `{}` is not {}", option, ty),
+                format!("add '{}' to the type of `{}`", option, name),
+            ),
+            Err(BindError::NonNumericOption(option)) => (
+                format!("option `{}` is not a number", option),
+                format!("declare `{}` as a `string` or a union of the options", name),
+            ),
+        };
+        self.error(
+            Diagnostic::error(
+                codes::BIND_TYPE_MISMATCH,
+                format!("`{}` cannot be bound to state `{}`", element.tag(), name),
+            )
+            .with_label(label)
+            .with_help(help),
+            path,
+        );
+    }
+
+    fn empty_options(&mut self, element: &Element, path: &str) {
+        self.error_at_key(
+            Diagnostic::error(
+                codes::EMPTY_OPTIONS,
+                format!("`{}` has no `options`", element.tag()),
+            )
+            .with_label("nothing to choose from")
+            .with_help("add at least one entry under `options:`"),
+            &format!("{}.element", path),
+        );
+    }
+
+    fn error(&mut self, diagnostic: Diagnostic, path: &str) {
+        self.diagnostics.push(self.index.locate(diagnostic, path));
+    }
+
+    fn error_at_key(&mut self, diagnostic: Diagnostic, path: &str) {
+        self.diagnostics
+            .push(self.index.locate_key(diagnostic, path));
+    }
+}
+
+/// Labels a value of type `found` where `expected` is needed: literals are
+/// described by what they are not, anything else by its type.
+/// The URLs an element links to or loads, keyed by their path relative to
+/// the element.
+fn urls(element: &Element) -> Vec<(String, &str)> {
+    let mut urls = Vec::new();
+    match element {
+        Element::Image(image) => urls.extend(image.src.iter().map(|src| ("src".to_string(), src))),
+        Element::Avatar(avatar) => {
+            urls.extend(avatar.src.iter().map(|src| ("src".to_string(), src)))
+        }
+        Element::Url(url) => urls.extend(url.href.iter().map(|href| ("href".to_string(), href))),
+        Element::Nav(nav) => {
+            for (i, link) in nav.links.iter().enumerate() {
+                urls.extend(
+                    link.href
+                        .iter()
+                        .map(|href| (format!("links[{}].href", i), href)),
+                );
+            }
+        }
+        Element::Menu(menu) => {
+            for (i, link) in menu.items.iter().enumerate() {
+                urls.extend(
+                    link.href
+                        .iter()
+                        .map(|href| (format!("items[{}].href", i), href)),
+                );
+            }
+        }
+        Element::Breadcrumb(breadcrumb) => {
+            for (i, item) in breadcrumb.items.iter().enumerate() {
+                urls.extend(
+                    item.href
+                        .iter()
+                        .map(|href| (format!("items[{}].href", i), href)),
+                );
+            }
+        }
+        _ => {}
+    }
+    urls.into_iter()
+        .map(|(key, url)| (key, url.as_str()))
+        .collect()
+}
+
+fn mismatch_label(text: &str, found: &StateType, expected: &StateType) -> String {
+    if parse(text).is_ok_and(|expr| expr.is_literal()) {
+        format!("`{}` is not {}", text, expected)
+    } else {
+        format!("`{}` is {}", text, found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::{Page, ProjectConfig};
+    use std::path::Path;
+
+    fn validate(source: &str) -> Vec<Diagnostic> {
+        let page: Page = serde_yaml::from_str(source).expect("page should parse");
+        let index = SourceIndex::new(source, None);
+        validate_page(
+            &page.page_data,
+            "page",
+            &index,
+            &Actions::default(),
+            &Components::default(),
+        )
+    }
+
+    #[test]
+    fn test_valid_page_has_no_diagnostics() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Ok
+  state:
+    - name: email
+      initial: ""
+  children:
+    - element: input
+      bind: email
+"#,
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_reports_all_errors_at_once() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Broken
+  state:
+    - name: count
+      initial: 0
+    - name: count
+      initial: 1
+  children:
+    - element: input
+      bind: missing
+    - element: layout
+      layout:
+        type: grid
+      children:
+        - element: select
+          bind: count
+    - element: modal
+      title: "Hi"
+"#,
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::DUPLICATE_STATE,
+                codes::UNDECLARED_STATE,
+                codes::GRID_WITHOUT_COLUMNS,
+                codes::EMPTY_OPTIONS,
+                codes::MODAL_WITHOUT_BIND,
+            ]
+        );
+
+        let undeclared = &diagnostics[1];
+        let span = undeclared.span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (11, 13));
+        assert!(undeclared.to_string().contains("bind: missing"));
+    }
+
+    #[test]
+    fn test_state_types_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Types
+  state:
+    - name: size
+      type: "'sm' | 'lg'"
+      initial: md
+    - name: user
+      type: "{ name: strng }"
+"#,
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code, codes::TYPE_MISMATCH);
+        assert_eq!(
+            diagnostics[0].label.as_deref(),
+            Some("`initial` is the string \"md\", expected one of \"sm\" | \"lg\"")
+        );
+
+        assert_eq!(diagnostics[1].code, codes::INVALID_TYPE);
+        let span = diagnostics[1].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (9, 22, 5));
+    }
+
+    #[test]
+    fn test_bound_state_types_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Bindings
+  state:
+    - name: volume
+      initial: "loud"
+    - name: size
+      type: "'sm' | 'lg'"
+    - name: upload
+      type: File | null
+  children:
+    - element: slider
+      bind: volume
+    - element: select
+      bind: size
+      options:
+        - value: sm
+        - value: xl
+    - element: file-upload
+      bind: upload
+    - element: file-upload
+      bind: upload
+      multiple: true
+"#,
+        );
+        let labels: Vec<&str> = diagnostics
+            .iter()
+            .map(|d| d.label.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "`volume` is `string`",
+                "option `xl` is not one of \"sm\" | \"lg\"",
+                "`upload` is `File | null`",
+            ]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.code == codes::BIND_TYPE_MISMATCH));
+        assert_eq!(
+            diagnostics[0].message,
+            "`slider` cannot be bound to state `volume`"
+        );
+        let span = diagnostics[0].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (13, 13));
+        assert_eq!(
+            diagnostics[2].help.as_deref(),
+            Some("`file-upload` needs a `File[]` state")
+        );
+    }
+
+    #[test]
+    fn test_list_templates_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Lists
+  state:
+    - name: projects
+      type: "{ id: number, name: string, tags: string[], owner: { email: string } }[]"
+    - name: title
+      initial: ""
+  children:
+    - element: list
+      data: projects
+      key: uid
+      children:
+        - element: heading
+          content: "{project.nme} by { project.owner.mail }"
+        - element: list
+          data: title
+        - element: list
+          data: project.tags
+          children:
+            - element: text
+              content: "{tag}"
+        - element: list
+          data: project.owner
+        - element: list
+          data: project.tagz
+    - element: list
+      as: entry
+"#,
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::UNKNOWN_FIELD,
+                codes::UNKNOWN_FIELD,
+                codes::UNKNOWN_FIELD,
+                codes::LIST_DATA_NOT_ARRAY,
+                codes::LIST_DATA_NOT_ARRAY,
+                codes::UNKNOWN_FIELD,
+                codes::INVALID_LIST,
+            ]
+        );
+        assert_eq!(
+            diagnostics[4].message,
+            "`data` refers to `project.owner`, which is not an array"
+        );
+        assert_eq!(diagnostics[5].message, "no field `tagz` on `project`");
+        assert_eq!(diagnostics[5].span.as_ref().unwrap().line, 26);
+
+        let field = &diagnostics[1];
+        assert_eq!(field.message, "no field `nme` on `project`");
+        assert_eq!(field.help.as_deref(), Some("did you mean `name`?"));
+        let span = field.span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (15, 30, 3));
+
+        let nested = &diagnostics[2];
+        assert_eq!(nested.message, "no field `mail` on `project.owner`");
+        assert_eq!(nested.span.as_ref().unwrap().column, 54);
+    }
+
+    #[test]
+    fn test_conditions_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Gate
+  state:
+    - name: loggedIn
+      initial: false
+    - name: user
+      initial: { name: "" }
+  children:
+    - element: heading
+      content: Welcome
+      if: "logedIn && user.name !== 'guest'"
+      else:
+        - element: input
+          bind: missing
+    - element: text
+      content: "{user.name}"
+      show: "typeof visible === 'undefined'"
+"#,
+        );
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.code == codes::UNDECLARED_STATE));
+
+        let condition = &diagnostics[0];
+        assert_eq!(
+            condition.message,
+            "condition refers to undeclared state `logedIn`"
+        );
+        assert_eq!(condition.help.as_deref(), Some("did you mean `loggedIn`?"));
+        let span = condition.span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (12, 12, 7));
+
+        assert_eq!(diagnostics[1].span.as_ref().unwrap().line, 15);
+        assert_eq!(diagnostics[2].span.as_ref().unwrap().line, 18);
+    }
+
+    #[test]
+    fn test_computed_values_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Cart
+  state:
+    - name: items
+      initial: []
+    - name: rate
+      initial: 0.2
+  computed:
+    - name: subtotal
+      value: "items.length * rate"
+      deps: [items, discount]
+    - name: a
+      value: b + 1
+    - name: b
+      value: a * 2
+    - name: rate
+      value: "0.5"
+  children:
+    - element: text
+      content: "{subtotal}"
+      if: "subtotal > limit"
+    - element: input
+      bind: subtotal
+"#,
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::DUPLICATE_STATE,
+                codes::UNDECLARED_STATE,
+                codes::MISSING_DEPENDENCY,
+                codes::COMPUTED_CYCLE,
+                codes::UNDECLARED_STATE,
+                codes::BIND_TO_COMPUTED,
+            ]
+        );
+        assert_eq!(diagnostics[0].message, "`rate` is declared more than once");
+        assert_eq!(diagnostics[0].notes, vec!["first declared on line 7"]);
+        assert_eq!(
+            diagnostics[2].message,
+            "computed value `subtotal` reads `rate` but does not list it in `deps`"
+        );
+        let span = diagnostics[2].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (11, 30, 4));
+        assert_eq!(diagnostics[3].label.as_deref(), Some("`a` → `b` → `a`"));
+        assert_eq!(
+            diagnostics[4].message,
+            "condition refers to undeclared state `limit`"
+        );
+    }
+
+    #[test]
+    fn test_state_names_are_compared_as_generated() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Names
+  state:
+    - name: user-name
+      initial: ""
+    - name: user_name
+      initial: ""
+    - name: class
+      initial: ""
+  children:
+    - element: text
+      content: "{class}"
+"#,
+        );
+        let messages: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.label.as_deref()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "state `user_name` is declared more than once",
+                    Some("`user-name` is also named `userName` in the generated code")
+                ),
+                (
+                    "`class` can't be used as a name",
+                    Some("`class` is a reserved word in JavaScript")
+                ),
+                (
+                    "invalid interpolation",
+                    Some("`class` is not supported in expressions")
+                ),
+            ]
+        );
+        assert_eq!(diagnostics[0].code, codes::DUPLICATE_STATE);
+        assert_eq!(diagnostics[1].code, codes::INVALID_NAME);
+    }
+
+    #[test]
+    fn test_generated_names_are_taken() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Names
+  state:
+    - name: navigate
+      initial: ""
+    - name: route-params
+      initial: {}
+  computed:
+    - name: Outlet
+      value: "navigate"
+  children:
+    - element: button
+      content: Home
+      onClick: "/"
+    - react: ./widgets/Stars
+      as: NavLink
+"#,
+        );
+        let messages: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.label.as_deref()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "the name `navigate` is already taken",
+                    Some("also the name of the function handlers navigate with")
+                ),
+                (
+                    "the name `routeParams` is already taken",
+                    Some("also the name of the page's route parameters as text")
+                ),
+                (
+                    "the name `Outlet` is already taken",
+                    Some("also the name of React Router's `Outlet`")
+                ),
+                (
+                    "the name `NavLink` is already taken",
+                    Some("also the name of React Router's `NavLink`")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expressions_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Shop
+  state:
+    - name: size
+      type: "'sm' | 'md'"
+    - name: price
+      initial: 0
+    - name: title
+      initial: ""
+  computed:
+    - name: label
+      value: "title + ' (' + price + ')'"
+      type: number
+  children:
+    - text: "Only {rond(price)} left"
+    - text: "{title * 2}"
+      if: "size == 'lg'"
+    - text: "{price +}"
+"#,
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::TYPE_MISMATCH,
+                codes::UNKNOWN_FUNCTION,
+                codes::TYPE_MISMATCH,
+                codes::TYPE_MISMATCH,
+                codes::INVALID_EXPRESSION,
+            ]
+        );
+        let spans: Vec<(usize, usize, usize)> = diagnostics
+            .iter()
+            .map(|d| {
+                let span = d.span.as_ref().unwrap();
+                (span.line, span.column, span.length)
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (13, 14, 28),
+                (16, 20, 4),
+                (18, 20, 4),
+                (17, 15, 5),
+                (19, 22, 1)
+            ]
+        );
+
+        assert_eq!(
+            diagnostics[0].label.as_deref(),
+            Some("this value is `string`")
+        );
+        assert_eq!(
+            diagnostics[0].notes,
+            vec!["`label` is declared as `number`"]
+        );
+        assert_eq!(diagnostics[1].message, "cannot find function `rond`");
+        assert_eq!(
+            diagnostics[1].help.as_deref(),
+            Some("did you mean `round`?")
+        );
+        assert_eq!(
+            diagnostics[2].message,
+            "cannot compare one of \"sm\" | \"md\" with `'lg'`"
+        );
+        assert_eq!(diagnostics[3].message, "`*` cannot be applied to `string`");
+        assert_eq!(diagnostics[4].message, "invalid interpolation");
+        assert_eq!(
+            diagnostics[4].label.as_deref(),
+            Some("expected an expression")
+        );
+    }
+
+    #[test]
+    fn test_objects_are_not_shown_as_text() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Profile
+  state:
+    - name: user
+      type: "{ name: string, age: number }"
+    - name: tags
+      type: "{ label: string }[]"
+  children:
+    - text: "Hello {user}"
+    - text: "{user.name} is {user.age}"
+    - heading: "{tags}"
+    - text: "{ { a: 1 } }"
+"#,
+        );
+        let messages: Vec<(&str, usize, usize)> = diagnostics
+            .iter()
+            .map(|d| {
+                let span = d.span.as_ref().unwrap();
+                (d.message.as_str(), span.line, span.column)
+            })
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "cannot show `{ name: string; age: number }` as text",
+                    10,
+                    21
+                ),
+                ("cannot show `{ label: string }[]` as text", 12, 18),
+                ("cannot show `{ a: number }` as text", 13, 16),
+            ]
+        );
+        assert_eq!(diagnostics[0].code, codes::TYPE_MISMATCH);
+        assert_eq!(
+            diagnostics[0].label.as_deref(),
+            Some("`user` is `{ name: string; age: number }`")
+        );
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("interpolate one of its fields, such as `{user.name}`")
+        );
+    }
+
+    #[test]
+    fn test_script_urls_are_rejected() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Links
+  children:
+    - image:
+        src: "javascript:alert(1)"
+    - nav:
+        links:
+          - label: Home
+            href: /
+          - label: Run
+            href: " JavaScript:void(0)"
+    - url:
+        href: "https://example.com/javascript:"
+    - button:
+        content: Go
+        onClick: { navigate: "javascript:go()" }
+"#,
+        );
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics.iter().all(|d| d.code == codes::SCRIPT_URL));
+        assert_eq!(diagnostics[0].message, "`javascript:` URLs are not allowed");
+        let lines: Vec<usize> = diagnostics
+            .iter()
+            .map(|d| d.span.as_ref().unwrap().line)
+            .collect();
+        assert_eq!(lines, vec![6, 12, 17]);
+    }
+
+    #[test]
+    fn test_action_calls_are_checked() {
+        let actions_source = r#"
+action:
+  - name: save
+    inputs: ["title: string", "priority: 'low' | 'high'"]
+    handler: "console.log(title, priority)"
+  - name: save
+  - name: clear
+    inputs: [all, all]
+"#;
+        let file: ActionFile = serde_yaml::from_str(actions_source).unwrap();
+        let actions_index = SourceIndex::new(actions_source, Some(Path::new("actions/app.yaml")));
+        let mut actions = Actions::default();
+        let diagnostics = validate_actions(&file, &actions_index, &mut actions);
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![codes::DUPLICATE_ACTION, codes::DUPLICATE_ACTION]
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "input `all` of action `clear` is declared more than once"
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "action `save` is declared more than once"
+        );
+        assert_eq!(
+            diagnostics[0].notes,
+            vec!["first declared at actions/app.yaml:3"]
+        );
+
+        let source = r#"
+page:
+  name: Editor
+  state:
+    - name: title
+      initial: ""
+    - name: count
+      initial: 0
+  children:
+    - element: button
+      content: Save
+      onClick: "save(title, 'high'); setCount(count + 1)"
+    - element: button
+      content: Save
+      onClick: "save(count, 'urgent')"
+    - element: button
+      content: Save
+      onClick: "sav(title, 'low')"
+    - element: form
+      onSubmit: "clear()"
+"#;
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        let diagnostics = validate_page(
+            &page.page_data,
+            "page",
+            &index,
+            &actions,
+            &Components::default(),
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::TYPE_MISMATCH,
+                codes::TYPE_MISMATCH,
+                codes::UNKNOWN_ACTION,
+                codes::ACTION_ARGUMENTS,
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "action `save` expects `title` to be `string`"
+        );
+        assert_eq!(diagnostics[0].label.as_deref(), Some("`count` is `number`"));
+        let span = diagnostics[0].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (15, 22, 5));
+        assert_eq!(
+            diagnostics[1].label.as_deref(),
+            Some("`'urgent'` is not one of \"low\" | \"high\"")
+        );
+        assert_eq!(diagnostics[2].help.as_deref(), Some("did you mean `save`?"));
+        assert_eq!(
+            diagnostics[3].message,
+            "action `clear` takes 1 input but 0 arguments were supplied"
+        );
+        assert_eq!(
+            diagnostics[3].label.as_deref(),
+            Some("expected `clear(all)`")
+        );
+    }
+
+    #[test]
+    fn test_handler_steps_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Todos
+  state:
+    - name: todos
+      type: string[]
+    - name: draft
+      initial: ""
+    - name: open
+      initial: false
+  computed:
+    - name: count
+      value: todos.length
+  children:
+    - element: button
+      content: Add
+      onClick:
+        - append: todos
+          value: open
+        - set: drafts
+          to: "''"
+        - set: count
+          to: 0
+    - element: button
+      content: Menu
+      onClick: {toggle: draft}
+    - element: button
+      content: Save
+      onClick: {call: save, with: [draftt]}
+"#,
+        );
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::TYPE_MISMATCH,
+                codes::UNDECLARED_STATE,
+                codes::BIND_TO_COMPUTED,
+                codes::TYPE_MISMATCH,
+                codes::UNDECLARED_STATE,
+                codes::UNKNOWN_ACTION,
+            ]
+        );
+        assert_eq!(diagnostics[0].label.as_deref(), Some("`open` is `boolean`"));
+        assert_eq!(diagnostics[0].notes, vec!["items of `todos` are `string`"]);
+        let span = diagnostics[0].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (19, 18));
+        assert_eq!(
+            diagnostics[1].message,
+            "`set` refers to undeclared state `drafts`"
+        );
+        assert_eq!(
+            diagnostics[2].message,
+            "`set` cannot change computed value `count`"
+        );
+        assert_eq!(
+            diagnostics[3].message,
+            "`toggle` needs a boolean state, but `draft` is `string`"
+        );
+        let span = diagnostics[3].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (26, 25));
+        assert_eq!(
+            diagnostics[4].message,
+            "argument refers to undeclared state `draftt`"
+        );
+        assert_eq!(diagnostics[5].message, "cannot find action `save`");
+    }
+
+    #[test]
+    fn test_form_validation_fields_must_be_state() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Form
+  children:
+    - element: form
+      validation:
+        email:
+          - required: true
+"#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, codes::UNDECLARED_STATE);
+        assert_eq!(diagnostics[0].span.as_ref().unwrap().line, 7);
+    }
+
+    #[test]
+    fn test_references_to_state_are_collected() {
+        let source = r#"
+page:
+  name: Refs
+  state:
+    - name: items
+      type: string[]
+      initial: []
+    - name: draft
+      initial: ""
+  computed:
+    - name: count
+      value: items.length
+      deps: [items]
+  children:
+    - text: "{count} items, {draft}"
+    - element: input
+      bind: draft
+    - element: list
+      data: items
+      as: draft
+      children:
+        - text: "{draft}"
+    - element: button
+      content: Add
+      onClick:
+        - append: items
+          value: draft
+"#;
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        let analysis = analyze_page(
+            &page.page_data,
+            "page",
+            &index,
+            &Actions::default(),
+            &Components::default(),
+        );
+        assert!(
+            analysis.diagnostics.is_empty(),
+            "{:?}",
+            analysis.diagnostics
+        );
+
+        let found: Vec<(&str, &str, usize, bool)> = analysis
+            .references
+            .iter()
+            .map(|r| (r.name.as_str(), r.path.as_str(), r.offset, r.declaration))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("items", "page.state[0].name", 0, true),
+                ("draft", "page.state[1].name", 0, true),
+                ("count", "page.computed[0].name", 0, true),
+                ("items", "page.computed[0].value", 0, false),
+                ("items", "page.computed[0].deps[0]", 0, false),
+                ("count", "page.children[0].content", 1, false),
+                ("draft", "page.children[0].content", 16, false),
+                ("draft", "page.children[1].bind", 0, false),
+                ("items", "page.children[2].data", 0, false),
+                ("items", "page.children[3].onClick[0].append", 0, false),
+                ("draft", "page.children[3].onClick[0].value", 0, false),
+            ]
+        );
+    }
+
+    /// Registers each component source as `components/<i>.yaml`, then
+    /// validates them like a build does.
+    fn components(sources: &[&str]) -> (Components, Vec<Diagnostic>) {
+        let mut components = Components::default();
+        let mut diagnostics = Vec::new();
+        let mut files = Vec::new();
+        for (i, source) in sources.iter().enumerate() {
+            let file: ComponentFile = serde_yaml::from_str(source).expect("component should parse");
+            let path = format!("components/{}.yaml", i);
+            let index = SourceIndex::new(source, Some(Path::new(&path)));
+            diagnostics.extend(register_component(&file, &index, &mut components));
+            files.push((file, index));
+        }
+        for (file, index) in &files {
+            diagnostics.extend(validate_component(
+                &file.component,
+                index,
+                &Actions::default(),
+                &components,
+            ));
+        }
+        diagnostics.extend(component_cycles(&components));
+        (components, diagnostics)
+    }
+
+    const CARD: &str = r#"
+component:
+  name: Card
+  props:
+    - name: title
+      type: string
+    - name: count
+      default: 0
+  slots: [footer]
+  children:
+    - heading: "{title}"
+      style: ["count-{count}"]
+    - slot:
+    - slot: footer
+"#;
+
+    #[test]
+    fn test_components_are_checked() {
+        let (_, diagnostics) = components(&[
+            CARD,
+            r#"
+component:
+  name: Card
+  props:
+    - name: size
+      type: numbr
+    - name: open
+      type: boolean
+      default: "yes"
+  slots: [size, footer, footer]
+  children:
+    - element: toggle
+      bind: open
+    - slot: footr
+    - text: "{missing}"
+"#,
+        ]);
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::DUPLICATE_COMPONENT,
+                codes::INVALID_TYPE,
+                codes::TYPE_MISMATCH,
+                codes::DUPLICATE_COMPONENT,
+                codes::DUPLICATE_COMPONENT,
+                codes::BIND_TO_COMPUTED,
+                codes::UNKNOWN_SLOT,
+                codes::UNDECLARED_STATE,
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].notes,
+            vec!["first declared at components/0.yaml:3"]
+        );
+        assert_eq!(
+            diagnostics[2].message,
+            "default value of prop `open` does not match its type"
+        );
+        assert_eq!(
+            diagnostics[5].message,
+            "`bind` cannot be bound to prop `open`"
+        );
+        assert_eq!(diagnostics[5].label.as_deref(), Some("props are read-only"));
+        assert_eq!(
+            diagnostics[6].help.as_deref(),
+            Some("did you mean `footer`?")
+        );
+    }
+
+    #[test]
+    fn test_uses_of_components_are_checked() {
+        let (components, diagnostics) = components(&[CARD]);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let source = r#"
+page:
+  name: Home
+  state:
+    - name: total
+      initial: 3
+    - name: name
+      initial: Ada
+  children:
+    - use: Card
+      props:
+        title: "Hi {name}"
+        count: "{total}"
+      slots:
+        footer:
+          - text: "{total}"
+      children:
+        - text: Body
+    - use: Card
+      props:
+        title: "{total}"
+        count: lots
+        colour: red
+      slots:
+        header:
+          - text: Top
+    - use: Crad
+    - use: Card
+    - slot: footer
+"#;
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        let analysis = analyze_page(
+            &page.page_data,
+            "page",
+            &index,
+            &Actions::default(),
+            &components,
+        );
+        let diagnostics = analysis.diagnostics;
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                codes::COMPONENT_PROPS,
+                codes::TYPE_MISMATCH,
+                codes::TYPE_MISMATCH,
+                codes::UNKNOWN_SLOT,
+                codes::UNKNOWN_COMPONENT,
+                codes::COMPONENT_PROPS,
+                codes::UNKNOWN_SLOT,
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "component `Card` has no prop `colour`"
+        );
+        assert_eq!(
+            diagnostics[1].label.as_deref(),
+            Some("`count` is the string \"lots\", expected `number`")
+        );
+        assert_eq!(
+            diagnostics[2].label.as_deref(),
+            Some("this value is `number`")
+        );
+        assert_eq!(
+            diagnostics[2].notes,
+            vec!["`title` is declared as `string` in component `Card`"]
+        );
+        assert_eq!(
+            diagnostics[3].message,
+            "component `Card` has no slot `header`"
+        );
+        assert_eq!(diagnostics[4].help.as_deref(), Some("did you mean `Card`?"));
+        assert_eq!(
+            diagnostics[5].message,
+            "component `Card` is missing prop `title`"
+        );
+        assert_eq!(
+            diagnostics[6].message,
+            "`slot` is only allowed in a component"
+        );
+        let span = diagnostics[4].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (27, 12));
+
+        let reads: Vec<&str> = analysis
+            .references
+            .iter()
+            .filter(|r| !r.declaration)
+            .map(|r| r.path.as_str())
+            .collect();
+        assert_eq!(
+            reads,
+            [
+                "page.children[0].props.count",
+                "page.children[0].props.title",
+                "page.children[0].slots.footer[0].content",
+                "page.children[1].props.title",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_react_elements_are_checked() {
+        let (components, _) = components(&[CARD]);
+        let source = r#"
+page:
+  name: Sales
+  state:
+    - name: selected
+      initial: 0
+  children:
+    - use: Card
+      props: { title: Hi }
+    - react: ./widgets/Chart
+      props:
+        points: "{missing}"
+        aria-label: Chart
+      events:
+        onSelect:
+          - set: selected
+            to: value
+    - react: ./charts/Chart
+    - react: recharts
+      export: line-chart
+    - react: ./widgets/card
+    - react: ./widgets/Chart
+      props:
+        on select: 1
+        onSelect: 2
+      events:
+        onSelect: "setSelected(value)"
+"#;
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let index = SourceIndex::new(source, None);
+        let diagnostics = validate_page(
+            &page.page_data,
+            "page",
+            &index,
+            &Actions::default(),
+            &components,
+        );
+        let messages: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.label.as_deref()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "prop refers to undeclared state `missing`",
+                    Some("not declared in `state:`")
+                ),
+                (
+                    "the name `Chart` is already taken",
+                    Some("also the default export of `./widgets/Chart`")
+                ),
+                (
+                    "invalid export name `line-chart`",
+                    Some("not a JavaScript identifier")
+                ),
+                (
+                    "the name `Card` is already taken",
+                    Some("also the name of component `Card`")
+                ),
+                (
+                    "invalid prop name `on select`",
+                    Some("not a JSX attribute name")
+                ),
+                (
+                    "prop `onSelect` is set more than once",
+                    Some("also set under `props:`")
+                ),
+            ]
+        );
+        let span = diagnostics[1].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column), (18, 14));
+    }
+
+    #[test]
+    fn test_params_are_checked() {
+        let diagnostics = validate(
+            r#"
+page:
+  name: Project
+  params:
+    - name: id
+      type: number
+    - name: tab
+      type: "'files' | 'settings'"
+      default: files
+    - name: filter
+      type: string[]
+    - name: page number
+      default: one
+      type: number
+  computed:
+    - name: next
+      value: id + 1
+  children:
+    - text: "Project {id} on tab {tab}"
+    - input: { bind: tab }
+    - button: Next
+      onClick:
+        - set: id
+          to: next
+"#,
+        );
+        let messages: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.label.as_deref()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "invalid type for param `filter`",
+                    Some("`string[]` can't be read from a URL")
+                ),
+                (
+                    "invalid parameter name `page number`",
+                    Some("route parameters are named with letters, digits, `_` and `-`")
+                ),
+                (
+                    "default value of param `page number` does not match its type",
+                    Some("`default` is the string \"one\", expected `number`")
+                ),
+                (
+                    "`bind` cannot be bound to param `tab`",
+                    Some("params are read from the URL")
+                ),
+                (
+                    "`set` cannot change param `id`",
+                    Some("params are read from the URL")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_routes_are_checked_against_params() {
+        let config_source = r#"
+name: Projects
+routes:
+  - path: /projects/:id/:tab?
+    page: pages/project.yaml
+  - path: /projects/:id/:id/:bad.name
+    page: pages/project.yaml
+  - path: /projects
+    page: pages/project.yaml
+"#;
+        let source = "page:\n  name: Project\n  params:\n    - name: id\n    - name: tab\n      default: files\n";
+        let config: ProjectConfig = serde_yaml::from_str(config_source).unwrap();
+        let config_index = SourceIndex::new(config_source, Some(Path::new("nwl.yaml")));
+        let page: Page = serde_yaml::from_str(source).unwrap();
+        let entries = routes::flatten(&config.routes);
+        let diagnostics: Vec<Diagnostic> = entries
+            .iter()
+            .flat_map(|entry| validate_route(entry, &page.page_data, &config_index))
+            .collect();
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "route parameter `id` appears more than once",
+                "invalid route parameter `:bad.name`",
+            ]
+        );
+        let span = diagnostics[0].span.as_ref().unwrap();
+        assert_eq!((span.line, span.column, span.length), (6, 25, 3));
+
+        let routes: Vec<&RouteEntry> = entries.iter().collect();
+        let index = SourceIndex::new(source, None);
+        let diagnostics = validate_params(&page.page_data, "page", &routes, &index);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, ["route `/projects` has no parameter `id`"]);
+        assert_eq!(diagnostics[0].span.as_ref().map(|span| span.line), Some(4));
+
+        let mut page = page.page_data;
+        page.params.clear();
+        let diagnostics = validate_route(&entries[0], &page, &config_index);
+        assert_eq!(
+            diagnostics[0].message,
+            "page `Project` does not declare route parameter `id`"
+        );
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("add `- name: id` to `params:` in `pages/project.yaml`")
+        );
+    }
+
+    #[test]
+    fn test_nested_routes_are_checked() {
+        let config_source = r#"
+name: Shop
+routes:
+  - path: /
+    layout: pages/shell.yaml
+    children:
+      - index: true
+        page: pages/home.yaml
+      - path: /about
+        page: pages/about.yaml
+      - path: products/:id
+        layout: pages/product.yaml
+        children:
+          - index: true
+            page: pages/overview.yaml
+          - path: reviews
+            page: pages/reviews.yaml
+            redirect: /reviews
+  - path: /
+    page: pages/home.yaml
+  - path: old-about
+    redirect: /about
+  - path: /empty
+  - page: pages/lost.yaml
+  - path: /account
+    page: pages/account.yaml
+    children:
+      - path: settings
+        page: pages/settings.yaml
+  - path: "*"
+    page: pages/404.yaml
+"#;
+        let config: ProjectConfig = serde_yaml::from_str(config_source).unwrap();
+        let config_index = SourceIndex::new(config_source, Some(Path::new("nwl.yaml")));
+        let diagnostics = validate_project(&config, &config_index);
+        let messages: Vec<(&str, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.span.as_ref().map(|span| span.line)))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("nested route path starts with `/`", Some(9)),
+                ("route has both `page` and `redirect`", Some(18)),
+                ("no route is served at `/reviews`", Some(18)),
+                ("more than one route is served at `/`", Some(19)),
+                ("route path doesn't start with `/`", Some(21)),
+                ("route has nothing to render", Some(23)),
+                ("route has no `path`", Some(24)),
+                ("only layout routes have `children`", Some(27)),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].help.as_deref(),
+            Some("remove `redirect:`, or move it to a route of its own")
+        );
+        assert_eq!(
+            diagnostics[2].help.as_deref(),
+            Some("add a route for it to `nwl.yaml`, or link to a full URL")
+        );
+        assert_eq!(diagnostics[3].notes, ["first served on line 7".to_string()]);
+
+        // Pages nested below a parameterized path may read its params
+        // without repeating them.
+        let entries = routes::flatten(&config.routes);
+        let overview: Page = serde_yaml::from_str(
+            "page:\n  name: Overview\n  params:\n    - name: id\n      type: number\n",
+        )
+        .unwrap();
+        let routes: Vec<&RouteEntry> = vec![&entries[4]];
+        assert_eq!(entries[4].full_path, "/products/:id");
+        let index = SourceIndex::new("", None);
+        assert!(validate_params(&overview.page_data, "page", &routes, &index).is_empty());
+        assert!(validate_route(&entries[4], &overview.page_data, &config_index).is_empty());
+
+        let product: Page = serde_yaml::from_str(
+            "page:\n  name: Product\n  params:\n    - name: id\n  children:\n    - text: Product\n",
+        )
+        .unwrap();
+        let diagnostics = validate_route(&entries[3], &product.page_data, &config_index);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, ["layout page `Product` has no `outlet`"]);
+    }
+
+    #[test]
+    fn test_lazy_routes_are_checked() {
+        let config_source = r#"
+name: Shop
+lazy: true
+fallback:
+  page: pages/loading.yaml
+  spinner:
+    label: Loading...
+routes:
+  - path: /
+    page: pages/home.yaml
+    lazy: false
+  - path: /old
+    redirect: /
+    lazy: true
+"#;
+        let config: ProjectConfig = serde_yaml::from_str(config_source).unwrap();
+        let index = SourceIndex::new(config_source, Some(Path::new("nwl.yaml")));
+        let diagnostics = validate_project(&config, &index);
+        let messages: Vec<(&str, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.span.as_ref().map(|span| span.line)))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                ("redirect route has `lazy`", Some(14)),
+                ("fallback has both `page` and `spinner`", Some(6)),
+            ]
+        );
+
+        let config: ProjectConfig = serde_yaml::from_str("name: Shop\nfallback: {}\n").unwrap();
+        let index = SourceIndex::new("name: Shop\nfallback: {}\n", None);
+        let diagnostics = validate_project(&config, &index);
+        assert_eq!(diagnostics[0].message, "fallback has nothing to render");
+
+        // The fallback shows while any lazy route loads, whatever its params.
+        let loading: Page = serde_yaml::from_str(
+            "page:\n  name: Loading\n  params:\n    - name: id\n    - name: tab\n      default: files\n",
+        )
+        .unwrap();
+        let config_source = "name: Shop\nfallback:\n  page: pages/loading.yaml\n";
+        let index = SourceIndex::new(config_source, Some(Path::new("nwl.yaml")));
+        let diagnostics = validate_fallback(&loading.page_data, &index);
+        let messages: Vec<(&str, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| (d.message.as_str(), d.span.as_ref().map(|span| span.line)))
+            .collect();
+        assert_eq!(
+            messages,
+            [(
+                "fallback page reads param `id` without a `default`",
+                Some(3)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_outlets_are_only_allowed_in_pages() {
+        let (_, diagnostics) =
+            components(&["component:\n  name: Shell\n  children:\n    - outlet:\n"]);
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(messages, ["`outlet` is only allowed in a page"]);
+        assert!(validate("page:\n  name: Shell\n  children:\n    - outlet:\n").is_empty());
+    }
+
+    #[test]
+    fn test_component_cycles_are_reported() {
+        let (_, diagnostics) = components(&[
+            "component:\n  name: Tree\n  children:\n    - use: Branch\n",
+            "component:\n  name: Branch\n  children:\n    - use: Leaf\n      if: deep\n      else:\n        - use: Tree\n",
+            "component:\n  name: Leaf\n  children:\n    - use: Leaf\n",
+        ]);
+        let cycles: Vec<(&str, Option<&str>)> = diagnostics
+            .iter()
+            .filter(|d| d.code == codes::COMPONENT_CYCLE)
+            .map(|d| (d.message.as_str(), d.label.as_deref()))
+            .collect();
+        assert_eq!(
+            cycles,
+            [
+                (
+                    "component `Tree` uses itself through a cycle",
+                    Some("`Tree` → `Branch` → `Tree`")
+                ),
+                ("component `Leaf` uses itself", Some("`Leaf` → `Leaf`")),
+            ]
+        );
+        let span = diagnostics
+            .iter()
+            .find(|d| d.code == codes::COMPONENT_CYCLE)
+            .and_then(|d| d.span.as_ref())
+            .unwrap();
+        assert_eq!(span.path.as_deref(), Some(Path::new("components/0.yaml")));
+        assert_eq!(span.line, 4);
+    }
+}
@@ -0,0 +1,179 @@
+//! Self-check for generated code. Every emitted file is parsed with a
+//! TypeScript/JSX parser, so output that would not compile is reported as a
+//! compiler bug instead of surfacing later in the user's bundler.
+
+use crate::codegen::{page_path, ReactGenerator};
+use crate::components::Component;
+use crate::diagnostics::codes;
+use crate::source_index::SourceIndex;
+use nwl_shared::{Diagnostic, Document};
+use oxc_allocator::Allocator;
+use oxc_parser::Parser;
+use oxc_span::SourceType;
+use std::fmt;
+
+/// The first parse error in a generated file, at a 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Generated code that does not parse, with the path of the innermost
+/// element responsible for it (`page` when no single element is).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("generated code for `{path}` does not parse: {error}")]
+pub struct InvalidOutput {
+    pub path: String,
+    pub error: SyntaxError,
+}
+
+impl InvalidOutput {
+    /// Reports the bug against the element in the page source, with the
+    /// position in the generated `file` as a note.
+    pub fn to_diagnostic(&self, file: &str, index: &SourceIndex) -> Diagnostic {
+        let diagnostic = Diagnostic::error(
+            codes::INVALID_OUTPUT,
+            format!(
+                "internal compiler error: generated `{}` does not parse",
+                file
+            ),
+        )
+        .with_label("while generating this")
+        .with_note(format!("{}:{}", file, self.error))
+        .with_note(format!("element path: {}", self.path))
+        .with_help("this is a bug in the NWL compiler, not in the page; please report it");
+        index.locate_key(diagnostic, &format!("{}.element", self.path))
+    }
+}
+
+/// Parses `source` as a `.tsx` module.
+pub fn parse_tsx(source: &str) -> Result<(), SyntaxError> {
+    parse(source, SourceType::tsx())
+}
+
+/// Parses `source` as a `.ts` module.
+pub fn parse_ts(source: &str) -> Result<(), SyntaxError> {
+    parse(source, SourceType::ts())
+}
+
+fn parse(source: &str, source_type: SourceType) -> Result<(), SyntaxError> {
+    let allocator = Allocator::default();
+    let parsed = Parser::new(&allocator, source, source_type).parse();
+    let Some(error) = parsed.errors.first() else {
+        return Ok(());
+    };
+    let offset = error
+        .labels
+        .iter()
+        .flatten()
+        .map(|label| label.offset())
+        .next()
+        .unwrap_or(source.len());
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rfind('\n')
+        .map_or(before, |newline| &before[newline + 1..])
+        .chars()
+        .count()
+        + 1;
+    Err(SyntaxError {
+        message: error.message.to_string(),
+        line,
+        column,
+    })
+}
+
+/// Checks `code`, the component module generated for `document`. When it
+/// does not parse, each page's elements are regenerated one at a time to
+/// find the one at fault, using the paths the validator reports against.
+pub fn verify_document(document: &Document, code: &str) -> Result<(), InvalidOutput> {
+    let Err(error) = parse_tsx(code) else {
+        return Ok(());
+    };
+    let path = document
+        .pages
+        .iter()
+        .enumerate()
+        .find_map(|(i, page)| {
+            ReactGenerator::broken_element(
+                &page.page_data.state,
+                &page.page_data.children,
+                &format!("{}.children", page_path(document, i)),
+            )
+        })
+        .unwrap_or_else(|| page_path(document, 0));
+    Err(InvalidOutput { path, error })
+}
+
+/// Like [`verify_document`], for the module generated for `component`.
+pub fn verify_component(component: &Component, code: &str) -> Result<(), InvalidOutput> {
+    let Err(error) = parse_tsx(code) else {
+        return Ok(());
+    };
+    let definition = &component.definition;
+    let path = ReactGenerator::broken_element(
+        &definition.state,
+        &definition.children,
+        "component.children",
+    )
+    .unwrap_or_else(|| "component".to_string());
+    Err(InvalidOutput { path, error })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_shared::Page;
+
+    #[test]
+    fn test_parse_tsx() {
+        assert!(parse_tsx("const a = <p className=\"x\">{b}</p>;\n").is_ok());
+        let error = parse_tsx("const a = (\n  <p>{{b}</p>\n);\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11), "{}", error);
+        assert!(parse_ts("export const a: number = 1;\n").is_ok());
+        assert!(parse_ts("const a = <p />;\n").is_err());
+    }
+
+    #[test]
+    fn test_verify_document_finds_element() {
+        let yaml = r#"
+page:
+  name: Broken
+  children:
+    - element: text
+      content: fine
+    - element: card
+      children:
+        - element: button
+          content: Go
+          onClick: "save("
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let document = Document { pages: vec![page] };
+        let code = "export default function Broken() {\n  return <>{{</>;\n}\n";
+        let error = verify_document(&document, code).unwrap_err();
+        assert_eq!(error.path, "page.children[1].children[0]");
+
+        let index = SourceIndex::new(yaml, Some(std::path::Path::new("pages/broken.yaml")));
+        let rendered = error.to_diagnostic("src/broken.tsx", &index).to_string();
+        assert!(rendered.starts_with(
+            "error[E0901]: internal compiler error: generated `src/broken.tsx` does not parse"
+        ));
+        assert!(
+            rendered.contains("--> pages/broken.yaml:9:11"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("= note: src/broken.tsx:"));
+        assert!(rendered.contains("= note: element path: page.children[1].children[0]"));
+    }
+}
@@ -0,0 +1,172 @@
+//! Golden tests for code generation. Each page in `tests/golden/` compiles
+//! to the snapshot recorded in `tests/snapshots/`, and the generated TSX
+//! must parse. Every element tag has a page of its own, or a component in
+//! `tests/golden/components/` for tags only components use. Pages may use
+//! those components, and the hand-written ones in `tests/golden/src/`.
+
+use nwl_compiler::actions::Actions;
+use nwl_compiler::codegen::{generate_component, generate_react_mapped};
+use nwl_compiler::components::Components;
+use nwl_compiler::source_index::SourceIndex;
+use nwl_compiler::verify::{verify_component, verify_document};
+use nwl_compiler::{load_components, modules, parse_yaml, validate};
+use nwl_shared::{Diagnostic, Element};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+fn components() -> Components {
+    let (components, diagnostics) =
+        load_components(&golden_dir(), &Actions::default()).unwrap_or_else(|e| panic!("{}", e));
+    assert_no_errors(&diagnostics);
+    components
+}
+
+fn assert_no_errors(diagnostics: &[Diagnostic]) {
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .map(|d| d.to_string())
+        .collect();
+    assert!(errors.is_empty(), "{}", errors.join("\n\n"));
+}
+
+fn golden(fixture: &str) -> String {
+    let components = components();
+    let component_path = Path::new("components").join(format!("{}.yaml", fixture));
+    if let Some(component) = components
+        .iter()
+        .find(|component| component.index.path() == Some(component_path.as_path()))
+    {
+        let code = generate_component(component, &Actions::default(), &components)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .code;
+        if let Err(error) = verify_component(component, &code) {
+            panic!("{}\n\n{}", error, code);
+        }
+        return code;
+    }
+
+    let path = golden_dir().join(format!("{}.yaml", fixture));
+    let source = fs::read_to_string(&path).unwrap();
+    let document = parse_yaml(&source).unwrap_or_else(|e| panic!("{}", e));
+    let index = SourceIndex::new(&source, None);
+    let diagnostics: Vec<Diagnostic> = document
+        .pages
+        .iter()
+        .flat_map(|page| {
+            let mut diagnostics = validate::validate_page(
+                &page.page_data,
+                "page",
+                &index,
+                &Actions::default(),
+                &components,
+            );
+            diagnostics.extend(modules::check_modules(
+                &page.page_data.children,
+                "page.children",
+                &index,
+                &golden_dir(),
+            ));
+            diagnostics
+        })
+        .collect();
+    assert_no_errors(&diagnostics);
+    let code = generate_react_mapped(&document, &Actions::default(), &components)
+        .unwrap_or_else(|e| panic!("{}", e))
+        .code;
+    if let Err(error) = verify_document(&document, &code) {
+        panic!("{}\n\n{}", error, code);
+    }
+    code
+}
+
+macro_rules! golden {
+    ($($test:ident => $fixture:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                insta::assert_snapshot!($fixture, golden($fixture));
+            }
+        )*
+
+        const FIXTURES: &[&str] = &[$($fixture),*];
+    };
+}
+
+golden! {
+    heading => "heading",
+    text => "text",
+    button => "button",
+    card => "card",
+    list => "list",
+    layout => "layout",
+    input => "input",
+    image => "image",
+    spacer => "spacer",
+    container => "container",
+    checkbox => "checkbox",
+    slider => "slider",
+    select => "select",
+    radio_group => "radio-group",
+    textarea => "textarea",
+    form => "form",
+    date_input => "date-input",
+    time_input => "time-input",
+    datetime_input => "datetime-input",
+    color_picker => "color-picker",
+    file_upload => "file-upload",
+    progress => "progress",
+    toggle => "toggle",
+    tabs => "tabs",
+    accordion => "accordion",
+    modal => "modal",
+    badge => "badge",
+    tag => "tag",
+    alert => "alert",
+    spinner => "spinner",
+    counter => "counter",
+    search_input => "search-input",
+    copy_button => "copy-button",
+    pagination => "pagination",
+    breadcrumb => "breadcrumb",
+    avatar => "avatar",
+    chip_input => "chip-input",
+    nav => "nav",
+    menu => "menu",
+    url => "url",
+    email => "email",
+    conditional => "conditional",
+    use_component => "use",
+    slot => "slot",
+    react => "react",
+    params => "params",
+    outlet => "outlet",
+}
+
+#[test]
+fn every_element_has_a_golden_page() {
+    for tag in Element::TAGS {
+        assert!(FIXTURES.contains(tag), "no golden page for `{}`", tag);
+    }
+
+    let dir = golden_dir();
+    let files = fs::read_dir(&dir)
+        .unwrap()
+        .chain(fs::read_dir(dir.join("components")).unwrap());
+    for entry in files {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "yaml") {
+            continue;
+        }
+        let fixture = path.file_stem().unwrap().to_str().unwrap().to_string();
+        assert!(
+            FIXTURES.contains(&fixture.as_str()),
+            "golden page `{}` has no test",
+            path.display()
+        );
+    }
+}
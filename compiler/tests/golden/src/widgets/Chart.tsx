@@ -0,0 +1,24 @@
+import React from "react";
+
+export interface ChartProps {
+  title: string;
+  points: number[];
+  height?: number;
+  onSelect?: (value: number) => void;
+  children?: React.ReactNode;
+}
+
+export default function Chart({ title, points, height = 200, onSelect, children }: ChartProps) {
+  const max = Math.max(1, ...points);
+  return (
+    <figure>
+      <figcaption>{title}</figcaption>
+      <svg height={height} viewBox={`0 0 ${points.length} ${max}`}>
+        {points.map((point, i) => (
+          <rect key={i} x={i} y={max - point} width={0.8} height={point} onClick={() => onSelect?.(point)} />
+        ))}
+      </svg>
+      {children}
+    </figure>
+  );
+}
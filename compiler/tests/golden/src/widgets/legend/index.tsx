@@ -0,0 +1,11 @@
+import React from "react";
+
+export function Legend({ labels }: { labels: string[] }) {
+  return (
+    <ul>
+      {labels.map((label) => (
+        <li key={label}>{label}</li>
+      ))}
+    </ul>
+  );
+}
@@ -0,0 +1,119 @@
+//! The JSON Schemas `nwl schema` prints must accept every page, project,
+//! action and component file the compiler does, and reject what it rejects.
+
+use jsonschema::Validator;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn validator(schema: serde_json::Value) -> Validator {
+    jsonschema::draft7::new(&schema).unwrap()
+}
+
+fn yaml(path: &Path) -> serde_json::Value {
+    serde_yaml::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn files(dir: &str) -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(dir);
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn assert_valid(validator: &Validator, path: &Path) {
+    let errors: Vec<String> = validator
+        .iter_errors(&yaml(path))
+        .map(|error| format!("{} at {}", error, error.instance_path()))
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "{}:\n{}",
+        path.display(),
+        errors.join("\n")
+    );
+}
+
+#[test]
+fn test_pages_match_page_schema() {
+    let validator = validator(nwl_shared::page_schema().to_value());
+    let mut pages = files("tests/golden");
+    pages.extend(files("../examples/demo/pages"));
+    for page in &pages {
+        assert_valid(&validator, page);
+    }
+}
+
+#[test]
+fn test_project_matches_project_schema() {
+    let validator = validator(nwl_shared::project_schema().to_value());
+    let project = Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/demo/nwl.yaml");
+    assert_valid(&validator, &project);
+}
+
+#[test]
+fn test_components_match_component_schema() {
+    let validator = validator(nwl_shared::component_schema().to_value());
+    for component in &files("tests/golden/components") {
+        assert_valid(&validator, component);
+    }
+    let component = |yaml: &str| -> serde_json::Value { serde_yaml::from_str(yaml).unwrap() };
+    assert!(!validator.is_valid(&component(
+        "component: { name: Card, props: [{ name: title, required: true }] }"
+    )));
+}
+
+#[test]
+fn test_actions_match_action_schema() {
+    let validator = validator(nwl_shared::action_schema().to_value());
+    let actions = |yaml: &str| -> serde_json::Value { serde_yaml::from_str(yaml).unwrap() };
+
+    assert!(validator.is_valid(&actions(
+        r#"action:
+  - name: addTodo
+    inputs: ["title: string", done]
+    handler: |
+      console.log(title)
+"#
+    )));
+    // Unquoted, `title: string` is a mapping rather than an input.
+    assert!(!validator.is_valid(&actions(
+        "action: [{ name: addTodo, inputs: [title: string] }]"
+    )));
+}
+
+#[test]
+fn test_page_schema_rejects_invalid_pages() {
+    let validator = validator(nwl_shared::page_schema().to_value());
+    let page = |yaml: &str| -> serde_json::Value { serde_yaml::from_str(yaml).unwrap() };
+
+    // Keyed elements, their shorthands and conditions are accepted...
+    assert!(validator.is_valid(&page(
+        r#"page:
+  name: Home
+  children:
+    - heading: Welcome
+    - button: Save
+      onClick: { set: saved, to: true }
+    - spacer:
+    - layout: { type: row }
+      children:
+        - text: Saved
+          if: saved
+          else: { text: Unsaved }
+"#
+    )));
+    // ...but not misspelt tags, unknown properties or wrong types.
+    for invalid in [
+        "page: { name: Home, children: [{ headng: Welcome }] }",
+        "page: { name: Home, children: [{ element: heading, content: Hi, colour: red }] }",
+        "page: { name: Home, children: [{ element: slider, min: low }] }",
+        "page: { name: Home, layout: { type: diagonal } }",
+        "page: { name: Home, children: [{ button: Go, onClick: { jump: /next } }] }",
+    ] {
+        assert!(!validator.is_valid(&page(invalid)), "accepted {}", invalid);
+    }
+}
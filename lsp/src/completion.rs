@@ -0,0 +1,305 @@
+//! Completion of keys, element tags and the names of a page's states.
+
+use crate::outline::{Line, Mapping};
+use crate::position::LineIndex;
+use crate::schema::mapping_schema;
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Documentation, MarkupContent,
+    MarkupKind, Position, Range, TextEdit,
+};
+use nwl_compiler::source_index::SourceIndex;
+use nwl_compiler::FileKind;
+use nwl_shared::Element;
+use nwl_shared::FileSchema;
+
+/// Keys whose value names a state.
+const STATE_KEYS: &[&str] = &["bind", "isOpen", "data", "set", "toggle", "append"];
+
+/// Keys whose value is an expression, as is every item of these sequences.
+const EXPRESSION_KEYS: &[&str] = &["if", "show", "to"];
+const EXPRESSION_LISTS: &[&str] = &["deps", "with"];
+
+/// Stands in for the key being typed, so the line reads as a mapping entry.
+const PLACEHOLDER: &str = "nwl-completion";
+
+pub fn completions(kind: FileKind, text: &str, position: Position) -> Vec<CompletionItem> {
+    let index = LineIndex::new(text);
+    let Some(position) = index.clamp(position) else {
+        return Vec::new();
+    };
+    let prefix = index.prefix(position);
+    // The word being completed, which the completion replaces.
+    let before =
+        prefix.trim_end_matches(|c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '$'));
+    let word = &prefix[before.len()..];
+    let start = position.character - word.encode_utf16().count() as u32;
+    let replace = Range::new(Position::new(position.line, start), position);
+
+    let schema = kind.schema();
+    let line = position.line as usize;
+    let mut lines: Vec<&str> = index.lines().to_vec();
+    let mut completion = Completion {
+        items: Vec::new(),
+        replace,
+    };
+
+    let Some(parsed) = Line::parse(before) else {
+        // Indentation only: a key of the mapping at this column.
+        let typed = format!("{}{}:", before, PLACEHOLDER);
+        lines[line] = &typed;
+        completion.keys(
+            &schema,
+            &lines,
+            Mapping {
+                line,
+                column: before.len(),
+            },
+        );
+        return completion.items;
+    };
+    if parsed.item && parsed.key.is_none() && parsed.value.is_empty() {
+        // `- `: a key of a new item, or an element.
+        let typed = format!("{}{}:", before, PLACEHOLDER);
+        lines[line] = &typed;
+        let mapping = Mapping {
+            line,
+            column: parsed.column,
+        };
+        completion.keys(&schema, &lines, mapping);
+        if let Some((_, key)) = mapping.parent(&lines) {
+            if EXPRESSION_LISTS.contains(&key) {
+                completion.names(text, line, true);
+            }
+        }
+        return completion.items;
+    }
+    let Some(key) = parsed.key else {
+        return completion.items;
+    };
+
+    let value = parsed.value;
+    if value.rfind('{') > value.rfind('}') {
+        completion.names(text, line, true);
+    } else if key == "element" {
+        completion.tags(&schema, false);
+    } else if STATE_KEYS.contains(&key) {
+        completion.names(text, line, false);
+    } else if EXPRESSION_KEYS.contains(&key) {
+        completion.names(text, line, true);
+    } else {
+        let mapping = Mapping {
+            line,
+            column: parsed.column,
+        };
+        let property = mapping_schema(&schema, &lines, mapping)
+            .and_then(|value| schema.property(value, &mapping.entries(&lines), key));
+        if let Some(property) = property {
+            for (value, description) in schema.values(property) {
+                completion.add(&value, &value, CompletionItemKind::ENUM_MEMBER, description);
+            }
+        }
+    }
+    completion.items
+}
+
+struct Completion {
+    items: Vec<CompletionItem>,
+    replace: Range,
+}
+
+impl Completion {
+    fn add(&mut self, label: &str, text: &str, kind: CompletionItemKind, docs: Option<&str>) {
+        self.items.push(CompletionItem {
+            label: label.to_string(),
+            kind: Some(kind),
+            documentation: docs.map(|docs| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: docs.to_string(),
+                })
+            }),
+            filter_text: Some(label.to_string()),
+            text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(
+                self.replace,
+                text.to_string(),
+            ))),
+            ..CompletionItem::default()
+        });
+    }
+
+    /// The keys `mapping` may still take, or the tags of the element it
+    /// starts.
+    fn keys(&mut self, schema: &FileSchema, lines: &[&str], mapping: Mapping) {
+        let Some(value) = mapping_schema(schema, lines, mapping) else {
+            return;
+        };
+        let entries: Vec<(&str, &str)> = mapping
+            .entries(lines)
+            .into_iter()
+            .filter(|(key, _)| *key != PLACEHOLDER)
+            .collect();
+        let properties = schema.properties(value, &entries);
+        if schema.is_element(value) && properties.is_empty() {
+            self.tags(schema, true);
+            self.add(
+                "element",
+                "element: ",
+                CompletionItemKind::PROPERTY,
+                Some("The tag of the element."),
+            );
+            return;
+        }
+        for (name, property) in properties {
+            if entries.iter().any(|(key, _)| *key == name) {
+                continue;
+            }
+            let docs = schema.description(property);
+            self.add(
+                name,
+                &format!("{}: ", name),
+                CompletionItemKind::PROPERTY,
+                docs,
+            );
+        }
+    }
+
+    /// Every element tag, as the key of a keyed element or as a value.
+    fn tags(&mut self, schema: &FileSchema, keyed: bool) {
+        for tag in Element::TAGS {
+            let text = if keyed {
+                format!("{}: ", tag)
+            } else {
+                tag.to_string()
+            };
+            let docs = schema.element_description(tag);
+            self.add(tag, &text, CompletionItemKind::CLASS, docs);
+        }
+    }
+
+    /// The states of the page or component around `line`, and its computed
+    /// values and props when `computed` is set.
+    fn names(&mut self, text: &str, line: usize, computed: bool) {
+        let index = SourceIndex::new(text, None);
+        let page = page_at(&index, line + 1);
+        let mut sections = vec![("state", CompletionItemKind::VARIABLE)];
+        if computed {
+            sections.push(("computed", CompletionItemKind::CONSTANT));
+            sections.push(("props", CompletionItemKind::PROPERTY));
+        }
+        for (section, kind) in sections {
+            for i in 0.. {
+                let entry = format!("{}.{}[{}]", page, section, i);
+                let Some(name) = index.scalar(&format!("{}.name", entry)) else {
+                    break;
+                };
+                let ty = index.scalar(&format!("{}.type", entry));
+                let docs = match (section, ty) {
+                    ("state", Some(ty)) => format!("state `{}`: `{}`", name, ty),
+                    ("state", None) => format!("state `{}`", name),
+                    ("props", Some(ty)) => format!("prop `{}`: `{}`", name, ty),
+                    ("props", None) => format!("prop `{}`", name),
+                    (_, _) => format!("computed value `{}`", name),
+                };
+                self.add(name, name, kind, Some(&docs));
+            }
+        }
+    }
+}
+
+/// The path of the `page:` mapping holding the 1-based `line`, or of the
+/// `component:` of a component file.
+pub fn page_at(index: &SourceIndex, line: usize) -> String {
+    if index.has_key("component") {
+        return "component".to_string();
+    }
+    if index.has_key("page") {
+        return "page".to_string();
+    }
+    let mut page = "page".to_string();
+    for i in 0.. {
+        let path = format!("pages[{}].page", i);
+        match index
+            .has_key(&path)
+            .then(|| index.key_span(&path))
+            .flatten()
+        {
+            Some(span) if span.line <= line => page = path,
+            _ => break,
+        }
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(text: &str) -> Vec<String> {
+        let cursor = text.find('|').unwrap();
+        let before = &text[..cursor];
+        let line = before.matches('\n').count() as u32;
+        let character = before.rsplit('\n').next().unwrap().chars().count() as u32;
+        let text = text.replace('|', "");
+        completions(FileKind::Page, &text, Position::new(line, character))
+            .into_iter()
+            .map(|item| item.label)
+            .collect()
+    }
+
+    const STATE: &str = "page:
+  name: Home
+  state:
+    - name: count
+      type: number
+    - name: open
+  computed:
+    - name: double
+      value: count * 2
+  children:
+";
+
+    #[test]
+    fn test_tags_and_keys() {
+        let tags = labels(&format!("{}    - |\n", STATE));
+        assert!(tags.contains(&"radio-group".to_string()));
+        assert!(tags.contains(&"element".to_string()));
+
+        let tags = labels(&format!("{}    - element: rad|\n", STATE));
+        assert_eq!(tags.len(), Element::TAGS.len());
+
+        let keys = labels(&format!(
+            "{}    - element: button\n      content: Go\n      |\n",
+            STATE
+        ));
+        assert!(keys.contains(&"onClick".to_string()));
+        assert!(!keys.contains(&"content".to_string()));
+        assert!(!keys.contains(&"element".to_string()));
+
+        let keys = labels(&format!("{}    - modal:\n        |\n", STATE));
+        assert!(keys.contains(&"isOpen".to_string()));
+
+        let values = labels("page:\n  name: A\n  layout:\n    type: |\n");
+        assert_eq!(values, ["column", "row", "stack", "grid"]);
+    }
+
+    #[test]
+    fn test_state_names() {
+        let names = labels(&format!("{}    - element: toggle\n      bind: o|\n", STATE));
+        assert_eq!(names, ["count", "open"]);
+
+        let names = labels(&format!("{}    - text: \"Total: {{dou|\"\n", STATE));
+        assert_eq!(names, ["count", "open", "double"]);
+
+        let names = labels(&format!("{}    - text: \"{{count}} |\"\n", STATE));
+        assert!(names.is_empty());
+
+        let component = "component:\n  name: Card\n  props:\n    - name: title\n  state:\n    - name: open\n  children:\n";
+        let names = labels(&format!("{}    - text: \"{{|\"\n", component));
+        assert_eq!(names, ["open", "title"]);
+        let names = labels(&format!(
+            "{}    - element: toggle\n      bind: |\n",
+            component
+        ));
+        assert_eq!(names, ["open"]);
+    }
+}
@@ -0,0 +1,276 @@
+//! What the server knows about an open file: which kind of project file it
+//! is, its problems, and where its pages name their states.
+
+use nwl_compiler::actions::Actions;
+use nwl_compiler::components::Components;
+use nwl_compiler::diagnostics::codes;
+use nwl_compiler::modules;
+use nwl_compiler::routes::{self, RouteEntry};
+use nwl_compiler::source_index::SourceIndex;
+use nwl_compiler::validate::{self, Reference};
+use nwl_compiler::{
+    load_actions, load_components, parse_actions, parse_component, parse_project, parse_source,
+    CompilerError, FileKind,
+};
+use nwl_shared::{Diagnostic, Page, RouteConfig, Span};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The closest directory above `path` holding an `nwl.yaml`.
+pub fn project_dir(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("nwl.yaml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// The actions and components of `project` as saved, or none outside a
+/// project.
+fn project_items(project: Option<&Path>) -> (Actions, Components) {
+    let Some(project) = project else {
+        return Default::default();
+    };
+    let actions = load_actions(project)
+        .map(|(actions, _)| actions)
+        .unwrap_or_default();
+    let components = load_components(project, &actions)
+        .map(|(components, _)| components)
+        .unwrap_or_default();
+    (actions, components)
+}
+
+/// The routes of `project`'s saved `nwl.yaml`, or none outside a project.
+fn project_routes(project: Option<&Path>) -> Vec<RouteConfig> {
+    project
+        .and_then(|project| fs::read_to_string(project.join("nwl.yaml")).ok())
+        .and_then(|text| parse_project(&text, None).ok())
+        .map(|config| config.routes)
+        .unwrap_or_default()
+}
+
+pub struct Analysis {
+    pub kind: FileKind,
+    pub index: SourceIndex,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Where each page of the file names its states, or the component its
+    /// props and states.
+    pub pages: Vec<Vec<Reference>>,
+}
+
+impl Analysis {
+    /// Parses and validates `text`, the contents of the file at `path`.
+    /// Pages and components are checked against the actions and components
+    /// of their project as saved, and the modules they import against its
+    /// files.
+    pub fn new(path: Option<&Path>, text: &str) -> Self {
+        let kind = FileKind::of(path);
+        let project = path.and_then(project_dir);
+        // Paths in messages are relative to the project, as in builds.
+        let source_path = match (path, &project) {
+            (Some(path), Some(project)) => Some(path.strip_prefix(project).unwrap_or(path)),
+            (path, _) => path.and_then(Path::file_name).map(Path::new),
+        };
+        let mut analysis = Analysis {
+            kind,
+            index: SourceIndex::new(text, source_path),
+            diagnostics: Vec::new(),
+            pages: Vec::new(),
+        };
+        match kind {
+            FileKind::Page => analysis.check_page(text, source_path, project.as_deref()),
+            FileKind::Project => analysis.check_project(text, source_path, project.as_deref()),
+            FileKind::Actions => analysis.check_actions(text, source_path),
+            FileKind::Component => analysis.check_component(text, source_path, project.as_deref()),
+        }
+        analysis
+    }
+
+    fn check_page(&mut self, text: &str, path: Option<&Path>, project: Option<&Path>) {
+        let document = match parse_source(text, path) {
+            Ok(document) => document,
+            Err(error) => return self.parse_error(error),
+        };
+        let (actions, components) = project_items(project);
+        let routes = project_routes(project);
+        let entries = routes::flatten(&routes);
+        // The routes rendering this file.
+        let served: Vec<&RouteEntry> = match (project, path) {
+            (Some(project), Some(path)) => entries
+                .iter()
+                .filter(|entry| {
+                    entry
+                        .route
+                        .file()
+                        .is_some_and(|file| project.join(file) == project.join(path))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for (i, page) in document.pages.iter().enumerate() {
+            let page_path = if self.index.has_key("page") {
+                "page".to_string()
+            } else {
+                format!("pages[{}].page", i)
+            };
+            let analysis = validate::analyze_page(
+                &page.page_data,
+                &page_path,
+                &self.index,
+                &actions,
+                &components,
+            );
+            self.diagnostics.extend(analysis.diagnostics);
+            self.pages.push(analysis.references);
+            self.diagnostics.extend(validate::validate_params(
+                &page.page_data,
+                &page_path,
+                &served,
+                &self.index,
+            ));
+            if let Some(project) = project {
+                self.diagnostics.extend(modules::check_modules(
+                    &page.page_data.children,
+                    &format!("{}.children", page_path),
+                    &self.index,
+                    project,
+                ));
+                self.diagnostics.extend(routes::check_links(
+                    &page.page_data.children,
+                    &format!("{}.children", page_path),
+                    &self.index,
+                    &routes,
+                ));
+            }
+        }
+    }
+
+    fn check_component(&mut self, text: &str, path: Option<&Path>, project: Option<&Path>) {
+        let file = match parse_component(text, path) {
+            Ok(file) => file,
+            Err(error) => return self.parse_error(error),
+        };
+        let (actions, components) = project_items(project);
+        let analysis =
+            validate::analyze_component(&file.component, &self.index, &actions, &components);
+        self.diagnostics.extend(analysis.diagnostics);
+        self.pages.push(analysis.references);
+        if let Some(project) = project {
+            self.diagnostics.extend(modules::check_modules(
+                &file.component.children,
+                "component.children",
+                &self.index,
+                project,
+            ));
+            self.diagnostics.extend(routes::check_links(
+                &file.component.children,
+                "component.children",
+                &self.index,
+                &project_routes(Some(project)),
+            ));
+        }
+    }
+
+    fn check_project(&mut self, text: &str, path: Option<&Path>, project: Option<&Path>) {
+        let config = match parse_project(text, path) {
+            Ok(config) => config,
+            Err(error) => return self.parse_error(error),
+        };
+        self.diagnostics
+            .extend(validate::validate_project(&config, &self.index));
+        let Some(project) = project.or_else(|| path.and_then(Path::parent)) else {
+            return;
+        };
+        for entry in routes::flatten(&config.routes) {
+            let Some(file) = entry.route.file() else {
+                continue;
+            };
+            let key = if entry.route.page.is_some() {
+                "page"
+            } else {
+                "layout"
+            };
+            if let Some(page) = self.load_page(project, file, &format!("{}.{}", entry.key, key)) {
+                self.diagnostics.extend(validate::validate_route(
+                    &entry,
+                    &page.page_data,
+                    &self.index,
+                ));
+            }
+        }
+        if let Some(file) = config
+            .fallback
+            .as_ref()
+            .and_then(|fallback| fallback.page.as_deref())
+        {
+            if let Some(page) = self.load_page(project, file, "fallback.page") {
+                self.diagnostics
+                    .extend(validate::validate_fallback(&page.page_data, &self.index));
+            }
+        }
+    }
+
+    /// The page file `file` of `project`, named at `path` in `nwl.yaml`,
+    /// reporting it there when it doesn't exist.
+    fn load_page(&mut self, project: &Path, file: &str, path: &str) -> Option<Page> {
+        if let Ok(page) = fs::read_to_string(project.join(file)) {
+            // Pages that don't parse are reported when they're opened.
+            return parse_source(&page, None)
+                .ok()
+                .and_then(|document| document.pages.into_iter().next());
+        }
+        let diagnostic = Diagnostic::error(
+            codes::PAGE_NOT_FOUND,
+            format!("page file `{}` not found", file),
+        )
+        .with_label("no such file in the project")
+        .with_help("page paths are relative to `nwl.yaml`, e.g. `pages/home.yaml`");
+        self.diagnostics.push(self.index.locate(diagnostic, path));
+        None
+    }
+
+    fn check_actions(&mut self, text: &str, path: Option<&Path>) {
+        match parse_actions(text, path) {
+            Ok(file) => self.diagnostics.extend(validate::validate_actions(
+                &file,
+                &self.index,
+                &mut Actions::default(),
+            )),
+            Err(error) => self.parse_error(error),
+        }
+    }
+
+    fn parse_error(&mut self, error: CompilerError) {
+        if let CompilerError::Parse(diagnostic) = error {
+            self.diagnostics.push(*diagnostic);
+        }
+    }
+
+    /// The position of the name `reference` is written with, when it can be
+    /// pointed at exactly.
+    pub fn span(&self, reference: &Reference) -> Option<Span> {
+        if reference.key {
+            return self
+                .index
+                .has_key(&reference.path)
+                .then(|| self.index.key_span(&reference.path))
+                .flatten();
+        }
+        self.index.span_in_value(
+            &reference.path,
+            reference.offset,
+            reference.name.chars().count(),
+        )
+    }
+
+    /// The reference at the 1-based `line` and `column`, and the page it is
+    /// on.
+    pub fn reference_at(&self, at: (usize, usize)) -> Option<(&[Reference], &Reference)> {
+        self.pages.iter().find_map(|page| {
+            let reference = page.iter().find(|reference| {
+                self.span(reference)
+                    .is_some_and(|span| crate::position::contains(&span, at))
+            })?;
+            Some((page.as_slice(), reference))
+        })
+    }
+}
@@ -0,0 +1,105 @@
+//! Documentation of elements and keys under the cursor.
+
+use crate::outline::{Line, Mapping};
+use crate::position::LineIndex;
+use crate::schema::mapping_schema;
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+use nwl_compiler::FileKind;
+use nwl_shared::Element;
+
+/// The docs of the element whose tag is under the cursor, or of the key
+/// under it.
+pub fn hover(kind: FileKind, text: &str, position: Position) -> Option<Hover> {
+    let index = LineIndex::new(text);
+    let position = index.clamp(position)?;
+    let line = position.line as usize;
+    let text_line = index.line(line);
+    let parsed = Line::parse(text_line)?;
+    let key = parsed.key?;
+    let (_, column) = index.location(position);
+    let offset = text_line
+        .char_indices()
+        .nth(column - 1)
+        .map_or(text_line.len(), |(i, _)| i);
+
+    let schema = kind.schema();
+    let lines = index.lines();
+    let mapping = Mapping {
+        line,
+        column: parsed.column,
+    };
+    let value = mapping_schema(&schema, lines, mapping)?;
+    let key_end = parsed.column + key.len();
+    let (docs, start, end) = if (parsed.column..=key_end).contains(&offset) {
+        let docs = if key == "element" {
+            schema.element_docs(parsed.value)
+        } else if schema.is_element(value) && Element::TAGS.contains(&key) {
+            schema.element_docs(key)
+        } else {
+            let property = schema.property(value, &mapping.entries(lines), key)?;
+            schema.description(property).map(str::to_string)
+        };
+        (docs?, parsed.column, key_end)
+    } else if key == "element" && !parsed.value.is_empty() {
+        let start = text_line.rfind(parsed.value)?;
+        let end = start + parsed.value.len();
+        if !(start..=end).contains(&offset) {
+            return None;
+        }
+        (schema.element_docs(parsed.value)?, start, end)
+    } else {
+        return None;
+    };
+
+    let column = |offset: usize| text_line[..offset].chars().count() + 1;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: docs,
+        }),
+        range: Some(Range::new(
+            index.position(line + 1, column(start)),
+            index.position(line + 1, column(end)),
+        )),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "page:
+  name: Home
+  children:
+    - element: button
+      content: Go
+    - slider:
+        min: 0
+";
+
+    fn docs(line: u32, character: u32) -> Option<String> {
+        let hover = hover(FileKind::Page, PAGE, Position::new(line, character))?;
+        match hover.contents {
+            HoverContents::Markup(markup) => Some(markup.value),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_element_docs() {
+        let button = docs(3, 18).unwrap();
+        assert!(button.starts_with("**button**"));
+        assert_eq!(docs(3, 8), Some(button));
+        assert!(docs(5, 8).unwrap().starts_with("**slider**"));
+    }
+
+    #[test]
+    fn test_key_docs() {
+        assert_eq!(
+            docs(1, 3).as_deref(),
+            Some("Name of the page. The generated component is named after it.")
+        );
+        assert!(docs(4, 7).is_some());
+        assert_eq!(docs(4, 16), None);
+    }
+}
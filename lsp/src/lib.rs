@@ -0,0 +1,31 @@
+//! A language server for NWL projects, spoken over stdio by `nwl lsp`. It
+//! checks pages, `nwl.yaml` and action files as they are typed, completes
+//! keys, element tags and state names, documents elements on hover, goes to
+//! the declaration of states and the files of routes, and renames states.
+
+mod completion;
+mod document;
+mod hover;
+mod navigation;
+mod outline;
+mod position;
+mod schema;
+mod server;
+
+use lsp_server::Connection;
+use server::{capabilities, Server};
+
+/// Serves the client on stdin and stdout until it exits.
+pub fn run_stdio() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(connection)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Initializes the client on `connection` and serves it until it shuts the
+/// server down.
+pub fn serve(connection: Connection) -> anyhow::Result<()> {
+    connection.initialize(serde_json::to_value(capabilities())?)?;
+    Server::new(connection).run()
+}
@@ -0,0 +1,103 @@
+//! Going from a state to where it is declared, from a route to its page
+//! file, and renaming states.
+
+use crate::document::Analysis;
+use crate::position::{contains, LineIndex};
+use lsp_types::{Location, Position, Range, TextEdit, Url, WorkspaceEdit};
+use nwl_compiler::FileKind;
+use std::collections::HashMap;
+
+/// Where the name under the cursor is declared.
+pub fn definition(
+    uri: &Url,
+    analysis: &Analysis,
+    text: &str,
+    position: Position,
+) -> Option<Location> {
+    let index = LineIndex::new(text);
+    let at = index.location(index.clamp(position)?);
+    match analysis.kind {
+        FileKind::Page | FileKind::Component => {
+            let (page, reference) = analysis.reference_at(at)?;
+            let declaration = page
+                .iter()
+                .find(|other| other.declaration && other.name == reference.name)?;
+            let span = analysis.span(declaration)?;
+            Some(Location::new(uri.clone(), index.range(&span)))
+        }
+        FileKind::Project => {
+            let route = (0..)
+                .map(|i| format!("routes[{}].page", i))
+                .take_while(|path| analysis.index.has_key(path))
+                .find(|path| {
+                    analysis
+                        .index
+                        .value_span(path)
+                        .is_some_and(|span| contains(&span, at))
+                })?;
+            let page = analysis.index.scalar(&route)?;
+            let file = uri.to_file_path().ok()?.parent()?.join(page);
+            if !file.is_file() {
+                return None;
+            }
+            let target = Url::from_file_path(file).ok()?;
+            Some(Location::new(target, Range::default()))
+        }
+        FileKind::Actions => None,
+    }
+}
+
+/// Renames the state or computed value under the cursor everywhere on its
+/// page. Fails with a message when it can't be renamed to `new_name`.
+pub fn rename(
+    uri: &Url,
+    analysis: &Analysis,
+    text: &str,
+    position: Position,
+    new_name: &str,
+) -> Result<Option<WorkspaceEdit>, String> {
+    let index = LineIndex::new(text);
+    let Some((page, reference)) = index
+        .clamp(position)
+        .and_then(|position| analysis.reference_at(index.location(position)))
+    else {
+        return Ok(None);
+    };
+    if !is_name(new_name) {
+        return Err(format!("`{}` is not a valid state name", new_name));
+    }
+    if new_name != reference.name
+        && page
+            .iter()
+            .any(|other| other.declaration && other.name == new_name)
+    {
+        return Err(format!("`{}` is already declared on this page", new_name));
+    }
+
+    let mut edits: Vec<TextEdit> = Vec::new();
+    for other in page.iter().filter(|other| other.name == reference.name) {
+        let Some(span) = analysis.span(other) else {
+            return Err(format!(
+                "`{}` is used in a value that can't be edited in place",
+                reference.name
+            ));
+        };
+        let edit = TextEdit::new(index.range(&span), new_name.to_string());
+        if !edits.contains(&edit) {
+            edits.push(edit);
+        }
+    }
+    Ok(Some(WorkspaceEdit {
+        changes: Some(HashMap::from([(uri.clone(), edits)])),
+        ..WorkspaceEdit::default()
+    }))
+}
+
+/// Whether `name` can name a state: an identifier in expressions.
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '_' | '$'))
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '$'))
+}
@@ -0,0 +1,218 @@
+//! Where the cursor is in a YAML file that is being typed and may not
+//! parse, judged from indentation alone: the mappings around it and the
+//! keys leading to it. Flow style (`{ … }`, `[ … ]`) isn't followed.
+
+/// The parts of a line that matter here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line<'a> {
+    pub indent: usize,
+    /// Whether the line starts a sequence item, `- …`.
+    pub item: bool,
+    /// Column of the key, or of the value when there is none, after any `- `.
+    pub column: usize,
+    pub key: Option<&'a str>,
+    /// Whatever follows the key's colon, or the whole item without a key.
+    pub value: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// Splits a line, or returns `None` for blank lines and comments.
+    pub fn parse(text: &'a str) -> Option<Line<'a>> {
+        let rest = text.trim_start_matches(' ');
+        let indent = text.len() - rest.len();
+        if rest.trim().is_empty() || rest.starts_with('#') {
+            return None;
+        }
+        let (item, body) = match rest.strip_prefix('-') {
+            Some(after) if after.is_empty() || after.starts_with(' ') => (true, after.trim_start()),
+            _ => (false, rest),
+        };
+        let column = indent + (rest.len() - body.len());
+        let (key, value) = match split_key(body) {
+            Some((key, value)) => (Some(key), value.trim()),
+            None => (None, body.trim()),
+        };
+        Some(Line {
+            indent,
+            item,
+            column,
+            key,
+            value,
+        })
+    }
+}
+
+/// Splits `key: value` into its key and value.
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let end = text.find(':')?;
+    let key = &text[..end];
+    let after = &text[end + 1..];
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '$'))
+        && (after.is_empty() || after.starts_with(' '));
+    is_key.then_some((key, after))
+}
+
+/// A block mapping, known by the column of its keys and a line holding one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapping {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Mapping {
+    /// Whether the mapping is an item of a sequence, which starts with `- `.
+    pub fn is_item(&self, lines: &[&str]) -> bool {
+        self.first_line(lines)
+            .and_then(|line| Line::parse(lines[line]))
+            .is_some_and(|line| line.item && line.column == self.column)
+    }
+
+    /// The keys of the mapping and their values as written on the key's
+    /// line, in order.
+    pub fn entries<'a>(&self, lines: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        let Some(first) = self.first_line(lines) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for (i, text) in lines.iter().enumerate().skip(first) {
+            let Some(line) = Line::parse(text) else {
+                continue;
+            };
+            if i > first && (line.indent < self.column || line.item && line.column <= self.column) {
+                break;
+            }
+            if line.column == self.column {
+                if let Some(key) = line.key {
+                    entries.push((key, line.value));
+                }
+            }
+        }
+        entries
+    }
+
+    /// The line the mapping starts on.
+    fn first_line(&self, lines: &[&str]) -> Option<usize> {
+        let mut first = None;
+        for i in (0..=self.line.min(lines.len().checked_sub(1)?)).rev() {
+            let Some(line) = Line::parse(lines[i]) else {
+                continue;
+            };
+            if line.column == self.column {
+                first = Some(i);
+                if line.item {
+                    break;
+                }
+            } else if line.column < self.column {
+                break;
+            }
+        }
+        first
+    }
+
+    /// The mapping holding this one and the key this one is the value of,
+    /// or an item of the value of.
+    pub fn parent<'a>(&self, lines: &[&'a str]) -> Option<(Mapping, &'a str)> {
+        let first = self.first_line(lines)?;
+        let start = Line::parse(lines[first])?;
+        // Items of a sequence are indented past its key, or as far.
+        let (limit, sequence) = if start.item && start.column == self.column {
+            (start.indent, true)
+        } else {
+            (self.column, false)
+        };
+        for i in (0..first).rev() {
+            let Some(line) = Line::parse(lines[i]) else {
+                continue;
+            };
+            let is_parent = line.column < limit || sequence && line.column == limit && !line.item;
+            if !is_parent {
+                continue;
+            }
+            let key = line.key?;
+            return Some((
+                Mapping {
+                    line: i,
+                    column: line.column,
+                },
+                key,
+            ));
+        }
+        None
+    }
+
+    /// The keys leading from the top of the document to this mapping, each
+    /// with the mapping it belongs to, outermost first.
+    pub fn ancestors<'a>(&self, lines: &[&'a str]) -> Vec<(Mapping, &'a str)> {
+        let mut ancestors = Vec::new();
+        let mut current = *self;
+        while let Some((parent, key)) = current.parent(lines) {
+            ancestors.push((parent, key));
+            current = parent;
+        }
+        ancestors.reverse();
+        ancestors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "page:
+  name: Home
+  children:
+    - element: card
+      children:
+        - heading:
+            content: Hi
+        - element: button
+
+          content: Go
+";
+
+    #[test]
+    fn test_line() {
+        let line = Line::parse("    - element: card").unwrap();
+        assert_eq!((line.indent, line.item, line.column), (4, true, 6));
+        assert_eq!((line.key, line.value), (Some("element"), "card"));
+
+        let line = Line::parse("      - x").unwrap();
+        assert_eq!((line.key, line.value), (None, "x"));
+        assert_eq!(Line::parse("  url: http://a").unwrap().key, Some("url"));
+        assert_eq!(Line::parse("  # comment"), None);
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let lines: Vec<&str> = PAGE.lines().collect();
+        let content = Mapping {
+            line: 9,
+            column: 10,
+        };
+        assert!(content.is_item(&lines));
+        assert_eq!(
+            content.entries(&lines),
+            [("element", "button"), ("content", "Go")]
+        );
+        let keys: Vec<&str> = content
+            .ancestors(&lines)
+            .into_iter()
+            .map(|(_, key)| key)
+            .collect();
+        assert_eq!(keys, ["page", "children", "children"]);
+
+        let heading = Mapping {
+            line: 6,
+            column: 12,
+        };
+        assert!(!heading.is_item(&lines));
+        let ancestors = heading.ancestors(&lines);
+        let keys: Vec<&str> = ancestors.iter().map(|(_, key)| *key).collect();
+        assert_eq!(keys, ["page", "children", "children", "heading"]);
+        assert!(ancestors[3].0.is_item(&lines));
+        assert_eq!(ancestors[1].0.entries(&lines)[0], ("name", "Home"));
+    }
+}
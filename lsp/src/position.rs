@@ -0,0 +1,113 @@
+//! Positions in a document. The compiler counts lines and columns from 1
+//! and columns in characters; LSP counts both from 0 and columns in UTF-16
+//! code units.
+
+use lsp_types::{Position, Range};
+use nwl_shared::Span;
+
+/// The lines of a document, for converting between the two.
+pub struct LineIndex<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        LineIndex {
+            lines: text
+                .split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .collect(),
+        }
+    }
+
+    /// The 0-based `line`, or an empty line past the end.
+    pub fn line(&self, line: usize) -> &'a str {
+        self.lines.get(line).copied().unwrap_or("")
+    }
+
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    /// `position` moved back to the end of its line when it is past it, or
+    /// `None` when its line is past the end of the document. Editors may
+    /// ask about positions in a version of the document before an edit.
+    pub fn clamp(&self, position: Position) -> Option<Position> {
+        let text = self.lines.get(position.line as usize)?;
+        let units = text.encode_utf16().count() as u32;
+        Some(Position::new(position.line, position.character.min(units)))
+    }
+
+    /// The LSP position of the 1-based `line` and character `column`.
+    pub fn position(&self, line: usize, column: usize) -> Position {
+        let text = self.line(line.saturating_sub(1));
+        let column = column.saturating_sub(1);
+        let units: usize = text.chars().take(column).map(char::len_utf16).sum();
+        // Columns past the end of the line, such as the end of a multi-line
+        // value, count one unit each.
+        let past = column.saturating_sub(text.chars().count());
+        Position::new(line.saturating_sub(1) as u32, (units + past) as u32)
+    }
+
+    /// The range `span` covers on its line.
+    pub fn range(&self, span: &Span) -> Range {
+        Range::new(
+            self.position(span.line, span.column),
+            self.position(span.line, span.column + span.length),
+        )
+    }
+
+    /// The 1-based line and character column of an LSP position.
+    pub fn location(&self, position: Position) -> (usize, usize) {
+        let text = self.line(position.line as usize);
+        let mut units = 0;
+        let mut column = 0;
+        for c in text.chars() {
+            if units >= position.character as usize {
+                break;
+            }
+            units += c.len_utf16();
+            column += 1;
+        }
+        (position.line as usize + 1, column + 1)
+    }
+
+    /// The text of the 0-based `line` before the character `position` points
+    /// at.
+    pub fn prefix(&self, position: Position) -> &'a str {
+        let text = self.line(position.line as usize);
+        let (_, column) = self.location(position);
+        let end = text
+            .char_indices()
+            .nth(column - 1)
+            .map_or(text.len(), |(i, _)| i);
+        &text[..end]
+    }
+}
+
+/// Whether the 1-based `line` and `column` fall on `span`, its end included
+/// so a cursor right after a name still counts.
+pub fn contains(span: &Span, (line, column): (usize, usize)) -> bool {
+    span.line == line && (span.column..=span.column + span.length).contains(&column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_count_utf16_units() {
+        let index = LineIndex::new("page:\n  name: \"😀 {count}\"\n");
+        let span = Span::new(2, 12).with_length(5);
+        let range = index.range(&span);
+        assert_eq!(range.start, Position::new(1, 12));
+        assert_eq!(range.end, Position::new(1, 17));
+        assert_eq!(index.location(Position::new(1, 12)), (2, 12));
+        assert_eq!(index.prefix(Position::new(1, 12)), "  name: \"😀 ");
+        assert_eq!(
+            index.clamp(Position::new(1, 40)),
+            Some(Position::new(1, 20))
+        );
+        assert_eq!(index.clamp(Position::new(3, 0)), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! Completion and hover read the JSON Schema of the file being edited, the
+//! same one `nwl schema` prints, so they know every key the compiler does.
+
+use crate::outline::Mapping;
+use nwl_shared::FileSchema;
+use serde_json::Value;
+
+/// The schema of the value of `mapping`, found by following the keys
+/// leading to it from the top of the document.
+pub fn mapping_schema<'s>(
+    schema: &'s FileSchema,
+    lines: &[&str],
+    mapping: Mapping,
+) -> Option<&'s Value> {
+    let ancestors = mapping.ancestors(lines);
+    let mut value = schema.root();
+    for (i, (parent, key)) in ancestors.iter().enumerate() {
+        let entries = parent.entries(lines);
+        value = schema.property(value, &entries, key)?;
+        let child = ancestors.get(i + 1).map_or(mapping, |(child, _)| *child);
+        if child.is_item(lines) {
+            value = schema.items(value)?;
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nwl_compiler::FileKind;
+
+    fn keys(text: &str, line: usize, column: usize) -> Vec<String> {
+        let schema = FileKind::Page.schema();
+        let lines: Vec<&str> = text.lines().collect();
+        let mapping = Mapping { line, column };
+        let value = mapping_schema(&schema, &lines, mapping).unwrap();
+        let entries = mapping.entries(&lines);
+        schema
+            .properties(value, &entries)
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_properties_follow_the_document() {
+        let page = "page:
+  name: Home
+  layout:
+    type: row
+  children:
+    - element: button
+      content: Go
+      onClick:
+        - set: count
+    - heading:
+        content: Hi
+";
+        assert_eq!(keys(page, 0, 0), ["page"]);
+        assert!(keys(page, 1, 2).contains(&"state".to_string()));
+        assert!(keys(page, 3, 4).contains(&"columns".to_string()));
+        let button = keys(page, 5, 6);
+        assert!(button.contains(&"onClick".to_string()));
+        assert!(!button.contains(&"level".to_string()));
+        assert_eq!(keys(page, 8, 10), ["set", "to"]);
+        assert_eq!(
+            keys(page, 10, 8),
+            ["content", "style", "if", "show", "else"]
+        );
+    }
+}
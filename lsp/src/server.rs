@@ -0,0 +1,262 @@
+//! The message loop: keeps the open files, publishes their diagnostics as
+//! they change and answers requests about them.
+
+use crate::completion::completions;
+use crate::document::Analysis;
+use crate::hover::hover;
+use crate::navigation::{definition, rename};
+use crate::position::LineIndex;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Rename, Request as _};
+use lsp_types::{
+    CompletionOptions, CompletionParams, DiagnosticSeverity, GotoDefinitionParams, HoverParams,
+    NumberOrString, OneOf, PublishDiagnosticsParams, RenameParams, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use nwl_shared::{Diagnostic, Severity};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".into(), " ".into(), "{".into()]),
+            ..CompletionOptions::default()
+        }),
+        hover_provider: Some(true.into()),
+        definition_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    }
+}
+
+struct Document {
+    text: String,
+    version: i32,
+    analysis: Analysis,
+}
+
+impl Document {
+    fn new(uri: &Url, text: String, version: i32) -> Self {
+        let path = uri.to_file_path().ok();
+        let analysis = Analysis::new(path.as_deref(), &text);
+        Document {
+            text,
+            version,
+            analysis,
+        }
+    }
+}
+
+pub struct Server {
+    connection: Connection,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    pub fn new(connection: Connection) -> Self {
+        Server {
+            connection,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Handles messages until the client shuts the server down.
+    pub fn run(mut self) -> anyhow::Result<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            Completion::METHOD => self.respond(id, request, |server, params: CompletionParams| {
+                let position = params.text_document_position;
+                let items = server
+                    .document(&position.text_document.uri)
+                    .map(|(_, document)| {
+                        completions(document.analysis.kind, &document.text, position.position)
+                    });
+                Ok(items)
+            }),
+            HoverRequest::METHOD => self.respond(id, request, |server, params: HoverParams| {
+                let position = params.text_document_position_params;
+                Ok(server
+                    .document(&position.text_document.uri)
+                    .and_then(|(_, document)| {
+                        hover(document.analysis.kind, &document.text, position.position)
+                    }))
+            }),
+            GotoDefinition::METHOD => {
+                self.respond(id, request, |server, params: GotoDefinitionParams| {
+                    let position = params.text_document_position_params;
+                    Ok(server
+                        .document(&position.text_document.uri)
+                        .and_then(|(uri, document)| {
+                            definition(uri, &document.analysis, &document.text, position.position)
+                        }))
+                })
+            }
+            Rename::METHOD => self.respond(id, request, |server, params: RenameParams| {
+                let position = params.text_document_position;
+                let Some((uri, document)) = server.document(&position.text_document.uri) else {
+                    return Ok(None);
+                };
+                rename(
+                    uri,
+                    &document.analysis,
+                    &document.text,
+                    position.position,
+                    &params.new_name,
+                )
+            }),
+            method => Response::new_err(
+                id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unhandled method `{}`", method),
+            ),
+        }
+    }
+
+    /// Answers `request` with `handle`, whose errors are reported to the
+    /// user as invalid requests.
+    fn respond<P, R>(
+        &self,
+        id: RequestId,
+        request: Request,
+        handle: impl FnOnce(&Self, P) -> Result<R, String>,
+    ) -> Response
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+    {
+        let params = match serde_json::from_value(request.params) {
+            Ok(params) => params,
+            Err(error) => {
+                return Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string())
+            }
+        };
+        match handle(self, params) {
+            Ok(result) => Response::new_ok(id, result),
+            Err(message) => Response::new_err(id, ErrorCode::InvalidRequest as i32, message),
+        }
+    }
+
+    fn document(&self, uri: &Url) -> Option<(&Url, &Document)> {
+        self.documents.get_key_value(uri)
+    }
+
+    fn notification(&mut self, notification: Notification) -> anyhow::Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let document = params.text_document;
+                self.open(document.uri, document.text, document.version)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                // Changes are whole documents; the last one is current.
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(());
+                };
+                let document = params.text_document;
+                self.open(document.uri, change.text, document.version)
+            }
+            DidSaveTextDocument::METHOD => {
+                // Pages are checked against the actions on disk, so any
+                // save may change the problems of every open file.
+                let uris: Vec<Url> = self.documents.keys().cloned().collect();
+                for uri in uris {
+                    let document = self.documents.remove(&uri).expect("listed above");
+                    self.open(uri, document.text, document.version)?;
+                }
+                Ok(())
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.publish(uri, Vec::new(), None)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the text of a document and publishes its diagnostics.
+    fn open(&mut self, uri: Url, text: String, version: i32) -> anyhow::Result<()> {
+        let document = Document::new(&uri, text, version);
+        let index = LineIndex::new(&document.text);
+        let diagnostics = document
+            .analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| to_lsp(diagnostic, &index))
+            .collect();
+        self.documents.insert(uri.clone(), document);
+        self.publish(uri, diagnostics, Some(version))
+    }
+
+    fn publish(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> anyhow::Result<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, version);
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+}
+
+/// A compiler diagnostic as the editor shows it. The label, notes and help
+/// follow the message on lines of their own.
+fn to_lsp(diagnostic: &Diagnostic, index: &LineIndex) -> lsp_types::Diagnostic {
+    let range = diagnostic
+        .span
+        .as_ref()
+        .map(|span| index.range(span))
+        .unwrap_or_default();
+    let mut message = diagnostic.message.clone();
+    if let Some(label) = &diagnostic.label {
+        message.push_str(&format!("\n{}", label));
+    }
+    for note in &diagnostic.notes {
+        message.push_str(&format!("\nnote: {}", note));
+    }
+    if let Some(help) = &diagnostic.help {
+        message.push_str(&format!("\nhelp: {}", help));
+    }
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        code: Some(NumberOrString::String(diagnostic.code.to_string())),
+        source: Some("nwl".to_string()),
+        message,
+        ..lsp_types::Diagnostic::default()
+    }
+}
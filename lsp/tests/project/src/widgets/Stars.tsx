@@ -0,0 +1,3 @@
+export default function Stars({ count }: { count: number }) {
+  return <span>{"★".repeat(count)}</span>;
+}
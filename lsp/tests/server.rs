@@ -0,0 +1,426 @@
+//! Drives the language server in-process, as an editor would, against the
+//! project in `tests/project/`. Open documents are sent with their text, so
+//! edits never touch the files.
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, GotoDefinition, HoverRequest, Initialize, Rename, Request as _, Shutdown,
+};
+use lsp_types::{
+    CompletionResponse, Diagnostic, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
+    GotoDefinitionResponse, HoverContents, InitializeParams, Position, PublishDiagnosticsParams,
+    Range, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+    TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::thread::{self, JoinHandle};
+
+struct Client {
+    connection: Connection,
+    server: JoinHandle<anyhow::Result<()>>,
+    /// Notifications received while waiting for a response.
+    notifications: VecDeque<Notification>,
+    next_id: i32,
+}
+
+impl Client {
+    fn start() -> Self {
+        let (server, connection) = Connection::memory();
+        let server = thread::spawn(move || nwl_lsp::serve(server));
+        let mut client = Client {
+            connection,
+            server,
+            notifications: VecDeque::new(),
+            next_id: 0,
+        };
+        let result = client
+            .request(Initialize::METHOD, InitializeParams::default())
+            .unwrap();
+        assert_eq!(result["capabilities"]["renameProvider"], true);
+        client.notify(Initialized::METHOD, json!({}));
+        client
+    }
+
+    fn request(&mut self, method: &str, params: impl serde::Serialize) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        let request = Request::new(id.clone(), method.to_string(), params);
+        self.connection.sender.send(request.into()).unwrap();
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(Response {
+                    id: response_id,
+                    result,
+                    error,
+                }) if response_id == id => {
+                    return match error {
+                        Some(error) => Err(error.message),
+                        None => Ok(result.unwrap_or(Value::Null)),
+                    };
+                }
+                Message::Notification(notification) => self.notifications.push_back(notification),
+                message => panic!("unexpected message {:?}", message),
+            }
+        }
+    }
+
+    fn notify(&self, method: &str, params: impl serde::Serialize) {
+        let notification = Notification::new(method.to_string(), params);
+        self.connection.sender.send(notification.into()).unwrap();
+    }
+
+    /// The next diagnostics published for `uri`.
+    fn diagnostics(&mut self, uri: &Url) -> Vec<Diagnostic> {
+        loop {
+            let notification = match self.notifications.pop_front() {
+                Some(notification) => notification,
+                None => match self.connection.receiver.recv().unwrap() {
+                    Message::Notification(notification) => notification,
+                    message => panic!("unexpected message {:?}", message),
+                },
+            };
+            if notification.method != PublishDiagnostics::METHOD {
+                continue;
+            }
+            let params: PublishDiagnosticsParams =
+                serde_json::from_value(notification.params).unwrap();
+            if params.uri == *uri {
+                return params.diagnostics;
+            }
+        }
+    }
+
+    fn open(&mut self, uri: &Url, text: &str) -> Vec<Diagnostic> {
+        let document = TextDocumentItem::new(uri.clone(), "yaml".into(), 1, text.into());
+        self.notify(
+            DidOpenTextDocument::METHOD,
+            DidOpenTextDocumentParams {
+                text_document: document,
+            },
+        );
+        self.diagnostics(uri)
+    }
+
+    fn change(&mut self, uri: &Url, version: i32, text: &str) -> Vec<Diagnostic> {
+        self.notify(
+            DidChangeTextDocument::METHOD,
+            DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier::new(uri.clone(), version),
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: text.into(),
+                }],
+            },
+        );
+        self.diagnostics(uri)
+    }
+
+    fn at(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+        TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(uri.clone()),
+            Position::new(line, character),
+        )
+    }
+
+    fn completion(&mut self, uri: &Url, line: u32, character: u32) -> Vec<String> {
+        let params = json!(Client::at(uri, line, character));
+        let result = self.request(Completion::METHOD, params).unwrap();
+        match serde_json::from_value(result).unwrap() {
+            Some(CompletionResponse::Array(items)) => {
+                items.into_iter().map(|item| item.label).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn shutdown(mut self) {
+        self.request(Shutdown::METHOD, ()).unwrap();
+        self.notify(Exit::METHOD, ());
+        self.server.join().unwrap().unwrap();
+    }
+}
+
+fn project(file: &str) -> (Url, String) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/project")
+        .join(file);
+    let text = std::fs::read_to_string(&path).unwrap();
+    (Url::from_file_path(path).unwrap(), text)
+}
+
+#[test]
+fn diagnostics_follow_edits() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    assert_eq!(client.open(&uri, &text), []);
+
+    // Checked against the project's actions.
+    let text = text.replace("with: [draft]", "with: [draft, 1]");
+    let diagnostics = client.change(&uri, 2, &text);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].code,
+        Some(lsp_types::NumberOrString::String("E0118".into()))
+    );
+    assert_eq!(
+        diagnostics[0].range,
+        Range::new(Position::new(15, 16), Position::new(15, 23))
+    );
+
+    let text = text.replace("bind: draft", "bind: drat");
+    let diagnostics = client.change(&uri, 3, &text);
+    let undeclared = &diagnostics[0];
+    assert_eq!(
+        undeclared.range,
+        Range::new(Position::new(11, 12), Position::new(11, 16))
+    );
+    assert!(undeclared
+        .message
+        .starts_with("`bind` refers to undeclared state `drat`\nnot declared in `state:`"));
+
+    let diagnostics = client.change(&uri, 4, "page:\n  name: Home\n  children: [\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].source.as_deref(), Some("nwl"));
+
+    let (config, text) = project("nwl.yaml");
+    assert_eq!(client.open(&config, &text), []);
+    let text = format!("{}  - path: /about\n    page: pages/about.yaml\n", text);
+    let diagnostics = client.change(&config, 2, &text);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message.lines().next(),
+        Some("page file `pages/about.yaml` not found")
+    );
+    assert_eq!(diagnostics[0].range.start, Position::new(6, 10));
+
+    client.shutdown();
+}
+
+#[test]
+fn components_are_checked() {
+    let mut client = Client::start();
+    let (uri, text) = project("components/todo-item.yaml");
+    assert_eq!(client.open(&uri, &text), []);
+
+    let diagnostics = client.change(&uri, 2, &text.replace("{title}", "{titel}"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start, Position::new(8, 14));
+
+    // Pages are checked against the project's components.
+    let (page, text) = project("pages/home.yaml");
+    let text = format!(
+        "{}    - use: TodoItem\n      props:\n        title: \"{{draft}}\"\n",
+        text
+    );
+    assert_eq!(client.open(&page, &text), []);
+    let diagnostics = client.change(&page, 2, &text.replace("title:", "name:"));
+    let codes: Vec<_> = diagnostics.iter().map(|d| d.code.clone()).collect();
+    assert_eq!(
+        codes,
+        [
+            Some(lsp_types::NumberOrString::String("E0126".into())),
+            Some(lsp_types::NumberOrString::String("E0126".into())),
+        ]
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn imported_modules_are_checked() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    let text = format!(
+        "{}    - react: ./widgets/Stars\n      props:\n        count: 3\n",
+        text
+    );
+    assert_eq!(client.open(&uri, &text), []);
+
+    let diagnostics = client.change(&uri, 2, &text.replace("Stars", "Rating"));
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0]
+        .message
+        .starts_with("cannot find module `./widgets/Rating`"));
+    assert_eq!(diagnostics[0].range.start, Position::new(17, 13));
+
+    client.shutdown();
+}
+
+#[test]
+fn completion() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    client.open(&uri, &text);
+
+    // A new element, and the keys of one whose tag is known.
+    let text = format!("{}    - \n", text);
+    client.change(&uri, 2, &text);
+    let tags = client.completion(&uri, 17, 6);
+    assert!(tags.contains(&"heading".to_string()));
+    assert!(tags.contains(&"date-input".to_string()));
+
+    let text = text.replace("    - \n", "    - element: slider\n      \n");
+    client.change(&uri, 3, &text);
+    let keys = client.completion(&uri, 18, 6);
+    assert!(keys.contains(&"min".to_string()));
+    assert!(keys.contains(&"bind".to_string()));
+    assert!(!keys.contains(&"element".to_string()));
+
+    // State names.
+    assert_eq!(client.completion(&uri, 11, 12), ["todos", "draft"]);
+    assert_eq!(client.completion(&uri, 9, 24), ["todos", "draft"]);
+
+    client.shutdown();
+}
+
+#[test]
+fn hover_documents_elements() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    client.open(&uri, &text);
+
+    let result = client
+        .request(HoverRequest::METHOD, Client::at(&uri, 12, 17))
+        .unwrap();
+    let hover: lsp_types::Hover = serde_json::from_value(result).unwrap();
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("hover is not Markdown");
+    };
+    assert!(markup.value.starts_with("**button**"));
+    assert!(markup.value.contains("`onClick`"));
+    assert_eq!(
+        hover.range,
+        Some(Range::new(Position::new(12, 15), Position::new(12, 21)))
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn go_to_definition() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    client.open(&uri, &text);
+
+    let definition = |client: &mut Client, uri: &Url, line, character| {
+        let result = client
+            .request(GotoDefinition::METHOD, Client::at(uri, line, character))
+            .unwrap();
+        match serde_json::from_value(result).unwrap() {
+            Some(GotoDefinitionResponse::Scalar(location)) => Some(location),
+            _ => None,
+        }
+    };
+
+    // From `bind:` and an interpolation to the state.
+    let location = definition(&mut client, &uri, 11, 14).unwrap();
+    assert_eq!(location.uri, uri);
+    assert_eq!(
+        location.range,
+        Range::new(Position::new(6, 12), Position::new(6, 17))
+    );
+    let location = definition(&mut client, &uri, 9, 25).unwrap();
+    assert_eq!(location.range.start, Position::new(3, 12));
+    assert!(definition(&mut client, &uri, 1, 9).is_none());
+
+    // From `bind:` of an element written with its tag as the key.
+    let keyed = format!("{}    - input:\n        bind: draft\n", text);
+    client.change(&uri, 2, &keyed);
+    let location = definition(&mut client, &uri, 18, 15).unwrap();
+    assert_eq!(location.range.start, Position::new(6, 12));
+
+    // From a route to its page.
+    let (config, text) = project("nwl.yaml");
+    client.open(&config, &text);
+    let location = definition(&mut client, &config, 4, 14).unwrap();
+    assert_eq!(location.uri, uri);
+
+    client.shutdown();
+}
+
+#[test]
+fn rename_state() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    client.open(&uri, &text);
+
+    let rename_at = |client: &mut Client, line: u32, character: u32, new_name: &str| {
+        let params = json!({
+            "textDocument": { "uri": uri },
+            "position": { "line": line, "character": character },
+            "newName": new_name,
+        });
+        client.request(Rename::METHOD, params)
+    };
+    let rename = |client: &mut Client, new_name: &str| rename_at(client, 11, 13, new_name);
+    let edit: lsp_types::WorkspaceEdit =
+        serde_json::from_value(rename(&mut client, "title").unwrap()).unwrap();
+    let mut edits = edit.changes.unwrap().remove(&uri).unwrap();
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+    let lines: Vec<u32> = edits.iter().map(|edit| edit.range.start.line).collect();
+    assert_eq!(lines, [6, 11, 16]);
+    assert!(edits.iter().all(|edit| edit.new_text == "title"));
+    assert_eq!(
+        edits[2].range,
+        Range::new(Position::new(16, 17), Position::new(16, 22))
+    );
+
+    assert_eq!(
+        rename(&mut client, "two words"),
+        Err("`two words` is not a valid state name".to_string())
+    );
+    assert_eq!(
+        rename(&mut client, "todos"),
+        Err("`todos` is already declared on this page".to_string())
+    );
+
+    // From the declaration, through an element written with its tag as
+    // the key.
+    let keyed = format!("{}    - input:\n        bind: draft\n", text);
+    client.change(&uri, 2, &keyed);
+    let edit: lsp_types::WorkspaceEdit =
+        serde_json::from_value(rename_at(&mut client, 6, 14, "title").unwrap()).unwrap();
+    let mut edits = edit.changes.unwrap().remove(&uri).unwrap();
+    edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+    let lines: Vec<u32> = edits.iter().map(|edit| edit.range.start.line).collect();
+    assert_eq!(lines, [6, 11, 16, 18]);
+    assert_eq!(
+        edits[3].range,
+        Range::new(Position::new(18, 14), Position::new(18, 19))
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn stale_positions_are_ignored() {
+    let mut client = Client::start();
+    let (uri, text) = project("pages/home.yaml");
+    client.open(&uri, &text);
+
+    // Positions past the end of the document, as an editor may send
+    // after the document shrinks.
+    assert_eq!(client.completion(&uri, 50, 4), Vec::<String>::new());
+    for method in [HoverRequest::METHOD, GotoDefinition::METHOD] {
+        let result = client.request(method, Client::at(&uri, 50, 4));
+        assert_eq!(result, Ok(Value::Null));
+    }
+    let params = json!({
+        "textDocument": { "uri": uri },
+        "position": { "line": 50, "character": 4 },
+        "newName": "title",
+    });
+    assert_eq!(client.request(Rename::METHOD, params), Ok(Value::Null));
+
+    // Past the end of a line, the position is at its end.
+    assert_eq!(client.completion(&uri, 11, 80), ["todos", "draft"]);
+
+    client.shutdown();
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A 1-based line/column position inside a YAML source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub path: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLine {
+    pub number: usize,
+    pub text: String,
+}
+
+/// A compiler message with enough context to be rendered rustc-style:
+/// code, location, the offending source line and a caret under the span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub span: Option<Span>,
+    pub label: Option<String>,
+    pub snippet: Vec<SourceLine>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize) -> Self {
+        Span {
+            path: None,
+            line,
+            column,
+            length: 1,
+        }
+    }
+
+    pub fn with_path(mut self, path: Option<PathBuf>) -> Self {
+        self.path = path;
+        self
+    }
+
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = length.max(1);
+        self
+    }
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            span: None,
+            label: None,
+            snippet: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Diagnostic::error(code, message)
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Captures the source line the span points at so the diagnostic can be
+    /// rendered later without access to the original file.
+    pub fn with_source(mut self, source: &str) -> Self {
+        if let Some(span) = &self.span {
+            self.snippet = source
+                .lines()
+                .enumerate()
+                .skip(span.line.saturating_sub(1))
+                .take(1)
+                .map(|(index, text)| SourceLine {
+                    number: index + 1,
+                    text: text.to_string(),
+                })
+                .collect();
+        }
+        self
+    }
+
+    pub fn with_path(mut self, path: Option<PathBuf>) -> Self {
+        if let Some(span) = &mut self.span {
+            span.path = path;
+        }
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}:{}", path.display(), self.line, self.column),
+            None => write!(f, "<input>:{}:{}", self.line, self.column),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+
+        let gutter = self
+            .snippet
+            .iter()
+            .map(|line| line.number.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(gutter);
+
+        if let Some(span) = &self.span {
+            write!(f, "\n{}--> {}", pad, span)?;
+            if !self.snippet.is_empty() {
+                write!(f, "\n{} |", pad)?;
+                for line in &self.snippet {
+                    write!(
+                        f,
+                        "\n{:>width$} | {}",
+                        line.number,
+                        line.text,
+                        width = gutter
+                    )?;
+                    if line.number == span.line {
+                        let offset: String = line
+                            .text
+                            .chars()
+                            .take(span.column.saturating_sub(1))
+                            .map(|c| if c == '\t' { '\t' } else { ' ' })
+                            .collect();
+                        write!(f, "\n{} | {}{}", pad, offset, "^".repeat(span.length))?;
+                        if let Some(label) = &self.label {
+                            write!(f, " {}", label)?;
+                        }
+                    }
+                }
+                write!(f, "\n{} |", pad)?;
+            }
+        }
+
+        for note in &self.notes {
+            write!(f, "\n{} = note: {}", pad, note)?;
+        }
+        if let Some(help) = &self.help {
+            write!(f, "\n{} = help: {}", pad, help)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,279 @@
+//! Reading the schema of a file to learn what its mappings hold: the keys
+//! each may have, in the order they are declared, and their descriptions.
+//! The language server completes and documents keys with it, and `nwl fmt`
+//! orders them.
+
+use crate::Element;
+use schemars::Schema;
+use serde_json::Value;
+
+/// The JSON Schema of a kind of project file, as `nwl schema` prints it.
+pub struct FileSchema {
+    root: Value,
+}
+
+impl FileSchema {
+    pub fn new(schema: Schema) -> Self {
+        FileSchema {
+            root: schema.to_value(),
+        }
+    }
+
+    /// The schema of a `pages/*.yaml` file.
+    pub fn page() -> Self {
+        FileSchema::new(crate::page_schema())
+    }
+
+    /// The schema of a project's `nwl.yaml`.
+    pub fn project() -> Self {
+        FileSchema::new(crate::project_schema())
+    }
+
+    /// The schema of an `actions/*.yaml` file.
+    pub fn action() -> Self {
+        FileSchema::new(crate::action_schema())
+    }
+
+    /// The schema of a `components/*.yaml` file.
+    pub fn component() -> Self {
+        FileSchema::new(crate::component_schema())
+    }
+
+    /// The schema of the whole file.
+    pub fn root(&self) -> &Value {
+        &self.root
+    }
+
+    /// The schema of `key` in a mapping of `schema` holding `entries`.
+    pub fn property<'s>(
+        &'s self,
+        schema: &'s Value,
+        entries: &[(&str, &str)],
+        key: &str,
+    ) -> Option<&'s Value> {
+        self.properties(schema, entries)
+            .into_iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, property)| property)
+    }
+
+    /// The keys a mapping of `schema` may hold, given the `entries` it
+    /// already has, in the order they are declared. Elements need their tag
+    /// to be known; of other alternatives, the first that has every entry is
+    /// taken, or all of them while none does.
+    pub fn properties<'s>(
+        &'s self,
+        schema: &'s Value,
+        entries: &[(&str, &str)],
+    ) -> Vec<(&'s str, &'s Value)> {
+        let schema = self.resolve(schema);
+        if self.is_element(schema) {
+            return self
+                .element_form(entries)
+                .map(|form| self.properties(form, entries))
+                .unwrap_or_default();
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            return properties.iter().map(|(k, v)| (k.as_str(), v)).collect();
+        }
+        let alternatives: Vec<Vec<(&str, &Value)>> = alternatives(schema)
+            .map(|alternative| self.properties(alternative, entries))
+            .filter(|properties| !properties.is_empty())
+            .collect();
+        let matching = alternatives.iter().find(|properties| {
+            !entries.is_empty()
+                && entries
+                    .iter()
+                    .all(|(key, _)| properties.iter().any(|(name, _)| name == key))
+        });
+        if let Some(matching) = matching {
+            return matching.clone();
+        }
+        let mut all: Vec<(&str, &Value)> = Vec::new();
+        for (name, property) in alternatives.into_iter().flatten() {
+            if !all.iter().any(|(other, _)| *other == name) {
+                all.push((name, property));
+            }
+        }
+        all
+    }
+
+    /// The schema of the items of the sequence `schema` describes.
+    pub fn items<'s>(&'s self, schema: &'s Value) -> Option<&'s Value> {
+        let schema = self.resolve(schema);
+        if let Some(items) = schema.get("items") {
+            return Some(items);
+        }
+        alternatives(schema).find_map(|alternative| self.items(alternative))
+    }
+
+    /// The values a scalar of `schema` may take, with their descriptions.
+    pub fn values<'s>(&'s self, schema: &'s Value) -> Vec<(String, Option<&'s str>)> {
+        let schema = self.resolve(schema);
+        let description = description(schema);
+        let mut values = Vec::new();
+        if let Some(Value::String(value)) = schema.get("const") {
+            values.push((value.clone(), description));
+        }
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            values.extend(
+                options
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|value| (value.to_string(), None)),
+            );
+        }
+        for alternative in alternatives(schema) {
+            values.extend(self.values(alternative));
+        }
+        values
+    }
+
+    /// Whether `schema` describes an element.
+    pub fn is_element(&self, schema: &Value) -> bool {
+        std::ptr::eq(self.resolve(schema), &self.root["definitions"]["Element"])
+    }
+
+    /// The form of element a mapping holding `entries` is written in:
+    /// tagged by its `element:`, or keyed by a tag.
+    fn element_form(&self, entries: &[(&str, &str)]) -> Option<&Value> {
+        let tagged = entries
+            .iter()
+            .find(|(key, _)| *key == "element")
+            .map(|(_, tag)| *tag);
+        let keyed = entries
+            .iter()
+            .map(|(key, _)| *key)
+            .find(|key| Element::TAGS.contains(key));
+        let forms = self.root["definitions"]["Element"]["anyOf"].as_array()?;
+        forms.iter().find(|form| match (tagged, keyed) {
+            (Some(tag), _) => form["properties"]["element"]["const"] == tag,
+            (None, Some(tag)) => form["required"] == serde_json::json!([tag]),
+            (None, None) => false,
+        })
+    }
+
+    /// The description of the element `tag`, in Markdown, with its
+    /// properties.
+    pub fn element_docs(&self, tag: &str) -> Option<String> {
+        let form = self.element_form(&[("element", tag)])?;
+        let mut docs = format!("**{}**", tag);
+        if let Some(description) = description(form) {
+            docs.push_str(&format!("\n\n{}", description));
+        }
+        let required: Vec<&str> = form["required"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        let mut properties = String::new();
+        for (name, property) in self.properties(form, &[("element", tag)]) {
+            if matches!(name, "element" | "if" | "show" | "else") {
+                continue;
+            }
+            properties.push_str(&format!("\n- `{}`", name));
+            if required.contains(&name) {
+                properties.push_str(" (required)");
+            }
+            if let Some(description) = self.description(property) {
+                properties.push_str(&format!(": {}", description.replace('\n', " ")));
+            }
+        }
+        if !properties.is_empty() {
+            docs.push_str(&format!("\n\nProperties:\n{}", properties));
+        }
+        Some(docs)
+    }
+
+    /// The description of the element `tag` alone.
+    pub fn element_description(&self, tag: &str) -> Option<&str> {
+        description(self.element_form(&[("element", tag)])?)
+    }
+
+    /// The description of `schema`, or of the definition it refers to.
+    pub fn description<'s>(&'s self, schema: &'s Value) -> Option<&'s str> {
+        description(schema).or_else(|| description(self.resolve(schema)))
+    }
+
+    /// Follows references, and `allOf` wrapping a single one.
+    fn resolve<'s>(&'s self, mut schema: &'s Value) -> &'s Value {
+        loop {
+            if let Some(Value::String(reference)) = schema.get("$ref") {
+                let Some(name) = reference.strip_prefix("#/definitions/") else {
+                    return schema;
+                };
+                schema = &self.root["definitions"][name];
+            } else if let Some(Value::Array(all)) = schema.get("allOf") {
+                match all.as_slice() {
+                    [only] => schema = only,
+                    _ => return schema,
+                }
+            } else {
+                return schema;
+            }
+        }
+    }
+}
+
+fn alternatives(schema: &Value) -> impl Iterator<Item = &Value> {
+    ["anyOf", "oneOf"]
+        .into_iter()
+        .filter_map(|key| schema.get(key)?.as_array())
+        .flatten()
+}
+
+fn description(schema: &Value) -> Option<&str> {
+    schema.get("description")?.as_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(properties: Vec<(&str, &Value)>) -> Vec<String> {
+        properties
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_properties_in_declared_order() {
+        let schema = FileSchema::page();
+        let page = schema.property(schema.root(), &[], "page").unwrap();
+        assert_eq!(
+            names(schema.properties(page, &[("name", "Home")])),
+            ["name", "layout", "style", "params", "state", "computed", "children"]
+        );
+
+        let children = schema.property(page, &[], "children").unwrap();
+        let element = schema.items(children).unwrap();
+        assert!(schema.is_element(element));
+        let button = names(schema.properties(element, &[("element", "button")]));
+        assert_eq!(button[..3], ["content", "onClick", "style"]);
+        let keyed = names(schema.properties(element, &[("heading", "")]));
+        assert_eq!(keyed[0], "content");
+        assert!(keyed.contains(&"heading".to_string()));
+        assert!(schema.properties(element, &[("to", "")]).is_empty());
+    }
+
+    #[test]
+    fn test_values_and_docs() {
+        let schema = FileSchema::page();
+        let layout_type = &schema.root()["definitions"]["Layout"]["properties"]["type"];
+        let values: Vec<String> = schema
+            .values(layout_type)
+            .into_iter()
+            .map(|(value, _)| value)
+            .collect();
+        assert_eq!(values, ["column", "row", "stack", "grid"]);
+
+        let docs = schema.element_docs("button").unwrap();
+        assert!(docs.starts_with("**button**\n\n"), "{}", docs);
+        assert!(docs.contains("- `content` (required): "), "{}", docs);
+        assert!(docs.contains("- `onClick`: Runs when the button is clicked."));
+        assert!(!docs.contains("`element`"));
+        assert_eq!(schema.element_docs("blink"), None);
+    }
+}
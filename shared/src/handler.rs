@@ -0,0 +1,262 @@
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_yaml::Value;
+use std::borrow::Cow;
+
+/// What an event such as `onClick` does.
+///
+/// Handlers are written either as a list of declarative steps, or as a raw
+/// JavaScript string pasted into the generated JSX:
+///
+/// ```yaml
+/// onClick:
+///   - set: count
+///     to: count + 1
+///   - navigate: /done
+/// onClose: "setOpen(false)"
+/// ```
+///
+/// A single step may be written without the surrounding list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Handler {
+    /// A JavaScript escape hatch, used verbatim.
+    Script(String),
+    Steps(Vec<Step>),
+}
+
+/// One declarative step of a [`Handler`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Step {
+    /// `navigate: /path` goes to another page.
+    Navigate { navigate: String },
+    /// `set: name` with `to: expression` assigns a state.
+    Set { set: String, to: String },
+    /// `toggle: name` flips a boolean state.
+    Toggle { toggle: String },
+    /// `append: name` with `value: expression` adds an item to an array state.
+    Append { append: String, value: String },
+    /// `call: action` with `with: [expressions]` runs a project action.
+    Call {
+        call: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        with: Vec<String>,
+    },
+}
+
+impl Step {
+    /// The keys that start each kind of step, in the order they are tried.
+    pub const KINDS: &'static [&'static str] = &["navigate", "set", "toggle", "append", "call"];
+
+    /// The key this step is written with.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Step::Navigate { .. } => "navigate",
+            Step::Set { .. } => "set",
+            Step::Toggle { .. } => "toggle",
+            Step::Append { .. } => "append",
+            Step::Call { .. } => "call",
+        }
+    }
+}
+
+impl From<&str> for Handler {
+    fn from(script: &str) -> Self {
+        Handler::Script(script.to_string())
+    }
+}
+
+impl Serialize for Handler {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Handler::Script(script) => serializer.serialize_str(script),
+            Handler::Steps(steps) if steps.len() == 1 => steps[0].serialize(serializer),
+            Handler::Steps(steps) => steps.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Handler {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(script) => Ok(Handler::Script(script)),
+            Value::Sequence(steps) => steps
+                .into_iter()
+                .map(step)
+                .collect::<Result<_, _>>()
+                .map(Handler::Steps),
+            value @ Value::Mapping(_) => Ok(Handler::Steps(vec![step(value)?])),
+            _ => Err(D::Error::custom(
+                "expected a handler: a step such as `set: count`, a list of steps, or a string",
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Step {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        step(Value::deserialize(deserializer)?)
+    }
+}
+
+impl JsonSchema for Handler {
+    fn schema_name() -> Cow<'static, str> {
+        "Handler".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let step = generator.subschema_for::<Step>();
+        json_schema!({
+            "description": "What an event does: a step, a list of steps, or JavaScript used verbatim.",
+            "anyOf": [
+                step,
+                { "type": "array", "items": step },
+                { "type": "string", "description": "A JavaScript escape hatch, used verbatim." },
+            ],
+        })
+    }
+}
+
+impl JsonSchema for Step {
+    fn schema_name() -> Cow<'static, str> {
+        "Step".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "One declarative step of a handler.",
+            "anyOf": [
+                NavigateStep::json_schema(generator),
+                SetStep::json_schema(generator),
+                ToggleStep::json_schema(generator),
+                AppendStep::json_schema(generator),
+                CallStep::json_schema(generator),
+            ],
+        })
+    }
+}
+
+/// Goes to another page.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct NavigateStep {
+    /// Path of the page to go to.
+    navigate: String,
+}
+
+/// Assigns a state.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct SetStep {
+    /// State to assign.
+    set: String,
+    /// Expression for the new value.
+    #[serde(deserialize_with = "expression")]
+    #[schemars(schema_with = "expression_schema")]
+    to: String,
+}
+
+/// Flips a boolean state.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct ToggleStep {
+    /// State to flip.
+    toggle: String,
+}
+
+/// Adds an item to an array state.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct AppendStep {
+    /// State to add the item to.
+    append: String,
+    /// Expression for the item.
+    #[serde(deserialize_with = "expression")]
+    #[schemars(schema_with = "expression_schema")]
+    value: String,
+}
+
+/// Runs a project action.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct CallStep {
+    /// Name of the action.
+    call: String,
+    /// Arguments of the action, as expressions. A single argument may be
+    /// written without the list.
+    #[serde(default, deserialize_with = "expressions")]
+    #[schemars(schema_with = "expressions_schema")]
+    with: Vec<String>,
+}
+
+/// Picks the kind of step from the key that starts it.
+fn step<E: Error>(value: Value) -> Result<Step, E> {
+    let kind = value
+        .as_mapping()
+        .and_then(|mapping| Step::KINDS.iter().find(|kind| mapping.contains_key(**kind)))
+        .ok_or_else(|| {
+            E::custom(format!(
+                "expected a step starting with one of {}",
+                Step::KINDS
+                    .iter()
+                    .map(|kind| format!("`{}`", kind))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })?;
+    let step = match *kind {
+        "navigate" => NavigateStep::deserialize(value).map(|s| Step::Navigate {
+            navigate: s.navigate,
+        }),
+        "set" => SetStep::deserialize(value).map(|s| Step::Set {
+            set: s.set,
+            to: s.to,
+        }),
+        "toggle" => ToggleStep::deserialize(value).map(|s| Step::Toggle { toggle: s.toggle }),
+        "append" => AppendStep::deserialize(value).map(|s| Step::Append {
+            append: s.append,
+            value: s.value,
+        }),
+        _ => CallStep::deserialize(value).map(|s| Step::Call {
+            call: s.call,
+            with: s.with,
+        }),
+    };
+    step.map_err(E::custom)
+}
+
+/// Accepts an expression written as a string or as a plain YAML scalar, so
+/// `to: 0` and `to: true` work without quotes.
+fn expression<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    scalar(Value::deserialize(deserializer)?)
+}
+
+fn scalar<E: Error>(value: Value) -> Result<String, E> {
+    match value {
+        Value::String(text) => Ok(text),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Bool(flag) => Ok(flag.to_string()),
+        Value::Null => Ok("null".to_string()),
+        _ => Err(E::custom("expected an expression")),
+    }
+}
+
+/// Accepts a list of expressions, or a single one for one argument.
+fn expressions<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Sequence(values) => values.into_iter().map(scalar).collect(),
+        value => Ok(vec![scalar(value)?]),
+    }
+}
+
+fn expression_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({ "type": ["string", "number", "boolean", "null"] })
+}
+
+fn expressions_schema(generator: &mut SchemaGenerator) -> Schema {
+    let expression = expression_schema(generator);
+    json_schema!({
+        "anyOf": [expression, { "type": "array", "items": expression }],
+    })
+}
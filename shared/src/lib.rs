@@ -1,31 +1,59 @@
-use serde::{Deserialize, Serialize};
+// Field names mirror the camelCase keys users write in page YAML.
+#![allow(non_snake_case)]
+
+mod diagnostics;
+mod file_schema;
+mod handler;
+mod schema;
+mod syntax;
+
+pub use diagnostics::{Diagnostic, Severity, SourceLine, Span};
+pub use file_schema::FileSchema;
+pub use handler::{Handler, Step};
+pub use schema::{action_schema, component_schema, page_schema, project_schema};
+pub use syntax::{to_yaml, ElementSyntax};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// How a layout arranges its children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum LayoutType {
+    /// Children stacked top to bottom.
     #[serde(rename = "column")]
     Column,
+    /// Children side by side.
     #[serde(rename = "row")]
     Row,
+    /// Children layered over each other.
     #[serde(rename = "stack")]
     Stack,
+    /// Children in a grid of `columns` columns.
     #[serde(rename = "grid")]
     Grid,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// How an element arranges its children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Layout {
+    /// How the children are arranged.
     #[serde(rename = "type")]
     pub layout_type: LayoutType,
+    /// Number of columns of a `grid`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub columns: Option<u32>,
+    /// Further Tailwind classes for the layout, such as `gap-4`.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub properties: Vec<String>,
 }
 
+// `remote = "Self"` turns the derives into inherent functions so the trait
+// impls below can accept the keyed syntax before delegating to them.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(tag = "element")]
+#[serde(tag = "element", remote = "Self")]
 pub enum Element {
     #[serde(rename = "heading")]
     Heading(HeadingElement),
@@ -109,32 +137,62 @@ pub enum Element {
     Url(UrlElement),
     #[serde(rename = "email")]
     Email(EmailElement),
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename = "use")]
+    Use(UseElement),
+    #[serde(rename = "slot")]
+    Slot(SlotElement),
+    #[serde(rename = "react")]
+    React(ReactElement),
+    #[serde(rename = "outlet")]
+    Outlet(OutletElement),
+    /// Any element written with `if:`. It has no tag of its own; the trait
+    /// impls below wrap and unwrap it around the element it guards.
+    #[serde(skip)]
+    Conditional(ConditionalElement),
+}
+
+/// An element rendered only while `condition` holds, with an optional
+/// `else:` branch rendered otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConditionalElement {
+    pub condition: String,
+    pub element: Box<Element>,
+    pub otherwise: Vec<Element>,
+}
+
+/// A page heading.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct HeadingElement {
     pub content: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A paragraph of text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TextElement {
     pub content: String,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A button.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ButtonElement {
     pub content: String,
+    /// Runs when the button is clicked.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onClick: Option<String>,
+    pub onClick: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A bordered box around its children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CardElement {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Element>,
@@ -142,59 +200,177 @@ pub struct CardElement {
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A static item of a list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ListItem {
+    /// Text of the item.
     pub content: String,
+    /// Runs when the item is clicked.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onClick: Option<String>,
+    pub onClick: Option<Handler>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A list of static `items`, or of `children` repeated for each item of
+/// a state array.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ListElement {
+    /// Static items, used when `data` is not set.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ListItem>,
+    /// State array to render `children` for, once per item, or an array
+    /// field of a state or of an enclosing list's item, such as
+    /// `project.tags`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// Name of the current item inside `children`. Defaults to the singular
+    /// of the last name in `data` (`projects` → `project`, `project.tags` →
+    /// `tag`), or `item` when that isn't a usable name (`news` → `new`).
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Item field used as the React `key`. Defaults to the item's index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Elements rendered for each item of `data`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
+    /// Rendered instead of the items when `data` is empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub empty: Vec<Element>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl ListElement {
+    /// The name `children` refer to the current item by.
+    pub fn item_name(&self) -> String {
+        if let Some(alias) = &self.alias {
+            return alias.clone();
+        }
+        let last = self
+            .data
+            .as_deref()
+            .map(|data| data.rsplit('.').next().unwrap_or(data).trim());
+        let singular = match last {
+            Some(data) if data.ends_with("ies") && data.len() > 3 => {
+                format!("{}y", &data[..data.len() - 3])
+            }
+            Some(data) if data.ends_with('s') && !data.ends_with("ss") && data.len() > 1 => {
+                data[..data.len() - 1].to_string()
+            }
+            _ => return "item".to_string(),
+        };
+        if RESERVED_WORDS.contains(&singular.as_str()) {
+            return "item".to_string();
+        }
+        singular
+    }
+}
+
+/// Words JavaScript reserves, which can't name a variable.
+pub const RESERVED_WORDS: &[&str] = &[
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Children arranged in a row, column, stack or grid.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct LayoutElement {
+    /// How the children are arranged.
     pub layout: Layout,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Element>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A single-line text input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct InputElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
+    /// Initial text of an input that is not bound.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An image.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ImageElement {
+    /// URL of the image.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub src: Option<String>,
+    /// Text describing the image.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alt: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Vertical space between elements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SpacerElement {
+    /// Accepted for compatibility; spacers are a fixed height.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub size: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A plain wrapper around its children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ContainerElement {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Element>,
@@ -202,498 +378,683 @@ pub struct ContainerElement {
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A checkbox.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CheckboxElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A range slider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SliderElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Smallest value.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<i32>,
+    /// Largest value.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<i32>,
+    /// Difference between neighbouring values.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub step: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An option of a select.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SelectOption {
+    /// Value stored when the option is chosen.
     pub value: String,
+    /// Text shown for the option. Defaults to `value`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A drop-down list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SelectElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// The options to choose from.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub options: Vec<SelectOption>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An option of a radio group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RadioOption {
+    /// Value stored when the option is chosen.
     pub value: String,
+    /// Text shown for the option. Defaults to `value`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A group of radio buttons.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RadioGroupElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// The options to choose from.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub options: Vec<RadioOption>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A multi-line text input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TextareaElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Visible height in lines.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub rows: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A captcha service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum CaptchaProvider {
+    /// Cloudflare Turnstile.
     #[serde(rename = "cloudflare")]
     Cloudflare,
+    /// Google reCAPTCHA.
     #[serde(rename = "recaptcha")]
     Recaptcha,
+    /// hCaptcha.
     #[serde(rename = "hcaptcha")]
     HCaptcha,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A captcha to solve before a form is submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CaptchaConfig {
+    /// The captcha service.
     pub provider: CaptchaProvider,
+    /// Public site key issued by the provider.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub siteKey: Option<String>,
+    /// Widget theme, such as `light` or `dark`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub theme: Option<String>,
+    /// reCAPTCHA version: `v2` (the default) or `v3`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    /// reCAPTCHA v3 action name. Defaults to `submit`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub action: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A check run on a form field before the form is submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ValidationRule {
+    /// The field must not be blank.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
+    /// Regular expression the field must match.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
+    /// Fewest characters allowed.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub minLength: Option<u32>,
+    /// Most characters allowed.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub maxLength: Option<u32>,
+    /// Error reported when the check fails, instead of the default one.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A form around input elements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FormElement {
+    /// Runs when the form is submitted and every check passes.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onSubmit: Option<String>,
+    pub onSubmit: Option<Handler>,
+    /// Checks for each field, keyed by the state the field is bound to.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub validation: Option<HashMap<String, Vec<ValidationRule>>>,
+    /// A captcha shown at the end of the form.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub captcha: Option<CaptchaConfig>,
+    /// Runs instead of `onSubmit` when a check fails.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onValidationError: Option<String>,
+    pub onValidationError: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Element>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A date picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DateInputElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Earliest date, as `YYYY-MM-DD`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<String>,
+    /// Latest date, as `YYYY-MM-DD`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A time picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TimeInputElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Earliest time, as `HH:MM`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<String>,
+    /// Latest time, as `HH:MM`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<String>,
+    /// Granularity of the time, in seconds.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub step: Option<u32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A date and time picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DateTimeInputElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Earliest date and time, as `YYYY-MM-DDTHH:MM`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<String>,
+    /// Latest date and time, as `YYYY-MM-DDTHH:MM`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A color picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ColorPickerElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Accepted for compatibility; the browser's picker is used.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub showPalette: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A file picker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FileUploadElement {
+    /// State the chosen file, or list of files with `multiple`, is stored in.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// File types to offer, as in the HTML `accept` attribute.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub accept: Option<String>,
+    /// Largest file size, such as `5MB`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub maxSize: Option<String>,
+    /// Allow choosing several files.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub multiple: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A progress bar, filled to a percentage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ProgressElement {
+    /// Expression for the percentage shown.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
+    /// State holding the percentage shown. Takes precedence over `value`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Value of a full bar, for assistive technology.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<u32>,
+    /// Show the percentage next to the bar.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub showLabel: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An on/off switch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ToggleElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Tailwind color of the switch when on. Defaults to `green`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub onColor: Option<String>,
+    /// Tailwind color of the switch when off. Defaults to `gray`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub offColor: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A tab of a tab bar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TabItem {
+    /// Value stored when the tab is selected.
     #[serde(rename = "id")]
     pub value: String,
+    /// Text of the tab. Defaults to `id`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
+    /// Accepted for compatibility; not rendered.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A row of tabs, one of which is selected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TabsElement {
+    /// Heading shown above the tabs.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// The tabs.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub options: Vec<TabItem>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A collapsible section of an accordion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AccordionItem {
+    /// Heading of the section, always shown.
     pub title: String,
+    /// Text shown while the section is open.
     pub content: String,
+    /// Accepted for compatibility; not rendered.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A stack of collapsible sections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AccordionElement {
+    /// Accepted for compatibility; sections open independently.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub multiple: Option<bool>,
+    /// The sections.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<AccordionItem>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A dialog shown over the page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ModalElement {
+    /// Heading of the dialog.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    /// Boolean state the dialog is shown while true.
     #[serde(default, skip_serializing_if = "Option::is_none", alias = "isOpen")]
     pub bind: Option<String>,
+    /// Runs when the backdrop or close button is clicked. Adds a close
+    /// button.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onClose: Option<String>,
+    pub onClose: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<Element>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A small status label.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct BadgeElement {
     pub content: String,
+    /// Colors of the badge: `success`, `warning`, `error` or `info`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub variant: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A label, optionally with a button that removes it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct TagElement {
     pub content: String,
+    /// Show a button that removes the tag.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub removable: Option<bool>,
+    /// Runs when the remove button is clicked.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onRemove: Option<String>,
+    pub onRemove: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A message box.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AlertElement {
     pub content: String,
+    /// Colors of the alert: `success`, `error`, `warning` or `info` (the
+    /// default).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alertType: Option<String>,
+    /// Show a button that dismisses the alert.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dismissible: Option<bool>,
+    /// Runs when the dismiss button is clicked.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onDismiss: Option<String>,
+    pub onDismiss: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A loading indicator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SpinnerElement {
+    /// `sm`, `md` (the default) or `lg`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub size: Option<String>,
+    /// Text shown next to the spinner, instead of a hidden "Loading...".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A number with buttons that decrease and increase it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CounterElement {
+    /// Number state shown and changed by the counter.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Smallest value. Defaults to 0.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<i32>,
+    /// Largest value. Defaults to 999.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<i32>,
+    /// Accepted for compatibility; the buttons always change the value by
+    /// one.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub step: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A search field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SearchInputElement {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
+    /// Allow clearing the search.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub clearable: Option<bool>,
+    /// Runs when a search is submitted.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onSearch: Option<String>,
+    pub onSearch: Option<Handler>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A button that copies text to the clipboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CopyButtonElement {
+    /// Text copied to the clipboard. Defaults to `text`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// Text of the button. Defaults to "Copy".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
+    /// Runs after the text is copied.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onCopy: Option<String>,
+    pub onCopy: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Previous and next buttons around the current page number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PaginationElement {
+    /// Number state holding the current page, starting at 1.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Number of items paged through. Defaults to 100.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub total: Option<u32>,
+    /// Number of items on a page. Defaults to 10.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub perPage: Option<u32>,
+    /// Accepted for compatibility; changes are made through `bind`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onChange: Option<String>,
+    pub onChange: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A step of a breadcrumb trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct BreadcrumbItem {
+    /// Text of the step.
     pub label: String,
+    /// Where the step links to. The current step usually has none.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub href: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A breadcrumb trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct BreadcrumbElement {
+    /// The steps, from the outermost.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<BreadcrumbItem>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A round picture of a person, or their initials.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AvatarElement {
+    /// URL of the picture.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub src: Option<String>,
+    /// Name shown next to the avatar.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// `sm`, `md` (the default) or `lg`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub size: Option<String>,
+    /// Initials shown when there is no `src`. Only the first two characters
+    /// are used.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fallback: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An input that collects a list of short values as chips.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ChipInputElement {
+    /// String array state holding the chips.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bind: Option<String>,
+    /// Values offered for adding with a click.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub suggestions: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>,
+    /// Accepted for compatibility; chips are added through `bind`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onAdd: Option<String>,
+    pub onAdd: Option<Handler>,
+    /// Accepted for compatibility; chips are removed through `bind`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub onRemove: Option<String>,
+    pub onRemove: Option<Handler>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A link of a navigation bar or menu.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct NavLink {
+    /// Text of the link.
     pub label: String,
+    /// Where the link goes.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub href: Option<String>,
+    /// Highlight the link as the current page.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub active: Option<bool>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A navigation bar.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct NavElement {
+    /// The links, shown on the right.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub links: Vec<NavLink>,
+    /// Text shown on the left, linking to `/`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub logo: Option<String>,
+    /// Keep the bar at the top of the window while scrolling.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sticky: Option<bool>,
+    /// Show the page behind the bar instead of a black background.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub transparent: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A navigation menu that collapses behind a button on small screens.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct MenuElement {
+    /// The links.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<NavLink>,
+    /// Accepted for compatibility; the menu collapses below Tailwind's `md`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mobileBreakpoint: Option<u32>,
+    /// Show a button that opens the links on small screens.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub hamburger: Option<bool>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
+    /// Accepted for compatibility; not applied.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub mobileStyle: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A link, or a URL input when `placeholder` or `bind` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct UrlElement {
+    /// Where the link goes.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub href: Option<String>,
+    /// Text of the link. Defaults to `href`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// Browsing context to open the link in, such as `_blank`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub target: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -704,12 +1065,25 @@ pub struct UrlElement {
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl UrlElement {
+    /// Whether the element is an input for a URL rather than a link.
+    pub fn is_input(&self) -> bool {
+        self.placeholder.is_some() || self.bind.is_some()
+    }
+}
+
+/// A `mailto:` link, or an email input when `placeholder` or `bind` is
+/// set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct EmailElement {
+    /// Address the link writes to.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub address: Option<String>,
+    /// Subject the message starts with.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub subject: Option<String>,
+    /// Text of the link. Defaults to `address`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -720,74 +1094,327 @@ pub struct EmailElement {
     pub style: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// An instance of a component from `components/`, given values for its
+/// props and elements for its slots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UseElement {
+    /// Name of the component.
+    pub component: String,
+    /// Values of the component's props, by name. Strings may interpolate
+    /// `{expression}`; a string that is a single `{expression}` passes its
+    /// value as is.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, serde_json::Value>")]
+    pub props: HashMap<String, serde_yaml::Value>,
+    /// Elements rendered in the component's named slots, by slot name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub slots: HashMap<String, Vec<Element>>,
+    /// Elements rendered where the component has a `slot` without a name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
+}
+
+/// Where a component renders the elements it is given by `use:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SlotElement {
+    /// Name of the slot, declared in the component's `slots`. Without one,
+    /// the children of `use:` are rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Where a layout page renders the page of the nested route the URL
+/// matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutletElement {}
+
+/// A React component written by hand, imported into the generated module
+/// and rendered with the given props and children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReactElement {
+    /// Module the component is imported from: a path relative to `src/`,
+    /// such as `./widgets/Chart`, or a package listed in `package.json`.
+    pub import: String,
+    /// Named export of the module to render. Without one, its default
+    /// export is rendered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    /// Name the component is imported as. Defaults to `export`, or to the
+    /// module's file name in PascalCase.
+    #[serde(rename = "as", default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Props passed to the component, by name. Strings may interpolate
+    /// `{expression}`; a string that is a single `{expression}` passes its
+    /// value as is.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    #[schemars(with = "HashMap<String, serde_json::Value>")]
+    pub props: HashMap<String, serde_yaml::Value>,
+    /// Handlers passed as props, such as `onSelect`. The first argument the
+    /// component calls one with can be read as `value`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub events: HashMap<String, Handler>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
+}
+
+/// A `pages/*.yaml` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Page {
+    /// The page.
     #[serde(rename = "page")]
     pub page_data: PageData,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A page of the app, compiled to a React component. Fields are declared
+/// in the order `nwl fmt` writes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct PageData {
+    /// Name of the page. The generated component is named after it.
     pub name: String,
+    /// How the page arranges its children.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layout: Option<Layout>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub style: Vec<String>,
+    /// Parameters of the page's route, read from its URL.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    pub children: Vec<Element>,
+    pub params: Vec<ParamDefinition>,
+    /// State variables of the page.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub state: Vec<StateDefinition>,
+    /// Values derived from state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub computed: Vec<ComputedDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A state variable of a page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct StateDefinition {
+    /// Name of the state, used in expressions and by `bind`.
     pub name: String,
+    /// Type of the state, such as `number`, `string[]` or `{ title: string }`.
+    /// Inferred from `initial` when omitted.
     #[serde(rename = "type")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub value_type: Option<String>,
+    /// Value the state starts with.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<serde_json::Value>")]
     pub initial: Option<serde_yaml::Value>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A parameter of a page's route, read from the `:name` segment of the URL
+/// it is served at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ParamDefinition {
+    /// Name of the parameter, as written after `:` in the route's path.
+    pub name: String,
+    /// Type the URL text is converted to: `string`, `number`, `boolean` or
+    /// a union of string literals. Inferred from `default`, or `string`.
+    #[serde(rename = "type")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    /// Value used when the segment is optional (`:name?`) and missing, or
+    /// when the route has no such segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<serde_json::Value>")]
+    pub default: Option<serde_yaml::Value>,
+}
+
+/// A value derived from state, recomputed only when its dependencies change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ComputedDefinition {
+    /// Name the value is read by in expressions.
+    pub name: String,
+    /// JavaScript expression over state and other computed values.
+    pub value: String,
+    /// State and computed values `value` reads. Inferred from `value` when
+    /// omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deps: Option<Vec<String>>,
+    /// Type of the value, written as a state's `type`.
+    #[serde(rename = "type")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+}
+
+/// An `actions/*.yaml` file. Every action it declares is exported from the
+/// project's generated `src/actions.ts` and can be called from any page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ActionFile {
+    /// The actions the file declares.
     #[serde(rename = "action")]
     pub actions: Vec<ActionDefinition>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A function exported from `src/actions.ts`, called from handlers with
+/// `call:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ActionDefinition {
+    /// Name the action is called by.
     pub name: String,
+    /// Parameters of the action, written `name` or `name: type`.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub inputs: Vec<String>,
+    /// Body of the generated function.
     #[serde(default, skip_serializing_if = "String::is_empty")]
     pub handler: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A `components/*.yaml` file. The component it declares compiles to
+/// `src/components/<Name>.tsx` and can be placed on any page with `use:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentFile {
+    /// The component.
+    pub component: ComponentDefinition,
+}
+
+/// A reusable tree of elements. Fields are declared in the order `nwl fmt`
+/// writes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ComponentDefinition {
+    /// Name `use:` refers to the component by. The generated component is
+    /// named after it.
+    pub name: String,
+    /// Values the component is given where it is used, read like state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub props: Vec<PropDefinition>,
+    /// Names of the slots `use:` may fill with elements, besides its
+    /// `children`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slots: Vec<String>,
+    /// State variables of each instance of the component.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state: Vec<StateDefinition>,
+    /// Values derived from props and state.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub computed: Vec<ComputedDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Element>,
+}
+
+/// A prop of a component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PropDefinition {
+    /// Name of the prop, used in expressions and by `use:`.
+    pub name: String,
+    /// Type of the prop, written as a state's `type`. Inferred from
+    /// `default` when omitted.
+    #[serde(rename = "type")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    /// Value used when `use:` doesn't give one. Props without a default
+    /// are required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<serde_json::Value>")]
+    pub default: Option<serde_yaml::Value>,
+}
+
+/// The `nwl.yaml` file at the root of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ProjectConfig {
+    /// Name of the app.
     pub name: String,
+    /// Accepted for compatibility; code is always generated in `src/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Accepted for compatibility; routes name their page files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<String>,
+    /// Loads the page of every route only when it is first shown, unless
+    /// the route sets `lazy: false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lazy: Option<bool>,
+    /// What shows while the page of a lazy route loads. A spinner by
+    /// default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<FallbackConfig>,
+    /// The pages of the app and the paths they are served at.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub routes: Vec<RouteConfig>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// What shows while the page of a lazy route loads: a page, or a spinner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FallbackConfig {
+    /// Page file, relative to the project, such as `pages/loading.yaml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    /// A spinner, written like a `spinner` element: its `size`, `label` and
+    /// `style`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spinner: Option<SpinnerElement>,
+}
+
+/// A page and the path it is served at, a layout page around nested
+/// routes, or a redirect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RouteConfig {
+    /// URL path, such as `/` or `/about`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub path: String,
-    pub page: String,
+    /// Serves `page` at the path of the route around it, instead of a path
+    /// of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<bool>,
+    /// Page file, relative to the project, such as `pages/home.yaml`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    /// Page file rendered around the routes in `children`, which show at
+    /// its `outlet` element.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+    /// Path the browser is sent to instead, such as `/about`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<String>,
+    /// Loads the page only when it is first shown, in a file of its own.
+    /// Defaults to the project's `lazy`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lazy: Option<bool>,
+    /// Routes rendered inside `layout`. Their paths are relative to this
+    /// route's, as in `settings` for `/account/settings`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<RouteConfig>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+impl RouteConfig {
+    /// The page file the route renders: its `page`, or its `layout`.
+    pub fn file(&self) -> Option<&str> {
+        self.page.as_deref().or(self.layout.as_deref())
+    }
+}
+
+/// Several pages in one file, written under `pages:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct Document {
+    /// The pages.
     pub pages: Vec<Page>,
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum CompileError {
-    #[error("YAML parsing error: {0}")]
-    Parse(String),
-    #[error("Semantic error: {0}")]
-    Semantic(String),
+    #[error("{0}")]
+    Parse(Box<Diagnostic>),
+    #[error("{0}")]
+    Semantic(Box<Diagnostic>),
     #[error("Code generation error: {0}")]
     Codegen(String),
     #[error("IO error: {0}")]
@@ -805,6 +1432,271 @@ impl fmt::Display for LayoutType {
     }
 }
 
+impl Serialize for Element {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Element::Conditional(conditional) => {
+                syntax::with_condition(conditional)?.serialize(serializer)
+            }
+            _ => Element::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Element {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_yaml::Value::deserialize(deserializer)?;
+        let mut value = syntax::normalize_element(value)?;
+        let condition = syntax::take_condition(&mut value)?;
+        let element = Element::deserialize(value).map_err(serde::de::Error::custom)?;
+        Ok(match condition {
+            Some((condition, otherwise)) => Element::Conditional(ConditionalElement {
+                condition,
+                element: Box::new(element),
+                otherwise,
+            }),
+            None => element,
+        })
+    }
+}
+
+impl Element {
+    /// Every tag accepted for `element:` (or as the key of a keyed element).
+    pub const TAGS: &'static [&'static str] = &[
+        "heading",
+        "text",
+        "button",
+        "card",
+        "list",
+        "layout",
+        "input",
+        "image",
+        "spacer",
+        "container",
+        "checkbox",
+        "slider",
+        "select",
+        "radio-group",
+        "textarea",
+        "form",
+        "date-input",
+        "time-input",
+        "datetime-input",
+        "color-picker",
+        "file-upload",
+        "progress",
+        "toggle",
+        "tabs",
+        "accordion",
+        "modal",
+        "badge",
+        "tag",
+        "alert",
+        "spinner",
+        "counter",
+        "search-input",
+        "copy-button",
+        "pagination",
+        "breadcrumb",
+        "avatar",
+        "chip-input",
+        "nav",
+        "menu",
+        "url",
+        "email",
+        "use",
+        "slot",
+        "react",
+        "outlet",
+    ];
+
+    /// The property a bare scalar given to the keyed form of `tag` sets:
+    /// `- heading: Hi` is its `content`, `- use: Card` names the component
+    /// and `- react: ./Chart` its module.
+    pub fn scalar_property(tag: &str) -> &'static str {
+        match tag {
+            "use" => "component",
+            "slot" => "name",
+            "react" => "import",
+            _ => "content",
+        }
+    }
+
+    /// The `element:` tag this variant is written as in page YAML.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Element::Heading(_) => "heading",
+            Element::Text(_) => "text",
+            Element::Button(_) => "button",
+            Element::Card(_) => "card",
+            Element::List(_) => "list",
+            Element::Layout(_) => "layout",
+            Element::Input(_) => "input",
+            Element::Image(_) => "image",
+            Element::Spacer(_) => "spacer",
+            Element::Container(_) => "container",
+            Element::Checkbox(_) => "checkbox",
+            Element::Slider(_) => "slider",
+            Element::Select(_) => "select",
+            Element::RadioGroup(_) => "radio-group",
+            Element::Textarea(_) => "textarea",
+            Element::Form(_) => "form",
+            Element::DateInput(_) => "date-input",
+            Element::TimeInput(_) => "time-input",
+            Element::DateTimeInput(_) => "datetime-input",
+            Element::ColorPicker(_) => "color-picker",
+            Element::FileUpload(_) => "file-upload",
+            Element::Progress(_) => "progress",
+            Element::Toggle(_) => "toggle",
+            Element::Tabs(_) => "tabs",
+            Element::Accordion(_) => "accordion",
+            Element::Modal(_) => "modal",
+            Element::Badge(_) => "badge",
+            Element::Tag(_) => "tag",
+            Element::Alert(_) => "alert",
+            Element::Spinner(_) => "spinner",
+            Element::Counter(_) => "counter",
+            Element::SearchInput(_) => "search-input",
+            Element::CopyButton(_) => "copy-button",
+            Element::Pagination(_) => "pagination",
+            Element::Breadcrumb(_) => "breadcrumb",
+            Element::Avatar(_) => "avatar",
+            Element::ChipInput(_) => "chip-input",
+            Element::Nav(_) => "nav",
+            Element::Menu(_) => "menu",
+            Element::Url(_) => "url",
+            Element::Email(_) => "email",
+            Element::Use(_) => "use",
+            Element::Slot(_) => "slot",
+            Element::React(_) => "react",
+            Element::Outlet(_) => "outlet",
+            Element::Conditional(c) => c.element.tag(),
+        }
+    }
+
+    /// The state variable this element is two-way bound to, if any.
+    pub fn bind(&self) -> Option<&str> {
+        let bind = match self {
+            Element::Input(e) => &e.bind,
+            Element::Checkbox(e) => &e.bind,
+            Element::Slider(e) => &e.bind,
+            Element::Select(e) => &e.bind,
+            Element::RadioGroup(e) => &e.bind,
+            Element::Textarea(e) => &e.bind,
+            Element::DateInput(e) => &e.bind,
+            Element::TimeInput(e) => &e.bind,
+            Element::DateTimeInput(e) => &e.bind,
+            Element::ColorPicker(e) => &e.bind,
+            Element::FileUpload(e) => &e.bind,
+            Element::Progress(e) => &e.bind,
+            Element::Toggle(e) => &e.bind,
+            Element::Tabs(e) => &e.bind,
+            Element::Modal(e) => &e.bind,
+            Element::Counter(e) => &e.bind,
+            Element::SearchInput(e) => &e.bind,
+            Element::Pagination(e) => &e.bind,
+            Element::ChipInput(e) => &e.bind,
+            Element::Url(e) => &e.bind,
+            Element::Email(e) => &e.bind,
+            Element::Conditional(c) => return c.element.bind(),
+            _ => return None,
+        };
+        bind.as_deref()
+    }
+
+    /// The event handlers set on this element, keyed by their path relative
+    /// to it (`onClick`, `items[1].onClick`).
+    pub fn handlers(&self) -> Vec<(String, &Handler)> {
+        let handlers: Vec<(&str, &Option<Handler>)> = match self {
+            Element::Button(e) => vec![("onClick", &e.onClick)],
+            Element::List(e) => {
+                return e
+                    .items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| {
+                        let handler = item.onClick.as_ref()?;
+                        Some((format!("items[{}].onClick", i), handler))
+                    })
+                    .collect();
+            }
+            Element::Input(e) => vec![("onChange", &e.onChange)],
+            Element::Checkbox(e) => vec![("onChange", &e.onChange)],
+            Element::Slider(e) => vec![("onChange", &e.onChange)],
+            Element::Select(e) => vec![("onChange", &e.onChange)],
+            Element::RadioGroup(e) => vec![("onChange", &e.onChange)],
+            Element::Textarea(e) => vec![("onChange", &e.onChange)],
+            Element::Form(e) => vec![
+                ("onSubmit", &e.onSubmit),
+                ("onValidationError", &e.onValidationError),
+            ],
+            Element::DateInput(e) => vec![("onChange", &e.onChange)],
+            Element::TimeInput(e) => vec![("onChange", &e.onChange)],
+            Element::DateTimeInput(e) => vec![("onChange", &e.onChange)],
+            Element::ColorPicker(e) => vec![("onChange", &e.onChange)],
+            Element::FileUpload(e) => vec![("onChange", &e.onChange)],
+            Element::Toggle(e) => vec![("onChange", &e.onChange)],
+            Element::Tabs(e) => vec![("onChange", &e.onChange)],
+            Element::Modal(e) => vec![("onClose", &e.onClose)],
+            Element::Tag(e) => vec![("onRemove", &e.onRemove)],
+            Element::Alert(e) => vec![("onDismiss", &e.onDismiss)],
+            Element::Counter(e) => vec![("onChange", &e.onChange)],
+            Element::SearchInput(e) => {
+                vec![("onSearch", &e.onSearch), ("onChange", &e.onChange)]
+            }
+            Element::CopyButton(e) => vec![("onCopy", &e.onCopy)],
+            Element::Pagination(e) => vec![("onChange", &e.onChange)],
+            Element::ChipInput(e) => vec![("onAdd", &e.onAdd), ("onRemove", &e.onRemove)],
+            Element::React(e) => {
+                let mut events: Vec<(String, &Handler)> = e
+                    .events
+                    .iter()
+                    .map(|(name, handler)| (format!("events.{}", name), handler))
+                    .collect();
+                events.sort_by(|(a, _), (b, _)| a.cmp(b));
+                return events;
+            }
+            Element::Conditional(c) => return c.element.handlers(),
+            _ => vec![],
+        };
+        handlers
+            .into_iter()
+            .filter_map(|(key, handler)| Some((key.to_string(), handler.as_ref()?)))
+            .collect()
+    }
+
+    /// The elements given to the named slots of a component, keyed by
+    /// their path relative to this element (`slots.footer`), in name order.
+    pub fn slots(&self) -> Vec<(String, &[Element])> {
+        let slots = match self {
+            Element::Use(e) => &e.slots,
+            Element::Conditional(c) => return c.element.slots(),
+            _ => return Vec::new(),
+        };
+        let mut slots: Vec<(String, &[Element])> = slots
+            .iter()
+            .map(|(name, children)| (format!("slots.{}", name), children.as_slice()))
+            .collect();
+        slots.sort_by(|(a, _), (b, _)| a.cmp(b));
+        slots
+    }
+
+    /// Nested child elements for container-like elements.
+    pub fn children(&self) -> &[Element] {
+        match self {
+            Element::Card(e) => &e.children,
+            Element::Layout(e) => &e.children,
+            Element::Container(e) => &e.children,
+            Element::Form(e) => &e.children,
+            Element::Modal(e) => &e.children,
+            Element::Use(e) => &e.children,
+            Element::React(e) => &e.children,
+            Element::Conditional(c) => c.element.children(),
+            _ => &[],
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -973,6 +1865,19 @@ page:
         assert_eq!(list.items[0].content, "Item 1");
     }
 
+    #[test]
+    fn test_list_item_name() {
+        let list = |yaml: &str| -> ListElement { serde_yaml::from_str(yaml).unwrap() };
+        assert_eq!(list("data: projects").item_name(), "project");
+        assert_eq!(list("data: categories").item_name(), "category");
+        assert_eq!(list("data: address").item_name(), "item");
+        assert_eq!(list("{data: news, as: story}").item_name(), "story");
+        assert_eq!(list("data: project.tags").item_name(), "tag");
+        // The singular of these is a JavaScript keyword.
+        assert_eq!(list("data: news").item_name(), "item");
+        assert_eq!(list("data: cases").item_name(), "item");
+    }
+
     #[test]
     fn test_parse_email_element_as_input() {
         let yaml = r#"
@@ -1057,4 +1962,339 @@ page:
         let captcha = form.captcha.as_ref().unwrap();
         assert_eq!(captcha.provider, CaptchaProvider::Cloudflare);
     }
+
+    #[test]
+    fn test_parse_keyed_elements() {
+        let yaml = r#"
+page:
+  name: Keyed
+  children:
+    - heading:
+        content: "Title"
+        style: [text-2xl]
+    - element: text
+      content: "Mixed with tagged form"
+    - card:
+        children:
+          - button: {content: "Go", onClick: "go()"}
+          - heading: "Shorthand"
+    - layout:
+        type: column
+      children:
+        - spacer:
+"#;
+        let page: Page = serde_yaml::from_str(yaml).expect("Should parse keyed elements");
+        let children = &page.page_data.children;
+        assert_eq!(children.len(), 4);
+        assert!(matches!(&children[0], Element::Heading(h) if h.content == "Title"));
+        assert!(matches!(&children[1], Element::Text(_)));
+
+        let card = match &children[2] {
+            Element::Card(c) => c,
+            _ => panic!("Expected Card element"),
+        };
+        assert!(
+            matches!(&card.children[0], Element::Button(b) if b.onClick == Some(Handler::from("go()")))
+        );
+        assert!(matches!(&card.children[1], Element::Heading(h) if h.content == "Shorthand"));
+
+        let layout = match &children[3] {
+            Element::Layout(l) => l,
+            _ => panic!("Expected Layout element"),
+        };
+        assert_eq!(layout.layout.layout_type, LayoutType::Column);
+        assert!(matches!(&layout.children[0], Element::Spacer(_)));
+    }
+
+    #[test]
+    fn test_parse_data_list() {
+        let yaml = r#"
+page:
+  name: Team
+  children:
+    - list:
+        data: users
+        key: id
+        children:
+          - text: "{user.name}"
+        empty:
+          - text: Nobody here
+    - list:
+        data: categories
+    - list:
+        data: staff
+        as: member
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let names: Vec<String> = page
+            .page_data
+            .children
+            .iter()
+            .map(|child| match child {
+                Element::List(list) => list.item_name(),
+                _ => panic!("expected list"),
+            })
+            .collect();
+        assert_eq!(names, vec!["user", "category", "member"]);
+
+        let Element::List(list) = &page.page_data.children[0] else {
+            unreachable!()
+        };
+        assert_eq!(list.key.as_deref(), Some("id"));
+        assert_eq!(list.children.len(), 1);
+        assert_eq!(list.empty.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_conditional_elements() {
+        let yaml = r#"
+page:
+  name: Gate
+  children:
+    - heading: Welcome back
+      if: loggedIn
+      else:
+        - button: Log in
+    - element: text
+      content: Loading
+      show: loading
+    - spinner:
+      if: "count > 0"
+      else:
+        text: Done
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let children = &page.page_data.children;
+        let Element::Conditional(first) = &children[0] else {
+            panic!("expected a conditional, got {:?}", children[0]);
+        };
+        assert_eq!(first.condition, "loggedIn");
+        assert_eq!(first.element.tag(), "heading");
+        assert_eq!(first.otherwise.len(), 1);
+        assert_eq!(children[1].tag(), "text");
+        let Element::Conditional(third) = &children[2] else {
+            panic!("expected a conditional");
+        };
+        assert_eq!(third.otherwise[0].tag(), "text");
+
+        let yaml = to_yaml(&page, ElementSyntax::Keyed).unwrap();
+        let reparsed: Page = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed, page);
+
+        let error = serde_yaml::from_str::<Page>(
+            "page:\n  name: Bad\n  children:\n    - text: Hi\n      else: []\n",
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("`else` without `if`"));
+    }
+
+    #[test]
+    fn test_parse_components() {
+        let yaml = r#"
+component:
+  name: Card
+  props:
+    - name: title
+      type: string
+    - name: count
+      default: 0
+  slots: [footer]
+  children:
+    - heading: "{title}"
+    - slot:
+    - slot: footer
+      if: "count > 0"
+"#;
+        let file: ComponentFile = serde_yaml::from_str(yaml).unwrap();
+        let component = &file.component;
+        assert_eq!(component.props[1].default, Some(serde_yaml::Value::from(0)));
+        assert_eq!(component.slots, ["footer"]);
+        assert_eq!(
+            component.children[1],
+            Element::Slot(SlotElement { name: None })
+        );
+        let Element::Conditional(footer) = &component.children[2] else {
+            panic!("expected a conditional");
+        };
+        assert_eq!(
+            *footer.element,
+            Element::Slot(SlotElement {
+                name: Some("footer".to_string())
+            })
+        );
+
+        let yaml = r#"
+page:
+  name: Home
+  children:
+    - use: Card
+      props: { title: "Hi {name}", count: 2 }
+      slots:
+        footer:
+          - button: More
+      children:
+        - text: Body
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let Element::Use(card) = &page.page_data.children[0] else {
+            panic!("expected a use, got {:?}", page.page_data.children[0]);
+        };
+        assert_eq!(card.component, "Card");
+        assert_eq!(card.props["count"], serde_yaml::Value::from(2));
+        assert_eq!(card.children[0].tag(), "text");
+        let slots = page.page_data.children[0].slots();
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].0, "slots.footer");
+        assert_eq!(slots[0].1[0].tag(), "button");
+
+        for syntax in [ElementSyntax::Keyed, ElementSyntax::Tagged] {
+            let yaml = to_yaml(&page, syntax).unwrap();
+            let reparsed: Page = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(reparsed, page);
+        }
+    }
+
+    #[test]
+    fn test_parse_react_element() {
+        let yaml = r#"
+page:
+  name: Sales
+  children:
+    - react: ./widgets/Chart
+      export: LineChart
+      props: { points: "{sales}", height: 240 }
+      events:
+        onSelect:
+          - set: selected
+            to: value
+        onHover: "console.log(value)"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let element = &page.page_data.children[0];
+        let Element::React(chart) = element else {
+            panic!("expected a react element, got {:?}", element);
+        };
+        assert_eq!(chart.import, "./widgets/Chart");
+        assert_eq!(chart.export.as_deref(), Some("LineChart"));
+        assert_eq!(chart.props["height"], serde_yaml::Value::from(240));
+        let handlers: Vec<String> = element.handlers().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(handlers, ["events.onHover", "events.onSelect"]);
+
+        let yaml = to_yaml(&page, ElementSyntax::Tagged).unwrap();
+        assert!(yaml.contains("element: react"));
+        let reparsed: Page = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed, page);
+    }
+
+    #[test]
+    fn test_parse_structured_handlers() {
+        let yaml = r#"
+page:
+  name: Todos
+  children:
+    - button:
+        content: Add
+        onClick:
+          - append: todos
+            value: draft
+          - set: draft
+            to: ""
+          - call: save
+            with: [todos, 1]
+    - button:
+        content: Menu
+        onClick: {toggle: open}
+    - button:
+        content: Legacy
+        onClick: "setOpen(false)"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+        let handler = |i: usize| match &page.page_data.children[i] {
+            Element::Button(button) => button.onClick.clone().unwrap(),
+            other => panic!("expected a button, got {:?}", other),
+        };
+        assert_eq!(
+            handler(0),
+            Handler::Steps(vec![
+                Step::Append {
+                    append: "todos".to_string(),
+                    value: "draft".to_string(),
+                },
+                Step::Set {
+                    set: "draft".to_string(),
+                    to: String::new(),
+                },
+                Step::Call {
+                    call: "save".to_string(),
+                    with: vec!["todos".to_string(), "1".to_string()],
+                },
+            ])
+        );
+        assert_eq!(
+            handler(1),
+            Handler::Steps(vec![Step::Toggle {
+                toggle: "open".to_string()
+            }])
+        );
+        assert_eq!(handler(2), Handler::from("setOpen(false)"));
+
+        let yaml = to_yaml(&page, ElementSyntax::Keyed).unwrap();
+        let reparsed: Page = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed, page);
+
+        let error = serde_yaml::from_str::<Page>(
+            "page:\n  name: Bad\n  children:\n    - button: {content: X, onClick: {set: a, too: 1}}\n",
+        )
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("unknown field `too`"),
+            "{}",
+            error
+        );
+        let error = serde_yaml::from_str::<Page>(
+            "page:\n  name: Bad\n  children:\n    - button: {content: X, onClick: {goto: /}}\n",
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("expected a step starting with one of `navigate`"));
+    }
+
+    #[test]
+    fn test_keyed_element_errors() {
+        let yaml = "page:\n  name: X\n  children:\n    - headng:\n        content: Hi\n";
+        let error = serde_yaml::from_str::<Page>(yaml).unwrap_err();
+        assert!(error.to_string().contains("unknown variant `headng`"));
+
+        let yaml = "page:\n  name: X\n  children:\n    - heading: Hi\n      text: Hi\n";
+        let error = serde_yaml::from_str::<Page>(yaml).unwrap_err();
+        assert!(error.to_string().contains("element is ambiguous"));
+    }
+
+    #[test]
+    fn test_emit_both_syntaxes() {
+        let yaml = r#"
+page:
+  name: RoundTrip
+  children:
+    - element: card
+      children:
+        - element: heading
+          content: "Hello"
+"#;
+        let page: Page = serde_yaml::from_str(yaml).unwrap();
+
+        let keyed = to_yaml(&page, ElementSyntax::Keyed).unwrap();
+        assert!(keyed.contains("- card:"));
+        assert!(keyed.contains("- heading:"));
+        assert!(!keyed.contains("element:"));
+
+        let tagged = to_yaml(&page, ElementSyntax::Tagged).unwrap();
+        assert!(tagged.contains("- element: card"));
+
+        for output in [keyed, tagged] {
+            let reparsed: Page = serde_yaml::from_str(&output).unwrap();
+            assert_eq!(reparsed, page);
+        }
+    }
 }
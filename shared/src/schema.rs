@@ -0,0 +1,345 @@
+//! JSON Schema for the YAML files of a project, so editors can complete and
+//! check them. The schemas are derived from the types of this crate; only
+//! elements, whose syntax is our own, are described by hand here.
+
+use crate::*;
+use schemars::generate::SchemaSettings;
+use schemars::{json_schema, Schema, SchemaGenerator};
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+
+/// The schema of a `pages/*.yaml` file.
+pub fn page_schema() -> Schema {
+    schema_for::<Page>()
+}
+
+/// The schema of a project's `nwl.yaml`.
+pub fn project_schema() -> Schema {
+    schema_for::<ProjectConfig>()
+}
+
+/// The schema of an `actions/*.yaml` file.
+pub fn action_schema() -> Schema {
+    schema_for::<ActionFile>()
+}
+
+/// The schema of a `components/*.yaml` file.
+pub fn component_schema() -> Schema {
+    schema_for::<ComponentFile>()
+}
+
+fn schema_for<T: JsonSchema>() -> Schema {
+    // yaml-language-server supports draft 7 best.
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+/// Builds the schema of an element's properties.
+type Properties = fn(&mut SchemaGenerator) -> Schema;
+
+/// The properties of each element, by tag, in the order of [`Element::TAGS`].
+const ELEMENTS: &[(&str, Properties)] = &[
+    ("heading", HeadingElement::json_schema),
+    ("text", TextElement::json_schema),
+    ("button", ButtonElement::json_schema),
+    ("card", CardElement::json_schema),
+    ("list", ListElement::json_schema),
+    ("layout", LayoutElement::json_schema),
+    ("input", InputElement::json_schema),
+    ("image", ImageElement::json_schema),
+    ("spacer", SpacerElement::json_schema),
+    ("container", ContainerElement::json_schema),
+    ("checkbox", CheckboxElement::json_schema),
+    ("slider", SliderElement::json_schema),
+    ("select", SelectElement::json_schema),
+    ("radio-group", RadioGroupElement::json_schema),
+    ("textarea", TextareaElement::json_schema),
+    ("form", FormElement::json_schema),
+    ("date-input", DateInputElement::json_schema),
+    ("time-input", TimeInputElement::json_schema),
+    ("datetime-input", DateTimeInputElement::json_schema),
+    ("color-picker", ColorPickerElement::json_schema),
+    ("file-upload", FileUploadElement::json_schema),
+    ("progress", ProgressElement::json_schema),
+    ("toggle", ToggleElement::json_schema),
+    ("tabs", TabsElement::json_schema),
+    ("accordion", AccordionElement::json_schema),
+    ("modal", ModalElement::json_schema),
+    ("badge", BadgeElement::json_schema),
+    ("tag", TagElement::json_schema),
+    ("alert", AlertElement::json_schema),
+    ("spinner", SpinnerElement::json_schema),
+    ("counter", CounterElement::json_schema),
+    ("search-input", SearchInputElement::json_schema),
+    ("copy-button", CopyButtonElement::json_schema),
+    ("pagination", PaginationElement::json_schema),
+    ("breadcrumb", BreadcrumbElement::json_schema),
+    ("avatar", AvatarElement::json_schema),
+    ("chip-input", ChipInputElement::json_schema),
+    ("nav", NavElement::json_schema),
+    ("menu", MenuElement::json_schema),
+    ("url", UrlElement::json_schema),
+    ("email", EmailElement::json_schema),
+    ("use", UseElement::json_schema),
+    ("slot", SlotElement::json_schema),
+    ("react", ReactElement::json_schema),
+    ("outlet", OutletElement::json_schema),
+];
+
+/// Descriptions of the properties most elements have, for the elements
+/// that don't describe them otherwise.
+const SHARED_PROPERTIES: &[(&str, &str)] = &[
+    ("content", "Text shown, with `{expression}` interpolations."),
+    ("children", "Elements rendered inside this one."),
+    ("style", "Tailwind classes added to the element."),
+    ("bind", "State the value is two-way bound to."),
+    ("onChange", "Runs when the value changes."),
+    ("placeholder", "Text shown while the field is empty."),
+    ("label", "Text labelling the element."),
+];
+
+/// Properties accepted under another name, by tag.
+const ALIASES: &[(&str, &str, &str)] = &[("modal", "isOpen", "bind")];
+
+/// Every element can be written in the tagged form, `element: heading` next
+/// to its properties, or in the keyed form, `heading:` holding them. Both
+/// take `if:` (or `show:`) and `else:`.
+impl JsonSchema for Element {
+    fn schema_name() -> Cow<'static, str> {
+        "Element".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let condition = condition_properties(generator);
+        let mut forms = Vec::new();
+        for (tag, properties) in ELEMENTS {
+            let mut properties = properties(generator);
+            describe_shared_properties(&mut properties);
+            add_aliases(tag, &mut properties);
+            forms.push(tagged(tag, &properties, &condition));
+            forms.push(keyed(tag, &properties, &condition, generator));
+        }
+        json_schema!({
+            "description": "An element of a page, written with `element: tag` or keyed by its tag.",
+            "anyOf": forms,
+        })
+    }
+}
+
+fn condition_properties(generator: &mut SchemaGenerator) -> Map<String, Value> {
+    let element = generator.subschema_for::<Element>();
+    let condition = json!({
+        "type": ["string", "boolean"],
+        "description": "Expression the element is rendered under.",
+    });
+    let mut show = condition.clone();
+    show["description"] = json!("Same as `if`.");
+    let mut properties = Map::new();
+    properties.insert("if".into(), condition);
+    properties.insert("show".into(), show);
+    properties.insert(
+        "else".into(),
+        json!({
+            "description": "Rendered instead while the condition is false: an element or a list of them.",
+            "anyOf": [element, { "type": "array", "items": element }],
+        }),
+    );
+    properties
+}
+
+fn describe_shared_properties(schema: &mut Schema) {
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+    for (name, description) in SHARED_PROPERTIES {
+        if let Some(Value::Object(property)) = properties.get_mut(*name) {
+            property
+                .entry("description")
+                .or_insert_with(|| json!(description));
+        }
+    }
+}
+
+fn add_aliases(tag: &str, schema: &mut Schema) {
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+    for (_, alias, name) in ALIASES.iter().filter(|(element, ..)| *element == tag) {
+        let Some(position) = properties.keys().position(|key| key == name) else {
+            continue;
+        };
+        let mut property = properties[*name].clone();
+        property["description"] = json!(format!("Same as `{}`.", name));
+        // Next to the property, for `nwl fmt` to put it there.
+        properties.shift_insert(position + 1, alias.to_string(), property);
+    }
+}
+
+/// `element: tag` next to the element's properties.
+fn tagged(tag: &str, properties: &Schema, condition: &Map<String, Value>) -> Schema {
+    let mut schema = properties.clone();
+    let object = schema.ensure_object();
+    let fields = property_map(object);
+    fields.insert("element".into(), json!({ "const": tag }));
+    fields.extend(condition.clone());
+    let mut required = vec![json!("element")];
+    if let Some(Value::Array(fields)) = object.remove("required") {
+        required.extend(fields);
+    }
+    object.insert("required".into(), Value::Array(required));
+    schema
+}
+
+/// `tag:` holding the element's properties and condition, its content (or
+/// the property a bare scalar sets), or nothing. The
+/// properties may also be written next to it, so none are required.
+fn keyed(
+    tag: &str,
+    properties: &Schema,
+    condition: &Map<String, Value>,
+    generator: &mut SchemaGenerator,
+) -> Schema {
+    let mut body = properties.clone();
+    let scalar = Element::scalar_property(tag);
+    let has_scalar = body
+        .get("properties")
+        .and_then(|properties| properties.get(scalar))
+        .is_some();
+    body.remove("required");
+    let description = body.remove("description");
+    property_map(body.ensure_object()).extend(condition.clone());
+
+    let mut forms = vec![json!({ "type": "null" })];
+    if has_scalar {
+        forms.push(json!({
+            "type": ["string", "number", "boolean"],
+            "description": format!("The element's `{}`.", scalar),
+        }));
+    }
+    forms.push(body.to_value());
+    if tag == "layout" {
+        // `layout: { type: row }` is the layout itself, not its properties.
+        forms.push(generator.subschema_for::<Layout>().to_value());
+    }
+
+    let mut schema = properties.clone();
+    schema.remove("required");
+    let object = schema.ensure_object();
+    let fields = property_map(object);
+    let mut value = json!({ "anyOf": forms });
+    if let Some(description) = description {
+        value["description"] = description;
+    }
+    fields.insert(tag.into(), value);
+    fields.extend(condition.clone());
+    object.insert("required".into(), json!([tag]));
+    schema
+}
+
+fn property_map(object: &mut Map<String, Value>) -> &mut Map<String, Value> {
+    object
+        .entry("properties")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .expect("object schemas have a map of properties")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_tag_has_a_schema() {
+        let tags: Vec<&str> = ELEMENTS.iter().map(|(tag, _)| *tag).collect();
+        assert_eq!(tags, Element::TAGS);
+    }
+
+    #[test]
+    fn test_page_schema() {
+        let schema = page_schema().to_value();
+        let definitions = &schema["definitions"];
+
+        let layout_types: Vec<&Value> = definitions["LayoutType"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| &variant["const"])
+            .collect();
+        assert_eq!(layout_types, ["column", "row", "stack", "grid"]);
+        assert_eq!(
+            definitions["CaptchaProvider"]["oneOf"][0]["description"],
+            "Cloudflare Turnstile."
+        );
+
+        let forms = definitions["Element"]["anyOf"].as_array().unwrap();
+        assert_eq!(forms.len(), 2 * Element::TAGS.len());
+        let button = &forms[4];
+        assert_eq!(button["properties"]["element"]["const"], "button");
+        assert_eq!(button["required"], json!(["element", "content"]));
+        assert_eq!(
+            button["properties"]["onClick"]["description"],
+            "Runs when the button is clicked."
+        );
+        assert_eq!(
+            button["properties"]["style"]["description"],
+            "Tailwind classes added to the element."
+        );
+        assert_eq!(button["additionalProperties"], false);
+
+        let modal = forms
+            .iter()
+            .find(|form| form["properties"]["element"]["const"] == "modal")
+            .unwrap();
+        let keys: Vec<&String> = modal["properties"].as_object().unwrap().keys().collect();
+        let bind = keys.iter().position(|key| *key == "bind").unwrap();
+        assert_eq!(keys[bind + 1], "isOpen");
+
+        let keyed = &forms[5];
+        assert_eq!(keyed["required"], json!(["button"]));
+        assert!(keyed["properties"]["if"].is_object());
+        assert!(keyed["properties"]["onClick"].is_object());
+        let body = &keyed["properties"]["button"]["anyOf"][2];
+        assert!(body["properties"]["show"].is_object());
+
+        assert_eq!(
+            definitions["PageData"]["properties"]["name"]["description"],
+            "Name of the page. The generated component is named after it."
+        );
+    }
+
+    #[test]
+    fn test_project_and_action_schemas() {
+        let project = project_schema().to_value();
+        assert_eq!(project["required"], json!(["name"]));
+        assert_eq!(
+            project["definitions"]["RouteConfig"]["properties"]["path"]["description"],
+            "URL path, such as `/` or `/about`."
+        );
+
+        let actions = action_schema().to_value();
+        assert_eq!(actions["required"], json!(["action"]));
+        assert!(actions["definitions"]["ActionDefinition"].is_object());
+    }
+
+    #[test]
+    fn test_component_schema() {
+        let component = component_schema().to_value();
+        assert_eq!(component["required"], json!(["component"]));
+        let definitions = &component["definitions"];
+        assert_eq!(
+            definitions["ComponentDefinition"]["required"],
+            json!(["name"])
+        );
+        assert!(definitions["PropDefinition"]["properties"]["default"].is_object());
+        // Component children are elements like a page's.
+        assert_eq!(
+            definitions["ComponentDefinition"]["properties"]["children"]["items"]["$ref"],
+            "#/definitions/Element"
+        );
+        assert_eq!(
+            definitions["Element"],
+            page_schema().to_value()["definitions"]["Element"]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{ConditionalElement, Element};
+use serde::de::Error;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+/// How elements are written in page YAML.
+///
+/// `Tagged` is the `- element: heading` form; `Keyed` uses the tag as the
+/// mapping key, as in `- heading: {content: Hi}`. Both are accepted when
+/// parsing and may be mixed freely within one file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementSyntax {
+    #[default]
+    Tagged,
+    Keyed,
+}
+
+/// Serializes `value` to YAML, writing every element in the given syntax.
+pub fn to_yaml<T: Serialize>(
+    value: &T,
+    syntax: ElementSyntax,
+) -> Result<String, serde_yaml::Error> {
+    let value = serde_yaml::to_value(value)?;
+    let value = match syntax {
+        ElementSyntax::Tagged => value,
+        ElementSyntax::Keyed => to_keyed(value),
+    };
+    serde_yaml::to_string(&value)
+}
+
+/// Rewrites a keyed element (`heading: {…}`) into the tagged form the
+/// derived deserializer understands. Tagged elements pass through untouched.
+///
+/// The keyed value may be a mapping of properties, a bare string used as
+/// `content` (`- heading: "Title"`) or whatever property
+/// [`Element::scalar_property`] names (`- use: Card`), or empty
+/// (`- spacer:`). Other keys next to the tag are merged into the
+/// properties, which allows the documented layout form:
+///
+/// ```yaml
+/// - layout:
+///     type: column
+///   children: [...]
+/// ```
+pub(crate) fn normalize_element<E: Error>(value: Value) -> Result<Value, E> {
+    let Value::Mapping(mut mapping) = value else {
+        return Ok(value);
+    };
+    if mapping.contains_key("element") {
+        return Ok(Value::Mapping(mapping));
+    }
+
+    let tags: Vec<String> = mapping
+        .keys()
+        .filter_map(Value::as_str)
+        .filter(|key| Element::TAGS.contains(key))
+        .map(str::to_string)
+        .collect();
+
+    let tag = match tags.as_slice() {
+        [tag] => tag.clone(),
+        [] => {
+            // A single unrecognised key is almost certainly a misspelt tag.
+            if let (1, Some(key)) = (mapping.len(), mapping.keys().next().and_then(Value::as_str)) {
+                return Err(E::unknown_variant(key, Element::TAGS));
+            }
+            return Ok(Value::Mapping(mapping));
+        }
+        _ => {
+            return Err(E::custom(format!(
+                "element is ambiguous: found more than one element key ({})",
+                tags.iter()
+                    .map(|tag| format!("`{}`", tag))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )))
+        }
+    };
+
+    let body = mapping.remove(tag.as_str()).unwrap_or(Value::Null);
+    let mut properties = match body {
+        Value::Null => Mapping::new(),
+        Value::Mapping(properties) if tag == "layout" && properties.contains_key("type") => {
+            let mut wrapped = Mapping::new();
+            wrapped.insert("layout".into(), Value::Mapping(properties));
+            wrapped
+        }
+        Value::Mapping(properties) => properties,
+        content @ (Value::String(_) | Value::Number(_) | Value::Bool(_)) => {
+            let mut wrapped = Mapping::new();
+            wrapped.insert(Element::scalar_property(&tag).into(), content);
+            wrapped
+        }
+        other => {
+            return Err(E::custom(format!(
+                "invalid value for element `{}`: expected a mapping of properties, found {}",
+                tag,
+                describe(&other)
+            )))
+        }
+    };
+
+    for (key, value) in mapping {
+        if properties.contains_key(&key) {
+            let name = key.as_str().unwrap_or("?").to_string();
+            return Err(E::custom(format!("duplicate field `{}`", name)));
+        }
+        properties.insert(key, value);
+    }
+
+    let mut tagged = Mapping::new();
+    tagged.insert("element".into(), Value::String(tag));
+    tagged.extend(properties);
+    Ok(Value::Mapping(tagged))
+}
+
+/// Removes `if:` (or its alias `show:`) and `else:` from a normalized
+/// element, returning the condition and the elements of the `else` branch.
+/// `else:` may hold a single element or a list of them.
+pub(crate) fn take_condition<E: Error>(
+    value: &mut Value,
+) -> Result<Option<(String, Vec<Element>)>, E> {
+    let Value::Mapping(mapping) = value else {
+        return Ok(None);
+    };
+    let condition = match (mapping.remove("if"), mapping.remove("show")) {
+        (Some(_), Some(_)) => {
+            return Err(E::custom("element has both `if` and `show`; use only one"))
+        }
+        (Some(condition), None) | (None, Some(condition)) => Some(condition),
+        (None, None) => None,
+    };
+    let otherwise = mapping.remove("else");
+
+    let condition = match (condition, otherwise.is_some()) {
+        (Some(condition), _) => match condition {
+            Value::String(condition) => condition,
+            Value::Bool(flag) => flag.to_string(),
+            other => {
+                return Err(E::custom(format!(
+                    "invalid type: {} for `if`, expected a condition string",
+                    describe(&other)
+                )))
+            }
+        },
+        (None, true) => return Err(E::custom("`else` without `if`")),
+        (None, false) => return Ok(None),
+    };
+    let otherwise = match otherwise {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Sequence(items)) => items
+            .into_iter()
+            .map(|item| <Element as Deserialize>::deserialize(item).map_err(E::custom))
+            .collect::<Result<_, _>>()?,
+        Some(item) => vec![<Element as Deserialize>::deserialize(item).map_err(E::custom)?],
+    };
+    Ok(Some((condition, otherwise)))
+}
+
+/// The serialized form of a conditional element: the guarded element's
+/// properties plus `if:` and `else:`.
+pub(crate) fn with_condition<E: serde::ser::Error>(
+    conditional: &ConditionalElement,
+) -> Result<Value, E> {
+    let mut value = serde_yaml::to_value(&*conditional.element).map_err(E::custom)?;
+    if let Value::Mapping(mapping) = &mut value {
+        mapping.insert("if".into(), Value::String(conditional.condition.clone()));
+        if !conditional.otherwise.is_empty() {
+            let otherwise = serde_yaml::to_value(&conditional.otherwise).map_err(E::custom)?;
+            mapping.insert("else".into(), otherwise);
+        }
+    }
+    Ok(value)
+}
+
+/// Recursively rewrites every tagged element in `value` into keyed form.
+fn to_keyed(value: Value) -> Value {
+    match value {
+        Value::Sequence(items) => Value::Sequence(items.into_iter().map(to_keyed).collect()),
+        Value::Mapping(mut mapping) => {
+            let tag = match mapping.get("element") {
+                Some(Value::String(tag)) if Element::TAGS.contains(&tag.as_str()) => {
+                    Some(tag.clone())
+                }
+                _ => None,
+            };
+            if tag.is_some() {
+                mapping.remove("element");
+            }
+            let mapping: Mapping = mapping
+                .into_iter()
+                .map(|(key, value)| (key, to_keyed(value)))
+                .collect();
+            match tag {
+                Some(tag) => {
+                    let mut keyed = Mapping::new();
+                    keyed.insert(Value::String(tag), Value::Mapping(mapping));
+                    Value::Mapping(keyed)
+                }
+                None => Value::Mapping(mapping),
+            }
+        }
+        other => other,
+    }
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Sequence(_) => "a sequence",
+        Value::Mapping(_) => "a mapping",
+        Value::Tagged(_) => "a tagged value",
+    }
+}